@@ -1,54 +1,255 @@
-use std::{fs, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
 use tokio::sync::watch;
 use tracing::Instrument;
 
 use crate::{
-    chain::ChainAdapter,
-    config::AppConfig,
+    canary::{CanaryProgress, CanaryResolution},
+    chain::{
+        ChainAdapter, ChainSource, FileChainSource, file_fixture_path, parse_proposal_id,
+        write_proposal_fixture,
+    },
+    config::{AgentMode, AppConfig, NetworkConfig, ReadBackend, SignerAccount},
     decision::decide,
-    ipfs::BundleFetcher,
+    decoder::{
+        KNOWN_PROPOSAL_CREATED_SIGNATURES, OTHER_GOVERNOR_EVENT_SIGNATURES, ProposalCreatedAbi,
+        ProposalInspection, inspect_calls,
+    },
+    export::{ExportWebhook, FlushReport},
+    forwarder::ForwarderVoteExecutor,
+    ipfs::{BundleFetcher, BundleSource},
+    jsonrpc::JsonRpcChain,
     llm::CompositeLlm,
-    notifier::MultiNotifier,
+    notifier::{MultiNotifier, NotificationLinks},
     observability,
+    outcome::OutcomeReport,
+    peers::{PeerClient, disagreement_summary},
+    report::{BatchReview, ReviewReport},
     review::review_proposal,
-    signer::{DryRunVoteExecutor, KeystoreVoteExecutor, VoteExecutor, signing_readiness_reason},
-    storage::{State, Storage},
-    types::ProcessedProposal,
+    rpc_stats::{self, RpcStats},
+    signer::{
+        ASSUMED_BLOCK_SECS, ChainClock, ClockMode, DryRunVoteExecutor, KeystoreVoteExecutor,
+        VoteExecutor, account_readiness_reason, signing_readiness_reason,
+    },
+    storage::{State, StateLock, Storage, short_proposal_id},
+    types::{
+        CatchUpProgress, CursorRewind, Decision, DecodedAction, Finding, FindingCode,
+        OperatorAnnotation, PeerComparison, PendingReview, ProcessedProposal, Proposal,
+        ProposalState, ReviewResult, Severity, SkipReason, SkippedProposal, SyntheticReview,
+        VoteChoice, VoteExecution,
+    },
 };
 
+struct VoteAccount {
+    label: String,
+    executor: Arc<dyn VoteExecutor>,
+}
+
+#[derive(Debug, Default)]
+struct ScanCycle {
+    discovered: usize,
+    skipped_logs: usize,
+    skipped_closed: usize,
+}
+
+// Collects what a catch-up pass processed so it can be reported in one digest at the end.
+struct CatchUp {
+    latest_block: u64,
+    skip_closed_votes: bool,
+    processed: Vec<ProcessedProposal>,
+}
+
+impl CatchUp {
+    fn progress(&self, start_block: u64) -> CatchUpProgress {
+        CatchUpProgress {
+            start_block,
+            proposal_ids: self
+                .processed
+                .iter()
+                .map(|processed| processed.proposal.proposal_id.clone())
+                .collect(),
+        }
+    }
+}
+
+// How an on-demand lookup finds a proposal that is not in local state. `wait` keeps retrying
+// while the RPC's log index catches up with a new proposal; `at_block` scans only its creation
+// block instead of everything since `network.from_block`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProposalLookup {
+    pub wait: Duration,
+    pub at_block: Option<u64>,
+}
+
+impl ProposalLookup {
+    // One creation block cannot hold every proposal of a batch, so `at_block` is for one id only.
+    pub fn check_batch(&self, proposal_ids: &[String]) -> Result<()> {
+        let distinct = proposal_ids.iter().collect::<BTreeSet<_>>().len();
+        if let Some(block) = self.at_block
+            && distinct > 1
+        {
+            bail!(
+                "--at-block {block} names a single creation block; it cannot be combined with {distinct} proposal ids"
+            );
+        }
+        Ok(())
+    }
+}
+
+// Scopes `proposals list` and `proposals outcomes` to one governance period: proposals created at
+// or after `since_block` and first seen by the agent at or after `since`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProposalFilter {
+    pub since_block: Option<u64>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl ProposalFilter {
+    pub fn matches(&self, processed: &ProcessedProposal) -> bool {
+        self.since_block
+            .is_none_or(|block| processed.proposal.block_number >= block)
+            && self
+                .since
+                .is_none_or(|since| processed.proposal.discovered_at >= since)
+    }
+}
+
+const PRUNE_INTERVAL: chrono::Duration = chrono::Duration::days(7);
+const LOOKUP_RETRY_INITIAL: Duration = Duration::from_millis(250);
+const LOOKUP_RETRY_MAX: Duration = Duration::from_secs(5);
+// How far back from the confirmed tip the startup check looks for ProposalCreated logs.
+const EVENT_PROBE_BLOCKS: u64 = 5_000;
+
 pub struct Agent {
     config: AppConfig,
-    chain: ChainAdapter,
+    chain: Box<dyn ChainSource>,
     storage: Storage,
     bundle_fetcher: BundleFetcher,
     llm: CompositeLlm,
     notifier: MultiNotifier,
+    export: Option<ExportWebhook>,
+    peers: Option<PeerClient>,
     prompt_override: Option<String>,
+    config_hash: String,
+    vote_executor_override: Option<Vec<VoteAccount>>,
+    voting_paused: AtomicBool,
+    chain_verified: AtomicBool,
+    // Consecutive scans that found the state cursor ahead of the chain tip.
+    cursor_anomalies: AtomicU32,
+    cursor_check: Option<Box<dyn ChainSource>>,
 }
 
 impl Agent {
     pub fn new(config: AppConfig) -> Result<Self> {
-        let prompt_override = config
-            .review
-            .prompt_file
-            .as_ref()
-            .and_then(|path| fs::read_to_string(path).ok());
+        let chain: Box<dyn ChainSource> = match file_fixture_path(&config.network.rpc_url) {
+            Some(path) => {
+                let source = FileChainSource::open(path, config.network.chain_id)?;
+                tracing::info!(path = %source.path().display(), "replaying proposals from fixture");
+                Box::new(source)
+            }
+            None => match config.network.read_backend {
+                ReadBackend::Alloy => Box::new(ChainAdapter::new(
+                    &config.network,
+                    &config.http,
+                    config.review.include_non_registry,
+                )?),
+                ReadBackend::JsonRpc => {
+                    tracing::info!("reading chain state through the plain json-rpc client");
+                    Box::new(JsonRpcChain::new(
+                        &config.network,
+                        &config.http,
+                        config.review.include_non_registry,
+                    )?)
+                }
+            },
+        };
+        let cursor_check = match &config.network.cursor_check_rpc_url {
+            Some(url) => {
+                let network = NetworkConfig {
+                    rpc_url: url.clone(),
+                    ..config.network.clone()
+                };
+                let check: Box<dyn ChainSource> = Box::new(
+                    JsonRpcChain::new(&network, &config.http, config.review.include_non_registry)
+                        .context("network.cursor_check_rpc_url")?,
+                );
+                Some(check)
+            }
+            None => None,
+        };
+        let mut agent = Self::with_chain_source(config, chain)?;
+        agent.cursor_check = cursor_check;
+        Ok(agent)
+    }
+
+    pub fn with_chain_source(config: AppConfig, chain: Box<dyn ChainSource>) -> Result<Self> {
+        let prompt_override = config.review.prompt_override();
+        let config_hash = config.decision_config_hash(prompt_override.as_deref());
+        RpcStats::global().set_window(config.observability.rpc_stats_window);
 
         Ok(Self {
-            chain: ChainAdapter::new(&config.network),
+            chain,
             storage: Storage::new(&config.storage)?,
-            bundle_fetcher: BundleFetcher::new(&config.ipfs)?,
-            llm: CompositeLlm::from_config(&config.llm),
-            notifier: MultiNotifier::from_config(&config.notifications),
+            bundle_fetcher: BundleFetcher::new(&config.ipfs, &config.http)?,
+            llm: CompositeLlm::from_config(&config.llm, &config.http, &config.storage.data_dir)?,
+            notifier: MultiNotifier::from_config(
+                &config.notifications,
+                &config.http,
+                NotificationLinks::from_config(&config),
+            )?,
+            export: config
+                .review
+                .export_webhook
+                .as_ref()
+                .map(|webhook| ExportWebhook::from_config(webhook, &config.http))
+                .transpose()?,
+            peers: PeerClient::from_config(&config.peers, &config.http)?,
             config,
             prompt_override,
+            config_hash,
+            vote_executor_override: None,
+            voting_paused: AtomicBool::new(false),
+            chain_verified: AtomicBool::new(false),
+            cursor_anomalies: AtomicU32::new(0),
+            cursor_check: None,
         })
     }
 
+    #[cfg(test)]
+    fn with_parts(
+        config: AppConfig,
+        chain: Box<dyn ChainSource>,
+        vote_executor: Arc<dyn VoteExecutor>,
+    ) -> Result<Self> {
+        let mut agent = Self::with_chain_source(config, chain)?;
+        agent.vote_executor_override = Some(vec![VoteAccount {
+            label: "default".to_string(),
+            executor: vote_executor,
+        }]);
+        Ok(agent)
+    }
+
     pub async fn run_loop(&self, once: bool) -> Result<()> {
+        self.ensure_signer_ready()?;
+        self.warn_expired_suppressions();
+        self.warn_pending_vote_intents()?;
         let shutdown = install_shutdown_signal_listener();
+        self.verify_chain_id().await?;
+        if self.config.decision.reevaluate_unvoted_on_start {
+            self.reevaluate_unvoted_decisions().await?;
+        }
 
         tracing::debug!(
             config = %self.redacted_config_json(),
@@ -56,13 +257,17 @@ impl Agent {
         );
         tracing::info!(
             poll_interval_secs = self.config.poll_interval_secs,
-            mode = if once { "single-pass" } else { "continuous" },
+            max_poll_interval_secs = self.config.max_poll_interval_secs,
+            loop_mode = if once { "single-pass" } else { "continuous" },
             state_path = %self.storage.state_path().display(),
             from_block = self.config.network.from_block,
+            mode = self.config.mode.as_str(),
             auto_vote = self.config.auto_vote,
             "agent run loop started"
         );
-        if self.config.auto_vote {
+        if self.config.mode == AgentMode::Observer {
+            tracing::info!("observer mode; signer checks skipped and votes are never submitted");
+        } else if self.config.auto_vote {
             if let Some(reason) = signing_readiness_reason(&self.config.signer) {
                 tracing::warn!(
                     reason = %reason,
@@ -75,24 +280,73 @@ impl Agent {
             );
         }
 
+        self.check_voting_paused().await;
+        match self.proposal_event_mismatch().await {
+            Ok(Some(mismatch)) => tracing::error!("{mismatch}"),
+            Ok(None) => {}
+            Err(err) => tracing::warn!(
+                error = %format!("{err:#}"),
+                "could not check the governor's ProposalCreated signature"
+            ),
+        }
+
+        let mut idle_cycles = 0u32;
         loop {
             if *shutdown.borrow() {
                 tracing::info!("shutdown signal received; stopping agent loop");
                 return Ok(());
             }
 
-            self.scan_and_process_once(Some(&shutdown)).await?;
+            let span = tracing::info_span!(
+                "scan_cycle",
+                discovered = tracing::field::Empty,
+                skipped_logs = tracing::field::Empty,
+            );
+            let cycle = self
+                .scan_and_process_once(Some(&shutdown))
+                .instrument(span.clone())
+                .await?;
+            let discovered = cycle.discovered;
+            span.record("discovered", discovered);
+            span.record("skipped_logs", cycle.skipped_logs);
             if once {
-                tracing::info!("agent run loop finished single pass");
+                tracing::info!(
+                    discovered,
+                    skipped_logs = cycle.skipped_logs,
+                    skipped_closed = cycle.skipped_closed,
+                    "agent run loop finished single pass"
+                );
                 return Ok(());
             }
+            if discovered > 0 {
+                if idle_cycles >= self.config.idle_cycles_before_backoff.max(1) {
+                    tracing::info!(
+                        discovered,
+                        idle_cycles,
+                        "new proposals found; returning to base poll interval"
+                    );
+                }
+                idle_cycles = 0;
+            } else {
+                idle_cycles = idle_cycles.saturating_add(1);
+            }
+            let sleep = poll_interval(
+                self.config.poll_interval_secs,
+                self.config.max_poll_interval_secs,
+                idle_cycles,
+                self.config.idle_cycles_before_backoff,
+            );
             tracing::info!(
-                sleep_secs = self.config.poll_interval_secs,
+                discovered,
+                skipped_logs = cycle.skipped_logs,
+                skipped_closed = cycle.skipped_closed,
+                sleep_secs = sleep.as_secs(),
+                idle_cycles,
                 "scan cycle complete; waiting before next block check"
             );
             let mut shutdown_wait = shutdown.clone();
             tokio::select! {
-                _ = tokio::time::sleep(Duration::from_secs(self.config.poll_interval_secs)) => {}
+                _ = tokio::time::sleep(sleep) => {}
                 changed = shutdown_wait.changed() => {
                     if changed.is_ok() && *shutdown_wait.borrow() {
                         tracing::info!("shutdown signal received during sleep; exiting loop");
@@ -103,64 +357,587 @@ impl Agent {
         }
     }
 
-    pub async fn backfill(&self, from_block: u64, to_block: Option<u64>) -> Result<()> {
+    pub async fn backfill(
+        &self,
+        from_block: u64,
+        to_block: Option<u64>,
+        capture: Option<&Path>,
+        review_closed: bool,
+    ) -> Result<()> {
+        self.ensure_signer_ready()?;
+        self.warn_expired_suppressions();
+        self.verify_chain_id().await?;
         let mut state = self.storage.load()?;
         let latest = self.chain.latest_block().await?;
-        let end = to_block.unwrap_or(latest);
-        self.process_range(&mut state, from_block, end, None)
+        let end = to_block.unwrap_or(confirmed_tip(latest, self.config.network.confirmations));
+        let (proposals, _) = self.fetch_range(from_block, end).await?;
+        if let Some(path) = capture {
+            write_proposal_fixture(path, &proposals)?;
+            tracing::info!(
+                count = proposals.len(),
+                path = %path.display(),
+                "captured proposals to fixture"
+            );
+        }
+        let skipped_closed = self
+            .process_proposals(
+                &mut state,
+                proposals,
+                from_block..=end,
+                None,
+                None,
+                review_closed,
+            )
             .await?;
+        if skipped_closed > 0 {
+            tracing::info!(
+                skipped_closed,
+                "skipped proposals whose voting had closed; pass --review-closed to review them"
+            );
+        }
         state.last_scanned_block = state.last_scanned_block.max(end);
+        self.flush_exports(&mut state, false).await;
         self.storage.save(&state)?;
         Ok(())
     }
 
-    pub async fn review_once(&self, proposal_id: String) -> Result<()> {
-        let proposal = self
-            .chain
-            .fetch_proposal_by_id(&proposal_id, self.config.network.from_block)
-            .await?;
-
-        let review = review_proposal(
-            &proposal,
-            &self.config.review,
-            &self.config.decision,
-            &self.bundle_fetcher,
-            &self.llm,
-            self.prompt_override.as_deref(),
-        )
-        .await?;
-
-        let decision = decide(&self.config.decision, &review);
+    pub async fn review_once(
+        &self,
+        proposal_id: String,
+        lookup: ProposalLookup,
+        json: bool,
+    ) -> Result<()> {
+        let report = self.review_on_demand(&proposal_id, lookup).await?;
+        let (score, decision) = (&report.score, &report.decision);
         let (approve_threshold, reject_threshold) = self.config.decision.resolved_thresholds();
-        let deterministic_score = review.deterministic_score.unwrap_or(review.score);
-        let deterministic_weight = review.deterministic_weight.unwrap_or(0.70);
-        let llm_weight = review.llm_weight.unwrap_or(0.30);
-        let llm_score = review
-            .llm_score
+        let deterministic_score = score.deterministic.unwrap_or(score.total);
+        let deterministic_weight = score.deterministic_weight.unwrap_or(0.70);
+        let llm_weight = score.llm_weight.unwrap_or(0.30);
+        let llm_score = score
+            .llm
             .map(|value| format!("{value:.2}"))
             .unwrap_or_else(|| "none".to_string());
         tracing::info!(
-            proposal_id = %proposal_id,
+            proposal_id = %report.proposal.proposal_id,
             vote = ?decision.vote,
             deterministic_score = %format_args!("{:.2}", deterministic_score),
             llm_score = %llm_score,
             deterministic_weight = %format_args!("{:.2}", deterministic_weight),
             llm_weight = %format_args!("{:.2}", llm_weight),
-            blended_score = %format_args!("{:.2}", review.score),
+            blended_score = %format_args!("{:.2}", score.total),
             reject_threshold = %format_args!("{:.2}", reject_threshold),
             approve_threshold = %format_args!("{:.2}", approve_threshold),
+            config_hash = %self.config_hash,
             reasons = ?decision.reasons,
             blocking_findings = ?decision.blocking_findings,
             requires_human_override = decision.requires_human_override,
             "review-once complete"
         );
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        Ok(())
+    }
+
+    // Shared by `review-once` and the API; like them it never writes state or votes.
+    pub async fn review_on_demand(
+        &self,
+        proposal_id: &str,
+        lookup: ProposalLookup,
+    ) -> Result<ReviewReport> {
+        let state = self.storage.load()?;
+        let proposal_id = state.resolve_proposal_id(proposal_id)?;
+        self.review_by_id(&proposal_id, &state, lookup).await
+    }
+
+    pub async fn review_many(&self, proposal_ids: &[String], lookup: ProposalLookup) -> Result<()> {
+        let results = self.review_batch(proposal_ids, lookup).await?;
+        let failed = results
+            .iter()
+            .filter(|result| result.error.is_some())
+            .map(|result| result.proposal_id.as_str())
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            tracing::warn!(proposal_ids = ?failed, "some proposals were not found or failed review");
+        }
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        Ok(())
+    }
+
+    async fn review_batch(
+        &self,
+        proposal_ids: &[String],
+        lookup: ProposalLookup,
+    ) -> Result<Vec<BatchReview>> {
+        let state = self.storage.load()?;
+        let mut seen = BTreeSet::new();
+        let mut results = Vec::new();
+        for input in proposal_ids {
+            let proposal_id = match state.resolve_proposal_id(input) {
+                Ok(proposal_id) => proposal_id,
+                Err(err) => {
+                    results.push(BatchReview {
+                        proposal_id: input.trim().to_string(),
+                        report: None,
+                        error: Some(format!("{err:#}")),
+                    });
+                    continue;
+                }
+            };
+            if !seen.insert(proposal_id.clone()) {
+                continue;
+            }
+
+            let result = match self.review_by_id(&proposal_id, &state, lookup).await {
+                Ok(report) => BatchReview {
+                    proposal_id,
+                    report: Some(report),
+                    error: None,
+                },
+                Err(err) => BatchReview {
+                    proposal_id,
+                    report: None,
+                    error: Some(format!("{err:#}")),
+                },
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    pub async fn review_cid(
+        &self,
+        root_cid: &str,
+        name: Option<&str>,
+        version: Option<&str>,
+    ) -> Result<()> {
+        let result = self.review_synthetic(root_cid, name, version).await?;
+        tracing::info!(
+            root_cid = %root_cid.trim(),
+            vote = ?result.decision.vote,
+            score = %format_args!("{:.2}", result.review.score),
+            config_hash = %self.config_hash,
+            "synthetic review complete; nothing was stored or voted"
+        );
+        let report = ReviewReport::new(&result.proposal, &result.review, &result.decision, true);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+
+    // State is only read, for reputation and bundle history; the result is never saved.
+    async fn review_synthetic(
+        &self,
+        root_cid: &str,
+        name: Option<&str>,
+        version: Option<&str>,
+    ) -> Result<SyntheticReview> {
+        let root_cid = root_cid.trim();
+        if root_cid.is_empty() {
+            bail!("--cid must not be empty");
+        }
+        let manifest = self.bundle_fetcher.fetch_manifest(root_cid).await.ok();
+        let name = name
+            .map(ToString::to_string)
+            .or_else(|| manifest.as_ref().and_then(|m| m.name.clone()))
+            .unwrap_or_else(|| "unnamed".to_string());
+        let version = version
+            .map(ToString::to_string)
+            .or_else(|| manifest.as_ref().and_then(|m| m.version.clone()))
+            .unwrap_or_else(|| "0.0.0".to_string());
+        let proposal = synthetic_proposal(root_cid, name, version);
+
+        let state = self.storage.load()?;
+        let review = review_proposal(
+            &proposal,
+            &self.config.review,
+            &self.config.decision,
+            &self.bundle_fetcher,
+            &self.llm,
+            self.prompt_override.as_deref(),
+            &state,
+        )
+        .await?;
+        let decision = self.decide(&review);
+        Ok(SyntheticReview {
+            proposal,
+            review,
+            decision,
+        })
+    }
+
+    async fn review_by_id(
+        &self,
+        proposal_id: &str,
+        state: &State,
+        lookup: ProposalLookup,
+    ) -> Result<ReviewReport> {
+        let stored = state
+            .proposals
+            .get(proposal_id)
+            .map(|processed| &processed.proposal)
+            .or_else(|| {
+                state
+                    .pending_reviews
+                    .get(proposal_id)
+                    .map(|pending| &pending.proposal)
+            });
+        let mut proposal = match stored {
+            Some(proposal) => proposal.clone(),
+            None => self.wait_for_proposal(proposal_id, lookup).await?,
+        };
+        self.attach_creation_context(&mut proposal).await;
+
+        let review = self
+            .review_keeping_operator_findings(&proposal, state)
+            .await?;
+        let decision = self.decide(&review);
+        Ok(ReviewReport::new(&proposal, &review, &decision, false))
+    }
+
+    // Operator findings on a stored review of the same proposal carry over, so no re-review
+    // drops what `proposals annotate` recorded.
+    async fn review_keeping_operator_findings(
+        &self,
+        proposal: &Proposal,
+        state: &State,
+    ) -> Result<ReviewResult> {
+        let mut review = review_proposal(
+            proposal,
+            &self.config.review,
+            &self.config.decision,
+            &self.bundle_fetcher,
+            &self.llm,
+            self.prompt_override.as_deref(),
+            state,
+        )
+        .await?;
+        if let Some(processed) = state.proposals.get(&proposal.proposal_id) {
+            review.merge_operator_findings(&processed.review);
+        }
+        Ok(review)
+    }
+
+    // Retries with backoff until the proposal is found or `lookup.wait` has passed, logging each
+    // miss with the latest block the RPC reports.
+    pub async fn wait_for_proposal(
+        &self,
+        proposal_id: &str,
+        lookup: ProposalLookup,
+    ) -> Result<Proposal> {
+        let deadline = Instant::now() + lookup.wait;
+        let mut delay = LOOKUP_RETRY_INITIAL;
+        let mut attempt = 1u32;
+        loop {
+            let err = match self.find_proposal(proposal_id, lookup.at_block).await {
+                Ok(proposal) => return Ok(proposal),
+                Err(err) => err,
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                if attempt == 1 {
+                    return Err(err);
+                }
+                return Err(err.context(format!(
+                    "gave up waiting for proposal {proposal_id} after {attempt} attempts"
+                )));
+            }
+            let latest_block = self.chain.latest_block().await.ok();
+            tracing::info!(
+                proposal_id,
+                attempt,
+                latest_block,
+                at_block = lookup.at_block,
+                remaining_secs = remaining.as_secs(),
+                error = %format!("{err:#}"),
+                "proposal not found yet; retrying"
+            );
+            tokio::time::sleep(delay.min(remaining)).await;
+            delay = (delay * 2).min(LOOKUP_RETRY_MAX);
+            attempt += 1;
+        }
+    }
+
+    async fn find_proposal(&self, proposal_id: &str, at_block: Option<u64>) -> Result<Proposal> {
+        let Some(block) = at_block else {
+            return self
+                .chain
+                .fetch_proposal_by_id(proposal_id, self.config.network.from_block)
+                .await;
+        };
+        let requested = parse_proposal_id(proposal_id)
+            .with_context(|| format!("invalid proposal id {proposal_id}"))?;
+        self.chain
+            .fetch_proposals(block, block)
+            .await?
+            .into_iter()
+            .find(|proposal| parse_proposal_id(&proposal.proposal_id).ok() == Some(requested))
+            .ok_or_else(|| anyhow!("proposal {proposal_id} not found in block {block}"))
+    }
+
+    pub async fn inspect(&self, proposal_id: &str) -> Result<()> {
+        let proposal_id = self.resolve_proposal_id(proposal_id)?;
+        let (proposal, topics) = self
+            .chain
+            .fetch_proposal_with_topics(&proposal_id, self.config.network.from_block)
+            .await?;
+
+        let inspection = ProposalInspection {
+            topics,
+            calls: inspect_calls(
+                &proposal.targets,
+                &proposal.values,
+                &proposal.calldatas,
+                &self.config.network.dapp_registry_address,
+            ),
+            proposal,
+        };
+
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+        Ok(())
+    }
+
+    pub fn list_proposals(&self, short: bool, filter: ProposalFilter) -> Result<()> {
+        let state = self.storage.load()?;
+        for (key, processed) in state
+            .proposals
+            .iter()
+            .filter(|(_, processed)| filter.matches(processed))
+        {
+            let id = if short {
+                short_proposal_id(key)
+            } else {
+                key.clone()
+            };
+            println!(
+                "{}\t{:?}\t{:.2}\tblock={}\tstate={}",
+                id,
+                processed.decision.vote,
+                processed.review.score,
+                processed.proposal.block_number,
+                processed
+                    .lifecycle
+                    .as_ref()
+                    .map_or("unknown", |lifecycle| lifecycle.state.as_str())
+            );
+        }
+        Ok(())
+    }
+
+    pub fn stored_proposals(&self) -> Result<BTreeMap<String, ProcessedProposal>> {
+        Ok(self.storage.load()?.proposals)
+    }
+
+    // Exact id match only (decimal or hex), archived proposals included: peers ask by full id
+    // and must never get a different proposal through a prefix match.
+    pub fn stored_proposal(&self, proposal_id: &str) -> Result<Option<ProcessedProposal>> {
+        let Ok(requested) = parse_proposal_id(proposal_id) else {
+            return Ok(None);
+        };
+        let mut state = self.storage.load()?;
+        let Some(key) = state
+            .known_ids()
+            .find(|key| parse_proposal_id(key).ok() == Some(requested))
+            .cloned()
+        else {
+            return Ok(None);
+        };
+        match (state.proposals.remove(&key), state.archived.get(&key)) {
+            (Some(processed), _) => Ok(Some(processed)),
+            (None, Some(archived)) => self.storage.load_archived(archived).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    // Read-only backtest of stored decisions against the governor's final states.
+    pub async fn compare_outcomes(&self, filter: ProposalFilter) -> Result<()> {
+        let state = self.storage.load()?;
+        let mut report = OutcomeReport::default();
+        for (proposal_id, processed) in state
+            .proposals
+            .iter()
+            .filter(|(_, processed)| filter.matches(processed))
+        {
+            let tally = self.chain.proposal_tally(proposal_id).await;
+            report.push(proposal_id.clone(), processed.decision.vote, tally);
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+
+    pub fn show_proposal(&self, proposal_id: &str) -> Result<()> {
+        let mut state = self.storage.load()?;
+        let key = state.resolve_proposal_id(proposal_id)?;
+        let mut processed = match (state.proposals.remove(&key), state.archived.get(&key)) {
+            (Some(processed), _) => processed,
+            (None, Some(archived)) => self.storage.load_archived(archived)?,
+            (None, None) => bail!("proposal {key} is not recorded in local state"),
+        };
+        processed.review.llm_audit = self.storage.load_audit(&processed.review);
+        println!("{}", serde_json::to_string_pretty(&processed)?);
+        Ok(())
+    }
+
+    // The new decision is recorded but not voted; the previous one is kept in decision_history.
+    pub async fn annotate_proposal(
+        &self,
+        proposal_id: &str,
+        severity: Severity,
+        message: &str,
+        author: &str,
+    ) -> Result<()> {
+        let mut state = self.storage.load()?;
+        let key = state.resolve_proposal_id(proposal_id)?;
+        let processed = state
+            .proposals
+            .get_mut(&key)
+            .ok_or_else(|| anyhow!("proposal {key} has no stored review to annotate"))?;
+        let finding = Finding {
+            code: FindingCode::OperatorFinding,
+            severity,
+            message: message.to_string(),
+            operator: Some(OperatorAnnotation {
+                author: author.to_string(),
+                annotated_at: Utc::now(),
+            }),
+        };
+        processed.review.findings.push(finding.clone());
+        let decision = self.decide(&processed.review);
+        let previous = std::mem::replace(&mut processed.decision, decision);
+        let previous_vote = previous.vote;
+        processed.decision_history.push(previous);
+        tracing::info!(
+            proposal_id = %key,
+            author,
+            severity = severity.as_str(),
+            previous_vote = ?previous_vote,
+            vote = ?processed.decision.vote,
+            "operator finding recorded and decision recomputed"
+        );
+        let processed = processed.clone();
+        self.storage.save(&state)?;
+
+        self.notifier
+            .proposal_annotated(&processed, &finding, previous_vote)
+            .await;
+        println!("{}", serde_json::to_string_pretty(&processed.decision)?);
+        Ok(())
+    }
+
+    // Records how a human resolved a stored decision. Each proposal counts once, in the order
+    // resolutions are confirmed.
+    // Holds the state lock for the read-modify-write, so it cannot interleave with a run loop.
+    // Decisions held while the canary ran are submitted by the run loop's next scan once promoted.
+    pub async fn confirm_canary(
+        &self,
+        proposal_id: &str,
+        human_vote: VoteChoice,
+        force_unlock: bool,
+    ) -> Result<()> {
+        let decision = &self.config.decision;
+        if !decision.canary_mode {
+            bail!("decision.canary_mode is off; there is no canary trial to confirm");
+        }
+        let _state_lock = self.storage.lock(force_unlock)?;
+        let mut state = self.storage.load()?;
+        let key = state.resolve_proposal_id(proposal_id)?;
+        let agent_vote = state
+            .proposals
+            .get(&key)
+            .map(|processed| processed.decision.vote)
+            .ok_or_else(|| anyhow!("proposal {key} has no stored decision to confirm"))?;
+        let progress = state.canary.record(
+            CanaryResolution {
+                proposal_id: key.clone(),
+                agent_vote,
+                human_vote,
+                resolved_at: Utc::now(),
+            },
+            decision.canary_required_agreements,
+            decision.canary_auto_promote,
+        )?;
+        self.storage.save(&state)?;
+
+        let streak = state.canary.streak;
+        let required = decision.canary_required_agreements;
+        tracing::info!(
+            proposal_id = %key,
+            agent_vote = ?agent_vote,
+            human_vote = ?human_vote,
+            streak,
+            required,
+            "canary resolution recorded"
+        );
+        match progress {
+            CanaryProgress::Agreed => {}
+            CanaryProgress::Disagreed => {
+                tracing::warn!(
+                    proposal_id = %key,
+                    "canary disagreement; consecutive agreement count reset"
+                );
+            }
+            CanaryProgress::Eligible => {
+                tracing::warn!(
+                    streak,
+                    "canary auto-vote eligibility met; set decision.canary_auto_promote or turn off decision.canary_mode to vote live"
+                );
+                self.notifier.canary_eligible(streak).await;
+            }
+            CanaryProgress::Promoted => {
+                tracing::warn!(
+                    streak,
+                    auto_vote = self.config.auto_vote,
+                    "CANARY PROMOTED: votes are no longer routed to dry-run; held votes on active proposals are submitted on the next scan"
+                );
+                self.notifier.canary_promoted(streak).await;
+            }
+        }
+        print!("{}", state.canary.render_report(required));
+        Ok(())
+    }
 
+    // Checked once per process before the first scan, so a wrong --rpc-url can never lead to
+    // votes on another chain. Later scans reuse the same endpoint.
+    async fn verify_chain_id(&self) -> Result<()> {
+        if self.chain_verified.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let chain_id = self.chain.health_check().await?;
+        if let Some(mismatch) = chain_id_mismatch(&self.config.network, chain_id) {
+            if !self.config.network.allow_chain_mismatch {
+                bail!(
+                    "{mismatch}; refusing to scan or vote. Fix the RPC URL, or pass --allow-chain-mismatch if this is intended"
+                );
+            }
+            tracing::warn!(
+                chain_id,
+                expected_chain_id = self.config.network.chain_id,
+                "{mismatch}; continuing because chain mismatches are allowed"
+            );
+        }
+        self.chain_verified.store(true, Ordering::Relaxed);
         Ok(())
     }
 
+    pub fn lock_state(&self, force_unlock: bool) -> Result<StateLock> {
+        self.storage.lock(force_unlock)
+    }
+
     pub async fn status(&self) -> Result<()> {
+        tracing::info!(
+            mode = self.config.mode.as_str(),
+            auto_vote = self.config.auto_vote,
+            "agent mode"
+        );
         let chain_id = self.chain.health_check().await?;
-        tracing::info!(chain_id, "rpc health check succeeded");
+        match chain_id_mismatch(&self.config.network, chain_id) {
+            None => tracing::info!(chain_id, "rpc health check succeeded"),
+            Some(mismatch) => tracing::error!(
+                chain_id,
+                expected_chain_id = self.config.network.chain_id,
+                allowed = self.config.network.allow_chain_mismatch,
+                "{mismatch}"
+            ),
+        }
         tracing::info!(
             transport = self.chain.transport().as_str(),
             "rpc transport mode"
@@ -176,14 +953,54 @@ impl Agent {
             "local scan state"
         );
 
+        print!(
+            "{}",
+            rpc_stats::render_table(&RpcStats::global().endpoints())
+        );
+        if let Some(snapshot) = &state.rpc_stats {
+            println!("rpc stats snapshot captured at {}:", snapshot.captured_at);
+            print!("{}", rpc_stats::render_table(&snapshot.endpoints));
+        }
+
+        if self.storage.voting_paused() {
+            tracing::warn!(
+                path = %self.storage.pause_path().display(),
+                "voting is paused; run `agent resume` to submit votes again"
+            );
+        }
+        if self.config.decision.canary_mode || !state.canary.resolutions.is_empty() {
+            print!(
+                "{}",
+                state
+                    .canary
+                    .render_report(self.config.decision.canary_required_agreements)
+            );
+        }
         if self.config.notifications.telegram.enabled {
             tracing::info!("telegram notifier enabled");
         }
+        if self.config.notifications.github.enabled {
+            tracing::info!(
+                repo = self
+                    .config
+                    .notifications
+                    .github
+                    .repo
+                    .as_deref()
+                    .unwrap_or("<unset>"),
+                "github notifier enabled"
+            );
+        }
 
         Ok(())
     }
 
-    async fn scan_and_process_once(&self, shutdown: Option<&watch::Receiver<bool>>) -> Result<()> {
+    // Counts the proposals discovered and logs skipped in newly scanned blocks.
+    async fn scan_and_process_once(
+        &self,
+        shutdown: Option<&watch::Receiver<bool>>,
+    ) -> Result<ScanCycle> {
+        self.verify_chain_id().await?;
         observability::record_poll_attempt();
         let scan_started = observability::now();
         let mut state = self.storage.load()?;
@@ -196,13 +1013,11 @@ impl Agent {
 
         let latest = self.chain.latest_block().await?;
         if state.last_scanned_block > latest {
-            tracing::warn!(
-                last_scanned_block = state.last_scanned_block,
-                latest_block = latest,
-                stored_proposals = state.proposals.len(),
-                "state cursor is ahead of chain tip; assuming chain reset and resetting local state"
-            );
-            state = State::default();
+            if !self.rewind_cursor(&mut state, latest).await? {
+                return Ok(ScanCycle::default());
+            }
+        } else {
+            self.cursor_anomalies.store(0, Ordering::Relaxed);
         }
 
         let (start, resume_source) = if state.last_scanned_block == 0 {
@@ -213,49 +1028,383 @@ impl Agent {
                 "state.last_scanned_block+1",
             )
         };
+        let confirmed = confirmed_tip(latest, self.config.network.confirmations);
+        let retried_pending = self.retry_pending_reviews(&mut state, shutdown).await?;
+        let retried_votes = self.retry_unsubmitted_votes(&mut state).await;
+        let alerted = self.alert_near_deadlines(&mut state, latest).await;
+        let lifecycle_changed = self.track_lifecycles(&mut state).await;
 
         tracing::info!(
             start_block = start,
             latest_block = latest,
+            confirmed_block = confirmed,
+            confirmations = self.config.network.confirmations,
             resume_source,
             "checking chain for new blocks"
         );
 
-        if latest < start {
+        if confirmed < start {
             tracing::info!(
                 start_block = start,
                 latest_block = latest,
-                "no new blocks to scan"
+                confirmed_block = confirmed,
+                "no new confirmed blocks to scan"
             );
+            let exported = self.flush_exports(&mut state, false).await.is_some();
+            if retried_pending || retried_votes || alerted || lifecycle_changed || exported {
+                self.storage.save(&state)?;
+            }
             observability::record_poll_success();
             observability::observe_stage_latency("scan", scan_started);
-            return Ok(());
+            return Ok(ScanCycle::default());
         }
 
-        self.process_range(&mut state, start, latest, shutdown)
-            .await?;
-        state.last_scanned_block = latest;
+        let threshold = self.config.catch_up.threshold_blocks;
+        let cycle =
+            if state.catch_up.is_some() || (threshold > 0 && confirmed - start + 1 > threshold) {
+                self.catch_up(&mut state, start, confirmed, latest, shutdown)
+                    .await?
+            } else {
+                let cycle = self
+                    .process_range(&mut state, start, confirmed, shutdown, None)
+                    .await?;
+                state.last_scanned_block = confirmed;
+                cycle
+            };
+        self.flush_exports(&mut state, false).await;
+        self.prune_if_due(&mut state);
+        if self.config.observability.rpc_stats_snapshot {
+            state.rpc_stats = Some(RpcStats::global().snapshot());
+        }
         self.storage.save(&state)?;
         observability::record_poll_success();
         observability::observe_stage_latency("scan", scan_started);
 
-        Ok(())
+        Ok(cycle)
     }
 
-    async fn process_range(
+    // Runs `storage prune` from the scan loop at most once per PRUNE_INTERVAL. A failure is
+    // logged and retried on the next cycle; the records stay in state until a prune succeeds.
+    fn prune_if_due(&self, state: &mut State) {
+        let Some(days) = self.config.storage.retention_days else {
+            return;
+        };
+        let now = Utc::now();
+        if state
+            .last_pruned_at
+            .is_some_and(|at| now - at < PRUNE_INTERVAL)
+        {
+            return;
+        }
+        match self
+            .storage
+            .prune(state, chrono::Duration::days(days as i64), now)
+        {
+            Ok(pruned) => tracing::info!(
+                archived = pruned.len(),
+                retention_days = days,
+                "pruned finalized proposals from state"
+            ),
+            Err(err) => tracing::warn!(
+                error = %format!("{err:#}"),
+                "failed to prune finalized proposals; keeping them in state"
+            ),
+        }
+    }
+
+    // A cursor past the tip means either the chain was reset (devnet restart) or the cursor was
+    // advanced by a bogus tip from a misbehaving provider. Either way stored proposals stay: the
+    // cursor only moves back once the anomaly has lasted `network.cursor_anomaly_cycles` scans
+    // and, when configured, a second RPC confirms the tip. Returns whether this cycle may scan.
+    async fn rewind_cursor(&self, state: &mut State, latest: u64) -> Result<bool> {
+        let previous_block = state.last_scanned_block;
+        let required = self.config.network.cursor_anomaly_cycles;
+        let cycles = self.cursor_anomalies.fetch_add(1, Ordering::Relaxed) + 1;
+        if cycles < required {
+            tracing::warn!(
+                last_scanned_block = previous_block,
+                latest_block = latest,
+                cycles,
+                required,
+                "state cursor is ahead of chain tip; skipping this scan until the anomaly persists"
+            );
+            return Ok(false);
+        }
+
+        let check_block = match &self.cursor_check {
+            Some(check) => Some(check.latest_block().await.context(
+                "failed to cross-check the chain tip with network.cursor_check_rpc_url",
+            )?),
+            None => None,
+        };
+        if let Some(check_block) = check_block
+            && check_block >= previous_block
+        {
+            tracing::error!(
+                last_scanned_block = previous_block,
+                latest_block = latest,
+                check_block,
+                cycles,
+                "state cursor is ahead of the rpc tip but not of the cross-check rpc; keeping the cursor and skipping this scan"
+            );
+            return Ok(false);
+        }
+
+        // Everything up to the newest stored proposal the chain still has was already scanned;
+        // with none, start over from network.from_block. Stored proposals are not reviewed twice.
+        let rewound_to = state
+            .proposals
+            .values()
+            .map(|processed| processed.proposal.block_number)
+            .filter(|block| *block <= latest)
+            .max()
+            .unwrap_or(0);
+        let rewind = CursorRewind {
+            previous_block,
+            rewound_to,
+            latest_block: latest,
+            check_block,
+            anomaly_cycles: cycles,
+            stored_proposals: state.proposals.len(),
+            rewound_at: Utc::now(),
+        };
+        tracing::error!(
+            last_scanned_block = previous_block,
+            rewound_to,
+            latest_block = latest,
+            check_block,
+            cycles,
+            stored_proposals = rewind.stored_proposals,
+            "state cursor was ahead of chain tip; rewound it and kept stored proposals"
+        );
+        state.last_scanned_block = rewound_to;
+        state.cursor_rewinds.push(rewind.clone());
+        self.storage.save(state)?;
+        self.notifier.cursor_rewound(&rewind).await;
+        self.cursor_anomalies.store(0, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    async fn process_range(
         &self,
         state: &mut State,
         from_block: u64,
         to_block: u64,
         shutdown: Option<&watch::Receiver<bool>>,
-    ) -> Result<()> {
+        catch_up: Option<&mut CatchUp>,
+    ) -> Result<ScanCycle> {
+        let (proposals, skipped_logs) = self.fetch_range(from_block, to_block).await?;
+        let discovered = proposals.len();
+        let skipped_closed = self
+            .process_proposals(
+                state,
+                proposals,
+                from_block..=to_block,
+                shutdown,
+                catch_up,
+                false,
+            )
+            .await?;
+        Ok(ScanCycle {
+            discovered,
+            skipped_logs,
+            skipped_closed,
+        })
+    }
+
+    // Scans a backlog left by downtime chunk by chunk, saving the cursor and the proposals found so
+    // far after each chunk, so a restart or a failed chunk resumes the same pass and its digest
+    // still covers them. Progress is announced at each quarter.
+    async fn catch_up(
+        &self,
+        state: &mut State,
+        start: u64,
+        end: u64,
+        latest_block: u64,
+        shutdown: Option<&watch::Receiver<bool>>,
+    ) -> Result<ScanCycle> {
+        let total = end - start + 1;
+        let chunk = self.config.catch_up.chunk_blocks.max(1);
+        tracing::info!(
+            from_block = start,
+            to_block = end,
+            gap_blocks = total,
+            chunk_blocks = chunk,
+            threshold_blocks = self.config.catch_up.threshold_blocks,
+            "block gap exceeds catch-up threshold; entering catch-up mode"
+        );
+        observability::record_catch_up_progress(Some(0.0));
+
+        let progress = state.catch_up.take().unwrap_or(CatchUpProgress {
+            start_block: start,
+            proposal_ids: Vec::new(),
+        });
+        if !progress.proposal_ids.is_empty() {
+            tracing::info!(
+                started_at_block = progress.start_block,
+                found = progress.proposal_ids.len(),
+                "resuming an unfinished catch-up pass"
+            );
+        }
+        let digest_start = progress.start_block;
+        let mut catch_up = CatchUp {
+            latest_block,
+            skip_closed_votes: self.config.catch_up.skip_closed_votes,
+            processed: progress
+                .proposal_ids
+                .iter()
+                .filter_map(|id| state.proposals.get(id).cloned())
+                .collect(),
+        };
+        let mut cycle = ScanCycle::default();
+        let mut reported_quarter = 0;
+        let mut from = start;
+        while from <= end && !shutdown_requested(shutdown) {
+            let to = from.saturating_add(chunk - 1).min(end);
+            let chunk_cycle = match self
+                .process_range(state, from, to, shutdown, Some(&mut catch_up))
+                .await
+            {
+                Ok(chunk_cycle) => chunk_cycle,
+                Err(err) => {
+                    observability::record_catch_up_progress(None);
+                    // Proposals stored before the failure stay in state, so they are not
+                    // processed again and would be missing from the digest unless recorded here.
+                    state.catch_up = Some(catch_up.progress(digest_start));
+                    if let Err(save_err) = self.storage.save(state) {
+                        tracing::warn!(
+                            error = %format!("{save_err:#}"),
+                            "failed to save catch-up progress after a failed chunk"
+                        );
+                    }
+                    return Err(err);
+                }
+            };
+            cycle.discovered += chunk_cycle.discovered;
+            cycle.skipped_logs += chunk_cycle.skipped_logs;
+            cycle.skipped_closed += chunk_cycle.skipped_closed;
+            state.last_scanned_block = to;
+            state.catch_up = Some(catch_up.progress(digest_start));
+            self.storage.save(state)?;
+
+            let scanned = to - start + 1;
+            let percent = scanned * 100 / total;
+            observability::record_catch_up_progress(Some(scanned as f64 / total as f64));
+            tracing::info!(
+                percent,
+                to_block = to,
+                end_block = end,
+                discovered = cycle.discovered,
+                "catch-up progress"
+            );
+            if to < end && percent / 25 > reported_quarter {
+                reported_quarter = percent / 25;
+                self.notifier
+                    .catch_up_progress(percent, cycle.discovered)
+                    .await;
+            }
+            from = to + 1;
+        }
+
+        observability::record_catch_up_progress(None);
+        state.catch_up = None;
+        tracing::info!(
+            from_block = digest_start,
+            to_block = state.last_scanned_block,
+            discovered = cycle.discovered,
+            missed = catch_up
+                .processed
+                .iter()
+                .filter(|processed| processed.vote_missed)
+                .count(),
+            completed = state.last_scanned_block >= end,
+            "leaving catch-up mode; returning to normal scanning"
+        );
+        self.notifier
+            .catch_up_digest(&catch_up.processed, digest_start, state.last_scanned_block)
+            .await;
+        Ok(cycle)
+    }
+
+    async fn retry_pending_reviews(
+        &self,
+        state: &mut State,
+        shutdown: Option<&watch::Receiver<bool>>,
+    ) -> Result<bool> {
+        let pending = state
+            .pending_reviews
+            .values()
+            .map(|pending| pending.proposal.clone())
+            .collect::<Vec<_>>();
+        let (Some(from_block), Some(to_block)) = (
+            pending.iter().map(|proposal| proposal.block_number).min(),
+            pending.iter().map(|proposal| proposal.block_number).max(),
+        ) else {
+            return Ok(false);
+        };
+
+        tracing::info!(
+            count = pending.len(),
+            "re-reviewing proposals deferred by transient failures"
+        );
+        self.process_proposals(state, pending, from_block..=to_block, shutdown, None, false)
+            .await?;
+        Ok(true)
+    }
+
+    // Undecodable logs are skipped and saved under data_dir/failed_logs. The scan fails, leaving
+    // the cursor in place, when every log in the range fails, since that points at an ABI or
+    // address mismatch rather than one bad event, or when the logs could not be saved, so they
+    // are never lost.
+    async fn fetch_range(&self, from_block: u64, to_block: u64) -> Result<(Vec<Proposal>, usize)> {
         let fetch_started = observability::now();
-        let proposals = self.chain.fetch_proposals(from_block, to_block).await?;
+        let scan = self.chain.scan_proposals(from_block, to_block).await?;
         observability::observe_stage_latency("fetch_proposals", fetch_started);
-        observability::incr_proposals_discovered(proposals.len());
+
+        let skipped_logs = scan.failed_logs.len();
+        if skipped_logs > 0 {
+            let path = self
+                .storage
+                .quarantine_logs(from_block, to_block, &scan.failed_logs)
+                .with_context(|| {
+                    format!(
+                        "{skipped_logs} undecodable proposal logs in blocks [{from_block}, {to_block}] could not be quarantined"
+                    )
+                })?;
+            if scan.proposals.is_empty() {
+                bail!(
+                    "all {skipped_logs} proposal logs in blocks [{from_block}, {to_block}] failed to decode; check the governor address and event ABI (raw logs in {})",
+                    path.display()
+                );
+            }
+            tracing::warn!(
+                skipped_logs,
+                from_block,
+                to_block,
+                path = %path.display(),
+                "skipped undecodable proposal logs; raw logs quarantined"
+            );
+        }
+
+        observability::incr_proposals_discovered(scan.proposals.len());
+        Ok((scan.proposals, skipped_logs))
+    }
+
+    // Outside catch-up, proposals whose voting already closed are stored as skipped without a
+    // review unless `review_closed` is set. Returns how many were skipped.
+    async fn process_proposals(
+        &self,
+        state: &mut State,
+        proposals: Vec<Proposal>,
+        blocks: RangeInclusive<u64>,
+        shutdown: Option<&watch::Receiver<bool>>,
+        mut catch_up: Option<&mut CatchUp>,
+        review_closed: bool,
+    ) -> Result<usize> {
+        let (from_block, to_block) = blocks.into_inner();
         if proposals.is_empty() {
             tracing::info!(from_block, to_block, "no proposals found in range");
-            return Ok(());
+            return Ok(0);
         }
 
         tracing::info!(
@@ -265,35 +1414,28 @@ impl Agent {
             "processing proposals"
         );
 
-        let vote_executor: Box<dyn VoteExecutor> = if self.config.auto_vote {
-            if let Some(reason) = signing_readiness_reason(&self.config.signer) {
-                tracing::warn!(
-                    reason = %reason,
-                    "signer is not fully configured; continuing in dry-run mode (cannot vote)"
-                );
-                Box::new(DryRunVoteExecutor)
-            } else {
-                match KeystoreVoteExecutor::from_config(&self.config.network, &self.config.signer)
-                    .await
-                {
-                    Ok(executor) => Box::new(executor),
-                    Err(err) => {
-                        tracing::warn!(
-                            error = %err,
-                            "failed to initialize signer executor; continuing in dry-run mode (cannot vote)"
-                        );
-                        Box::new(DryRunVoteExecutor)
-                    }
-                }
-            }
-        } else {
-            Box::new(DryRunVoteExecutor)
-        };
+        let vote_accounts = self.vote_accounts().await;
+        let canary_hold = self.canary_holds_votes(state);
         let (approve_threshold, reject_threshold) = self.config.decision.resolved_thresholds();
+        let mut processed_count = 0usize;
+        let mut suppressions_applied = 0usize;
+        let mut skipped_closed = 0usize;
+        let latest_block = match catch_up.as_deref() {
+            Some(catch_up) => catch_up.latest_block,
+            None => self.chain.latest_block().await.unwrap_or(to_block),
+        };
 
-        for proposal in proposals {
-            let proposal_span =
-                tracing::info_span!("proposal_lifecycle", proposal_id = %proposal.proposal_id);
+        for mut proposal in proposals {
+            let proposal_span = tracing::info_span!(
+                "proposal_lifecycle",
+                proposal_id = %proposal.proposal_id,
+                proposer = %observability::log_address(&proposal.proposer)
+            );
+            tracing::debug!(
+                parent: &proposal_span,
+                proposer = %proposal.proposer,
+                "proposal proposer"
+            );
 
             if shutdown_requested(shutdown) {
                 tracing::info!(
@@ -303,22 +1445,50 @@ impl Agent {
             }
 
             let key = proposal.proposal_id.clone();
-            if state.proposals.contains_key(&key) {
+            if state.is_known(&key)
+                || (!review_closed && state.skipped_proposals.contains_key(&key))
+            {
+                continue;
+            }
+
+            // Catch-up reviews closed proposals on purpose, to report the votes it missed.
+            let closed = catch_up.is_none() && self.voting_closed(&proposal, latest_block).await;
+            if closed && !review_closed {
+                tracing::info!(
+                    parent: &proposal_span,
+                    vote_end = proposal.vote_end,
+                    latest_block,
+                    "voting already closed; storing proposal as skipped without review"
+                );
+                state.pending_reviews.remove(&key);
+                state.skipped_proposals.insert(
+                    key,
+                    SkippedProposal {
+                        proposal,
+                        skipped: SkipReason::VotingClosed,
+                        skipped_at: Utc::now(),
+                    },
+                );
+                skipped_closed += 1;
                 continue;
             }
 
+            self.attach_creation_context(&mut proposal)
+                .instrument(proposal_span.clone())
+                .await;
+            self.pin_bundles(&proposal)
+                .instrument(proposal_span.clone())
+                .await;
+
             let review_started = observability::now();
-            let review = match review_proposal(
-                &proposal,
-                &self.config.review,
-                &self.config.decision,
-                &self.bundle_fetcher,
-                &self.llm,
-                self.prompt_override.as_deref(),
-            )
-            .instrument(proposal_span.clone())
-            .await
-            {
+            let review = self
+                .review_keeping_operator_findings(&proposal, state)
+                .instrument(proposal_span.clone())
+                .await;
+            self.unpin_bundles(&proposal)
+                .instrument(proposal_span.clone())
+                .await;
+            let review = match review {
                 Ok(review) => review,
                 Err(err) => {
                     observability::observe_stage_latency("review", review_started);
@@ -333,7 +1503,50 @@ impl Agent {
             };
             observability::observe_stage_latency("review", review_started);
 
-            let decision = decide(&self.config.decision, &review);
+            if let Some(error) = &review.transient_failure {
+                let attempts = state
+                    .pending_reviews
+                    .get(&key)
+                    .map_or(0, |pending| pending.attempts)
+                    + 1;
+                if attempts <= self.config.review.max_retry_attempts {
+                    tracing::warn!(
+                        proposal_id = %proposal.proposal_id,
+                        attempts,
+                        max_retry_attempts = self.config.review.max_retry_attempts,
+                        error = %error,
+                        "review hit a transient failure; deferring decision to a later cycle"
+                    );
+                    observability::incr_proposals_failed("review_deferred");
+                    state.pending_reviews.insert(
+                        key,
+                        PendingReview {
+                            proposal,
+                            attempts,
+                            last_error: error.clone(),
+                            last_attempt_at: Utc::now(),
+                        },
+                    );
+                    continue;
+                }
+                tracing::warn!(
+                    proposal_id = %proposal.proposal_id,
+                    attempts,
+                    error = %error,
+                    "transient review failures exhausted retry budget; finalizing decision"
+                );
+            }
+            state.pending_reviews.remove(&key);
+            suppressions_applied += review.suppressions.len();
+
+            let mut decision = self.decide(&review);
+            let peer_check = self
+                .compare_with_peers(&mut decision)
+                .instrument(proposal_span.clone())
+                .await;
+            let peer_hold = peer_check.as_ref().is_some_and(|check| check.held);
+            state.reputation.record(&proposal, &decision);
+            state.bundles.record(&proposal, &review);
             let deterministic_score = review.deterministic_score.unwrap_or(review.score);
             let deterministic_weight = review.deterministic_weight.unwrap_or(0.70);
             let llm_weight = review.llm_weight.unwrap_or(0.30);
@@ -359,115 +1572,2792 @@ impl Agent {
                 blended_score = %format_args!("{:.2}", review.score),
                 reject_threshold = %format_args!("{:.2}", reject_threshold),
                 approve_threshold = %format_args!("{:.2}", approve_threshold),
+                config_hash = %self.config_hash,
                 "proposal decision computed"
             );
-            let vote_started = observability::now();
-            let vote_execution = match vote_executor
-                .submit_vote(&proposal, &decision)
+            let voting_paused = self.check_voting_paused().await;
+            let vote_missed = closed
+                || match catch_up.as_deref() {
+                    Some(catch_up) if catch_up.skip_closed_votes => {
+                        self.voting_closed(&proposal, catch_up.latest_block).await
+                    }
+                    _ => false,
+                };
+            let vote_executions = if vote_missed {
+                tracing::info!(
+                    parent: &proposal_span,
+                    vote_end = proposal.vote_end,
+                    "voting already closed; marking vote as missed"
+                );
+                Vec::new()
+            } else {
+                submit_votes(
+                    &vote_accounts,
+                    (voting_paused || canary_hold || peer_hold).then(|| self.dry_run_executor()),
+                    &proposal,
+                    &decision,
+                )
                 .instrument(proposal_span.clone())
                 .await
-            {
-                Ok(vote) => {
-                    observability::record_vote_submit(true);
-                    Some(vote)
-                }
-                Err(err) => {
-                    observability::record_vote_submit(false);
-                    observability::incr_proposals_failed("vote");
-                    tracing::warn!(proposal_id = proposal.proposal_id, error = %err, "vote submission failed");
-                    None
-                }
             };
-            observability::observe_stage_latency("vote_submit", vote_started);
 
             let processed = ProcessedProposal {
                 proposal,
                 review,
                 decision,
-                vote_execution,
+                vote_executions,
+                decision_history: Vec::new(),
+                voting_paused,
+                lifecycle: None,
+                vote_missed,
+                peer_check,
             };
 
-            self.notifier
-                .notify_all(&format!(
-                    "gov-agent processed proposal {} with vote {:?}",
-                    processed.proposal.proposal_id, processed.decision.vote
-                ))
-                .instrument(proposal_span.clone())
-                .await;
+            match catch_up.as_deref_mut() {
+                Some(catch_up) => catch_up.processed.push(processed.clone()),
+                None => {
+                    let clock = self.clock_at(to_block).await;
+                    self.notifier
+                        .review_completed(&processed, clock)
+                        .instrument(proposal_span.clone())
+                        .await;
+                }
+            }
 
+            if self.export.is_some() {
+                state.export_outbox.enqueue(&key, &self.config_hash);
+            }
+            state.skipped_proposals.remove(&key);
             state.proposals.insert(key, processed);
+            processed_count += 1;
             observability::incr_proposals_processed();
             observability::record_last_processed_proposal_timestamp();
         }
 
-        Ok(())
+        tracing::info!(
+            processed = processed_count,
+            suppressions_applied,
+            skipped_closed,
+            from_block,
+            to_block,
+            "proposal range processed"
+        );
+
+        Ok(skipped_closed)
     }
 
-    fn redacted_config_json(&self) -> String {
-        let mut config = self.config.clone();
-        if config.signer.keystore_password.is_some() {
-            config.signer.keystore_password = Some("[REDACTED]".to_string());
+    async fn reevaluate_unvoted_decisions(&self) -> Result<()> {
+        self.verify_chain_id().await?;
+        let mut state = self.storage.load()?;
+        // Once any account has voted, the decision stands; the accounts still missing a vote
+        // are retried with it by `retry_unsubmitted_votes`.
+        let candidates = state
+            .proposals
+            .iter()
+            .filter(|(_, processed)| !processed.vote_executions.iter().any(|vote| vote.submitted))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Ok(());
         }
 
-        serde_json::to_string_pretty(&config)
-            .unwrap_or_else(|_| "<failed to serialize config>".to_string())
-    }
-}
+        let vote_accounts = self.vote_accounts().await;
+        let canary_hold = self.canary_holds_votes(&state);
+        let mut changed = 0usize;
+        for key in candidates {
+            let Some(processed) = state.proposals.get_mut(&key) else {
+                continue;
+            };
+            // A peer disagreement is settled by a human, not by a config change.
+            if processed
+                .peer_check
+                .as_ref()
+                .is_some_and(|check| check.held)
+            {
+                continue;
+            }
 
-fn install_shutdown_signal_listener() -> watch::Receiver<bool> {
-    let (tx, rx) = watch::channel(false);
+            match self.chain.is_proposal_active(&processed.proposal).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    tracing::warn!(
+                        proposal_id = %key,
+                        error = %err,
+                        "failed to read proposal state during decision reevaluation; skipping"
+                    );
+                    continue;
+                }
+            }
 
-    tokio::spawn(async move {
-        wait_for_shutdown_signal().await;
-        let _ = tx.send(true);
-    });
+            let decision = self.decide(&processed.review);
+            if decision.vote == processed.decision.vote {
+                continue;
+            }
 
-    rx
-}
+            tracing::info!(
+                proposal_id = %key,
+                previous_vote = ?processed.decision.vote,
+                vote = ?decision.vote,
+                reasons = ?decision.reasons,
+                "decision changed after reevaluating stored review with current config"
+            );
+            changed += 1;
 
-fn shutdown_requested(shutdown: Option<&watch::Receiver<bool>>) -> bool {
-    shutdown.is_some_and(|signal| *signal.borrow())
-}
+            let previous = std::mem::replace(&mut processed.decision, decision);
+            processed.decision_history.push(previous);
 
-async fn wait_for_shutdown_signal() {
-    #[cfg(unix)]
-    {
-        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
-            Ok(mut sigterm) => {
-                tokio::select! {
-                    _ = tokio::signal::ctrl_c() => {}
-                    _ = sigterm.recv() => {}
+            if processed.decision.vote != VoteChoice::Abstain {
+                processed.peer_check = self.compare_with_peers(&mut processed.decision).await;
+                let peer_hold = processed
+                    .peer_check
+                    .as_ref()
+                    .is_some_and(|check| check.held);
+                processed.voting_paused = self.check_voting_paused().await;
+                processed.vote_executions = submit_votes(
+                    &vote_accounts,
+                    (processed.voting_paused || canary_hold || peer_hold)
+                        .then(|| self.dry_run_executor()),
+                    &processed.proposal,
+                    &processed.decision,
+                )
+                .await;
+                self.notifier.proposal_reevaluated(processed).await;
+            }
+        }
+
+        tracing::info!(changed, "startup decision reevaluation complete");
+        if changed > 0 {
+            self.storage.save(&state)?;
+        }
+        Ok(())
+    }
+
+    // Accounts with no submitted vote on a stored decision: a failed submission, or a vote
+    // routed to dry-run while voting was paused or the canary held votes. They are retried
+    // with the stored decision while the proposal is active and the account can vote, and
+    // each retry replaces that account's unsubmitted records. Peer holds are left to a human.
+    async fn retry_unsubmitted_votes(&self, state: &mut State) -> bool {
+        let labels = match &self.vote_executor_override {
+            Some(accounts) => accounts
+                .iter()
+                .map(|account| account.label.clone())
+                .collect::<Vec<_>>(),
+            None => self
+                .config
+                .signer
+                .resolved_accounts()
+                .into_iter()
+                .map(|account| account.label)
+                .collect(),
+        };
+        let candidates = state
+            .proposals
+            .iter()
+            .filter(|(_, processed)| {
+                !processed.vote_missed
+                    && !processed
+                        .peer_check
+                        .as_ref()
+                        .is_some_and(|check| check.held)
+                    && labels.iter().any(|label| !account_voted(processed, label))
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if candidates.is_empty()
+            || self.check_voting_paused().await
+            || self.canary_holds_votes(state)
+        {
+            return false;
+        }
+        let accounts = self.vote_accounts().await;
+        if !accounts.iter().any(|account| account.executor.is_live()) {
+            return false;
+        }
+
+        let mut retried = false;
+        for key in candidates {
+            let Some(processed) = state.proposals.get_mut(&key) else {
+                continue;
+            };
+            match self.chain.is_proposal_active(&processed.proposal).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    tracing::warn!(
+                        proposal_id = %key,
+                        error = %err,
+                        "failed to read proposal state before retrying votes; skipping"
+                    );
+                    continue;
                 }
             }
-            Err(err) => {
-                tracing::warn!(error = %err, "failed to register SIGTERM handler; falling back to ctrl_c only");
-                let _ = tokio::signal::ctrl_c().await;
+            for account in &accounts {
+                if !account.executor.is_live() || account_voted(processed, &account.label) {
+                    continue;
+                }
+                tracing::info!(
+                    proposal_id = %key,
+                    account = %account.label,
+                    vote = ?processed.decision.vote,
+                    "retrying an unsubmitted vote"
+                );
+                let execution = submit_vote(
+                    account.executor.as_ref(),
+                    &account.label,
+                    &processed.proposal,
+                    &processed.decision,
+                )
+                .await;
+                processed.vote_executions.retain(|vote| {
+                    vote.submitted || vote.account.as_deref() != Some(account.label.as_str())
+                });
+                // A vote held by the kill switch is no longer held once it went out.
+                processed.voting_paused &= !execution.submitted;
+                processed.vote_executions.push(execution);
+                retried = true;
             }
         }
+        retried
     }
 
-    #[cfg(not(unix))]
-    {
-        let _ = tokio::signal::ctrl_c().await;
+    fn decide(&self, review: &ReviewResult) -> Decision {
+        let mut decision = decide(&self.config.decision, review);
+        decision.config_hash = Some(self.config_hash.clone());
+        decision
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use tokio::sync::watch;
+    // None without `peers.urls`. A quorum of disagreeing peers flags the decision for a human;
+    // peers that fail or have not decided yet are recorded but never block the vote.
+    async fn compare_with_peers(&self, decision: &mut Decision) -> Option<PeerComparison> {
+        let peers = self.peers.as_ref()?;
+        let comparison = peers.compare(decision).await;
+        for review in &comparison.reviews {
+            if let Some(error) = &review.error {
+                tracing::info!(peer = %review.peer, error = %error, "peer decision unavailable");
+            }
+        }
+        if comparison.held {
+            let summary = disagreement_summary(&comparison, decision.vote);
+            tracing::warn!(
+                disagreeing = comparison.disagreeing,
+                quorum = comparison.quorum,
+                "{summary}"
+            );
+            decision.requires_human_override = true;
+            decision.reasons.push(summary);
+        }
+        Some(comparison)
+    }
 
-    use super::shutdown_requested;
+    async fn vote_accounts(&self) -> Vec<VoteAccount> {
+        if let Some(accounts) = &self.vote_executor_override {
+            return accounts
+                .iter()
+                .map(|account| VoteAccount {
+                    label: account.label.clone(),
+                    executor: account.executor.clone(),
+                })
+                .collect();
+        }
+        let mut accounts = Vec::new();
+        for account in self.config.signer.resolved_accounts() {
+            accounts.push(VoteAccount {
+                executor: Arc::from(self.build_vote_executor(&account).await),
+                label: account.label,
+            });
+        }
+        accounts
+    }
 
-    #[test]
-    fn shutdown_flag_defaults_to_false() {
-        let (_tx, rx) = watch::channel(false);
-        assert!(!shutdown_requested(Some(&rx)));
+    fn canary_holds_votes(&self, state: &State) -> bool {
+        let hold = self.config.decision.canary_mode && state.canary.holds_votes();
+        if hold {
+            tracing::info!(
+                streak = state.canary.streak,
+                required = self.config.decision.canary_required_agreements,
+                "canary mode: votes are routed to dry-run until enough decisions are confirmed"
+            );
+        }
+        hold
     }
 
-    #[test]
-    fn shutdown_flag_reflects_signal_state() {
-        let (tx, rx) = watch::channel(false);
-        tx.send(true).expect("send shutdown signal");
-        assert!(shutdown_requested(Some(&rx)));
+    fn dry_run_executor(&self) -> DryRunVoteExecutor {
+        DryRunVoteExecutor {
+            support_mapping: self.config.signer.support_mapping,
+        }
+    }
+
+    // An account that cannot sign falls back to dry-run on its own; the others still vote.
+    async fn build_vote_executor(&self, account: &SignerAccount) -> Box<dyn VoteExecutor> {
+        if self.config.mode == AgentMode::Observer {
+            Box::new(self.dry_run_executor())
+        } else if self.config.auto_vote {
+            if let Some(reason) = account_readiness_reason(account) {
+                tracing::warn!(
+                    account = %account.label,
+                    reason = %reason,
+                    "signer is not fully configured; continuing in dry-run mode (cannot vote)"
+                );
+                Box::new(self.dry_run_executor())
+            } else {
+                let clock_mode = self.chain.clock_mode().await;
+                let executor = match &self.config.signer.forwarder {
+                    Some(forwarder) => ForwarderVoteExecutor::from_config(
+                        &self.config.network,
+                        &self.config.signer,
+                        forwarder,
+                        account,
+                        &self.config.http,
+                        self.storage.vote_intents(),
+                        clock_mode,
+                    )
+                    .await
+                    .map(|executor| Box::new(executor) as Box<dyn VoteExecutor>),
+                    None => KeystoreVoteExecutor::from_config(
+                        &self.config.network,
+                        &self.config.signer,
+                        account,
+                        &self.config.http,
+                        self.storage.vote_intents(),
+                        clock_mode,
+                    )
+                    .await
+                    .map(|executor| Box::new(executor) as Box<dyn VoteExecutor>),
+                };
+                match executor {
+                    Ok(executor) => executor,
+                    Err(err) => {
+                        tracing::warn!(
+                            account = %account.label,
+                            error = %err,
+                            "failed to initialize signer executor; continuing in dry-run mode (cannot vote)"
+                        );
+                        Box::new(self.dry_run_executor())
+                    }
+                }
+            }
+        } else {
+            Box::new(self.dry_run_executor())
+        }
+    }
+
+    // Each proposal is alerted once; it can alert again only after leaving the waiting set.
+    // `deadline_alert_blocks` is converted to time for governors on a timestamp clock.
+    async fn alert_near_deadlines(&self, state: &mut State, current_block: u64) -> bool {
+        let threshold = self.config.notifications.deadline_alert_blocks;
+        if threshold == 0 {
+            return false;
+        }
+        let clock = self.clock_at(current_block).await;
+        let waiting = awaiting_human(state)
+            .into_iter()
+            .filter(|(proposal, _)| proposal.vote_end > clock.now)
+            .map(|(proposal, reason)| (proposal.clone(), reason))
+            .collect::<Vec<_>>();
+        let before = state.deadline_alerts.clone();
+        state.deadline_alerts.retain(|id| {
+            waiting
+                .iter()
+                .any(|(proposal, _)| &proposal.proposal_id == id)
+        });
+
+        for (proposal, reason) in &waiting {
+            let secs_left = clock.secs_left(proposal.vote_end);
+            if secs_left > threshold.saturating_mul(ASSUMED_BLOCK_SECS)
+                || state.deadline_alerts.contains(&proposal.proposal_id)
+            {
+                continue;
+            }
+            tracing::warn!(
+                proposal_id = proposal.proposal_id,
+                reason,
+                secs_left,
+                vote_end = proposal.vote_end,
+                clock = clock.mode.as_str(),
+                "voting deadline approaching for proposal waiting on a human"
+            );
+            self.notifier
+                .deadline_approaching(proposal, reason, clock)
+                .await;
+            state.deadline_alerts.insert(proposal.proposal_id.clone());
+        }
+        state.deadline_alerts != before
+    }
+
+    // Polls governor state for stored proposals until they reach a final state. The executed
+    // notification needs an earlier non-executed reading, so proposals from before lifecycle
+    // tracking existed do not alert on their first check.
+    async fn track_lifecycles(&self, state: &mut State) -> bool {
+        let mut changed = false;
+        for processed in state.proposals.values_mut() {
+            let previous = processed
+                .lifecycle
+                .as_ref()
+                .map(|lifecycle| lifecycle.state);
+            if previous.is_some_and(ProposalState::is_final) {
+                continue;
+            }
+            let proposal_id = &processed.proposal.proposal_id;
+            let lifecycle = match self.chain.proposal_lifecycle(proposal_id).await {
+                Ok(Some(lifecycle)) => lifecycle,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!(
+                        proposal_id,
+                        error = %err,
+                        "failed to read proposal lifecycle state"
+                    );
+                    continue;
+                }
+            };
+            if previous == Some(lifecycle.state)
+                && processed
+                    .lifecycle
+                    .as_ref()
+                    .is_some_and(|current| current.eta == lifecycle.eta)
+            {
+                continue;
+            }
+            tracing::info!(
+                proposal_id,
+                state = lifecycle.state.as_str(),
+                eta = ?lifecycle.eta,
+                "proposal lifecycle state changed"
+            );
+            let executed = lifecycle.state == ProposalState::Executed;
+            processed.lifecycle = Some(lifecycle);
+            changed = true;
+            let voted_for = processed.decision.vote == VoteChoice::For
+                && processed
+                    .vote_executions
+                    .iter()
+                    .any(|execution| execution.submitted);
+            if executed && previous.is_some() && voted_for {
+                self.notifier.proposal_executed(processed).await;
+            }
+        }
+        changed
+    }
+
+    // Governor state when the chain source can read it, otherwise the stored vote_end. A failed
+    // read counts as open so the vote is still attempted.
+    async fn voting_closed(&self, proposal: &Proposal, latest_block: u64) -> bool {
+        match self.chain.proposal_lifecycle(&proposal.proposal_id).await {
+            Ok(Some(lifecycle)) => !matches!(
+                lifecycle.state,
+                ProposalState::Pending | ProposalState::Active
+            ),
+            Ok(None) => proposal.vote_end <= self.clock_at(latest_block).await.now,
+            Err(err) => {
+                tracing::warn!(
+                    proposal_id = proposal.proposal_id,
+                    error = %err,
+                    "failed to read proposal state; treating voting as open"
+                );
+                false
+            }
+        }
+    }
+
+    // Now on the governor's clock. A block-number clock reuses `latest_block`; a timestamp one
+    // reads the latest block's time, or the wall clock when the source cannot.
+    async fn clock_at(&self, latest_block: u64) -> ChainClock {
+        match self.chain.clock_mode().await {
+            ClockMode::BlockNumber => ChainClock::blocks(latest_block),
+            ClockMode::Timestamp => {
+                let now = match self.chain.latest_block_timestamp().await {
+                    Ok(now) => now,
+                    Err(err) => {
+                        tracing::warn!(
+                            error = %err,
+                            "failed to read the latest block time; using the local clock"
+                        );
+                        u64::try_from(Utc::now().timestamp()).unwrap_or_default()
+                    }
+                };
+                ChainClock {
+                    mode: ClockMode::Timestamp,
+                    now,
+                }
+            }
+        }
+    }
+
+    pub async fn flush_outbox(&self) -> Result<()> {
+        if self.export.is_none() {
+            bail!("review.export_webhook is not configured");
+        }
+        let mut state = self.storage.load()?;
+        let report = self
+            .flush_exports(&mut state, true)
+            .await
+            .unwrap_or_default();
+        self.storage.save(&state)?;
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "delivered": report.delivered,
+                "failed": report.failed,
+                "deferred": report.deferred,
+                "pending": state.export_outbox.pending,
+            }))?
+        );
+        Ok(())
+    }
+
+    // Sends the queued review exports that are due, with their LLM audits attached. None when
+    // nothing was queued, so the caller can skip saving state. `outbox flush` ignores the backoff.
+    async fn flush_exports(&self, state: &mut State, ignore_backoff: bool) -> Option<FlushReport> {
+        let export = self.export.as_ref()?;
+        if state.export_outbox.pending.is_empty() {
+            return None;
+        }
+        let proposals = &state.proposals;
+        let report = export
+            .flush(&mut state.export_outbox, ignore_backoff, |proposal_id| {
+                let mut processed = proposals
+                    .get(proposal_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("proposal {proposal_id} is no longer in state"))?;
+                processed.review.llm_audit = self.storage.load_audit(&processed.review);
+                Ok(processed)
+            })
+            .await;
+        tracing::info!(
+            delivered = report.delivered,
+            failed = report.failed,
+            deferred = report.deferred,
+            pending = report.pending,
+            "flushed review export outbox"
+        );
+        Some(report)
+    }
+
+    // Re-read on every vote so dropping the kill switch file takes effect mid-range.
+    async fn check_voting_paused(&self) -> bool {
+        let paused = self.storage.voting_paused();
+        if self.voting_paused.swap(paused, Ordering::Relaxed) == paused {
+            return paused;
+        }
+        if paused {
+            tracing::warn!(
+                path = %self.storage.pause_path().display(),
+                "VOTING PAUSED: kill switch file present; reviews continue but votes are routed to dry-run"
+            );
+        } else {
+            tracing::warn!(
+                path = %self.storage.pause_path().display(),
+                "VOTING RESUMED: kill switch file removed; votes are submitted again"
+            );
+        }
+        self.notifier.voting_paused(paused).await;
+        paused
+    }
+
+    // Pinned bundles are read through the node's own gateway; a failed or refused pin only
+    // means review reads that bundle through the public gateway instead.
+    async fn pin_bundles(&self, proposal: &Proposal) {
+        if !self.bundle_fetcher.pins_on_discovery() {
+            return;
+        }
+        for root_cid in proposal
+            .actions
+            .iter()
+            .filter_map(DecodedAction::root_cid)
+            .collect::<BTreeSet<_>>()
+        {
+            match self.bundle_fetcher.pin(root_cid).await {
+                Ok(()) => tracing::info!(root_cid, "pinned bundle on the local ipfs node"),
+                Err(err) => tracing::warn!(
+                    root_cid,
+                    error = %format!("{err:#}"),
+                    "failed to pin bundle; review reads it through the gateway"
+                ),
+            }
+        }
+    }
+
+    // Reviewed bundles live in the file cache, so the node does not keep them pinned.
+    async fn unpin_bundles(&self, proposal: &Proposal) {
+        if !self.bundle_fetcher.pins_on_discovery() {
+            return;
+        }
+        for root_cid in proposal
+            .actions
+            .iter()
+            .filter_map(DecodedAction::root_cid)
+            .collect::<BTreeSet<_>>()
+        {
+            if let Err(err) = self.bundle_fetcher.unpin(root_cid).await {
+                tracing::warn!(
+                    root_cid,
+                    error = %format!("{err:#}"),
+                    "failed to unpin bundle"
+                );
+            }
+        }
+    }
+
+    async fn attach_creation_context(&self, proposal: &mut Proposal) {
+        if proposal.creation_context.is_some() {
+            return;
+        }
+        let Some(tx_hash) = proposal.tx_hash.as_deref() else {
+            return;
+        };
+
+        match self.chain.fetch_tx_context(tx_hash).await {
+            Ok(context) => proposal.creation_context = Some(context),
+            Err(err) => {
+                tracing::warn!(
+                    proposal_id = %proposal.proposal_id,
+                    tx_hash,
+                    error = %err,
+                    "failed to fetch proposal creation tx context; continuing without it"
+                );
+            }
+        }
+    }
+
+    fn resolve_proposal_id(&self, input: &str) -> Result<String> {
+        self.storage.load()?.resolve_proposal_id(input)
+    }
+
+    fn ensure_signer_ready(&self) -> Result<()> {
+        if self.config.mode != AgentMode::Voter || !self.config.signer.require_ready {
+            return Ok(());
+        }
+
+        if let Some(reason) = signing_readiness_reason(&self.config.signer) {
+            bail!("signer.require_ready is set but signer is not ready: {reason}");
+        }
+
+        Ok(())
+    }
+
+    fn warn_expired_suppressions(&self) {
+        let today = Utc::now().date_naive();
+        for suppression in self.config.review.expired_suppressions(today) {
+            tracing::warn!(
+                message_contains = %suppression.message_contains,
+                expires = ?suppression.expires,
+                "review suppression has expired and is ignored; remove or renew it"
+            );
+        }
+    }
+
+    // A governor upgraded to a different ProposalCreated layout makes every scan come back empty
+    // without an error. Blocks after the newest stored proposal are probed: if they hold no log
+    // with the configured topic, their logs are matched against the other known layouts, and any
+    // governor log that is not one of its usual events is reported as a possible new layout.
+    async fn proposal_event_mismatch(&self) -> Result<Option<String>> {
+        let state = self.storage.load()?;
+        let network = &self.config.network;
+        let tip = confirmed_tip(self.chain.latest_block().await?, network.confirmations);
+        let after_known = state
+            .proposals
+            .values()
+            .map(|processed| processed.proposal.block_number + 1)
+            .max()
+            .unwrap_or_default();
+        let from = after_known.max(network.from_block).max(
+            state
+                .last_scanned_block
+                .min(tip)
+                .saturating_sub(EVENT_PROBE_BLOCKS),
+        );
+        if from > tip {
+            return Ok(None);
+        }
+        let topics = self.chain.governor_log_topics(from, tip).await?;
+        let configured =
+            ProposalCreatedAbi::from_signature(network.proposal_created_signature.as_deref())?;
+        if topics.contains(&configured.topic0()) {
+            return Ok(None);
+        }
+
+        let hint = "set network.proposal_created_signature to the governor's ProposalCreated event";
+        for (name, signature) in KNOWN_PROPOSAL_CREATED_SIGNATURES {
+            let candidate = ProposalCreatedAbi::parse(signature)?;
+            if candidate.topic0() == configured.topic0() {
+                continue;
+            }
+            let count = topics
+                .iter()
+                .filter(|topic| **topic == candidate.topic0())
+                .count();
+            if count > 0 {
+                return Ok(Some(format!(
+                    "PROPOSAL EVENT MISMATCH: blocks {from}-{tip} have {count} {name} ProposalCreated log(s) ({}) but none with the configured signature ({}); scans will find no new proposals until you {hint}",
+                    candidate.signature(),
+                    configured.signature()
+                )));
+            }
+        }
+
+        let usual = OTHER_GOVERNOR_EVENT_SIGNATURES
+            .iter()
+            .map(|signature| keccak256(signature.as_bytes()))
+            .collect::<BTreeSet<_>>();
+        let unknown = topics
+            .iter()
+            .filter(|topic| !usual.contains(*topic))
+            .map(ToString::to_string)
+            .collect::<BTreeSet<_>>();
+        if unknown.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(format!(
+            "PROPOSAL EVENT MISMATCH: blocks {from}-{tip} have governor logs with unrecognized topics ({}) but no ProposalCreated log with the configured signature ({}) or any known variant; if proposals were created there, the governor may have been upgraded to a new event layout, so {hint}",
+            unknown.into_iter().collect::<Vec<_>>().join(", "),
+            configured.signature()
+        )))
+    }
+
+    // Intents left by a crash are reconciled by the executor before it votes on that proposal
+    // again; this only makes them visible at startup.
+    fn warn_pending_vote_intents(&self) -> Result<()> {
+        for intent in self.storage.vote_intents().load()?.values() {
+            tracing::warn!(
+                proposal_id = %intent.proposal_id,
+                signer = %observability::log_address(&intent.signer),
+                nonce = intent.nonce,
+                tx_hash = intent.tx_hash.as_deref().unwrap_or("unknown"),
+                "vote intent from an interrupted run; it is checked on chain before any new vote"
+            );
+        }
+        Ok(())
+    }
+
+    fn redacted_config_json(&self) -> String {
+        serde_json::to_string_pretty(&self.config.redacted())
+            .unwrap_or_else(|_| "<failed to serialize config>".to_string())
+    }
+}
+
+fn install_shutdown_signal_listener() -> watch::Receiver<bool> {
+    let (tx, rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        let _ = tx.send(true);
+    });
+
+    rx
+}
+
+// Accounts vote one after another; a failed account is logged and recorded as not submitted,
+// and the rest still vote. `paused` replaces every account's executor while the kill switch is
+// active.
+async fn submit_votes(
+    accounts: &[VoteAccount],
+    paused: Option<DryRunVoteExecutor>,
+    proposal: &Proposal,
+    decision: &Decision,
+) -> Vec<VoteExecution> {
+    let mut executions = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let executor: &dyn VoteExecutor = match &paused {
+            Some(dry_run) => dry_run,
+            None => account.executor.as_ref(),
+        };
+        executions.push(submit_vote(executor, &account.label, proposal, decision).await);
+    }
+    executions
+}
+
+// A record without `account` predates multi-account voting and covers every account.
+fn account_voted(processed: &ProcessedProposal, label: &str) -> bool {
+    processed.vote_executions.iter().any(|vote| {
+        vote.submitted
+            && vote
+                .account
+                .as_deref()
+                .is_none_or(|account| account == label)
+    })
+}
+
+async fn submit_vote(
+    vote_executor: &dyn VoteExecutor,
+    account: &str,
+    proposal: &Proposal,
+    decision: &Decision,
+) -> VoteExecution {
+    let vote_started = observability::now();
+    let span = tracing::info_span!(
+        "vote_tx",
+        proposal_id = %proposal.proposal_id,
+        account,
+        vote = ?decision.vote,
+        status = tracing::field::Empty,
+        tx_hash = tracing::field::Empty,
+    );
+    let result = vote_executor
+        .submit_vote(proposal, decision)
+        .instrument(span.clone())
+        .await;
+    match &result {
+        Ok(vote) => {
+            span.record(
+                "status",
+                if vote.submitted {
+                    "submitted"
+                } else {
+                    "not_submitted"
+                },
+            );
+            if let Some(tx_hash) = &vote.tx_hash {
+                span.record("tx_hash", tx_hash.as_str());
+            }
+        }
+        Err(_) => {
+            span.record("status", "error");
+        }
+    }
+    let vote_execution = match result {
+        Ok(vote) => {
+            observability::record_vote_submit(true);
+            tracing::info!(
+                proposal_id = proposal.proposal_id,
+                account,
+                submitted = vote.submitted,
+                tx_hash = vote.tx_hash.as_deref().unwrap_or("none"),
+                "vote recorded"
+            );
+            vote
+        }
+        Err(err) => {
+            observability::record_vote_submit(false);
+            observability::incr_proposals_failed("vote");
+            tracing::warn!(proposal_id = proposal.proposal_id, account, error = %err, "vote submission failed");
+            VoteExecution {
+                proposal_id: proposal.proposal_id.clone(),
+                submitted: false,
+                tx_hash: None,
+                reason: format!("vote submission failed: {err:#}"),
+                at: Utc::now(),
+                account: None,
+            }
+        }
+    };
+    observability::observe_stage_latency("vote_submit", vote_started);
+    VoteExecution {
+        account: Some(account.to_string()),
+        ..vote_execution
+    }
+}
+
+// No block, transaction, calldata or proposer: only the PublishDapp action is real.
+fn synthetic_proposal(root_cid: &str, name: String, version: String) -> Proposal {
+    Proposal {
+        proposal_id: format!("synthetic:{root_cid}"),
+        proposer: "0x0000000000000000000000000000000000000000".to_string(),
+        description: format!(
+            "Synthetic pre-flight review of {name} {version} ({root_cid}); not an on-chain proposal"
+        ),
+        vote_start: 0,
+        vote_end: 0,
+        block_number: 0,
+        tx_hash: None,
+        targets: vec![],
+        values: vec![],
+        calldatas: vec![],
+        actions: vec![DecodedAction::PublishDapp {
+            root_cid: root_cid.to_string(),
+            description: format!("{name} {version}"),
+            name,
+            version,
+        }],
+        discovered_at: Utc::now(),
+        creation_context: None,
+    }
+}
+
+// Deferred reviews and decisions that need an operator: flagged for override, or an abstain
+// that no account has voted on.
+fn awaiting_human(state: &State) -> Vec<(&Proposal, &'static str)> {
+    let pending = state
+        .pending_reviews
+        .values()
+        .map(|pending| (&pending.proposal, "review pending"));
+    let undecided = state.proposals.values().filter_map(|processed| {
+        if processed.vote_executions.iter().any(|vote| vote.submitted) {
+            return None;
+        }
+        if processed.decision.requires_human_override {
+            Some((&processed.proposal, "requires human override"))
+        } else if processed.decision.vote == VoteChoice::Abstain {
+            Some((&processed.proposal, "abstained"))
+        } else {
+            None
+        }
+    });
+    pending.chain(undecided).collect()
+}
+
+fn chain_id_mismatch(network: &NetworkConfig, chain_id: u64) -> Option<String> {
+    (chain_id != network.chain_id).then(|| {
+        format!(
+            "the RPC endpoint reports chain id {chain_id}, but network.chain_id is {} (network {})",
+            network.chain_id, network.name
+        )
+    })
+}
+
+fn confirmed_tip(latest: u64, confirmations: u64) -> u64 {
+    latest.saturating_sub(confirmations)
+}
+
+// Doubles the wait for each idle cycle from the backoff threshold on, capped at the maximum.
+fn poll_interval(base_secs: u64, max_secs: u64, idle_cycles: u32, backoff_after: u32) -> Duration {
+    if idle_cycles == 0 || idle_cycles < backoff_after {
+        return Duration::from_secs(base_secs);
+    }
+    let steps = idle_cycles - backoff_after.saturating_sub(1);
+    let factor = 1u64.checked_shl(steps.min(63)).unwrap_or(u64::MAX);
+    Duration::from_secs(
+        base_secs
+            .saturating_mul(factor)
+            .min(max_secs.max(base_secs)),
+    )
+}
+
+fn shutdown_requested(shutdown: Option<&watch::Receiver<bool>>) -> bool {
+    shutdown.is_some_and(|signal| *signal.borrow())
+}
+
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to register SIGTERM handler; falling back to ctrl_c only");
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        path::{Path, PathBuf},
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::{Duration, Instant},
+    };
+
+    use alloy::primitives::{B256, keccak256};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        sync::watch,
+    };
+
+    use super::{
+        Agent, ProposalFilter, ProposalLookup, VoteAccount, account_voted, awaiting_human,
+        confirmed_tip, poll_interval, shutdown_requested,
+    };
+    use crate::{
+        api::ApiServer,
+        chain::{ChainSource, ProposalScan, TransportKind, testing::MockChain},
+        config::{ApiConfig, ApiStyle, AppConfig, ConfidenceProfile, PeerEndpoint, ProviderConfig},
+        decoder::{KNOWN_PROPOSAL_CREATED_SIGNATURES, ProposalCreatedAbi},
+        report::{REVIEW_REPORT_SCHEMA_VERSION, ReviewReport},
+        signer::{ClockMode, testing::MockVoteExecutor},
+        storage::State,
+        types::{
+            Decision, DecodedAction, FindingCode, PendingReview, ProcessedProposal, Proposal,
+            ProposalLifecycle, ProposalState, ReviewResult, Severity, SkipReason, VoteChoice,
+            VoteExecution,
+        },
+    };
+
+    #[test]
+    fn shutdown_flag_defaults_to_false() {
+        let (_tx, rx) = watch::channel(false);
+        assert!(!shutdown_requested(Some(&rx)));
+    }
+
+    #[test]
+    fn shutdown_flag_reflects_signal_state() {
+        let (tx, rx) = watch::channel(false);
+        tx.send(true).expect("send shutdown signal");
+        assert!(shutdown_requested(Some(&rx)));
+    }
+
+    #[test]
+    fn poll_interval_backs_off_when_idle_and_caps_at_max() {
+        let secs = |idle| poll_interval(5, 60, idle, 3).as_secs();
+        assert_eq!(secs(0), 5);
+        assert_eq!(secs(2), 5);
+        assert_eq!(secs(3), 10);
+        assert_eq!(secs(4), 20);
+        assert_eq!(secs(5), 40);
+        assert_eq!(secs(6), 60);
+        assert_eq!(secs(u32::MAX), 60);
+        assert_eq!(poll_interval(12, 5, 10, 1).as_secs(), 12);
+    }
+
+    #[test]
+    fn confirmed_tip_stays_behind_latest_block() {
+        assert_eq!(confirmed_tip(100, 0), 100);
+        assert_eq!(confirmed_tip(100, 12), 88);
+        assert_eq!(confirmed_tip(5, 12), 0);
+    }
+
+    fn vote(submitted: bool, account: Option<&str>) -> VoteExecution {
+        VoteExecution {
+            proposal_id: "1".to_string(),
+            submitted,
+            tx_hash: None,
+            reason: String::new(),
+            at: Utc::now(),
+            account: account.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn account_voted_counts_only_submitted_votes_from_that_account() {
+        let mut processed = processed(proposal("1", 3, "bafy-clean"), VoteChoice::For, 0.9);
+        assert!(!account_voted(&processed, "treasury"));
+
+        processed.vote_executions = vec![vote(false, Some("treasury")), vote(true, Some("ops"))];
+        assert!(!account_voted(&processed, "treasury"));
+        assert!(account_voted(&processed, "ops"));
+
+        // Votes recorded before multi-account support carry no label and count for every account.
+        processed.vote_executions = vec![vote(true, None)];
+        assert!(account_voted(&processed, "treasury"));
+    }
+
+    #[test]
+    fn awaiting_human_lists_pending_reviews_and_unvoted_holds() {
+        let mut state = State::default();
+        state.pending_reviews.insert(
+            "1".to_string(),
+            PendingReview {
+                proposal: proposal("1", 3, "bafy-clean"),
+                attempts: 2,
+                last_error: "gateway timeout".to_string(),
+                last_attempt_at: Utc::now(),
+            },
+        );
+        let mut held = processed(proposal("2", 3, "bafy-clean"), VoteChoice::For, 0.9);
+        held.decision.requires_human_override = true;
+        let mut voted_hold = held.clone();
+        voted_hold.proposal.proposal_id = "5".to_string();
+        voted_hold.vote_executions = vec![vote(true, None)];
+        for (id, processed) in [
+            ("2", held),
+            (
+                "3",
+                processed(proposal("3", 3, "bafy-clean"), VoteChoice::Abstain, 0.6),
+            ),
+            (
+                "4",
+                processed(proposal("4", 3, "bafy-clean"), VoteChoice::For, 0.9),
+            ),
+            ("5", voted_hold),
+        ] {
+            state.proposals.insert(id.to_string(), processed);
+        }
+
+        let waiting = awaiting_human(&state)
+            .into_iter()
+            .map(|(proposal, reason)| (proposal.proposal_id.as_str(), reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            waiting,
+            vec![
+                ("1", "review pending"),
+                ("2", "requires human override"),
+                ("3", "abstained"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn process_range_votes_for_clean_and_against_critical_proposals() {
+        let root = temp_root("gov-agent-pipeline");
+        let config = test_config(&root);
+        let cache_dir = root.join("cache");
+        write_bundle(
+            &cache_dir,
+            "bafy-critical",
+            r#"{"files":[{"path":"vibefi.json","bytes":2},{"path":"package.json","bytes":2}]}"#,
+        );
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(
+            config,
+            10,
+            vec![
+                proposal("1", 3, "bafy-clean"),
+                proposal("2", 4, "bafy-critical"),
+            ],
+            &executor,
+        );
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert_eq!(
+            executor.recorded(),
+            vec![
+                ("1".to_string(), VoteChoice::For),
+                ("2".to_string(), VoteChoice::Against),
+            ]
+        );
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.last_scanned_block, 10);
+        assert_eq!(state.proposals.len(), 2);
+        assert_eq!(
+            state.proposals["1"].decision.config_hash.as_deref(),
+            Some(agent.config_hash.as_str())
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn operator_findings_recompute_the_decision_and_survive_re_review() {
+        let root = temp_root("gov-agent-annotate");
+        let config = test_config(&root);
+
+        let agent = mock_agent(
+            config,
+            10,
+            vec![proposal("1", 3, "bafy-clean")],
+            &MockVoteExecutor::default(),
+        );
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert_eq!(
+            agent.storage.load().expect("load state").proposals["1"]
+                .decision
+                .vote,
+            VoteChoice::For
+        );
+
+        agent
+            .annotate_proposal("1", Severity::Critical, "drains the treasury", "alice")
+            .await
+            .expect("annotate");
+        let state = agent.storage.load().expect("load state");
+        let processed = &state.proposals["1"];
+        assert_eq!(processed.decision.vote, VoteChoice::Against);
+        assert_eq!(processed.decision_history[0].vote, VoteChoice::For);
+        assert!(
+            processed
+                .decision
+                .reasons
+                .contains(&"operator critical finding by alice: drains the treasury".to_string())
+        );
+        let finding = processed.review.findings.last().expect("operator finding");
+        assert_eq!(
+            finding
+                .operator
+                .as_ref()
+                .map(|operator| operator.author.as_str()),
+            Some("alice")
+        );
+
+        let report = agent
+            .review_by_id("1", &state, ProposalLookup::default())
+            .await
+            .expect("re-review");
+        let operator_findings = report
+            .findings
+            .iter()
+            .filter(|finding| finding.operator.is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(operator_findings.len(), 1);
+        assert_eq!(operator_findings[0].code, FindingCode::OperatorFinding);
+        assert_eq!(report.decision.vote, VoteChoice::Against);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn large_gap_is_caught_up_in_chunks_and_closed_votes_are_marked_missed() {
+        let root = temp_root("gov-agent-catch-up");
+        let mut config = test_config(&root);
+        config.catch_up.threshold_blocks = 50;
+        config.catch_up.chunk_blocks = 40;
+        let confirmed = confirmed_tip(200, config.network.confirmations);
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(
+            config,
+            200,
+            vec![
+                proposal("1", 3, "bafy-clean"),
+                proposal("2", 150, "bafy-clean"),
+            ],
+            &executor,
+        );
+
+        let cycle = agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert_eq!(cycle.discovered, 2);
+        assert_eq!(
+            executor.recorded(),
+            vec![("2".to_string(), VoteChoice::For)]
+        );
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.last_scanned_block, confirmed);
+        assert!(state.proposals["1"].vote_missed);
+        assert!(state.proposals["1"].vote_executions.is_empty());
+        assert!(!state.proposals["2"].vote_missed);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn failed_catch_up_chunk_keeps_the_digest_for_the_resumed_pass() {
+        let root = temp_root("gov-agent-catch-up-resume");
+        let mut config = test_config(&root);
+        config.catch_up.threshold_blocks = 50;
+        config.catch_up.chunk_blocks = 40;
+        let confirmed = confirmed_tip(200, config.network.confirmations);
+
+        let agent = Agent::with_parts(
+            config,
+            Box::new(FlakyChain {
+                inner: MockChain {
+                    latest_block: 200,
+                    proposals: vec![
+                        proposal("1", 3, "bafy-clean"),
+                        proposal("2", 150, "bafy-clean"),
+                    ],
+                },
+                fail_at: 150,
+                failures: AtomicUsize::new(1),
+            }),
+            Arc::new(MockVoteExecutor::default()),
+        )
+        .expect("build agent");
+
+        let err = agent
+            .scan_and_process_once(None)
+            .await
+            .expect_err("chunk fails");
+        assert!(format!("{err:#}").contains("rpc timed out"));
+        let state = agent.storage.load().expect("load state");
+        let progress = state.catch_up.expect("progress kept");
+        assert_eq!(progress.proposal_ids, vec!["1".to_string()]);
+        assert!(state.last_scanned_block < 150);
+        assert!(state.proposals.contains_key("1"));
+
+        // The next scan resumes the same pass and ends it, so one digest covers both proposals.
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan resumes");
+        let state = agent.storage.load().expect("load state");
+        assert!(state.catch_up.is_none());
+        assert_eq!(state.last_scanned_block, confirmed);
+        assert!(state.proposals.contains_key("2"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn chain_id_mismatch_stops_scanning_unless_allowed() {
+        let root = temp_root("gov-agent-chain-id");
+        let mut config = test_config(&root);
+        config.network.chain_id = 11155111;
+
+        let build = |config: AppConfig, executor: &MockVoteExecutor| {
+            mock_agent(config, 20, vec![proposal("1", 3, "bafy-clean")], executor)
+        };
+
+        let executor = MockVoteExecutor::default();
+        let agent = build(config.clone(), &executor);
+        let err = agent
+            .scan_and_process_once(None)
+            .await
+            .expect_err("mismatched chain");
+        assert!(err.to_string().contains("reports chain id 31337"));
+        let err = agent
+            .backfill(0, Some(20), None, false)
+            .await
+            .expect_err("mismatched chain");
+        assert!(err.to_string().contains("--allow-chain-mismatch"));
+        agent
+            .reevaluate_unvoted_decisions()
+            .await
+            .expect_err("mismatched chain");
+        assert!(executor.recorded().is_empty());
+        assert!(
+            agent
+                .storage
+                .load()
+                .expect("load state")
+                .proposals
+                .is_empty()
+        );
+
+        config.network.allow_chain_mismatch = true;
+        let agent = build(config, &executor);
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("mismatch allowed");
+        assert_eq!(executor.recorded().len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn closed_proposals_are_skipped_until_backfill_reviews_them() {
+        let root = temp_root("gov-agent-closed");
+        let config = test_config(&root);
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(
+            config,
+            20,
+            vec![proposal("1", 3, "bafy-clean"), {
+                let mut closed = proposal("2", 4, "bafy-clean");
+                closed.vote_end = 15;
+                closed
+            }],
+            &executor,
+        );
+
+        let cycle = agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert_eq!(cycle.skipped_closed, 1);
+        assert_eq!(
+            executor.recorded(),
+            vec![("1".to_string(), VoteChoice::For)]
+        );
+        let state = agent.storage.load().expect("load state");
+        assert!(!state.proposals.contains_key("2"));
+        assert_eq!(
+            state.skipped_proposals["2"].skipped,
+            SkipReason::VotingClosed
+        );
+
+        agent
+            .backfill(0, Some(20), None, true)
+            .await
+            .expect("backfill succeeds");
+
+        assert_eq!(executor.recorded().len(), 1);
+        let state = agent.storage.load().expect("load state");
+        assert!(state.skipped_proposals.is_empty());
+        assert!(state.proposals["2"].vote_missed);
+        assert!(state.proposals["2"].vote_executions.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn each_account_votes_and_a_failing_account_does_not_block_others() {
+        let root = temp_root("gov-agent-accounts");
+        let config = test_config(&root);
+
+        let failing = MockVoteExecutor {
+            fail: true,
+            ..MockVoteExecutor::default()
+        };
+        let working = MockVoteExecutor::default();
+        let mut agent = mock_agent(config, 10, vec![proposal("1", 3, "bafy-clean")], &working);
+        agent.vote_executor_override = Some(vec![
+            VoteAccount {
+                label: "treasury".to_string(),
+                executor: Arc::new(failing.clone()),
+            },
+            VoteAccount {
+                label: "delegate".to_string(),
+                executor: Arc::new(working.clone()),
+            },
+        ]);
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert_eq!(failing.recorded(), vec![("1".to_string(), VoteChoice::For)]);
+        assert_eq!(working.recorded(), vec![("1".to_string(), VoteChoice::For)]);
+        let state = agent.storage.load().expect("load state");
+        let executions = &state.proposals["1"].vote_executions;
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions[0].account.as_deref(), Some("treasury"));
+        assert!(!executions[0].submitted);
+        assert_eq!(
+            executions[0].reason,
+            "vote submission failed: mock executor rejected the vote"
+        );
+        assert_eq!(executions[1].account.as_deref(), Some("delegate"));
+        assert!(executions[1].submitted);
+
+        // The next cycle retries only the account that has not voted, replacing its failure.
+        let recovered = MockVoteExecutor::default();
+        agent.vote_executor_override = Some(vec![
+            VoteAccount {
+                label: "treasury".to_string(),
+                executor: Arc::new(recovered.clone()),
+            },
+            VoteAccount {
+                label: "delegate".to_string(),
+                executor: Arc::new(working.clone()),
+            },
+        ]);
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert_eq!(
+            recovered.recorded(),
+            vec![("1".to_string(), VoteChoice::For)]
+        );
+        assert_eq!(working.recorded().len(), 1);
+        let state = agent.storage.load().expect("load state");
+        let executions = &state.proposals["1"].vote_executions;
+        assert_eq!(executions.len(), 2);
+        assert!(executions.iter().all(|vote| vote.submitted));
+        assert_eq!(executions[1].account.as_deref(), Some("treasury"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn review_lookup_waits_for_a_lagging_log_index() {
+        let root = temp_root("gov-agent-review-wait");
+        let config = test_config(&root);
+
+        let chain = |misses| LaggingChain {
+            inner: MockChain {
+                latest_block: 10,
+                proposals: vec![proposal("1", 3, "bafy-clean")],
+            },
+            misses: AtomicUsize::new(misses),
+        };
+        let build = |chain: LaggingChain| {
+            Agent::with_parts(
+                config.clone(),
+                Box::new(chain),
+                Arc::new(MockVoteExecutor::default()),
+            )
+            .expect("build agent")
+        };
+
+        let agent = build(chain(2));
+        let err = agent
+            .wait_for_proposal("1", ProposalLookup::default())
+            .await
+            .expect_err("no wait");
+        assert_eq!(err.to_string(), "proposal 1 not found");
+
+        let wait = ProposalLookup {
+            wait: std::time::Duration::from_secs(10),
+            at_block: None,
+        };
+        let report = agent
+            .review_on_demand("1", wait)
+            .await
+            .expect("found after retrying");
+        assert_eq!(report.decision.vote, VoteChoice::For);
+
+        let pinned = |at_block, secs| ProposalLookup {
+            wait: std::time::Duration::from_secs(secs),
+            at_block: Some(at_block),
+        };
+        let agent = build(chain(0));
+        agent
+            .wait_for_proposal("1", pinned(3, 0))
+            .await
+            .expect("found in its creation block");
+        let err = agent
+            .wait_for_proposal("1", pinned(4, 1))
+            .await
+            .expect_err("wrong block");
+        assert!(
+            format!("{err:#}").starts_with("gave up waiting for proposal 1 after"),
+            "{err:#}"
+        );
+        assert!(format!("{err:#}").ends_with("proposal 1 not found in block 4"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn at_block_lookup_takes_a_single_proposal_id() {
+        let ids = |ids: &[&str]| ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let pinned = ProposalLookup {
+            wait: Duration::ZERO,
+            at_block: Some(3),
+        };
+        assert!(pinned.check_batch(&ids(&["1"])).is_ok());
+        assert!(pinned.check_batch(&ids(&["1", "1"])).is_ok());
+        let err = pinned.check_batch(&ids(&["1", "2"])).expect_err("two ids");
+        assert!(
+            err.to_string()
+                .contains("cannot be combined with 2 proposal ids")
+        );
+        assert!(
+            ProposalLookup::default()
+                .check_batch(&ids(&["1", "2"]))
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_proposal_logs_are_matched_against_known_event_signatures() {
+        let root = temp_root("gov-agent-event-signature");
+        let config = test_config(&root);
+
+        let build = |topics: Vec<B256>| {
+            Agent::with_parts(
+                config.clone(),
+                Box::new(TopicLogsChain {
+                    inner: MockChain {
+                        latest_block: 10,
+                        proposals: vec![proposal("1", 3, "bafy-clean")],
+                    },
+                    topics,
+                }),
+                Arc::new(MockVoteExecutor::default()),
+            )
+            .expect("build agent")
+        };
+        let topic = |name: &str| {
+            let (_, signature) = KNOWN_PROPOSAL_CREATED_SIGNATURES
+                .iter()
+                .find(|(known, _)| *known == name)
+                .expect("known signature");
+            ProposalCreatedAbi::parse(signature)
+                .expect("signature")
+                .topic0()
+        };
+
+        let agent = build(vec![topic("openzeppelin")]);
+        assert!(
+            agent
+                .proposal_event_mismatch()
+                .await
+                .expect("check")
+                .is_none(),
+            "configured logs are found"
+        );
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert!(
+            agent
+                .proposal_event_mismatch()
+                .await
+                .expect("check")
+                .is_none()
+        );
+
+        // Only blocks after the stored proposal at block 3 are probed.
+        let mismatch = build(vec![topic("aave-v2")])
+            .proposal_event_mismatch()
+            .await
+            .expect("check")
+            .expect("mismatch");
+        assert!(
+            mismatch.contains("blocks 4-10 have 1 aave-v2 ProposalCreated log(s)"),
+            "{mismatch}"
+        );
+
+        // A quiet governor, or one only casting votes, is no sign of a new layout.
+        let vote_cast = keccak256("VoteCast(address,uint256,uint8,uint256,string)".as_bytes());
+        for topics in [Vec::new(), vec![vote_cast]] {
+            assert!(
+                build(topics)
+                    .proposal_event_mismatch()
+                    .await
+                    .expect("check")
+                    .is_none()
+            );
+        }
+        let mismatch = build(vec![vote_cast, B256::repeat_byte(7)])
+            .proposal_event_mismatch()
+            .await
+            .expect("check")
+            .expect("mismatch");
+        assert!(mismatch.contains(&format!("unrecognized topics ({})", B256::repeat_byte(7))));
+        assert!(mismatch.contains("or any known variant"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn cursor_ahead_of_tip_is_rewound_only_after_it_persists() {
+        let root = temp_root("gov-agent-cursor-rewind");
+        let mut config = test_config(&root);
+        config.network.cursor_anomaly_cycles = 2;
+        let build = || {
+            mock_agent(
+                config.clone(),
+                10,
+                vec![
+                    proposal("1", 3, "bafy-clean"),
+                    proposal("2", 8, "bafy-clean"),
+                ],
+                &MockVoteExecutor::default(),
+            )
+        };
+        let jump_cursor = |agent: &Agent| {
+            let mut state = agent.storage.load().expect("load state");
+            state.last_scanned_block = 5_000_000;
+            agent.storage.save(&state).expect("save state");
+        };
+
+        // A second RPC that also sees the high tip keeps the cursor where it is.
+        let mut agent = build();
+        agent.scan_and_process_once(None).await.expect("scan");
+        jump_cursor(&agent);
+        agent.cursor_check = Some(Box::new(MockChain {
+            latest_block: 5_000_100,
+            proposals: Vec::new(),
+        }));
+        for _ in 0..3 {
+            agent.scan_and_process_once(None).await.expect("scan");
+        }
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.last_scanned_block, 5_000_000);
+        assert!(state.cursor_rewinds.is_empty());
+
+        let agent = build();
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("first cycle");
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(
+            state.last_scanned_block, 5_000_000,
+            "one cycle is not enough"
+        );
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("second cycle");
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.proposals.len(), 2, "history is kept");
+        assert_eq!(state.last_scanned_block, 10);
+        let [rewind] = state.cursor_rewinds.as_slice() else {
+            panic!("expected one rewind record, got {:?}", state.cursor_rewinds);
+        };
+        assert_eq!(rewind.previous_block, 5_000_000);
+        assert_eq!(rewind.rewound_to, 8);
+        assert_eq!(rewind.latest_block, 10);
+        assert_eq!(rewind.anomaly_cycles, 2);
+        assert_eq!(rewind.stored_proposals, 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    // Reports logs for the given topics in every block.
+    struct TopicLogsChain {
+        inner: MockChain,
+        topics: Vec<B256>,
+    }
+
+    #[async_trait]
+    impl ChainSource for TopicLogsChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+
+        async fn governor_log_topics(&self, _from_block: u64, _to_block: u64) -> Result<Vec<B256>> {
+            Ok(self.topics.clone())
+        }
+    }
+
+    // Returns no proposals for the first `misses` log queries, like an RPC whose log index has
+    // not caught up with the latest block yet.
+    struct LaggingChain {
+        inner: MockChain,
+        misses: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ChainSource for LaggingChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            let lagging = self
+                .misses
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |misses| {
+                    misses.checked_sub(1)
+                })
+                .is_ok();
+            if lagging {
+                return Ok(Vec::new());
+            }
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+    }
+
+    // Fails the first `failures` scans that reach `fail_at`, like an RPC timing out mid catch-up.
+    struct FlakyChain {
+        inner: MockChain,
+        fail_at: u64,
+        failures: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ChainSource for FlakyChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            let failing = to_block >= self.fail_at
+                && self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                        left.checked_sub(1)
+                    })
+                    .is_ok();
+            if failing {
+                anyhow::bail!("rpc timed out");
+            }
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+    }
+
+    struct UndecodableLogsChain {
+        inner: MockChain,
+        failed_logs: usize,
+    }
+
+    #[async_trait]
+    impl ChainSource for UndecodableLogsChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+
+        async fn scan_proposals(&self, from_block: u64, to_block: u64) -> Result<ProposalScan> {
+            Ok(ProposalScan {
+                proposals: self.fetch_proposals(from_block, to_block).await?,
+                failed_logs: (0..self.failed_logs)
+                    .map(|index| json!({ "logIndex": index, "data": "0xdead" }))
+                    .collect(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn undecodable_logs_are_quarantined_and_only_fail_a_fully_broken_range() {
+        let root = temp_root("gov-agent-failed-logs");
+        let config = test_config(&root);
+        let agent_with = |data_dir: &str, latest_block: u64, proposals: Vec<Proposal>| {
+            let mut config = config.clone();
+            config.storage.data_dir = root.join(data_dir);
+            Agent::with_parts(
+                config,
+                Box::new(UndecodableLogsChain {
+                    inner: MockChain {
+                        latest_block,
+                        proposals,
+                    },
+                    failed_logs: 2,
+                }),
+                Arc::new(MockVoteExecutor::default()),
+            )
+            .expect("build agent")
+        };
+
+        let agent = agent_with("data", 10, vec![proposal("1", 3, "bafy-clean")]);
+        let cycle = agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds despite bad logs");
+        assert_eq!((cycle.discovered, cycle.skipped_logs), (1, 2));
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.last_scanned_block, 10);
+        assert!(state.proposals.contains_key("1"));
+        let quarantined =
+            fs::read_to_string(root.join("data/failed_logs/0-10.jsonl")).expect("quarantined logs");
+        assert_eq!(quarantined.lines().count(), 2);
+
+        // A range where every log fails is quarantined but keeps the cursor in place.
+        let broken = agent_with("data", 20, vec![]);
+        let err = broken
+            .scan_and_process_once(None)
+            .await
+            .expect_err("fully undecodable range fails");
+        assert!(err.to_string().contains("all 2 proposal logs"));
+        assert_eq!(
+            broken
+                .storage
+                .load()
+                .expect("load state")
+                .last_scanned_block,
+            10
+        );
+        assert!(root.join("data/failed_logs/11-20.jsonl").exists());
+
+        // Logs that cannot be quarantined fail the range even when others decoded.
+        fs::create_dir_all(root.join("unwritable")).expect("create data dir");
+        fs::write(root.join("unwritable/failed_logs"), "").expect("block failed_logs dir");
+        let agent = agent_with("unwritable", 10, vec![proposal("1", 3, "bafy-clean")]);
+        let err = agent
+            .scan_and_process_once(None)
+            .await
+            .expect_err("unquarantined logs fail the range");
+        assert!(format!("{err:#}").contains("could not be quarantined"));
+        assert_eq!(
+            agent.storage.load().expect("load state").last_scanned_block,
+            0
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn review_batch_dedups_ids_and_reports_missing_proposals() {
+        let root = temp_root("gov-agent-review-batch");
+        let config = test_config(&root);
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(
+            config,
+            10,
+            vec![
+                proposal("1", 3, "bafy-clean"),
+                proposal("2", 4, "bafy-clean"),
+            ],
+            &executor,
+        );
+
+        let ids = ["1", "0x2", "2", "1", "99"].map(String::from);
+        let results = agent
+            .review_batch(&ids, ProposalLookup::default())
+            .await
+            .expect("batch review");
+
+        let summary = results
+            .iter()
+            .map(|result| {
+                (
+                    result.proposal_id.as_str(),
+                    result.report.as_ref().map(|report| report.decision.vote),
+                    result.error.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("1", Some(VoteChoice::For), false),
+                ("2", Some(VoteChoice::For), false),
+                ("99", None, true),
+            ]
+        );
+        assert!(executor.recorded().is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn synthetic_cid_review_is_decided_but_never_stored() {
+        let root = temp_root("gov-agent-review-cid");
+        let config = test_config(&root);
+        let cache_dir = root.join("cache");
+        write_bundle(
+            &cache_dir,
+            "bafy-preflight",
+            r#"{"name":"Preflight","version":"0.3.0","files":[{"path":"vibefi.json","bytes":2},{"path":"src/app.ts","bytes":20}]}"#,
+        );
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(config, 10, vec![], &executor);
+
+        let result = agent
+            .review_synthetic(" bafy-preflight ", None, Some("0.4.0"))
+            .await
+            .expect("synthetic review");
+
+        assert_eq!(result.proposal.proposal_id, "synthetic:bafy-preflight");
+        assert_eq!(result.proposal.block_number, 0);
+        assert!(matches!(
+            &result.proposal.actions[..],
+            [DecodedAction::PublishDapp { name, version, .. }] if name == "Preflight" && version == "0.4.0"
+        ));
+        assert_eq!(result.review.root_cid.as_deref(), Some("bafy-preflight"));
+        assert_eq!(result.decision.vote, VoteChoice::For);
+        let report = ReviewReport::new(&result.proposal, &result.review, &result.decision, true);
+        let json = serde_json::to_value(&report).expect("serialize report");
+        assert_eq!(json["schema_version"], REVIEW_REPORT_SCHEMA_VERSION);
+        assert_eq!(json["proposal"]["synthetic"], true);
+        assert_eq!(json["proposal"]["actions"][0]["kind"], "publish_dapp");
+        assert_eq!(json["proposal"]["actions"][0]["version"], "0.4.0");
+        assert_eq!(json["decision"]["vote"], "for");
+        assert!(json["score"]["total"].is_number());
+        assert!(executor.recorded().is_empty());
+        assert!(!agent.storage.state_path().exists());
+        assert!(agent.review_synthetic("  ", None, None).await.is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn pause_file_routes_votes_to_dry_run_until_removed() {
+        let root = temp_root("gov-agent-pause");
+        let config = test_config(&root);
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(config, 10, vec![proposal("1", 3, "bafy-clean")], &executor);
+        assert!(agent.storage.set_voting_paused(true).expect("pause"));
+        assert!(!agent.storage.set_voting_paused(true).expect("pause again"));
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert!(executor.recorded().is_empty());
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.last_scanned_block, 10);
+        let processed = &state.proposals["1"];
+        assert!(processed.voting_paused);
+        assert_eq!(processed.decision.vote, VoteChoice::For);
+        let execution = processed.vote_executions.first().expect("dry-run record");
+        assert!(!execution.submitted);
+
+        assert!(agent.storage.set_voting_paused(false).expect("resume"));
+        assert!(!agent.check_voting_paused().await);
+        assert!(!agent.storage.pause_path().exists());
+
+        // The decision held while paused is submitted on the first scan after resuming.
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert_eq!(executor.recorded().len(), 1);
+        let state = agent.storage.load().expect("load state");
+        let processed = &state.proposals["1"];
+        assert!(!processed.voting_paused);
+        assert_eq!(processed.vote_executions.len(), 1);
+        assert!(processed.vote_executions[0].submitted);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn proposal_filter_scopes_by_creation_block_and_discovery_time() {
+        let mut early = proposal("1", 3, "bafy-clean");
+        early.discovered_at = Utc::now() - chrono::Duration::days(30);
+        let stored = [
+            processed(early, VoteChoice::For, 0.9),
+            processed(proposal("2", 7, "bafy-clean"), VoteChoice::For, 0.9),
+        ];
+        let scoped = |filter: ProposalFilter| {
+            stored
+                .iter()
+                .filter(|processed| filter.matches(processed))
+                .map(|processed| processed.proposal.proposal_id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(scoped(ProposalFilter::default()), vec!["1", "2"]);
+        let by_block = ProposalFilter {
+            since_block: Some(7),
+            since: None,
+        };
+        assert_eq!(scoped(by_block), vec!["2"]);
+        let by_time = ProposalFilter {
+            since_block: None,
+            since: Some(Utc::now() - chrono::Duration::days(1)),
+        };
+        assert_eq!(scoped(by_time), vec!["2"]);
+        let both = ProposalFilter {
+            since_block: Some(3),
+            since: Some(Utc::now() + chrono::Duration::days(1)),
+        };
+        assert!(scoped(both).is_empty());
+    }
+
+    #[tokio::test]
+    async fn disagreeing_peer_quorum_holds_the_vote_for_a_human() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind mock peer");
+        let peer_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                    line.clear();
+                }
+                let body = r#"{"decision":{"vote":"against"}}"#;
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+
+        let root = temp_root("gov-agent-peers");
+        let mut config = test_config(&root);
+        config.peers.urls = vec![PeerEndpoint::Url(peer_url.clone())];
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(config, 10, vec![proposal("1", 3, "bafy-clean")], &executor);
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        assert!(executor.recorded().is_empty());
+        let state = agent.storage.load().expect("load state");
+        let processed = &state.proposals["1"];
+        assert_eq!(processed.decision.vote, VoteChoice::For);
+        assert!(processed.decision.requires_human_override);
+        assert!(processed.decision.reasons.iter().any(|reason| reason
+            == &format!(
+                "1 of 1 peer agents disagree with For (quorum 1): {peer_url} voted Against; vote held for human override"
+            )));
+        let peer_check = processed
+            .peer_check
+            .as_ref()
+            .expect("peer responses stored");
+        assert!(peer_check.held);
+        assert_eq!(peer_check.reviews[0].vote, Some(VoteChoice::Against));
+        assert!(!processed.vote_executions[0].submitted);
+
+        // A decision that changes on re-evaluation is checked with the peers before it votes.
+        let mut state = agent.storage.load().expect("load state");
+        let processed = state.proposals.get_mut("1").expect("stored");
+        processed.decision.vote = VoteChoice::Abstain;
+        processed.decision.requires_human_override = false;
+        processed.peer_check = None;
+        agent.storage.save(&state).expect("save state");
+        agent
+            .reevaluate_unvoted_decisions()
+            .await
+            .expect("reevaluate");
+        assert!(executor.recorded().is_empty());
+        let state = agent.storage.load().expect("load state");
+        let processed = &state.proposals["1"];
+        assert_eq!(processed.decision.vote, VoteChoice::For);
+        assert!(processed.decision.requires_human_override);
+        assert!(
+            processed
+                .peer_check
+                .as_ref()
+                .is_some_and(|check| check.held)
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn canary_mode_holds_votes_until_confirmed_decisions_promote_it() {
+        let root = temp_root("gov-agent-canary");
+        let mut config = test_config(&root);
+        config.decision.canary_mode = true;
+        config.decision.canary_required_agreements = 1;
+        config.decision.canary_auto_promote = true;
+
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(config, 10, vec![proposal("1", 3, "bafy-clean")], &executor);
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert!(executor.recorded().is_empty());
+        let state = agent.storage.load().expect("load state");
+        let processed = &state.proposals["1"];
+        assert!(!processed.voting_paused);
+        assert!(!processed.vote_executions[0].submitted);
+        assert!(agent.canary_holds_votes(&state));
+
+        {
+            let _run_loop = agent.lock_state(false).expect("lock");
+            assert!(
+                agent
+                    .confirm_canary("1", VoteChoice::For, false)
+                    .await
+                    .is_err()
+            );
+        }
+        agent
+            .confirm_canary("1", VoteChoice::For, false)
+            .await
+            .expect("confirm");
+        let state = agent.storage.load().expect("load state");
+        assert_eq!(state.canary.streak, 1);
+        assert!(state.canary.promoted_at.is_some());
+        assert!(!agent.canary_holds_votes(&state));
+        assert!(
+            agent
+                .confirm_canary("1", VoteChoice::For, false)
+                .await
+                .is_err()
+        );
+
+        // The decision held during the trial is submitted once the canary is promoted.
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+        assert_eq!(
+            executor.recorded(),
+            vec![("1".to_string(), VoteChoice::For)]
+        );
+        let state = agent.storage.load().expect("load state");
+        let executions = &state.proposals["1"].vote_executions;
+        assert_eq!(executions.len(), 1);
+        assert!(executions[0].submitted);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn transient_manifest_failure_defers_decision_until_retries_exhausted() {
+        let root = temp_root("gov-agent-transient-review");
+        let mut config = test_config(&root);
+        config.review.max_retry_attempts = 1;
+
+        let executor = MockVoteExecutor::default();
+        let mut agent = mock_agent(
+            config,
+            10,
+            vec![proposal("9", 3, "bafyunreachable")],
+            &executor,
+        );
+        agent.bundle_fetcher = agent
+            .bundle_fetcher
+            .with_retry_base_delay(Duration::from_millis(1));
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("first scan succeeds");
+        let state = agent.storage.load().expect("load state");
+        assert!(executor.recorded().is_empty());
+        assert!(state.proposals.is_empty());
+        assert_eq!(state.pending_reviews["9"].attempts, 1);
+
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("second scan succeeds");
+        let state = agent.storage.load().expect("load state");
+        assert!(state.pending_reviews.is_empty());
+        let processed = &state.proposals["9"];
+        assert!(processed.review.transient_failure.is_some());
+        assert!(processed.decision.blocking_findings.is_empty());
+        assert_eq!(
+            executor.recorded(),
+            vec![("9".to_string(), VoteChoice::Abstain)]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn reevaluation_flips_abstain_to_vote_and_keeps_history() {
+        let root = temp_root("gov-agent-reevaluate");
+        let mut config = test_config(&root);
+        config.decision.profile = Some(ConfidenceProfile::Balanced);
+        config.decision.reevaluate_unvoted_on_start = true;
+
+        let stored = proposal("7", 3, "bafy-stored");
+        let executor = MockVoteExecutor::default();
+        let agent = mock_agent(config, 10, vec![stored.clone()], &executor);
+
+        let mut stored = processed(stored, VoteChoice::Abstain, 0.78);
+        stored.decision.requires_human_override = true;
+        let mut state = State::default();
+        state.proposals.insert("7".to_string(), stored);
+        agent.storage.save(&state).expect("save state");
+
+        agent
+            .reevaluate_unvoted_decisions()
+            .await
+            .expect("reevaluation succeeds");
+
+        assert_eq!(
+            executor.recorded(),
+            vec![("7".to_string(), VoteChoice::For)]
+        );
+        let reloaded = agent.storage.load().expect("load state");
+        let processed = &reloaded.proposals["7"];
+        assert_eq!(processed.decision.vote, VoteChoice::For);
+        assert_eq!(processed.decision_history.len(), 1);
+        assert_eq!(processed.decision_history[0].vote, VoteChoice::Abstain);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn deadline_alert_fires_once_for_waiting_proposals_near_vote_end() {
+        let root = temp_root("gov-agent-deadline");
+        let mut config = test_config(&root);
+        config.notifications.deadline_alert_blocks = 300;
+        let agent = mock_agent(config, 50, vec![], &MockVoteExecutor::default());
+
+        let mut state = State::default();
+        for (id, block_number) in [("8", 10), ("9", 5_000)] {
+            state.pending_reviews.insert(
+                id.to_string(),
+                PendingReview {
+                    proposal: proposal(id, block_number, "bafy-pending"),
+                    attempts: 1,
+                    last_error: "gateway timeout".to_string(),
+                    last_attempt_at: Utc::now(),
+                },
+            );
+        }
+
+        assert!(agent.alert_near_deadlines(&mut state, 50).await);
+        assert_eq!(state.deadline_alerts.iter().collect::<Vec<_>>(), vec!["8"]);
+        assert!(!agent.alert_near_deadlines(&mut state, 60).await);
+
+        state.pending_reviews.remove("8");
+        assert!(agent.alert_near_deadlines(&mut state, 70).await);
+        assert!(state.deadline_alerts.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    const CHAIN_TIME: u64 = 1_700_000_000;
+
+    // A governor on an ERC-6372 timestamp clock: vote_start and vote_end are unix times.
+    struct TimestampChain {
+        inner: MockChain,
+    }
+
+    #[async_trait]
+    impl ChainSource for TimestampChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+
+        async fn clock_mode(&self) -> ClockMode {
+            ClockMode::Timestamp
+        }
+
+        async fn latest_block_timestamp(&self) -> Result<u64> {
+            Ok(CHAIN_TIME)
+        }
+    }
+
+    #[tokio::test]
+    async fn timestamp_clock_governors_are_compared_in_seconds() {
+        let root = temp_root("gov-agent-timestamp-clock");
+        let mut config = test_config(&root);
+        config.notifications.deadline_alert_blocks = 300;
+        let agent = Agent::with_parts(
+            config,
+            Box::new(TimestampChain {
+                inner: MockChain {
+                    latest_block: 50,
+                    proposals: vec![],
+                },
+            }),
+            Arc::new(MockVoteExecutor::default()),
+        )
+        .expect("build agent");
+
+        let mut open = proposal("8", 10, "bafy-open");
+        open.vote_start = CHAIN_TIME - 600;
+        open.vote_end = CHAIN_TIME + 1_800;
+        assert!(agent.chain.is_proposal_active(&open).await.expect("active"));
+        assert!(!agent.voting_closed(&open, 50).await);
+
+        // A block number read as a timestamp ended long ago.
+        let ended = proposal("7", 10, "bafy-ended");
+        assert!(
+            !agent
+                .chain
+                .is_proposal_active(&ended)
+                .await
+                .expect("inactive")
+        );
+        assert!(agent.voting_closed(&ended, 50).await);
+
+        // 300 blocks is about an hour, so only the proposal with 30 minutes left alerts.
+        let mut later = open.clone();
+        later.proposal_id = "9".to_string();
+        later.vote_end = CHAIN_TIME + 7_200;
+        let mut state = State::default();
+        for proposal in [open, later] {
+            state.pending_reviews.insert(
+                proposal.proposal_id.clone(),
+                PendingReview {
+                    proposal,
+                    attempts: 1,
+                    last_error: "gateway timeout".to_string(),
+                    last_attempt_at: Utc::now(),
+                },
+            );
+        }
+        assert!(agent.alert_near_deadlines(&mut state, 50).await);
+        assert_eq!(state.deadline_alerts.iter().collect::<Vec<_>>(), vec!["8"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    struct LifecycleChain {
+        inner: MockChain,
+        states: Mutex<Vec<ProposalState>>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ChainSource for LifecycleChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+
+        async fn proposal_lifecycle(
+            &self,
+            _proposal_id: &str,
+        ) -> Result<Option<ProposalLifecycle>> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            let state = self.states.lock().expect("states lock").remove(0);
+            Ok(Some(ProposalLifecycle {
+                state,
+                eta: (state == ProposalState::Queued).then_some(1_700_000_000),
+                checked_at: Utc::now(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn lifecycle_is_tracked_until_the_proposal_reaches_a_final_state() {
+        let root = temp_root("gov-agent-lifecycle");
+        let config = test_config(&root);
+        let reads = Arc::new(AtomicUsize::new(0));
+        let agent = Agent::with_parts(
+            config,
+            Box::new(LifecycleChain {
+                inner: MockChain {
+                    latest_block: 500,
+                    proposals: vec![],
+                },
+                states: Mutex::new(vec![
+                    ProposalState::Succeeded,
+                    ProposalState::Queued,
+                    ProposalState::Queued,
+                    ProposalState::Executed,
+                ]),
+                reads: reads.clone(),
+            }),
+            Arc::new(MockVoteExecutor::default()),
+        )
+        .expect("build agent");
+
+        let mut state = State::default();
+        state.proposals.insert(
+            "5".to_string(),
+            processed(proposal("5", 10, "bafy-executed"), VoteChoice::For, 0.9),
+        );
+
+        let observed = |state: &State| {
+            state.proposals["5"]
+                .lifecycle
+                .as_ref()
+                .map(|lifecycle| (lifecycle.state, lifecycle.eta))
+        };
+        assert!(agent.track_lifecycles(&mut state).await);
+        assert_eq!(observed(&state), Some((ProposalState::Succeeded, None)));
+        assert!(agent.track_lifecycles(&mut state).await);
+        assert_eq!(
+            observed(&state),
+            Some((ProposalState::Queued, Some(1_700_000_000)))
+        );
+        assert!(!agent.track_lifecycles(&mut state).await);
+        assert!(agent.track_lifecycles(&mut state).await);
+        assert_eq!(observed(&state), Some((ProposalState::Executed, None)));
+
+        assert!(!agent.track_lifecycles(&mut state).await);
+        assert_eq!(reads.load(Ordering::Relaxed), 4);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    fn proposal(id: &str, block_number: u64, root_cid: &str) -> Proposal {
+        Proposal {
+            proposal_id: id.to_string(),
+            proposer: "0x0000000000000000000000000000000000000001".to_string(),
+            description: format!("proposal {id}"),
+            vote_start: block_number + 1,
+            vote_end: block_number + 100,
+            block_number,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::PublishDapp {
+                root_cid: root_cid.to_string(),
+                name: "app".to_string(),
+                version: "1.0.0".to_string(),
+                description: "fixture".to_string(),
+            }],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        }
+    }
+
+    fn processed(proposal: Proposal, vote: VoteChoice, score: f32) -> ProcessedProposal {
+        let root_cid = proposal.actions.iter().find_map(|action| match action {
+            DecodedAction::PublishDapp { root_cid, .. } => Some(root_cid.clone()),
+            _ => None,
+        });
+        ProcessedProposal {
+            review: ReviewResult {
+                proposal_id: proposal.proposal_id.clone(),
+                root_cid,
+                findings: vec![],
+                deterministic_score: Some(score),
+                deterministic_weight: Some(0.70),
+                llm_weight: Some(0.30),
+                llm_score: None,
+                llm_audit: None,
+                llm_audit_ref: None,
+                score,
+                suppressions: vec![],
+                transient_failure: None,
+                reputation: None,
+                score_cap: None,
+                actions: vec![],
+                reviewed_at: Utc::now(),
+            },
+            decision: Decision {
+                proposal_id: proposal.proposal_id.clone(),
+                vote,
+                reasons: vec![],
+                blocking_findings: vec![],
+                requires_human_override: false,
+                decided_at: Utc::now(),
+                config_hash: None,
+                allocation: None,
+            },
+            proposal,
+            vote_executions: Vec::new(),
+            decision_history: Vec::new(),
+            voting_paused: false,
+            lifecycle: None,
+            vote_missed: false,
+            peer_check: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn api_serves_on_demand_reviews_and_stored_proposals() {
+        let root = temp_root("gov-agent-api");
+        let config = test_config(&root);
+
+        let agent = mock_agent(
+            config,
+            10,
+            vec![proposal("1", 3, "bafy-clean")],
+            &MockVoteExecutor::default(),
+        );
+        agent
+            .scan_and_process_once(None)
+            .await
+            .expect("scan succeeds");
+
+        let api = ApiServer::listen(
+            "127.0.0.1:0".parse().expect("addr"),
+            Some("secret".to_string()),
+            &ApiConfig::default(),
+        )
+        .await
+        .expect("bind api");
+        let base = format!("http://{}", api.local_addr().expect("local addr"));
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .expect("client");
+        let requests = async {
+            let send = |request: reqwest::RequestBuilder| async move {
+                let response = request.send().await.expect("send request");
+                let status = response.status().as_u16();
+                (status, response.json::<serde_json::Value>().await.ok())
+            };
+            let unauthorized = send(client.get(format!("{base}/proposals"))).await;
+            let wrong_token = send(
+                client
+                    .get(format!("{base}/proposals"))
+                    .bearer_auth("secreT"),
+            )
+            .await;
+            let proposals = send(
+                client
+                    .get(format!("{base}/proposals"))
+                    .bearer_auth("secret"),
+            )
+            .await;
+            let review = send(
+                client
+                    .post(format!("{base}/review/1"))
+                    .bearer_auth("secret"),
+            )
+            .await;
+            let wrong_method =
+                send(client.get(format!("{base}/review/1")).bearer_auth("secret")).await;
+            let stored = send(
+                client
+                    .get(format!("{base}/proposals/0x1"))
+                    .bearer_auth("secret"),
+            )
+            .await;
+            let unknown = send(
+                client
+                    .get(format!("{base}/proposals/12"))
+                    .bearer_auth("secret"),
+            )
+            .await;
+            (
+                unauthorized,
+                wrong_token,
+                proposals,
+                review,
+                wrong_method,
+                stored,
+                unknown,
+            )
+        };
+        let (unauthorized, wrong_token, proposals, review, wrong_method, stored, unknown) = tokio::select! {
+            result = api.serve(&agent) => panic!("api server stopped: {result:?}"),
+            responses = requests => responses,
+        };
+
+        assert_eq!(unauthorized.0, 401);
+        assert_eq!(wrong_token.0, 401);
+        assert_eq!(proposals.0, 200);
+        let proposals = proposals.1.expect("proposals json");
+        assert_eq!(proposals["1"]["decision"]["vote"], json!("for"));
+        assert_eq!(review.0, 200);
+        let review = review.1.expect("review json");
+        assert_eq!(review["proposal_id"], json!("1"));
+        assert_eq!(review["report"]["schema_version"], json!(1));
+        assert!(review["report"]["score"]["total"].is_number());
+        assert!(review["report"]["decision"]["vote"].is_string());
+        assert_eq!(wrong_method.0, 405);
+        assert_eq!(stored.0, 200);
+        assert_eq!(
+            stored.1.expect("stored json")["decision"]["vote"],
+            json!("for")
+        );
+        assert_eq!(unknown.0, 404);
+        // On-demand reviews never touch the stored decision.
+        assert_eq!(agent.stored_proposals().expect("state").len(), 1);
+
+        // A client stalled mid-headers holds the only slot until the header timeout drops it;
+        // the next client waits for the slot rather than being served alongside it.
+        let api = ApiServer::listen(
+            "127.0.0.1:0".parse().expect("addr"),
+            None,
+            &ApiConfig {
+                max_connections: 1,
+                header_read_timeout_secs: 1,
+                ..ApiConfig::default()
+            },
+        )
+        .await
+        .expect("bind api");
+        let addr = api.local_addr().expect("local addr");
+        let requests = async {
+            let mut stalled = tokio::net::TcpStream::connect(addr).await.expect("connect");
+            stalled
+                .write_all(b"GET /proposals HTTP/1.1\r\n")
+                .await
+                .expect("write partial request");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let started = Instant::now();
+            let status = client
+                .get(format!("http://{addr}/proposals"))
+                .send()
+                .await
+                .expect("send request")
+                .status()
+                .as_u16();
+            let waited = started.elapsed();
+            let mut rest = Vec::new();
+            stalled.read_to_end(&mut rest).await.expect("stalled read");
+            (status, waited)
+        };
+        let (status, waited) = tokio::select! {
+            result = api.serve(&agent) => panic!("api server stopped: {result:?}"),
+            responses = requests => responses,
+        };
+        assert_eq!(status, 200);
+        assert!(waited >= Duration::from_millis(500), "waited {waited:?}");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    // Devnet config writing under `root`, with the clean fixture bundle `bafy-clean` in its cache,
+    // an unreachable gateway, and no LLM.
+    fn test_config(root: &Path) -> AppConfig {
+        let cache_dir = root.join("cache");
+        write_bundle(&cache_dir, "bafy-clean", CLEAN_MANIFEST);
+        let mut config = AppConfig::for_profile("devnet");
+        config.storage.data_dir = root.join("data");
+        config.ipfs.cache_dir = Some(cache_dir);
+        config.ipfs.gateway_url = "http://127.0.0.1:1".to_string();
+        config.ipfs.request_timeout_secs = 1;
+        disable_llm(&mut config);
+        config
+    }
+
+    fn mock_agent(
+        config: AppConfig,
+        latest_block: u64,
+        proposals: Vec<Proposal>,
+        executor: &MockVoteExecutor,
+    ) -> Agent {
+        Agent::with_parts(
+            config,
+            Box::new(MockChain {
+                latest_block,
+                proposals,
+            }),
+            Arc::new(executor.clone()),
+        )
+        .expect("build agent")
+    }
+
+    fn disable_llm(config: &mut AppConfig) {
+        for provider in [
+            &mut config.llm.openai,
+            &mut config.llm.anthropic,
+            &mut config.llm.ollama,
+            &mut config.llm.venice,
+        ] {
+            *provider = ProviderConfig {
+                enabled: false,
+                base_url: None,
+                api_key_env: None,
+                model: None,
+                max_tokens: None,
+                system_prompt: None,
+                temperature: None,
+                api_style: ApiStyle::default(),
+            };
+        }
+    }
+
+    const CLEAN_MANIFEST: &str =
+        r#"{"files":[{"path":"vibefi.json","bytes":2},{"path":"src/app.ts","bytes":20}]}"#;
+
+    fn write_bundle(cache_dir: &Path, root_cid: &str, manifest: &str) {
+        let cid_dir = cache_dir.join(root_cid);
+        fs::create_dir_all(cid_dir.join("src")).expect("create bundle dir");
+        fs::write(cid_dir.join("manifest.json"), manifest).expect("write manifest");
+        fs::write(cid_dir.join("vibefi.json"), "{}").expect("write vibefi.json");
+        fs::write(cid_dir.join("package.json"), "{}").expect("write package.json");
+        fs::write(cid_dir.join("src/app.ts"), "export const x = 1;\n").expect("write source");
+    }
+
+    fn temp_root(prefix: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", prefix, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("create temp root");
+        path
     }
 }
@@ -0,0 +1,248 @@
+use std::{convert::Infallible, env, io, net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result, anyhow};
+use futures::stream::{FuturesUnordered, StreamExt};
+use http_body_util::Full;
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::{Bytes, Incoming},
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use serde::Serialize;
+use serde_json::json;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+
+use crate::{
+    agent::{Agent, ProposalLookup},
+    config::ApiConfig,
+    report::BatchReview,
+};
+
+// Served from the `run` loop's task: connections are polled alongside the scan loop rather than
+// spawned, so handlers can borrow the agent and its internals are not duplicated.
+pub struct ApiServer {
+    listener: TcpListener,
+    token: Option<String>,
+    max_connections: usize,
+    header_read_timeout: Duration,
+}
+
+impl ApiServer {
+    // None when `api.bind_addr` is unset.
+    pub async fn bind(cfg: &ApiConfig) -> Result<Option<Self>> {
+        let Some(bind_addr) = &cfg.bind_addr else {
+            return Ok(None);
+        };
+        let addr = bind_addr
+            .parse::<SocketAddr>()
+            .with_context(|| format!("invalid api.bind_addr {bind_addr}"))?;
+        let token = cfg
+            .bearer_token_env
+            .as_ref()
+            .map(|name| {
+                env::var(name)
+                    .ok()
+                    .filter(|token| !token.trim().is_empty())
+                    .ok_or_else(|| anyhow!("api token env var {name} is not set"))
+            })
+            .transpose()?;
+        Self::listen(addr, token, cfg).await.map(Some)
+    }
+
+    // Only the connection limits are read from `cfg`; `addr` is already parsed.
+    pub(crate) async fn listen(
+        addr: SocketAddr,
+        token: Option<String>,
+        cfg: &ApiConfig,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind api server on {addr}"))?;
+        if token.is_none() && !addr.ip().is_loopback() {
+            tracing::warn!(
+                bind = %addr,
+                "api server listens beyond loopback without api.bearer_token_env; anyone who can reach it can trigger reviews"
+            );
+        }
+        tracing::info!(bind = %addr, auth = token.is_some(), "api server enabled");
+        Ok(Self {
+            listener,
+            token,
+            max_connections: cfg.max_connections.max(1),
+            header_read_timeout: Duration::from_secs(cfg.header_read_timeout_secs.max(1)),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    // Runs until the caller drops it; accept and connection errors are logged, never returned.
+    // Once `api.max_connections` are open, new clients wait in the listen backlog until one
+    // closes, and a client that stalls before finishing its request headers is dropped after
+    // `api.header_read_timeout_secs`.
+    pub async fn serve(self, agent: &Agent) -> Result<()> {
+        let token = self.token.as_deref();
+        let permits = Arc::new(Semaphore::new(self.max_connections));
+        let mut connections = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                (permit, accepted) = accept(&self.listener, &permits) => {
+                    let stream = match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(err) => {
+                            tracing::warn!(error = %err, "api server failed to accept a connection");
+                            continue;
+                        }
+                    };
+                    let service = service_fn(move |request| handle(agent, token, request));
+                    let connection = http1::Builder::new()
+                        .timer(TokioTimer::new())
+                        .header_read_timeout(self.header_read_timeout)
+                        .serve_connection(TokioIo::new(stream), service);
+                    connections.push(async move {
+                        let _permit = permit;
+                        connection.await
+                    });
+                }
+                Some(result) = connections.next(), if !connections.is_empty() => {
+                    if let Err(err) = result {
+                        tracing::debug!(error = %err, "api connection closed with an error");
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Waits for a free connection slot before accepting, so excess clients stay in the backlog
+// instead of holding a socket here. Both awaits are cancel-safe, which `select!` relies on.
+async fn accept(
+    listener: &TcpListener,
+    permits: &Arc<Semaphore>,
+) -> (OwnedSemaphorePermit, io::Result<(TcpStream, SocketAddr)>) {
+    let permit = Arc::clone(permits)
+        .acquire_owned()
+        .await
+        .expect("connection semaphore is never closed");
+    (permit, listener.accept().await)
+}
+
+async fn handle(
+    agent: &Agent,
+    token: Option<&str>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    if let Some(token) = token
+        && !is_authorized(request.headers().get(AUTHORIZATION), token)
+    {
+        return Ok(json_response(
+            StatusCode::UNAUTHORIZED,
+            &json!({ "error": "missing or invalid bearer token" }),
+        ));
+    }
+
+    let path = request.uri().path();
+    let review_id = path_id(path, "/review/");
+    let stored_id = path_id(path, "/proposals/");
+    let response = match (request.method(), path, review_id, stored_id) {
+        (&Method::POST, _, Some(proposal_id), _) => review(agent, proposal_id).await,
+        (&Method::GET, "/proposals", ..) => match agent.stored_proposals() {
+            Ok(proposals) => json_response(StatusCode::OK, &proposals),
+            Err(err) => json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &json!({ "error": format!("{err:#}") }),
+            ),
+        },
+        (&Method::GET, _, _, Some(proposal_id)) => match agent.stored_proposal(proposal_id) {
+            Ok(Some(processed)) => json_response(StatusCode::OK, &processed),
+            Ok(None) => json_response(
+                StatusCode::NOT_FOUND,
+                &json!({ "error": format!("proposal {proposal_id} is not recorded") }),
+            ),
+            Err(err) => json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &json!({ "error": format!("{err:#}") }),
+            ),
+        },
+        (_, "/proposals", ..) | (_, _, Some(_), _) | (_, _, _, Some(_)) => json_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            &json!({ "error": "method not allowed" }),
+        ),
+        _ => json_response(StatusCode::NOT_FOUND, &json!({ "error": "not found" })),
+    };
+    Ok(response)
+}
+
+async fn review(agent: &Agent, proposal_id: &str) -> Response<Full<Bytes>> {
+    match agent
+        .review_on_demand(proposal_id, ProposalLookup::default())
+        .await
+    {
+        Ok(report) => {
+            tracing::info!(
+                proposal_id = %report.proposal.proposal_id,
+                vote = ?report.decision.vote,
+                score = %format_args!("{:.2}", report.score.total),
+                "api review complete"
+            );
+            json_response(
+                StatusCode::OK,
+                &BatchReview {
+                    proposal_id: report.proposal.proposal_id.clone(),
+                    report: Some(report),
+                    error: None,
+                },
+            )
+        }
+        Err(err) => {
+            tracing::warn!(proposal_id, error = %format!("{err:#}"), "api review failed");
+            json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &BatchReview {
+                    proposal_id: proposal_id.to_string(),
+                    report: None,
+                    error: Some(format!("{err:#}")),
+                },
+            )
+        }
+    }
+}
+
+fn path_id<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix)
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+// Compared without an early exit so response timing does not reveal a matching prefix.
+fn is_authorized(header: Option<&HeaderValue>, token: &str) -> bool {
+    let Some(presented) = header
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    presented.len() == token.len()
+        && presented
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Full<Bytes>> {
+    let body = serde_json::to_vec_pretty(body)
+        .unwrap_or_else(|err| format!("{{\"error\":\"{err}\"}}").into_bytes());
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
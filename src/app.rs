@@ -1,54 +1,397 @@
-use std::time::Duration;
+use std::{env, path::PathBuf, time::Duration};
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Utc;
 use clap::Parser;
+#[cfg(feature = "otlp")]
 use opentelemetry::{KeyValue, trace::TracerProvider};
+#[cfg(feature = "otlp")]
 use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "otlp")]
 use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
-    agent::Agent,
-    cli::{Cli, Command, ConfigCommand},
-    config::{AppConfig, ObservabilityConfig},
-    observability,
+    agent::{Agent, ProposalLookup},
+    api::ApiServer,
+    cli::{
+        AgentCommand, CacheCommand, CanaryCommand, Cli, Command, ConfigCommand, ConfigFormat,
+        LintArgs, NotifyCommand, OutboxCommand, ProposalsCommand, SignerCommand, StorageCommand,
+    },
+    config::{self, AppConfig, ObservabilityConfig, ProfileCatalog},
+    config_schema::config_schema,
+    env_file::{self, EnvFileLoad},
+    init,
+    ipfs::BundleFetcher,
+    local_bundle::LocalBundleSource,
+    notifier::{MultiNotifier, NotificationLinks},
+    observability, review, signer,
+    storage::Storage,
+    types::Severity,
 };
 
-pub async fn run() -> Result<()> {
+pub struct Startup {
+    cli: Cli,
+    env_file: Option<(PathBuf, EnvFileLoad)>,
+}
+
+// Runs on the main thread before the async runtime exists, so `.env` values can be
+// written into the process environment safely.
+pub fn prepare() -> Result<Startup> {
     let cli = Cli::parse();
+    let Some(path) = cli.env_file.clone() else {
+        return Ok(Startup {
+            cli,
+            env_file: None,
+        });
+    };
+    let loaded = env_file::load(&path)?;
+    // Parse again so clap's `env = ...` fallbacks also see values from the file.
+    Ok(Startup {
+        cli: Cli::parse(),
+        env_file: Some((path, loaded)),
+    })
+}
+
+pub async fn run(startup: Startup) -> Result<()> {
+    let Startup { cli, env_file } = startup;
+    match &cli.command {
+        Command::Init(args) => return init::run(&cli, args),
+        // Needs no config, so it still works while the config file is broken.
+        Command::Config(args) if matches!(args.command, ConfigCommand::Schema) => {
+            println!("{}", serde_json::to_string_pretty(&config_schema())?);
+            return Ok(());
+        }
+        // Also runs before config loading, so an unknown --profile can still be looked up.
+        Command::Config(args) if matches!(args.command, ConfigCommand::Profiles) => {
+            let profiles = ProfileCatalog::load(cli.config.as_deref())?.list();
+            println!("{}", serde_json::to_string_pretty(&profiles)?);
+            return Ok(());
+        }
+        Command::Lint(args) => return lint(&cli, args).await,
+        _ => {}
+    }
     let config = AppConfig::load(&cli)?;
+    observability::set_mask_addresses(config.logging.mask_addresses);
 
     let _telemetry_guard = init_tracing(cli.json_logs, &config.observability)?;
+    if let Some((path, loaded)) = &env_file {
+        tracing::info!(
+            path = %path.display(),
+            applied = ?loaded.applied,
+            already_set = ?loaded.kept,
+            "loaded env file"
+        );
+    }
+    tracing::info!(
+        profile = %config.profile,
+        source = %config.profile_source,
+        network = %config.network.name,
+        chain_id = config.network.chain_id,
+        "profile defaults applied"
+    );
+    for warning in &config.load_warnings {
+        tracing::warn!("{warning}");
+    }
     if should_init_metrics(&cli.command) {
         observability::init_metrics(&config.observability)?;
     }
 
     match &cli.command {
-        Command::Config(args) => match args.command {
-            ConfigCommand::Print => {
-                println!("{}", serde_json::to_string_pretty(&config)?);
+        Command::Config(args) => match &args.command {
+            ConfigCommand::Print(print) => {
+                let config_hash =
+                    config.decision_config_hash(config.review.prompt_override().as_deref());
+                match print.format {
+                    ConfigFormat::Json => {
+                        let mut printed = serde_json::to_value(config.redacted())?;
+                        printed["config_hash"] = config_hash.into();
+                        println!("{}", serde_json::to_string_pretty(&printed)?);
+                    }
+                    ConfigFormat::Toml => {
+                        print!("# config_hash = {config_hash}\n{}", config.redacted_toml()?);
+                    }
+                }
+                Ok(())
+            }
+            ConfigCommand::Schema | ConfigCommand::Profiles => {
+                unreachable!("config schema and profiles run before config loading")
+            }
+        },
+        Command::Proposals(args) => match &args.command {
+            ProposalsCommand::List(list) => {
+                let agent = Agent::new(config)?;
+                agent.list_proposals(list.short, list.filter.into())
+            }
+            ProposalsCommand::Show(show) => {
+                let agent = Agent::new(config)?;
+                agent.show_proposal(&show.proposal_id)
+            }
+            ProposalsCommand::Outcomes(outcomes) => {
+                let agent = Agent::new(config)?;
+                agent.compare_outcomes(outcomes.filter.into()).await
+            }
+            ProposalsCommand::Annotate(annotate) => {
+                let agent = Agent::new(config)?;
+                let _state_lock = agent.lock_state(cli.force_unlock)?;
+                let author = annotate
+                    .author
+                    .clone()
+                    .or_else(|| env::var("USER").ok())
+                    .or_else(|| env::var("USERNAME").ok())
+                    .filter(|author| !author.trim().is_empty())
+                    .ok_or_else(|| anyhow!("could not determine the OS username; pass --author"))?;
+                agent
+                    .annotate_proposal(
+                        &annotate.proposal_id,
+                        annotate.severity.into(),
+                        &annotate.message,
+                        author.trim(),
+                    )
+                    .await
+            }
+        },
+        Command::Storage(args) => match &args.command {
+            StorageCommand::Restore(restore) => {
+                let storage = Storage::new(&config.storage)?;
+                let _state_lock = storage.lock(cli.force_unlock)?;
+                let state = storage.restore_backup(restore.backup)?;
+                tracing::info!(
+                    backup = restore.backup,
+                    path = %storage.state_path().display(),
+                    last_scanned_block = state.last_scanned_block,
+                    stored_proposals = state.proposals.len(),
+                    "restored state from backup"
+                );
+                Ok(())
+            }
+            StorageCommand::Prune(prune) => {
+                let days = prune
+                    .retention_days
+                    .or(config.storage.retention_days)
+                    .filter(|days| *days > 0)
+                    .ok_or_else(|| {
+                        anyhow!("pass --retention-days or set storage.retention_days (at least 1)")
+                    })?;
+                let storage = Storage::new(&config.storage)?;
+                let _state_lock = storage.lock(cli.force_unlock)?;
+                let mut state = storage.load()?;
+                let pruned =
+                    storage.prune(&mut state, chrono::Duration::days(days as i64), Utc::now())?;
+                tracing::info!(
+                    archived = pruned.len(),
+                    retention_days = days,
+                    stored_proposals = state.proposals.len(),
+                    archived_total = state.archived.len(),
+                    "pruned finalized proposals from state"
+                );
+                for proposal_id in pruned {
+                    println!("{proposal_id}");
+                }
+                Ok(())
+            }
+        },
+        Command::Cache(args) => match &args.command {
+            CacheCommand::Invalidate(invalidate) => {
+                let fetcher = BundleFetcher::new(&config.ipfs, &config.http)?;
+                if fetcher.invalidate(&invalidate.cid)? {
+                    tracing::info!(cid = %invalidate.cid, "removed cached bundle files");
+                } else {
+                    tracing::info!(cid = %invalidate.cid, "no cached files for cid");
+                }
+                Ok(())
+            }
+        },
+        Command::Agent(args) => {
+            let storage = Storage::new(&config.storage)?;
+            let paused = matches!(args.command, AgentCommand::Pause);
+            let changed = storage.set_voting_paused(paused)?;
+            tracing::info!(
+                path = %storage.pause_path().display(),
+                changed,
+                "voting {}",
+                if paused { "paused" } else { "resumed" }
+            );
+            Ok(())
+        }
+        Command::Canary(args) => match &args.command {
+            CanaryCommand::Confirm(confirm) => {
+                let agent = Agent::new(config)?;
+                agent
+                    .confirm_canary(&confirm.proposal_id, confirm.vote.into(), cli.force_unlock)
+                    .await
+            }
+        },
+        Command::Notify(args) => match &args.command {
+            NotifyCommand::Test(test) => {
+                let notifier = MultiNotifier::from_config(
+                    &config.notifications,
+                    &config.http,
+                    NotificationLinks::from_config(&config),
+                )?;
+                let deliveries = notifier.send_test(test.event).await;
+                println!("{}", serde_json::to_string_pretty(&deliveries)?);
+                let failed = deliveries
+                    .iter()
+                    .filter(|delivery| delivery.error.is_some())
+                    .map(|delivery| delivery.notifier)
+                    .collect::<Vec<_>>();
+                if !failed.is_empty() {
+                    bail!("test notification failed for: {}", failed.join(", "));
+                }
+                Ok(())
+            }
+        },
+        Command::Outbox(args) => match args.command {
+            OutboxCommand::Flush => {
+                let agent = Agent::new(config)?;
+                let _state_lock = agent.lock_state(cli.force_unlock)?;
+                agent.flush_outbox().await
+            }
+        },
+        Command::Signer(args) => match &args.command {
+            SignerCommand::Rotate(rotate) => {
+                let config_path = match (rotate.write_config, &cli.config) {
+                    (true, None) => {
+                        bail!("--write-config needs --config to know which file to edit")
+                    }
+                    (true, Some(path)) => Some(path),
+                    (false, _) => None,
+                };
+                let rotation = signer::rotate_keystore(&config.signer, &rotate.new_keystore)?;
+                if let Some(path) = config_path {
+                    config::write_key_rotation(path, &rotation)?;
+                }
+                let storage = Storage::new(&config.storage)?;
+                let _state_lock = storage.lock(cli.force_unlock)?;
+                let mut state = storage.load()?;
+                state.key_rotations.push(rotation.clone());
+                storage.save(&state)?;
+                tracing::info!(
+                    old_address = %rotation.old_address,
+                    new_address = %rotation.new_address,
+                    keystore_path = %rotation.keystore_path,
+                    config_updated = config_path.is_some(),
+                    "signer key rotated"
+                );
+                if config_path.is_none() {
+                    tracing::warn!(
+                        "config not updated; set signer.keystore_path to the new keystore and add {} to signer.previous_addresses",
+                        rotation.old_address
+                    );
+                }
+                println!("{}", serde_json::to_string_pretty(&rotation)?);
                 Ok(())
             }
         },
+        Command::Init(_) => unreachable!("init runs before config loading"),
+        Command::Lint(_) => unreachable!("lint runs before config loading"),
         Command::Status => {
             let agent = Agent::new(config)?;
             agent.status().await
         }
+        Command::Whoami => {
+            let mut identities = Vec::new();
+            for account in config.signer.resolved_accounts() {
+                let identity = signer::whoami(&config.network, &account, &config.http)
+                    .await
+                    .with_context(|| format!("signer account {}", account.label))?;
+                identities.push(identity);
+            }
+            println!("{}", serde_json::to_string_pretty(&identities)?);
+            Ok(())
+        }
+        Command::TestKeystore => {
+            let checks = config
+                .signer
+                .resolved_accounts()
+                .iter()
+                .map(signer::test_keystore)
+                .collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&checks)?);
+            let failed = checks.iter().filter(|check| !check.ok()).count();
+            if failed > 0 {
+                bail!("{failed} of {} keystores failed to decrypt", checks.len());
+            }
+            Ok(())
+        }
         Command::Run(args) => {
+            let api = ApiServer::bind(&config.api).await?;
             let agent = Agent::new(config)?;
-            agent.run_loop(args.once).await
+            let _state_lock = agent.lock_state(cli.force_unlock)?;
+            match api {
+                Some(api) => tokio::select! {
+                    result = agent.run_loop(args.once) => result,
+                    result = api.serve(&agent) => result,
+                },
+                None => agent.run_loop(args.once).await,
+            }
         }
         Command::Backfill(args) => {
             let agent = Agent::new(config)?;
-            agent.backfill(args.from_block, args.to_block).await
+            let _state_lock = agent.lock_state(cli.force_unlock)?;
+            agent
+                .backfill(
+                    args.from_block,
+                    args.to_block,
+                    args.capture.as_deref(),
+                    args.review_closed,
+                )
+                .await
         }
         Command::ReviewOnce(args) => {
             let agent = Agent::new(config)?;
-            agent.review_once(args.proposal_id.clone()).await
+            let lookup = ProposalLookup {
+                wait: Duration::from_secs(args.wait_secs),
+                at_block: args.at_block,
+            };
+            match &args.proposal_id {
+                Some(proposal_id) => {
+                    agent
+                        .review_once(proposal_id.clone(), lookup, args.json)
+                        .await
+                }
+                None => {
+                    lookup.check_batch(&args.proposal_ids)?;
+                    agent.review_many(&args.proposal_ids, lookup).await
+                }
+            }
+        }
+        Command::ReviewCid(args) => {
+            let agent = Agent::new(config)?;
+            agent
+                .review_cid(&args.cid, args.name.as_deref(), args.version.as_deref())
+                .await
+        }
+        Command::Inspect(args) => {
+            let agent = Agent::new(config)?;
+            agent.inspect(&args.proposal_id).await
         }
     }
 }
 
+// Dapp authors usually have no agent config, so the profile's review limits are used unless a
+// config file is given; nothing else in the config matters offline.
+async fn lint(cli: &Cli, args: &LintArgs) -> Result<()> {
+    let review = match &cli.config {
+        Some(_) => AppConfig::load(cli)?.review,
+        None => ProfileCatalog::new(None).resolve(&cli.profile)?.review,
+    };
+    let source = LocalBundleSource::new(&args.dir)?;
+    let root = args.dir.display().to_string();
+    let report = review::lint_bundle(&source, &root, &review).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    let critical = report
+        .findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Critical)
+        .count();
+    if critical > 0 {
+        bail!("{critical} critical finding(s) in {root}");
+    }
+    Ok(())
+}
+
 fn should_init_metrics(command: &Command) -> bool {
     matches!(
         command,
@@ -60,17 +403,17 @@ fn init_tracing(json_logs: bool, cfg: &ObservabilityConfig) -> Result<TelemetryG
     let filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
 
-    let mut guard = TelemetryGuard::default();
     let otlp_endpoint = cfg
         .otlp_endpoint
         .clone()
         .filter(|endpoint| !endpoint.trim().is_empty());
 
+    #[cfg(feature = "otlp")]
     if let Some(endpoint) = otlp_endpoint {
         let exporter = opentelemetry_otlp::SpanExporter::builder()
             .with_tonic()
             .with_endpoint(endpoint)
-            .with_timeout(Duration::from_secs(cfg.otlp_timeout_secs))
+            .with_timeout(std::time::Duration::from_secs(cfg.otlp_timeout_secs))
             .build()?;
 
         let resource = Resource::builder_empty()
@@ -102,8 +445,12 @@ fn init_tracing(json_logs: bool, cfg: &ObservabilityConfig) -> Result<TelemetryG
             service_name = %cfg.otlp_service_name,
             "otlp tracing exporter enabled"
         );
-        guard.tracer_provider = Some(tracer_provider);
-    } else if json_logs {
+        return Ok(TelemetryGuard {
+            tracer_provider: Some(tracer_provider),
+        });
+    }
+
+    if json_logs {
         tracing_subscriber::registry()
             .with(filter)
             .with(tracing_subscriber::fmt::layer().json())
@@ -114,15 +461,24 @@ fn init_tracing(json_logs: bool, cfg: &ObservabilityConfig) -> Result<TelemetryG
             .with(tracing_subscriber::fmt::layer())
             .try_init()?;
     }
+    #[cfg(not(feature = "otlp"))]
+    if otlp_endpoint.is_some() {
+        tracing::warn!(
+            "observability.otlp_endpoint is set but this build has no otlp feature; traces are not exported"
+        );
+    }
 
-    Ok(guard)
+    Ok(TelemetryGuard::default())
 }
 
+// Shutting the provider down flushes spans still queued in the batch exporter.
 #[derive(Default)]
 struct TelemetryGuard {
+    #[cfg(feature = "otlp")]
     tracer_provider: Option<SdkTracerProvider>,
 }
 
+#[cfg(feature = "otlp")]
 impl Drop for TelemetryGuard {
     fn drop(&mut self) {
         if let Some(provider) = self.tracer_provider.take() {
@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+
+use alloy::primitives::keccak256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ipfs::Manifest,
+    types::{DecodedAction, ProcessedProposal, Proposal, ReviewResult},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleRecord {
+    pub proposal_id: String,
+    pub dapp_name: String,
+    pub proposer: String,
+    pub root_cid: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BundleIndex {
+    #[serde(default)]
+    pub by_cid: BTreeMap<String, BundleRecord>,
+    #[serde(default)]
+    pub by_fingerprint: BTreeMap<String, BundleRecord>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BundleMatch<'a> {
+    SameCid(&'a BundleRecord),
+    SameLayout(&'a BundleRecord),
+}
+
+impl BundleIndex {
+    pub fn from_proposals<'a>(processed: impl IntoIterator<Item = &'a ProcessedProposal>) -> Self {
+        let mut index = Self::default();
+        for processed in processed {
+            index.record(&processed.proposal, &processed.review);
+        }
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_cid.is_empty() && self.by_fingerprint.is_empty()
+    }
+
+    // The first proposal to ship a bundle keeps the entry, so a copycat can never become the
+    // reference that later proposals are compared against.
+    pub fn record(&mut self, proposal: &Proposal, review: &ReviewResult) {
+        for (index, action) in proposal.actions.iter().enumerate() {
+            let (DecodedAction::PublishDapp { root_cid, name, .. }
+            | DecodedAction::UpgradeDapp { root_cid, name, .. }) = action
+            else {
+                continue;
+            };
+            let record = BundleRecord {
+                proposal_id: proposal.proposal_id.clone(),
+                dapp_name: name.clone(),
+                proposer: proposal.proposer.clone(),
+                root_cid: root_cid.clone(),
+            };
+            if let Some(fingerprint) = review
+                .actions
+                .iter()
+                .find(|action| action.index == index)
+                .and_then(|action| action.fingerprint.clone())
+            {
+                self.by_fingerprint
+                    .entry(fingerprint)
+                    .or_insert_with(|| record.clone());
+            }
+            self.by_cid.entry(root_cid.clone()).or_insert(record);
+        }
+    }
+
+    // Only new publications are checked: upgrades legitimately reuse their own dapp's bundles.
+    pub fn find_copy(
+        &self,
+        proposal: &Proposal,
+        action: &DecodedAction,
+        fingerprint: Option<&str>,
+    ) -> Option<BundleMatch<'_>> {
+        let DecodedAction::PublishDapp { root_cid, name, .. } = action else {
+            return None;
+        };
+        let other_publisher = |record: &BundleRecord| {
+            record.proposal_id != proposal.proposal_id
+                && (normalize_name(&record.dapp_name) != normalize_name(name)
+                    || !record.proposer.eq_ignore_ascii_case(&proposal.proposer))
+        };
+
+        if let Some(record) = self.by_cid.get(root_cid) {
+            return other_publisher(record).then_some(BundleMatch::SameCid(record));
+        }
+        let record = self.by_fingerprint.get(fingerprint?)?;
+        (record.root_cid != *root_cid && other_publisher(record))
+            .then_some(BundleMatch::SameLayout(record))
+    }
+}
+
+// Sorted paths and sizes: identical for bundles that only differ in file contents.
+pub fn manifest_fingerprint(manifest: &Manifest) -> Option<String> {
+    let mut entries = manifest
+        .files
+        .as_ref()?
+        .iter()
+        .map(|file| format!("{}:{}", file.path, file.bytes))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return None;
+    }
+    entries.sort();
+    Some(keccak256(entries.join("\n")).to_string())
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{BundleIndex, BundleMatch, manifest_fingerprint};
+    use crate::{
+        ipfs::{Manifest, ManifestFile},
+        types::{ActionReview, DecodedAction, Proposal, ReviewResult},
+    };
+
+    fn publish(proposal_id: &str, proposer: &str, name: &str, root_cid: &str) -> Proposal {
+        Proposal {
+            proposal_id: proposal_id.to_string(),
+            proposer: proposer.to_string(),
+            description: "publish".to_string(),
+            vote_start: 1,
+            vote_end: 100,
+            block_number: 1,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::PublishDapp {
+                root_cid: root_cid.to_string(),
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+                description: "dapp".to_string(),
+            }],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        }
+    }
+
+    fn review(proposal_id: &str, fingerprint: &str) -> ReviewResult {
+        ReviewResult {
+            proposal_id: proposal_id.to_string(),
+            root_cid: None,
+            findings: vec![],
+            deterministic_score: None,
+            deterministic_weight: None,
+            llm_weight: None,
+            llm_score: None,
+            llm_audit: None,
+            llm_audit_ref: None,
+            score: 0.9,
+            suppressions: vec![],
+            transient_failure: None,
+            reputation: None,
+            score_cap: None,
+            actions: vec![ActionReview {
+                index: 0,
+                label: "publish".to_string(),
+                root_cid: None,
+                score: 0.9,
+                critical: false,
+                fingerprint: Some(fingerprint.to_string()),
+                source_scan: None,
+            }],
+            reviewed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn flags_reused_cid_and_copied_layout_from_other_publishers() {
+        const ORIGINAL: &str = "0x00000000000000000000000000000000000000aa";
+        const COPYCAT: &str = "0x00000000000000000000000000000000000000bb";
+        let mut index = BundleIndex::default();
+        index.record(
+            &publish("1", ORIGINAL, "Swap", "bafyswap"),
+            &review("1", "fp-swap"),
+        );
+        index.record(
+            &publish("2", COPYCAT, "Swap Pro", "bafyswap"),
+            &review("2", "fp-swap"),
+        );
+        assert_eq!(index.by_cid["bafyswap"].proposal_id, "1");
+
+        let renamed = publish("3", COPYCAT, "Swap Pro", "bafyswap");
+        let Some(BundleMatch::SameCid(record)) =
+            index.find_copy(&renamed, &renamed.actions[0], None)
+        else {
+            panic!("expected same-cid match");
+        };
+        assert_eq!(record.dapp_name, "Swap");
+
+        let tweaked = publish("4", COPYCAT, "Swap", "bafytweaked");
+        assert!(matches!(
+            index.find_copy(&tweaked, &tweaked.actions[0], Some("fp-swap")),
+            Some(BundleMatch::SameLayout(record)) if record.root_cid == "bafyswap"
+        ));
+
+        let republished = publish("5", ORIGINAL, " swap ", "bafyswap");
+        assert!(
+            index
+                .find_copy(&republished, &republished.actions[0], Some("fp-swap"))
+                .is_none()
+        );
+        let unrelated = publish("6", COPYCAT, "Lend", "bafylend");
+        assert!(
+            index
+                .find_copy(&unrelated, &unrelated.actions[0], Some("fp-lend"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_file_order_but_not_sizes() {
+        let manifest = |files: &[(&str, u64)]| Manifest {
+            name: None,
+            version: None,
+            description: None,
+            entry: None,
+            files: Some(
+                files
+                    .iter()
+                    .map(|(path, bytes)| ManifestFile {
+                        path: path.to_string(),
+                        bytes: *bytes,
+                    })
+                    .collect(),
+            ),
+        };
+
+        let original = manifest_fingerprint(&manifest(&[("index.html", 10), ("app.js", 20)]));
+        assert!(original.is_some());
+        assert_eq!(
+            original,
+            manifest_fingerprint(&manifest(&[("app.js", 20), ("index.html", 10)]))
+        );
+        assert_ne!(
+            original,
+            manifest_fingerprint(&manifest(&[("app.js", 21), ("index.html", 10)]))
+        );
+        assert!(manifest_fingerprint(&manifest(&[])).is_none());
+    }
+}
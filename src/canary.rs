@@ -0,0 +1,188 @@
+use std::fmt::Write as _;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::VoteChoice;
+
+// Track record of a `decision.canary_mode` trial. Resolutions are kept in the order humans
+// recorded them; only the run of agreements since the last disagreement counts toward promotion.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CanaryState {
+    #[serde(default)]
+    pub resolutions: Vec<CanaryResolution>,
+    #[serde(default)]
+    pub streak: u32,
+    #[serde(default)]
+    pub eligible_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub promoted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryResolution {
+    pub proposal_id: String,
+    pub agent_vote: VoteChoice,
+    pub human_vote: VoteChoice,
+    pub resolved_at: DateTime<Utc>,
+}
+
+impl CanaryResolution {
+    pub fn agreed(&self) -> bool {
+        self.agent_vote == self.human_vote
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanaryProgress {
+    Agreed,
+    Disagreed,
+    // The streak reached the requirement for the first time; votes are still held.
+    Eligible,
+    Promoted,
+}
+
+impl CanaryState {
+    // Promotion is one-way: later disagreements reset the streak but do not stop live voting.
+    pub fn holds_votes(&self) -> bool {
+        self.promoted_at.is_none()
+    }
+
+    pub fn record(
+        &mut self,
+        resolution: CanaryResolution,
+        required: u32,
+        auto_promote: bool,
+    ) -> Result<CanaryProgress> {
+        if self
+            .resolutions
+            .iter()
+            .any(|previous| previous.proposal_id == resolution.proposal_id)
+        {
+            bail!(
+                "proposal {} was already resolved for the canary",
+                resolution.proposal_id
+            );
+        }
+        let at = resolution.resolved_at;
+        let agreed = resolution.agreed();
+        self.resolutions.push(resolution);
+        if !agreed {
+            self.streak = 0;
+            self.eligible_at = None;
+            return Ok(CanaryProgress::Disagreed);
+        }
+
+        self.streak += 1;
+        if self.streak < required {
+            return Ok(CanaryProgress::Agreed);
+        }
+        let newly_eligible = self.eligible_at.is_none();
+        self.eligible_at.get_or_insert(at);
+        if auto_promote && self.promoted_at.is_none() {
+            self.promoted_at = Some(at);
+            Ok(CanaryProgress::Promoted)
+        } else if newly_eligible {
+            Ok(CanaryProgress::Eligible)
+        } else {
+            Ok(CanaryProgress::Agreed)
+        }
+    }
+
+    pub fn render_report(&self, required: u32) -> String {
+        let agreed = self
+            .resolutions
+            .iter()
+            .filter(|resolution| resolution.agreed())
+            .count();
+        let disagreed = self.resolutions.len() - agreed;
+        let status = match (self.promoted_at, self.eligible_at) {
+            (Some(at), _) => format!("promoted to live voting at {at}"),
+            (None, Some(at)) => format!("eligible for auto-vote since {at}; votes held in dry-run"),
+            (None, None) => "votes held in dry-run".to_string(),
+        };
+        let mut out = format!(
+            "canary: {}/{required} consecutive agreements ({agreed} agreed, {disagreed} disagreed); {status}\n",
+            self.streak
+        );
+        for resolution in self.resolutions.iter().filter(|r| !r.agreed()) {
+            let _ = writeln!(
+                out,
+                "  disagreement: proposal {} agent={:?} human={:?} at {}",
+                resolution.proposal_id,
+                resolution.agent_vote,
+                resolution.human_vote,
+                resolution.resolved_at
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{CanaryProgress, CanaryResolution, CanaryState};
+    use crate::types::VoteChoice;
+
+    fn resolution(id: &str, agent: VoteChoice, human: VoteChoice) -> CanaryResolution {
+        CanaryResolution {
+            proposal_id: id.to_string(),
+            agent_vote: agent,
+            human_vote: human,
+            resolved_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn disagreements_reset_the_streak_before_eligibility() {
+        let mut canary = CanaryState::default();
+        let mut record = |id: &str, human: VoteChoice| {
+            canary
+                .record(resolution(id, VoteChoice::For, human), 2, false)
+                .expect("record")
+        };
+        assert_eq!(record("1", VoteChoice::For), CanaryProgress::Agreed);
+        assert_eq!(record("2", VoteChoice::Against), CanaryProgress::Disagreed);
+        assert_eq!(record("3", VoteChoice::For), CanaryProgress::Agreed);
+        assert_eq!(record("4", VoteChoice::For), CanaryProgress::Eligible);
+        assert_eq!(record("5", VoteChoice::For), CanaryProgress::Agreed);
+        assert!(canary.holds_votes());
+        assert_eq!(canary.streak, 3);
+
+        let err = canary
+            .record(resolution("4", VoteChoice::For, VoteChoice::For), 2, false)
+            .expect_err("duplicate");
+        assert!(err.to_string().contains("already resolved"));
+
+        let report = canary.render_report(2);
+        assert!(report.starts_with("canary: 3/2 consecutive agreements (4 agreed, 1 disagreed)"));
+        assert!(report.contains("proposal 2 agent=For human=Against"));
+    }
+
+    #[test]
+    fn auto_promote_releases_votes_once_the_streak_is_met() {
+        let mut canary = CanaryState::default();
+        let progress = canary
+            .record(
+                resolution("1", VoteChoice::Against, VoteChoice::Against),
+                1,
+                true,
+            )
+            .expect("record");
+        assert_eq!(progress, CanaryProgress::Promoted);
+        assert!(!canary.holds_votes());
+
+        canary
+            .record(
+                resolution("2", VoteChoice::For, VoteChoice::Abstain),
+                1,
+                true,
+            )
+            .expect("record");
+        assert_eq!(canary.streak, 0);
+        assert!(!canary.holds_votes());
+    }
+}
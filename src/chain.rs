@@ -1,33 +1,51 @@
-use std::str::FromStr;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
 
 use alloy::{
-    primitives::{Address, U256},
+    contract::Error as ContractError,
+    eips::BlockNumberOrTag,
+    network::TransactionResponse,
+    primitives::{Address, B256, U256},
     providers::{DynProvider, Provider, ProviderBuilder},
-    rpc::types::Filter,
+    rpc::types::{Filter, Log as RpcLog},
 };
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use tokio::sync::Mutex;
 
 use crate::{
-    config::NetworkConfig,
-    decoder::{decode_proposal_log, proposal_created_topic0},
-    observability,
-    types::Proposal,
+    config::{HttpConfig, NetworkConfig, redact_url_secrets},
+    decoder::{ProposalCreatedAbi, targets_address},
+    observability, rpc_stats,
+    signer::{ACTIVE_PROPOSAL_STATE, ClockMode, IVfiGovernor},
+    types::{Proposal, ProposalCreationContext, ProposalLifecycle, ProposalState, ProposalTally},
 };
 
+const CREATION_WINDOW_MARGIN_BLOCKS: u64 = 1_024;
+
 pub struct ChainAdapter {
     rpc_url: String,
     governor_address: Option<Address>,
     dapp_registry_address: String,
-    topic0: String,
+    include_non_registry: bool,
+    proposal_event: ProposalCreatedAbi,
     transport: TransportKind,
+    http: HttpConfig,
     provider: Mutex<Option<DynProvider>>,
+    clock_mode: OnceLock<ClockMode>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum TransportKind {
     Http,
     Ws,
+    File,
 }
 
 impl TransportKind {
@@ -35,12 +53,136 @@ impl TransportKind {
         match self {
             Self::Http => "http",
             Self::Ws => "ws",
+            Self::File => "file",
         }
     }
 }
 
+#[derive(Debug, Default)]
+pub struct ProposalScan {
+    pub proposals: Vec<Proposal>,
+    // Raw JSON of logs that failed to decode and were skipped.
+    pub failed_logs: Vec<Value>,
+}
+
+#[async_trait]
+pub trait ChainSource: Send + Sync {
+    fn transport(&self) -> TransportKind;
+    async fn health_check(&self) -> Result<u64>;
+    async fn latest_block(&self) -> Result<u64>;
+    async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>>;
+
+    async fn scan_proposals(&self, from_block: u64, to_block: u64) -> Result<ProposalScan> {
+        Ok(ProposalScan {
+            proposals: self.fetch_proposals(from_block, to_block).await?,
+            failed_logs: Vec::new(),
+        })
+    }
+
+    async fn fetch_proposal_by_id(&self, proposal_id: &str, from_block: u64) -> Result<Proposal> {
+        let requested = parse_proposal_id(proposal_id)
+            .with_context(|| format!("invalid proposal id {}", proposal_id))?;
+        let matches =
+            |proposal: &Proposal| parse_proposal_id(&proposal.proposal_id).ok() == Some(requested);
+
+        if let Some((start, end)) = targeted_window(self, proposal_id).await {
+            match self.fetch_proposals(start, end).await {
+                Ok(proposals) => {
+                    if let Some(proposal) = proposals.into_iter().find(matches) {
+                        return Ok(proposal);
+                    }
+                    tracing::info!(
+                        proposal_id,
+                        start_block = start,
+                        end_block = end,
+                        "proposal not in targeted log window; falling back to full scan"
+                    );
+                }
+                Err(err) => tracing::warn!(
+                    proposal_id,
+                    error = %err,
+                    "targeted proposal log fetch failed; falling back to full scan"
+                ),
+            }
+        }
+
+        let latest = self.latest_block().await?;
+        self.fetch_proposals(from_block, latest)
+            .await?
+            .into_iter()
+            .find(matches)
+            .ok_or_else(|| anyhow!("proposal {proposal_id} not found"))
+    }
+
+    // A narrow block range expected to hold the proposal's ProposalCreated log, so a
+    // lookup by id does not have to scan every log since `from_block`.
+    async fn proposal_log_window(&self, _proposal_id: &str) -> Result<Option<(u64, u64)>> {
+        Ok(None)
+    }
+
+    async fn fetch_proposal_with_topics(
+        &self,
+        proposal_id: &str,
+        from_block: u64,
+    ) -> Result<(Proposal, Vec<String>)> {
+        let proposal = self.fetch_proposal_by_id(proposal_id, from_block).await?;
+        Ok((proposal, Vec::new()))
+    }
+
+    // vote_start and vote_end are in this unit. Sources that cannot read the governor count in
+    // blocks.
+    async fn clock_mode(&self) -> ClockMode {
+        ClockMode::BlockNumber
+    }
+
+    async fn latest_block_timestamp(&self) -> Result<u64> {
+        Err(anyhow!(
+            "{} chain source cannot read block timestamps",
+            self.transport().as_str()
+        ))
+    }
+
+    async fn is_proposal_active(&self, proposal: &Proposal) -> Result<bool> {
+        let now = match self.clock_mode().await {
+            ClockMode::BlockNumber => self.latest_block().await?,
+            ClockMode::Timestamp => self.latest_block_timestamp().await?,
+        };
+        Ok(proposal.vote_start <= now && now < proposal.vote_end)
+    }
+
+    // None when the source cannot read governor state, as with file fixtures.
+    async fn proposal_lifecycle(&self, _proposal_id: &str) -> Result<Option<ProposalLifecycle>> {
+        Ok(None)
+    }
+
+    // None when the source cannot read governor state, as with file fixtures.
+    async fn proposal_tally(&self, _proposal_id: &str) -> Result<Option<ProposalTally>> {
+        Ok(None)
+    }
+
+    async fn fetch_tx_context(&self, tx_hash: &str) -> Result<ProposalCreationContext> {
+        Err(anyhow!(
+            "{} chain source cannot fetch transaction {tx_hash}",
+            self.transport().as_str()
+        ))
+    }
+
+    // The topic0 of every log the governor emitted in the range, decoded or not, to tell which
+    // ProposalCreated signature it emits.
+    async fn governor_log_topics(&self, _from_block: u64, _to_block: u64) -> Result<Vec<B256>> {
+        Err(anyhow!(
+            "{} chain source cannot query raw logs",
+            self.transport().as_str()
+        ))
+    }
+}
+
 impl ChainAdapter {
-    pub fn new(network: &NetworkConfig) -> Self {
+    pub fn new(
+        network: &NetworkConfig,
+        http: &HttpConfig,
+        include_non_registry: bool,
+    ) -> Result<Self> {
         let governor_address = Address::from_str(&network.governor_address).ok();
         let transport = if is_ws_url(&network.rpc_url) {
             TransportKind::Ws
@@ -48,21 +190,147 @@ impl ChainAdapter {
             TransportKind::Http
         };
 
-        Self {
+        Ok(Self {
             rpc_url: network.rpc_url.clone(),
             governor_address,
             dapp_registry_address: network.dapp_registry_address.clone(),
-            topic0: proposal_created_topic0(),
+            include_non_registry,
+            proposal_event: ProposalCreatedAbi::from_signature(
+                network.proposal_created_signature.as_deref(),
+            )?,
             transport,
+            http: http.clone(),
             provider: Mutex::new(None),
+            clock_mode: OnceLock::new(),
+        })
+    }
+
+    pub async fn fetch_proposal_log_by_id(
+        &self,
+        proposal_id: &str,
+        from_block: u64,
+    ) -> Result<(RpcLog, Proposal)> {
+        let requested = parse_proposal_id(proposal_id)
+            .with_context(|| format!("invalid proposal id {}", proposal_id))?;
+
+        if let Some((start, end)) = targeted_window(self, proposal_id).await {
+            match self.find_proposal_log(requested, start, end).await {
+                Ok(Some(found)) => return Ok(found),
+                Ok(None) => tracing::info!(
+                    proposal_id,
+                    start_block = start,
+                    end_block = end,
+                    "proposal not in targeted log window; falling back to full scan"
+                ),
+                Err(err) => tracing::warn!(
+                    proposal_id,
+                    error = %err,
+                    "targeted proposal log fetch failed; falling back to full scan"
+                ),
+            }
+        }
+
+        let latest = self.latest_block().await?;
+        self.find_proposal_log(requested, from_block, latest)
+            .await?
+            .ok_or_else(|| anyhow!("proposal {proposal_id} not found"))
+    }
+
+    async fn find_proposal_log(
+        &self,
+        requested: U256,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Option<(RpcLog, Proposal)>> {
+        let logs = self
+            .fetch_logs(Some(self.proposal_event.topic0()), from_block, to_block)
+            .await?;
+        for log in logs {
+            let Some(proposal) =
+                decode_or_skip(&log, &self.proposal_event, &self.dapp_registry_address)
+            else {
+                continue;
+            };
+            let Ok(candidate) = parse_proposal_id(&proposal.proposal_id) else {
+                tracing::warn!(
+                    proposal_id = %proposal.proposal_id,
+                    "skipping proposal with unparsable proposal id"
+                );
+                continue;
+            };
+
+            if candidate == requested {
+                return Ok(Some((log, proposal)));
+            }
         }
+
+        Ok(None)
     }
 
-    pub fn transport(&self) -> TransportKind {
+    // Without a topic0, every log the governor emitted in the range.
+    async fn fetch_logs(
+        &self,
+        topic0: Option<B256>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<RpcLog>> {
+        let Some(governor) = self.governor_address else {
+            return Ok(Vec::new());
+        };
+
+        let mut filter = Filter::new()
+            .address(governor)
+            .from_block(from_block)
+            .to_block(to_block);
+        if let Some(topic0) = topic0 {
+            filter = filter.event_signature(topic0);
+        }
+
+        let provider = self.provider().await?;
+        let rpc_fetch_started = observability::now();
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .with_context(|| {
+                format!("failed to fetch governor logs in range [{from_block}, {to_block}]")
+            })
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_logs"))?;
+        observability::observe_stage_latency("rpc_fetch_logs", rpc_fetch_started);
+
+        Ok(logs)
+    }
+
+    async fn provider(&self) -> Result<DynProvider> {
+        let mut guard = self.provider.lock().await;
+        if let Some(provider) = guard.as_ref() {
+            return Ok(provider.clone());
+        }
+
+        let mut rpc_url = self.rpc_url.clone();
+        redact_url_secrets(&mut rpc_url);
+        tracing::info!(
+            rpc_url = %rpc_url,
+            transport = self.transport.as_str(),
+            "connecting chain provider"
+        );
+
+        let client = rpc_stats::instrumented_client(&self.rpc_url, &self.http)
+            .await
+            .inspect_err(|_| observability::record_provider_error("rpc", "connect"))?;
+        let provider = ProviderBuilder::new().connect_client(client).erased();
+
+        *guard = Some(provider.clone());
+        Ok(provider)
+    }
+}
+
+#[async_trait]
+impl ChainSource for ChainAdapter {
+    fn transport(&self) -> TransportKind {
         self.transport
     }
 
-    pub async fn health_check(&self) -> Result<u64> {
+    async fn health_check(&self) -> Result<u64> {
         let provider = self.provider().await?;
         provider
             .get_chain_id()
@@ -71,7 +339,7 @@ impl ChainAdapter {
             .inspect_err(|_| observability::record_provider_error("rpc", "get_chain_id"))
     }
 
-    pub async fn latest_block(&self) -> Result<u64> {
+    async fn latest_block(&self) -> Result<u64> {
         let provider = self.provider().await?;
         provider
             .get_block_number()
@@ -80,107 +348,374 @@ impl ChainAdapter {
             .inspect_err(|_| observability::record_provider_error("rpc", "get_block_number"))
     }
 
-    pub async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
-        let Some(governor) = self.governor_address else {
-            return Ok(Vec::new());
+    async fn clock_mode(&self) -> ClockMode {
+        if let Some(mode) = self.clock_mode.get() {
+            return *mode;
+        }
+        let Some(governor_address) = self.governor_address else {
+            return ClockMode::BlockNumber;
         };
+        let read = match self.provider().await {
+            Ok(provider) => {
+                match IVfiGovernor::new(governor_address, provider)
+                    .CLOCK_MODE()
+                    .call()
+                    .await
+                {
+                    Ok(value) => Ok(Some(value)),
+                    Err(err) if is_contract_revert(&err) => Ok(None),
+                    Err(err) => Err(anyhow!(err)),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        cache_clock_mode(&self.clock_mode, read)
+    }
 
-        let topic0 = self
-            .topic0
-            .parse::<alloy::primitives::B256>()
-            .with_context(|| format!("invalid topic0 hash {}", self.topic0))?;
+    async fn latest_block_timestamp(&self) -> Result<u64> {
+        let provider = self.provider().await?;
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await
+            .context("failed to read latest block")
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_block"))?
+            .ok_or_else(|| anyhow!("latest block not returned by rpc"))?;
+        Ok(block.header.timestamp)
+    }
 
-        let filter = Filter::new()
-            .address(governor)
-            .event_signature(topic0)
-            .from_block(from_block)
-            .to_block(to_block);
+    async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+        Ok(self.scan_proposals(from_block, to_block).await?.proposals)
+    }
 
+    async fn scan_proposals(&self, from_block: u64, to_block: u64) -> Result<ProposalScan> {
+        let logs = self
+            .fetch_logs(Some(self.proposal_event.topic0()), from_block, to_block)
+            .await?;
+        Ok(decode_proposal_logs(
+            logs,
+            &self.proposal_event,
+            &self.dapp_registry_address,
+            self.include_non_registry,
+        ))
+    }
+
+    async fn governor_log_topics(&self, from_block: u64, to_block: u64) -> Result<Vec<B256>> {
+        Ok(self
+            .fetch_logs(None, from_block, to_block)
+            .await?
+            .iter()
+            .filter_map(|log| log.topic0().copied())
+            .collect())
+    }
+
+    async fn fetch_proposal_by_id(&self, proposal_id: &str, from_block: u64) -> Result<Proposal> {
+        let (_, proposal) = self
+            .fetch_proposal_log_by_id(proposal_id, from_block)
+            .await?;
+        Ok(proposal)
+    }
+
+    // The proposal was created votingDelay before its snapshot. The margin covers a voting
+    // delay that governance raised after the proposal was created.
+    async fn proposal_log_window(&self, proposal_id: &str) -> Result<Option<(u64, u64)>> {
+        let Some(governor_address) = self.governor_address else {
+            return Ok(None);
+        };
+        let id = parse_proposal_id(proposal_id)?;
         let provider = self.provider().await?;
-        let rpc_fetch_started = observability::now();
-        let logs = provider
-            .get_logs(&filter)
-            .await
-            .with_context(|| {
-                format!("failed to fetch ProposalCreated logs in range [{from_block}, {to_block}]")
-            })
-            .inspect_err(|_| observability::record_provider_error("rpc", "get_logs"))?;
-        observability::observe_stage_latency("rpc_fetch_logs", rpc_fetch_started);
+        let governor = IVfiGovernor::new(governor_address, provider);
 
-        let mut out = Vec::with_capacity(logs.len());
-        for log in logs {
-            let decode_started = observability::now();
-            match decode_proposal_log(&log, &self.dapp_registry_address) {
-                Ok(proposal) => out.push(proposal),
-                Err(err) => {
-                    observability::record_provider_error("decoder", "proposal_log");
-                    observability::incr_proposals_failed("decode");
-                    tracing::warn!(error = %err, "failed to decode proposal log; skipping");
-                }
-            }
-            observability::observe_stage_latency("decode", decode_started);
+        if self.clock_mode().await == ClockMode::Timestamp {
+            return Ok(None);
         }
 
-        Ok(out)
+        let proposer = governor
+            .proposalProposer(id)
+            .call()
+            .await
+            .context("failed to read proposal proposer")
+            .inspect_err(|_| observability::record_provider_error("rpc", "governor_proposer"))?;
+        if proposer == Address::ZERO {
+            bail!("governor has no proposal {proposal_id}");
+        }
+        let snapshot = governor
+            .proposalSnapshot(id)
+            .call()
+            .await
+            .context("failed to read proposal snapshot")
+            .inspect_err(|_| observability::record_provider_error("rpc", "governor_snapshot"))?;
+        let voting_delay = governor
+            .votingDelay()
+            .call()
+            .await
+            .context("failed to read voting delay")
+            .inspect_err(|_| {
+                observability::record_provider_error("rpc", "governor_voting_delay")
+            })?;
+
+        let snapshot = u64::try_from(snapshot).context("proposal snapshot exceeds u64")?;
+        let voting_delay = u64::try_from(voting_delay).unwrap_or(u64::MAX);
+        let created = snapshot.saturating_sub(voting_delay);
+        Ok(Some((
+            created.saturating_sub(CREATION_WINDOW_MARGIN_BLOCKS),
+            snapshot,
+        )))
+    }
+
+    async fn is_proposal_active(&self, proposal: &Proposal) -> Result<bool> {
+        let governor = self
+            .governor_address
+            .ok_or_else(|| anyhow!("governor address is not configured"))?;
+        let proposal_id = parse_proposal_id(&proposal.proposal_id)?;
+        let provider = self.provider().await?;
+        let state = IVfiGovernor::new(governor, provider)
+            .state(proposal_id)
+            .call()
+            .await
+            .map_err(|err| governor_state_error(&proposal.proposal_id, err))?;
+        Ok(state == ACTIVE_PROPOSAL_STATE)
+    }
+
+    // proposalEta is 0 until queued and reverts on governors without a timelock extension;
+    // both read as no eta.
+    async fn proposal_lifecycle(&self, proposal_id: &str) -> Result<Option<ProposalLifecycle>> {
+        let Some(governor_address) = self.governor_address else {
+            return Ok(None);
+        };
+        let id = parse_proposal_id(proposal_id)?;
+        let provider = self.provider().await?;
+        let governor = IVfiGovernor::new(governor_address, provider);
+        let raw_state = governor
+            .state(id)
+            .call()
+            .await
+            .map_err(|err| governor_state_error(proposal_id, err))?;
+        let state = ProposalState::from_governor(raw_state)
+            .ok_or_else(|| anyhow!("governor returned unknown proposal state {raw_state}"))?;
+        let eta = match state {
+            ProposalState::Queued | ProposalState::Executed => governor
+                .proposalEta(id)
+                .call()
+                .await
+                .ok()
+                .and_then(|eta| u64::try_from(eta).ok())
+                .filter(|eta| *eta > 0),
+            _ => None,
+        };
+        Ok(Some(ProposalLifecycle {
+            state,
+            eta,
+            checked_at: Utc::now(),
+        }))
+    }
+
+    async fn proposal_tally(&self, proposal_id: &str) -> Result<Option<ProposalTally>> {
+        let Some(governor_address) = self.governor_address else {
+            return Ok(None);
+        };
+        let id = parse_proposal_id(proposal_id)?;
+        let provider = self.provider().await?;
+        let governor = IVfiGovernor::new(governor_address, provider);
+        let raw_state = governor
+            .state(id)
+            .call()
+            .await
+            .map_err(|err| governor_state_error(proposal_id, err))?;
+        let state = ProposalState::from_governor(raw_state)
+            .ok_or_else(|| anyhow!("governor returned unknown proposal state {raw_state}"))?;
+        let votes = governor
+            .proposalVotes(id)
+            .call()
+            .await
+            .context("failed to read proposal votes")
+            .inspect_err(|_| observability::record_provider_error("rpc", "governor_votes"))?;
+        Ok(Some(ProposalTally {
+            state,
+            for_votes: votes.forVotes.to_string(),
+            against_votes: votes.againstVotes.to_string(),
+            abstain_votes: votes.abstainVotes.to_string(),
+        }))
     }
 
-    pub async fn fetch_proposal_by_id(
+    async fn fetch_proposal_with_topics(
         &self,
         proposal_id: &str,
         from_block: u64,
-    ) -> Result<Proposal> {
-        let requested = parse_proposal_id(proposal_id)
-            .with_context(|| format!("invalid proposal id {}", proposal_id))?;
-        let latest = self.latest_block().await?;
-        let proposals = self.fetch_proposals(from_block, latest).await?;
+    ) -> Result<(Proposal, Vec<String>)> {
+        let (log, proposal) = self
+            .fetch_proposal_log_by_id(proposal_id, from_block)
+            .await?;
+        let topics = log
+            .topics()
+            .iter()
+            .map(|topic| format!("{:#x}", topic))
+            .collect();
+        Ok((proposal, topics))
+    }
 
-        for proposal in proposals {
-            let Ok(candidate) = parse_proposal_id(&proposal.proposal_id) else {
-                tracing::warn!(
-                    proposal_id = %proposal.proposal_id,
-                    "skipping proposal with unparsable proposal id"
-                );
-                continue;
-            };
+    async fn fetch_tx_context(&self, tx_hash: &str) -> Result<ProposalCreationContext> {
+        let hash = tx_hash
+            .parse::<B256>()
+            .with_context(|| format!("invalid transaction hash {tx_hash}"))?;
+        let provider = self.provider().await?;
 
-            if candidate == requested {
-                return Ok(proposal);
-            }
-        }
+        let tx = provider
+            .get_transaction_by_hash(hash)
+            .await
+            .with_context(|| format!("failed to fetch transaction {tx_hash}"))
+            .inspect_err(|_| {
+                observability::record_provider_error("rpc", "get_transaction_by_hash")
+            })?
+            .ok_or_else(|| anyhow!("transaction {tx_hash} not found"))?;
+        let sender = tx.from();
+
+        let code = provider
+            .get_code_at(sender)
+            .await
+            .with_context(|| format!("failed to fetch code for sender {sender:#x}"))
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_code_at"))?;
+
+        let block_timestamp = match tx.block_number {
+            Some(number) => provider
+                .get_block_by_number(number.into())
+                .await
+                .with_context(|| format!("failed to fetch block {number}"))
+                .inspect_err(|_| {
+                    observability::record_provider_error("rpc", "get_block_by_number")
+                })?
+                .and_then(|block| {
+                    DateTime::<Utc>::from_timestamp(block.header.timestamp as i64, 0)
+                }),
+            None => None,
+        };
+
+        let gas_used = provider
+            .get_transaction_receipt(hash)
+            .await
+            .with_context(|| format!("failed to fetch receipt for {tx_hash}"))
+            .inspect_err(|_| {
+                observability::record_provider_error("rpc", "get_transaction_receipt")
+            })?
+            .map(|receipt| receipt.gas_used);
+
+        Ok(ProposalCreationContext {
+            sender: format!("{sender:#x}"),
+            sender_is_contract: !code.is_empty(),
+            block_timestamp,
+            gas_used,
+        })
+    }
+}
+
+pub struct FileChainSource {
+    path: PathBuf,
+    chain_id: u64,
+    proposals: Vec<Proposal>,
+}
 
-        Err(anyhow!("proposal {proposal_id} not found"))
+impl FileChainSource {
+    pub fn open(path: impl AsRef<Path>, chain_id: u64) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read proposal fixture {}", path.display()))?;
+        let mut proposals = parse_proposal_fixture(&raw)
+            .with_context(|| format!("failed to parse proposal fixture {}", path.display()))?;
+        proposals.sort_by_key(|proposal| (proposal.block_number, proposal.tx_hash.clone()));
+        Ok(Self {
+            path,
+            chain_id,
+            proposals,
+        })
     }
 
-    async fn provider(&self) -> Result<DynProvider> {
-        let mut guard = self.provider.lock().await;
-        if let Some(provider) = guard.as_ref() {
-            return Ok(provider.clone());
-        }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
 
-        tracing::info!(
-            rpc_url = %self.rpc_url,
-            transport = self.transport.as_str(),
-            "connecting chain provider"
-        );
+#[async_trait]
+impl ChainSource for FileChainSource {
+    fn transport(&self) -> TransportKind {
+        TransportKind::File
+    }
 
-        let provider = ProviderBuilder::new()
-            .connect(&self.rpc_url)
-            .await
-            .with_context(|| format!("failed to connect to rpc url {}", self.rpc_url))
-            .inspect_err(|_| observability::record_provider_error("rpc", "connect"))?
-            .erased();
+    async fn health_check(&self) -> Result<u64> {
+        Ok(self.chain_id)
+    }
 
-        *guard = Some(provider.clone());
-        Ok(provider)
+    async fn latest_block(&self) -> Result<u64> {
+        Ok(self
+            .proposals
+            .iter()
+            .map(|proposal| proposal.block_number)
+            .max()
+            .unwrap_or(0))
+    }
+
+    async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+        Ok(self
+            .proposals
+            .iter()
+            .filter(|proposal| (from_block..=to_block).contains(&proposal.block_number))
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_tx_context(&self, tx_hash: &str) -> Result<ProposalCreationContext> {
+        self.proposals
+            .iter()
+            .find(|proposal| {
+                proposal
+                    .tx_hash
+                    .as_deref()
+                    .is_some_and(|hash| hash.eq_ignore_ascii_case(tx_hash))
+            })
+            .and_then(|proposal| proposal.creation_context.clone())
+            .ok_or_else(|| anyhow!("no creation context recorded for {tx_hash}"))
     }
 }
 
+fn parse_proposal_fixture(raw: &str) -> Result<Vec<Proposal>> {
+    let trimmed = raw.trim_start();
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed).context("invalid JSON proposal array");
+    }
+
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line).with_context(|| format!("invalid JSONL at line {}", idx + 1))
+        })
+        .collect()
+}
+
+pub fn write_proposal_fixture(path: &Path, proposals: &[Proposal]) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut out = String::new();
+    for proposal in proposals {
+        out.push_str(&serde_json::to_string(proposal)?);
+        out.push('\n');
+    }
+    fs::write(path, out).with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub fn file_fixture_path(url: &str) -> Option<&str> {
+    url.trim().strip_prefix("file://")
+}
+
 fn is_ws_url(url: &str) -> bool {
     let trimmed = url.trim().to_ascii_lowercase();
     trimmed.starts_with("ws://") || trimmed.starts_with("wss://")
 }
 
-fn parse_proposal_id(value: &str) -> Result<U256> {
+pub fn parse_proposal_id(value: &str) -> Result<U256> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         return Err(anyhow!("proposal id is empty"));
@@ -197,9 +732,404 @@ fn parse_proposal_id(value: &str) -> Result<U256> {
     U256::from_str(trimmed).map_err(|err| anyhow!("invalid decimal proposal id {}: {}", value, err))
 }
 
+// `state()` reverts for ids the governor never created, and an address without governor code
+// answers with empty return data. Only the remaining failures are the node's fault.
+pub(crate) fn governor_state_error(proposal_id: &str, err: ContractError) -> anyhow::Error {
+    if is_contract_revert(&err) {
+        return nonexistent_proposal(proposal_id, err);
+    }
+    observability::record_provider_error("rpc", "governor_state");
+    anyhow!(err).context("rpc error while reading proposal state")
+}
+
+fn is_contract_revert(err: &ContractError) -> bool {
+    match err {
+        ContractError::ZeroData(..) => true,
+        ContractError::TransportError(err) => err
+            .as_error_resp()
+            .is_some_and(|resp| is_revert_response(resp.code, &resp.message)),
+        _ => false,
+    }
+}
+
+// The governor's clock is read once per process. `read` is None when CLOCK_MODE reverted, i.e.
+// the governor predates ERC-6372 and counts in blocks. A failed read is not cached, so a flaky
+// RPC only costs block-number units until the next call.
+pub(crate) fn cache_clock_mode(
+    cache: &OnceLock<ClockMode>,
+    read: Result<Option<String>>,
+) -> ClockMode {
+    match read {
+        Ok(value) => *cache.get_or_init(|| {
+            let mode = value
+                .as_deref()
+                .map_or(ClockMode::BlockNumber, ClockMode::from_clock_mode);
+            tracing::info!(
+                clock_mode = value.as_deref().unwrap_or("unsupported"),
+                resolved = mode.as_str(),
+                "detected governor clock mode"
+            );
+            mode
+        }),
+        Err(err) => {
+            tracing::warn!(
+                error = %err,
+                "failed to read governor CLOCK_MODE; assuming block-number clock for now"
+            );
+            ClockMode::BlockNumber
+        }
+    }
+}
+
+// Geth and most clients answer a reverted eth_call with code 3; older nodes only say so in the
+// message.
+pub(crate) fn is_revert_response(code: i64, message: &str) -> bool {
+    code == 3 || message.contains("revert")
+}
+
+pub(crate) fn nonexistent_proposal(
+    proposal_id: &str,
+    err: impl Into<anyhow::Error>,
+) -> anyhow::Error {
+    err.into().context(format!(
+        "proposal {proposal_id} does not exist or is not a governor proposal"
+    ))
+}
+
+async fn targeted_window<C: ChainSource + ?Sized>(
+    source: &C,
+    proposal_id: &str,
+) -> Option<(u64, u64)> {
+    match source.proposal_log_window(proposal_id).await {
+        Ok(window) => window,
+        Err(err) => {
+            tracing::warn!(
+                proposal_id,
+                error = %err,
+                "targeted proposal lookup failed; falling back to full scan"
+            );
+            None
+        }
+    }
+}
+
+// One malformed event must not abort the whole range, so failures are logged with enough
+// coordinates to find the log on an explorer and then skipped. Proposals that never touch the
+// registry are dropped here, before any IPFS or LLM work, unless `include_non_registry` is set.
+pub(crate) fn decode_proposal_logs(
+    logs: Vec<RpcLog>,
+    proposal_event: &ProposalCreatedAbi,
+    dapp_registry: &str,
+    include_non_registry: bool,
+) -> ProposalScan {
+    let mut scan = ProposalScan {
+        proposals: Vec::with_capacity(logs.len()),
+        failed_logs: Vec::new(),
+    };
+    for log in logs {
+        let decode_started = observability::now();
+        if let Some(proposal) = decode_or_skip(&log, proposal_event, dapp_registry) {
+            if include_non_registry || targets_address(&proposal.targets, dapp_registry) {
+                scan.proposals.push(proposal);
+            } else {
+                tracing::debug!(
+                    proposal_id = %proposal.proposal_id,
+                    block_number = proposal.block_number,
+                    targets = proposal.targets.len(),
+                    "skipping proposal that does not target the dapp registry"
+                );
+            }
+        } else {
+            observability::record_provider_error("decoder", "proposal_log");
+            observability::incr_proposals_failed("decode");
+            scan.failed_logs
+                .push(serde_json::to_value(&log).unwrap_or(Value::Null));
+        }
+        observability::observe_stage_latency("decode", decode_started);
+    }
+    scan
+}
+
+fn decode_or_skip(
+    log: &RpcLog,
+    proposal_event: &ProposalCreatedAbi,
+    dapp_registry: &str,
+) -> Option<Proposal> {
+    let tx_hash = log
+        .transaction_hash
+        .map(|hash| format!("{hash:#x}"))
+        .unwrap_or_else(|| "unknown".to_string());
+    let block_number = log.block_number.unwrap_or_default();
+    let log_index = log.log_index.unwrap_or_default();
+    tracing::trace!(
+        tx_hash = %tx_hash,
+        block_number,
+        log_index,
+        topics = ?log.topics(),
+        data_len = log.data().data.len(),
+        "decoding ProposalCreated log"
+    );
+
+    match proposal_event.decode(log, dapp_registry) {
+        Ok(proposal) => {
+            tracing::trace!(
+                tx_hash = %tx_hash,
+                block_number,
+                log_index,
+                proposal_id = %proposal.proposal_id,
+                targets = proposal.targets.len(),
+                actions = proposal.actions.len(),
+                "decoded ProposalCreated log"
+            );
+            Some(proposal)
+        }
+        Err(err) => {
+            observability::incr_proposal_logs_skipped();
+            tracing::warn!(
+                tx_hash = %tx_hash,
+                block_number,
+                log_index,
+                error = %err,
+                "failed to decode proposal log; skipping"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    use super::{ChainSource, TransportKind};
+    use crate::types::Proposal;
+
+    pub(crate) struct MockChain {
+        pub latest_block: u64,
+        pub proposals: Vec<Proposal>,
+    }
+
+    #[async_trait]
+    impl ChainSource for MockChain {
+        fn transport(&self) -> TransportKind {
+            TransportKind::Http
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            Ok(31337)
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            Ok(self.latest_block)
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            Ok(self
+                .proposals
+                .iter()
+                .filter(|proposal| (from_block..=to_block).contains(&proposal.block_number))
+                .cloned()
+                .collect())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{is_ws_url, parse_proposal_id};
+    use alloy::{
+        contract::Error as ContractError,
+        primitives::{Address, B256, Bytes, LogData, U256},
+        rpc::json_rpc::ErrorPayload,
+        rpc::types::Log as RpcLog,
+        sol_types::{SolCall, SolEvent},
+        transports::{TransportError, TransportErrorKind},
+    };
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::{
+        ChainSource, FileChainSource, TransportKind, decode_or_skip, decode_proposal_logs,
+        file_fixture_path, governor_state_error, is_ws_url, parse_proposal_id, testing::MockChain,
+        write_proposal_fixture,
+    };
+    use crate::{
+        decoder::{ProposalCreated, ProposalCreatedAbi, publishDappCall},
+        types::{DecodedAction, Proposal},
+    };
+
+    fn proposal(id: &str, block_number: u64) -> Proposal {
+        Proposal {
+            proposal_id: id.to_string(),
+            proposer: "0x0000000000000000000000000000000000000001".to_string(),
+            description: "test".to_string(),
+            vote_start: block_number + 1,
+            vote_end: block_number + 20,
+            block_number,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::Unsupported {
+                reason: "test".to_string(),
+            }],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        }
+    }
+
+    struct WindowedChain {
+        inner: MockChain,
+        window: Option<(u64, u64)>,
+        log_queries: Mutex<Vec<(u64, u64)>>,
+    }
+
+    #[async_trait]
+    impl ChainSource for WindowedChain {
+        fn transport(&self) -> TransportKind {
+            self.inner.transport()
+        }
+
+        async fn health_check(&self) -> Result<u64> {
+            self.inner.health_check().await
+        }
+
+        async fn latest_block(&self) -> Result<u64> {
+            self.inner.latest_block().await
+        }
+
+        async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+            self.log_queries
+                .lock()
+                .expect("log query lock")
+                .push((from_block, to_block));
+            self.inner.fetch_proposals(from_block, to_block).await
+        }
+
+        async fn proposal_log_window(&self, _proposal_id: &str) -> Result<Option<(u64, u64)>> {
+            Ok(self.window)
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_by_id_queries_targeted_window_before_full_scan() {
+        let chain = |window| WindowedChain {
+            inner: MockChain {
+                latest_block: 100_000,
+                proposals: vec![proposal("1", 10), proposal("2", 90_000)],
+            },
+            window,
+            log_queries: Mutex::new(Vec::new()),
+        };
+
+        let targeted = chain(Some((89_000, 91_000)));
+        let found = targeted
+            .fetch_proposal_by_id("2", 0)
+            .await
+            .expect("targeted lookup");
+        assert_eq!(found.block_number, 90_000);
+        assert_eq!(
+            *targeted.log_queries.lock().expect("log query lock"),
+            vec![(89_000, 91_000)]
+        );
+
+        let missed = chain(Some((50_000, 51_000)));
+        missed
+            .fetch_proposal_by_id("2", 0)
+            .await
+            .expect("fallback lookup");
+        assert_eq!(
+            *missed.log_queries.lock().expect("log query lock"),
+            vec![(50_000, 51_000), (0, 100_000)]
+        );
+
+        let unknown = chain(None);
+        unknown
+            .fetch_proposal_by_id("1", 0)
+            .await
+            .expect("full scan lookup");
+        assert_eq!(
+            *unknown.log_queries.lock().expect("log query lock"),
+            vec![(0, 100_000)]
+        );
+    }
+
+    #[test]
+    fn malformed_proposal_log_is_skipped_instead_of_failing() {
+        let proposal_event = ProposalCreatedAbi::default();
+        let mut log = RpcLog {
+            block_number: Some(42),
+            transaction_hash: Some(B256::repeat_byte(0xab)),
+            log_index: Some(3),
+            ..Default::default()
+        };
+        log.inner.data =
+            LogData::new_unchecked(vec![proposal_event.topic0()], Bytes::from(vec![0x01, 0x02]));
+
+        assert!(
+            decode_or_skip(
+                &log,
+                &proposal_event,
+                "0x0000000000000000000000000000000000000002"
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn proposals_that_skip_the_registry_are_dropped_unless_included() {
+        let registry = Address::repeat_byte(0x22);
+        let publish = publishDappCall {
+            rootCid: Bytes::from(b"bafy-app".to_vec()),
+            name: "App".to_string(),
+            version: "1.0.0".to_string(),
+            description: "desc".to_string(),
+        };
+        let log = |id: u64, target: Address, calldata: Vec<u8>| {
+            let event = ProposalCreated {
+                proposalId: U256::from(id),
+                proposer: Address::repeat_byte(0x01),
+                targets: vec![target],
+                values: vec![U256::ZERO],
+                signatures: vec![String::new()],
+                calldatas: vec![Bytes::from(calldata)],
+                voteStart: U256::from(10),
+                voteEnd: U256::from(20),
+                description: format!("proposal {id}"),
+            };
+            let mut log = RpcLog {
+                block_number: Some(id),
+                ..Default::default()
+            };
+            log.inner.data = event.encode_log_data();
+            log
+        };
+        let logs = vec![
+            log(1, registry, publish.abi_encode()),
+            log(2, Address::repeat_byte(0x33), vec![0xde, 0xad]),
+        ];
+
+        let proposal_event = ProposalCreatedAbi::default();
+        let scan = decode_proposal_logs(
+            logs.clone(),
+            &proposal_event,
+            &format!("{registry:#x}"),
+            false,
+        );
+        assert_eq!(scan.proposals.len(), 1);
+        assert_eq!(scan.proposals[0].proposal_id, "1");
+        assert!(scan.failed_logs.is_empty());
+
+        let scan = decode_proposal_logs(logs, &proposal_event, &registry.to_checksum(None), true);
+        assert_eq!(scan.proposals.len(), 2);
+        assert!(matches!(
+            scan.proposals[1].actions[..],
+            [DecodedAction::Unsupported { .. }]
+        ));
+    }
 
     #[test]
     fn ws_detection_works_for_ws_and_wss() {
@@ -219,4 +1149,75 @@ mod tests {
         let parsed_hex = parse_proposal_id(&hex).expect("hex parses");
         assert_eq!(parsed_decimal, parsed_hex);
     }
+
+    #[test]
+    fn state_reverts_are_told_apart_from_rpc_failures() {
+        let reverted = ContractError::TransportError(TransportError::ErrorResp(ErrorPayload {
+            code: 3,
+            message: "execution reverted".into(),
+            data: None,
+        }));
+        let err = governor_state_error("7", reverted);
+        assert_eq!(
+            err.to_string(),
+            "proposal 7 does not exist or is not a governor proposal"
+        );
+
+        let err = governor_state_error(
+            "7",
+            ContractError::TransportError(TransportErrorKind::custom_str("connection refused")),
+        );
+        assert_eq!(err.to_string(), "rpc error while reading proposal state");
+        assert!(format!("{err:#}").contains("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn default_fetch_by_id_matches_hex_and_decimal_ids() {
+        let chain = MockChain {
+            latest_block: 10,
+            proposals: vec![proposal("255", 1)],
+        };
+
+        let found = chain
+            .fetch_proposal_by_id("0xff", 0)
+            .await
+            .expect("hex id resolves");
+        assert_eq!(found.proposal_id, "255");
+        assert!(chain.fetch_proposal_by_id("7", 0).await.is_err());
+        assert!(chain.fetch_tx_context("0x00").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn file_chain_source_replays_captured_and_array_fixtures() {
+        let dir = std::env::temp_dir().join(format!(
+            "gov-agent-fixture-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let jsonl = dir.join("proposals.jsonl");
+        write_proposal_fixture(&jsonl, &[proposal("2", 30), proposal("1", 10)])
+            .expect("capture writes");
+
+        let url = format!("file://{}", jsonl.display());
+        let path = file_fixture_path(&url).expect("file url detected");
+        let source = FileChainSource::open(path, 31337).expect("jsonl fixture loads");
+        assert_eq!(source.health_check().await.unwrap(), 31337);
+        assert_eq!(source.latest_block().await.unwrap(), 30);
+        let ranged = source.fetch_proposals(0, 20).await.unwrap();
+        assert_eq!(ranged.len(), 1);
+        assert_eq!(ranged[0].proposal_id, "1");
+        assert!(source.fetch_proposal_by_id("0x2", 0).await.is_ok());
+
+        let array = dir.join("proposals.json");
+        std::fs::write(
+            &array,
+            serde_json::to_string_pretty(&vec![proposal("3", 5)]).unwrap(),
+        )
+        .unwrap();
+        let source = FileChainSource::open(&array, 1).expect("json array fixture loads");
+        assert_eq!(source.fetch_proposals(0, 100).await.unwrap().len(), 1);
+        assert!(file_fixture_path("http://127.0.0.1:8545").is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
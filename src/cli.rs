@@ -1,6 +1,14 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    agent::ProposalFilter,
+    config::AgentMode,
+    notifier::NotificationEvent,
+    types::{Severity, VoteChoice},
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "gov-agent")]
@@ -9,6 +17,17 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".env",
+        help = "Load KEY=VALUE pairs from a .env file (default ./.env, or --env-file=PATH) without overriding variables already set"
+    )]
+    pub env_file: Option<PathBuf>,
+
     #[arg(
         long,
         global = true,
@@ -17,9 +36,33 @@ pub struct Cli {
     )]
     pub profile: String,
 
+    #[arg(
+        long,
+        global = true,
+        env = "GOV_AGENT_STRICT_PROFILE",
+        help = "Fail instead of warning when the config file's profile key names a different profile than --profile"
+    )]
+    pub strict_profile: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "GOV_AGENT_MODE",
+        help = "Operating mode: observer never votes, voter may vote with --auto-vote"
+    )]
+    pub mode: Option<AgentMode>,
+
     #[arg(long, global = true, env = "GOV_AGENT_RPC_URL")]
     pub rpc_url: Option<String>,
 
+    #[arg(
+        long,
+        global = true,
+        env = "GOV_AGENT_ALLOW_CHAIN_MISMATCH",
+        help = "Keep running when the RPC reports a different chain id than network.chain_id"
+    )]
+    pub allow_chain_mismatch: bool,
+
     #[arg(
         long,
         global = true,
@@ -31,22 +74,49 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub json_logs: bool,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Take over the data_dir state lock even if another instance appears to hold it"
+    )]
+    pub force_unlock: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
+    #[command(
+        about = "Write a starter config file and create the data and cache directories",
+        long_about = "Asks for the RPC URL, governor and registry addresses (pre-filled from the profile), auto-vote, keystore path, and LLM providers, or takes them from flags with --yes. Writes a commented config that passes the normal config validation, creates the data and cache directories, and prints the env vars that still need to be exported. An existing file is only replaced with --force."
+    )]
+    Init(InitArgs),
     #[command(
         about = "Run the agent scan/review loop",
         long_about = "Continuously scans new blocks for proposals, reviews them, and optionally submits votes when auto-vote is enabled."
     )]
     Run(RunArgs),
     #[command(
-        about = "Review a single proposal by id",
-        long_about = "Fetches one proposal by id, runs bundle + LLM review, and prints the resulting decision logs."
+        about = "Review one or more proposals by id",
+        long_about = "Fetches one proposal by id, runs bundle + LLM review, and prints the resulting decision logs. With --proposal-ids, reviews each listed proposal in one invocation and prints a JSON array of results."
     )]
     ReviewOnce(ReviewOnceArgs),
+    #[command(
+        about = "Pre-flight review of a bundle CID without an on-chain proposal",
+        long_about = "Reviews a root CID as if it were proposed in a PublishDapp action, using the normal review and LLM config, and prints the review and the hypothetical decision as JSON. The result is marked synthetic; it is never stored in state and never voted on."
+    )]
+    ReviewCid(ReviewCidArgs),
+    #[command(
+        about = "Run the static bundle checks against a local directory",
+        long_about = "Builds a manifest from the files in a directory, honouring .gitignore files, and runs the same manifest, package.json, and source token checks a published bundle gets. Prints the findings and the resulting deterministic score as JSON. It never contacts the chain, IPFS, or an LLM. Exits with an error if any finding is Critical."
+    )]
+    Lint(LintArgs),
+    #[command(
+        about = "Dump the raw, undecoded proposal for debugging",
+        long_about = "Fetches one proposal by id and prints its targets, values, calldatas, log topics, and the selector matches attempted by the action decoder. It does not run review or submit votes."
+    )]
+    Inspect(InspectArgs),
     #[command(
         about = "Process a historical block range",
         long_about = "Backfills proposal processing for a block range. Use --to-block to cap the range, or omit it to scan through the current chain tip."
@@ -57,8 +127,69 @@ pub enum Command {
         long_about = "Verifies RPC connectivity and chain id, reports transport mode, prints configured storage path, and indicates notifier configuration. It does not scan proposals or submit votes."
     )]
     Status,
+    #[command(
+        about = "Print each signer address, its ETH balance, and its voting power",
+        long_about = "Decrypts each configured keystore, then prints the signer address, its ETH balance, and its getVotes result on the configured governor one clock tick before the latest block, as JSON. It only makes read calls and never submits a transaction."
+    )]
+    Whoami,
+    #[command(
+        about = "Check that each keystore decrypts with its configured password",
+        long_about = "Decrypts each configured keystore with its resolved password and prints, as JSON, the signer address or the exact decryption error. It never contacts the chain. Exits with an error if any keystore fails."
+    )]
+    TestKeystore,
     #[command(about = "Inspect resolved runtime configuration")]
     Config(ConfigArgs),
+    #[command(about = "Inspect proposals stored in local state")]
+    Proposals(ProposalsArgs),
+    #[command(
+        about = "Manage the local state file and its backups",
+        visible_alias = "state"
+    )]
+    Storage(StorageArgs),
+    #[command(about = "Manage the local IPFS bundle cache")]
+    Cache(CacheArgs),
+    #[command(about = "Pause or resume vote submission without stopping the agent")]
+    Agent(AgentArgs),
+    #[command(about = "Manage queued review exports")]
+    Outbox(OutboxArgs),
+    #[command(about = "Manage the signer key")]
+    Signer(SignerArgs),
+    #[command(about = "Record human resolutions for a decision.canary_mode trial")]
+    Canary(CanaryArgs),
+    #[command(about = "Check notifier delivery and templates")]
+    Notify(NotifyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Config file to write (default ~/.gov-agent/config.toml)"
+    )]
+    pub out: Option<PathBuf>,
+
+    #[arg(long, help = "Overwrite an existing config file")]
+    pub force: bool,
+
+    #[arg(long, help = "Do not prompt; use flags and profile defaults")]
+    pub yes: bool,
+
+    #[arg(long, help = "Governor address (defaults to the profile's)")]
+    pub governor_address: Option<String>,
+
+    #[arg(long, help = "DappRegistry address (defaults to the profile's)")]
+    pub dapp_registry_address: Option<String>,
+
+    #[arg(long, value_name = "PATH", help = "Keystore file used for auto-vote")]
+    pub keystore_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Comma-separated LLM providers to enable: openai, anthropic, ollama, venice"
+    )]
+    pub llm: Vec<String>,
 }
 
 #[derive(Debug, Args)]
@@ -68,8 +199,75 @@ pub struct RunArgs {
 }
 
 #[derive(Debug, Args)]
+#[group(skip)]
+#[command(group(ArgGroup::new("ids").required(true).multiple(false)))]
 pub struct ReviewOnceArgs {
-    #[arg(long, help = "Proposal id to review (uint256 as decimal or 0x hex)")]
+    #[arg(
+        long,
+        group = "ids",
+        help = "Proposal id to review (uint256 as decimal, 0x hex, or a unique prefix of a stored id)"
+    )]
+    pub proposal_id: Option<String>,
+
+    #[arg(
+        long,
+        group = "ids",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Comma-separated proposal ids to review in one run; duplicates are reviewed once"
+    )]
+    pub proposal_ids: Vec<String>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Keep retrying a proposal that is not found yet, with backoff, for up to this many seconds"
+    )]
+    pub wait_secs: u64,
+
+    #[arg(
+        long,
+        help = "Look for the proposal only in this block (its creation block) instead of scanning from network.from_block"
+    )]
+    pub at_block: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Print the review report as JSON on stdout; --proposal-ids always prints JSON"
+    )]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    #[arg(long, value_name = "PATH", help = "Dapp directory to check")]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ReviewCidArgs {
+    #[arg(long, help = "Root CID of the bundle to review")]
+    pub cid: String,
+
+    #[arg(
+        long,
+        help = "Dapp name to review under; defaults to the manifest name"
+    )]
+    pub name: Option<String>,
+
+    #[arg(
+        long,
+        help = "Dapp version to review under; defaults to the manifest version"
+    )]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    #[arg(
+        long,
+        help = "Proposal id to inspect (uint256 as decimal, 0x hex, or a unique prefix of a stored id)"
+    )]
     pub proposal_id: String,
 }
 
@@ -80,6 +278,19 @@ pub struct BackfillArgs {
 
     #[arg(long, help = "End block number (inclusive); defaults to latest block")]
     pub to_block: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write fetched proposals as JSONL for replay via rpc_url = \"file://<PATH>\""
+    )]
+    pub capture: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Review proposals whose voting already closed instead of skipping them (no vote is sent)"
+    )]
+    pub review_closed: bool,
 }
 
 #[derive(Debug, Args)]
@@ -88,8 +299,371 @@ pub struct ConfigArgs {
     pub command: ConfigCommand,
 }
 
+#[derive(Debug, Args)]
+pub struct ConfigPrintArgs {
+    #[arg(long, value_enum, default_value_t = ConfigFormat::Json, help = "Output format")]
+    pub format: ConfigFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
-    #[command(about = "Print the fully resolved config as JSON")]
-    Print,
+    #[command(
+        about = "Print the fully resolved config as JSON or TOML",
+        long_about = "Prints the resolved config (profile defaults, config file, env, and flags) with keystore passwords redacted. JSON output adds the config_hash; TOML output carries it as a comment and can be saved as a starting config file."
+    )]
+    Print(ConfigPrintArgs),
+    #[command(
+        about = "Print a JSON Schema for the config file",
+        long_about = "Prints a JSON Schema describing every key the config file accepts, for editor validation and completion. Does not load any config."
+    )]
+    Schema,
+    #[command(
+        about = "List the built-in and custom profiles",
+        long_about = "Lists the built-in profiles and the custom ones defined by [profiles.<name>] tables in the --config file or by profiles/<name>.toml files next to it (~/.gov-agent/profiles without --config), as JSON with each profile's source, network name, and chain id. A profile that fails to load is listed with its error."
+    )]
+    Profiles,
+}
+
+#[derive(Debug, Args)]
+pub struct ProposalsArgs {
+    #[command(subcommand)]
+    pub command: ProposalsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProposalsCommand {
+    #[command(about = "List proposals recorded in local state")]
+    List(ProposalsListArgs),
+    #[command(
+        about = "Print one stored proposal as JSON",
+        long_about = "Prints the stored proposal, review, and decision, with the LLM audit loaded from data_dir/audits."
+    )]
+    Show(ProposalsShowArgs),
+    #[command(
+        about = "Add a reviewer finding to a stored proposal",
+        long_about = "Appends an operator finding to the stored review and recomputes the decision; a critical finding forces Against. The finding is kept when the proposal is reviewed again. No vote is submitted."
+    )]
+    Annotate(ProposalsAnnotateArgs),
+    #[command(
+        about = "Compare stored decisions with how the DAO actually voted",
+        long_about = "Reads the governor state and vote totals of every stored proposal and prints, as JSON, an agreement report: a matrix of agent vote against DAO outcome (passed or defeated), the agreement rate, and one row per proposal. Proposals that are not final or were canceled are reported as unresolved. Only read calls are made. --since-block and --since limit the report to one governance period."
+    )]
+    Outcomes(ProposalsOutcomesArgs),
+}
+
+#[derive(Debug, Clone, Copy, Args)]
+pub struct ProposalFilterArgs {
+    #[arg(
+        long,
+        value_name = "BLOCK",
+        help = "Only proposals created at or after this block"
+    )]
+    pub since_block: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "RFC3339",
+        help = "Only proposals the agent discovered at or after this time, e.g. 2025-01-01T00:00:00Z"
+    )]
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl From<ProposalFilterArgs> for ProposalFilter {
+    fn from(args: ProposalFilterArgs) -> Self {
+        Self {
+            since_block: args.since_block,
+            since: args.since,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ProposalsListArgs {
+    #[arg(
+        long,
+        help = "Print the first 8 hex chars of each id as a handle usable with --proposal-id"
+    )]
+    pub short: bool,
+
+    #[command(flatten)]
+    pub filter: ProposalFilterArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ProposalsOutcomesArgs {
+    #[command(flatten)]
+    pub filter: ProposalFilterArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ProposalsShowArgs {
+    #[arg(
+        long,
+        help = "Proposal id to show (uint256 as decimal, 0x hex, or a unique prefix of a stored id)"
+    )]
+    pub proposal_id: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ProposalsAnnotateArgs {
+    #[arg(
+        long,
+        help = "Proposal id to annotate (uint256 as decimal, 0x hex, or a unique prefix of a stored id)"
+    )]
+    pub proposal_id: String,
+
+    #[arg(long, value_enum, help = "Severity of the finding")]
+    pub severity: AnnotationSeverity,
+
+    #[arg(long, help = "What the reviewer found")]
+    pub message: String,
+
+    #[arg(long, help = "Reviewer name; defaults to the OS username")]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AnnotationSeverity {
+    Warning,
+    Critical,
+}
+
+impl From<AnnotationSeverity> for Severity {
+    fn from(value: AnnotationSeverity) -> Self {
+        match value {
+            AnnotationSeverity::Warning => Severity::Warning,
+            AnnotationSeverity::Critical => Severity::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct StorageArgs {
+    #[command(subcommand)]
+    pub command: StorageCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StorageCommand {
+    #[command(
+        about = "Replace the state file with a rotated backup",
+        long_about = "Validates that state.json.<N> parses before swapping it in. The replaced state file is kept alongside as <state_file>.pre-restore."
+    )]
+    Restore(StorageRestoreArgs),
+    #[command(
+        about = "Move old finalized proposals out of the state file",
+        long_about = "Proposals that were executed, defeated, canceled or expired longer ago than storage.retention_days are written to data_dir/archive/<id>.json. State keeps an index entry (vote, vote tx hash, archive path), so scans still skip them and `proposals show` reads the archive. `run` does the same once a week when storage.retention_days is set."
+    )]
+    Prune(StoragePruneArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct StorageRestoreArgs {
+    #[arg(long, help = "Backup generation to restore (1 is the most recent)")]
+    pub backup: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct StoragePruneArgs {
+    #[arg(
+        long,
+        value_name = "DAYS",
+        help = "Retention window; defaults to storage.retention_days"
+    )]
+    pub retention_days: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    #[command(
+        about = "Remove every cached file for one root CID",
+        long_about = "Deletes the <cache_dir>/<cid> directory so the next review re-fetches the manifest and bundle files from the gateway."
+    )]
+    Invalidate(CacheInvalidateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CacheInvalidateArgs {
+    #[arg(long, help = "Root CID whose cache directory should be removed")]
+    pub cid: String,
+}
+
+#[derive(Debug, Args)]
+pub struct AgentArgs {
+    #[command(subcommand)]
+    pub command: AgentCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AgentCommand {
+    #[command(
+        about = "Stop submitting votes while reviews continue",
+        long_about = "Creates data_dir/PAUSE_VOTING. A running agent keeps scanning and reviewing, but routes every decision through the dry-run executor and marks stored records as voting paused."
+    )]
+    Pause,
+    #[command(
+        about = "Resume vote submission",
+        long_about = "Removes data_dir/PAUSE_VOTING so a running agent submits votes again."
+    )]
+    Resume,
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyArgs {
+    #[command(subcommand)]
+    pub command: NotifyCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NotifyCommand {
+    #[command(
+        about = "Render and send a sample event through every enabled notifier",
+        long_about = "Builds a sample proposal and review, renders the event with each notifier's template_file (or the bundled English templates), sends it, and prints each notifier's message and result as JSON. Exits with an error if any notifier failed. GitHub only comments when the sample proposal maps to an issue, so it usually reports success without posting."
+    )]
+    Test(NotifyTestArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyTestArgs {
+    #[arg(long, value_enum, help = "Event to render, e.g. review_completed")]
+    pub event: NotificationEvent,
+}
+
+#[derive(Debug, Args)]
+pub struct OutboxArgs {
+    #[command(subcommand)]
+    pub command: OutboxCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OutboxCommand {
+    #[command(
+        about = "Retry every queued review export now",
+        long_about = "POSTs each proposal still queued for review.export_webhook, records the ones the endpoint accepts so they are never sent again, and prints what is still pending with its last error as JSON."
+    )]
+    Flush,
+}
+
+#[derive(Debug, Args)]
+pub struct SignerArgs {
+    #[command(subcommand)]
+    pub command: SignerCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SignerCommand {
+    #[command(
+        about = "Switch the signer to a new keystore and remember the old address",
+        long_about = "Decrypts the current and the new keystore with the configured password, then records the rotation (old address, new address, time) in state and the log. With --write-config, the --config file is edited in place: signer.keystore_path points at the new keystore and the old address is added to signer.previous_addresses. It never contacts the chain."
+    )]
+    Rotate(SignerRotateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SignerRotateArgs {
+    #[arg(long, value_name = "PATH", help = "Keystore of the new signer key")]
+    pub new_keystore: PathBuf,
+
+    #[arg(
+        long,
+        help = "Update the --config file with the new keystore_path and previous_addresses"
+    )]
+    pub write_config: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CanaryArgs {
+    #[command(subcommand)]
+    pub command: CanaryCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CanaryCommand {
+    #[command(
+        about = "Record how a human resolved a stored decision",
+        long_about = "Compares the given vote with the stored decision and updates the canary streak: an agreement extends it, a disagreement resets it. Once decision.canary_required_agreements decisions in a row agree, auto-vote eligibility is logged and notified; with decision.canary_auto_promote the agent switches to live voting. Each proposal can be confirmed once. Prints the canary report."
+    )]
+    Confirm(CanaryConfirmArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CanaryConfirmArgs {
+    #[arg(
+        long,
+        help = "Proposal id to confirm (uint256 as decimal, 0x hex, or a unique prefix of a stored id)"
+    )]
+    pub proposal_id: String,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "How the human reviewer resolved the proposal"
+    )]
+    pub vote: CanaryVote,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CanaryVote {
+    For,
+    Against,
+    Abstain,
+}
+
+impl From<CanaryVote> for VoteChoice {
+    fn from(value: CanaryVote) -> Self {
+        match value {
+            CanaryVote::For => VoteChoice::For,
+            CanaryVote::Against => VoteChoice::Against,
+            CanaryVote::Abstain => VoteChoice::Abstain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Cli, Command, ReviewOnceArgs};
+
+    fn review_once(args: &[&str]) -> clap::error::Result<ReviewOnceArgs> {
+        let cli = Cli::try_parse_from(
+            ["gov-agent", "review-once"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )?;
+        match cli.command {
+            Command::ReviewOnce(args) => Ok(args),
+            other => panic!("parsed as {other:?}"),
+        }
+    }
+
+    #[test]
+    fn review_once_takes_exactly_one_id_source_alongside_its_options() {
+        let args = review_once(&["--proposal-id", "7", "--wait-secs", "5"]).expect("wait-secs");
+        assert_eq!(args.proposal_id.as_deref(), Some("7"));
+        assert_eq!(args.wait_secs, 5);
+        let args = review_once(&["--proposal-id", "7", "--at-block", "120"]).expect("at-block");
+        assert_eq!(args.at_block, Some(120));
+
+        let args = review_once(&["--proposal-id", "7", "--json"]).expect("single id json");
+        assert!(args.json);
+        let args = review_once(&["--proposal-ids", "7,8", "--json"]).expect("batch json");
+        assert_eq!(args.proposal_ids, ["7", "8"]);
+        assert!(args.json);
+
+        assert!(review_once(&["--wait-secs", "5"]).is_err());
+        assert!(review_once(&["--proposal-id", "7", "--proposal-ids", "8,9"]).is_err());
+    }
 }
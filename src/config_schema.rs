@@ -0,0 +1,124 @@
+use schemars::generate::SchemaSettings;
+use serde_json::{Value, json};
+
+use crate::config::{AppConfig, BUILTIN_PROFILES};
+
+// Derived from the config types, so it always matches what the loader accepts. Every key is
+// optional, since files are merged over profile defaults, and `deny_unknown_fields` becomes
+// `additionalProperties: false`.
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft2020_12()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<AppConfig>().to_value();
+    tomlify(&mut schema);
+    // `[profiles.<name>]` tables take the same keys, plus the built-in profile they extend.
+    let mut profile = schema.clone();
+    if let Value::Object(fields) = &mut profile {
+        fields.retain(|key, _| !key.starts_with('$') && key != "title");
+    }
+    profile["properties"]["extends"] = json!({ "type": "string", "enum": BUILTIN_PROFILES });
+    schema["properties"]["profiles"] = json!({ "type": "object", "additionalProperties": profile });
+    schema["title"] = "gov-agent config".into();
+    schema
+}
+
+// Drops `required`, and, since TOML has no null, describes an optional key by its inner type.
+// Default and enum values are data, not subschemas, so they are not walked.
+fn tomlify(schema: &mut Value) {
+    let Value::Object(fields) = schema else {
+        return;
+    };
+    fields.remove("required");
+    match fields.get_mut("default") {
+        Some(Value::Null) => {
+            fields.remove("default");
+        }
+        Some(default) => drop_nulls(default),
+        None => {}
+    }
+    if let Some(Value::Array(types)) = fields.get_mut("type") {
+        types.retain(|ty| ty != "null");
+        if let [single] = types.as_slice() {
+            let single = single.clone();
+            fields.insert("type".to_string(), single);
+        }
+    }
+    if let Some(Value::Array(values)) = fields.get_mut("enum") {
+        values.retain(|value| !value.is_null());
+    }
+    let only_key = fields.len() == 1;
+    if let Some(Value::Array(variants)) = fields.get_mut("anyOf") {
+        variants.retain(|variant| variant.get("type").is_none_or(|ty| ty != "null"));
+        if let [single] = variants.as_slice()
+            && only_key
+        {
+            *schema = single.clone();
+            return tomlify(schema);
+        }
+    }
+    for (key, value) in fields.iter_mut() {
+        match (key.as_str(), value) {
+            ("default" | "enum" | "const" | "examples", _) => {}
+            (_, Value::Array(items)) => items.iter_mut().for_each(tomlify),
+            (_, value) => tomlify(value),
+        }
+    }
+}
+
+fn drop_nulls(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, value| !value.is_null());
+            fields.values_mut().for_each(drop_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(drop_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::config_schema;
+
+    #[test]
+    fn schema_lists_nested_fields_and_rejects_unknown_keys() {
+        let schema = config_schema();
+        assert_eq!(schema["additionalProperties"], false);
+        let decision = &schema["properties"]["decision"];
+        assert_eq!(decision["additionalProperties"], false);
+        assert_eq!(
+            decision["properties"]["approve_threshold"]["type"],
+            "number"
+        );
+        assert_eq!(
+            decision["properties"]["profile"]["enum"],
+            serde_json::json!(["conservative", "balanced", "aggressive"])
+        );
+        let mainnet = &schema["properties"]["profiles"]["additionalProperties"];
+        assert_eq!(mainnet["properties"]["extends"]["enum"][1], "sepolia");
+        assert_eq!(
+            mainnet["properties"]["network"]["additionalProperties"],
+            false
+        );
+        let signer = &schema["properties"]["signer"]["properties"];
+        assert_eq!(signer["max_gas_price_gwei"]["type"], "integer");
+        assert_eq!(
+            signer["accounts"]["items"]["properties"]["label"]["type"],
+            "string"
+        );
+        let caps = &schema["properties"]["review"]["properties"]["score_caps"];
+        assert_eq!(
+            caps["additionalProperties"]["properties"]["max"]["type"],
+            "number"
+        );
+        assert!(schema["properties"].get("load_warnings").is_none());
+        // TOML has no null: optional keys take their inner type and null defaults are dropped.
+        assert_eq!(
+            schema["properties"]["signer"]["properties"]["keystore_path"]["type"],
+            "string"
+        );
+        assert!(!schema.to_string().contains("null"));
+        assert!(!schema.to_string().contains("\"required\""));
+    }
+}
@@ -2,7 +2,7 @@ use chrono::Utc;
 
 use crate::{
     config::DecisionConfig,
-    types::{Decision, ReviewResult, Severity, VoteChoice},
+    types::{Decision, ReviewResult, Severity, VoteAllocation, VoteChoice},
 };
 
 pub fn decide(config: &DecisionConfig, review: &ReviewResult) -> Decision {
@@ -11,10 +11,13 @@ pub fn decide(config: &DecisionConfig, review: &ReviewResult) -> Decision {
     let blocking_findings = review
         .findings
         .iter()
-        .filter(|finding| finding.severity == Severity::Critical)
+        .filter(|finding| config.blocking_severity.blocks(finding.severity))
         .map(|finding| finding.message.clone())
         .collect::<Vec<_>>();
-    let has_critical = !blocking_findings.is_empty();
+    let has_critical = review
+        .findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Critical);
 
     let (vote, mut reasons, requires_human_override) = if has_critical {
         (
@@ -22,6 +25,15 @@ pub fn decide(config: &DecisionConfig, review: &ReviewResult) -> Decision {
             vec!["critical finding detected in proposal review".to_string()],
             false,
         )
+    } else if !blocking_findings.is_empty() {
+        (
+            VoteChoice::Against,
+            vec![format!(
+                "warning finding detected in proposal review; decision.blocking_severity is {}",
+                config.blocking_severity.as_str()
+            )],
+            false,
+        )
     } else if review.score >= approve_min {
         (
             VoteChoice::For,
@@ -51,27 +63,98 @@ pub fn decide(config: &DecisionConfig, review: &ReviewResult) -> Decision {
         )
     };
 
+    for finding in &review.findings {
+        if let Some(operator) = &finding.operator {
+            reasons.push(format!(
+                "operator {} finding by {}: {}",
+                finding.severity.as_str(),
+                operator.author,
+                finding.message
+            ));
+        }
+    }
+
+    // Multi-action proposals are scored on their riskiest action; say which one that was.
+    if review.actions.len() > 1
+        && let Some(worst) = review.actions.iter().min_by(|a, b| {
+            b.critical
+                .cmp(&a.critical)
+                .then(a.score.total_cmp(&b.score))
+        })
+    {
+        reasons.push(format!(
+            "worst of {} actions: action {} ({}) scored {:.2}{}",
+            review.actions.len(),
+            worst.index + 1,
+            worst.label,
+            worst.score,
+            if worst.critical {
+                " with a critical finding"
+            } else {
+                ""
+            }
+        ));
+    }
     if let Some(score) = review.llm_score {
         reasons.push(format!("llm score: {:.2}", score));
     }
     let deterministic_weight = review.deterministic_weight.unwrap_or(0.70);
     let llm_weight = review.llm_weight.unwrap_or(0.30);
     let deterministic_score = review.deterministic_score.unwrap_or(review.score);
+    let blended_score = review
+        .reputation
+        .as_ref()
+        .map_or(review.score, |reputation| reputation.base_score);
     match review.llm_score {
         Some(llm_score) => reasons.push(format!(
             "blended score = {:.2}*{:.2} + {:.2}*{:.2} = {:.2}",
-            deterministic_weight, deterministic_score, llm_weight, llm_score, review.score
+            deterministic_weight, deterministic_score, llm_weight, llm_score, blended_score
         )),
         None => reasons.push(format!(
             "llm score unavailable; using deterministic score {:.2}",
             deterministic_score
         )),
     }
+    if let Some(reputation) = &review.reputation {
+        reasons.push(format!(
+            "reputation adjustment {:+.3} (weight {:.2} x signal {:+.2}) -> {:.2}; {}",
+            reputation.adjustment,
+            reputation.weight,
+            reputation.signal,
+            review
+                .score_cap
+                .as_ref()
+                .map_or(review.score, |cap| cap.uncapped_score),
+            reputation.inputs.join("; ")
+        ));
+    }
+    if let Some(cap) = &review.score_cap {
+        reasons.push(format!(
+            "score capped at {:.2} by review.score_caps.{} (uncapped {:.2})",
+            cap.cap, cap.rule, cap.uncapped_score
+        ));
+    }
     reasons.push(format!(
         "decision thresholds: reject <= {:.2}, approve >= {:.2}",
         reject_max, approve_min
     ));
 
+    // Finding-forced Againsts and abstains are cast whole; score-based votes lean by the score.
+    let allocation = (config.fractional
+        && !has_critical
+        && blocking_findings.is_empty()
+        && vote != VoteChoice::Abstain)
+        .then(|| {
+            let share = match vote {
+                VoteChoice::For => review.score,
+                _ => 1.0 - review.score,
+            };
+            VoteAllocation::leaning(vote, share)
+        });
+    if let Some(allocation) = allocation {
+        reasons.push(format!("fractional split: {allocation}"));
+    }
+
     Decision {
         proposal_id: review.proposal_id.clone(),
         vote,
@@ -79,6 +162,8 @@ pub fn decide(config: &DecisionConfig, review: &ReviewResult) -> Decision {
         blocking_findings,
         requires_human_override,
         decided_at: Utc::now(),
+        config_hash: None,
+        allocation,
     }
 }
 
@@ -87,8 +172,10 @@ mod tests {
     use chrono::Utc;
 
     use crate::{
-        config::{ConfidenceProfile, DecisionConfig},
-        types::{Finding, ReviewResult, Severity, VoteChoice},
+        config::{BlockingSeverity, ConfidenceProfile, DecisionConfig},
+        types::{
+            ActionReview, Finding, FindingCode, ReviewResult, Severity, VoteAllocation, VoteChoice,
+        },
     };
 
     use super::decide;
@@ -103,7 +190,13 @@ mod tests {
             llm_weight: Some(0.30),
             llm_score: None,
             llm_audit: None,
+            llm_audit_ref: None,
             score,
+            suppressions: vec![],
+            transient_failure: None,
+            reputation: None,
+            score_cap: None,
+            actions: vec![],
             reviewed_at: Utc::now(),
         }
     }
@@ -115,6 +208,12 @@ mod tests {
             reject_threshold: None,
             deterministic_weight: Some(0.70),
             llm_weight: Some(0.30),
+            reevaluate_unvoted_on_start: false,
+            blocking_severity: BlockingSeverity::Critical,
+            canary_mode: false,
+            canary_required_agreements: 5,
+            canary_auto_promote: false,
+            fractional: false,
         }
     }
 
@@ -124,6 +223,50 @@ mod tests {
         assert_eq!(decision.vote, VoteChoice::For);
     }
 
+    #[test]
+    fn fractional_mode_splits_score_based_votes_with_abstain() {
+        let fractional = DecisionConfig {
+            fractional: true,
+            ..conservative_cfg()
+        };
+        let approved = decide(&fractional, &review(0.85, vec![]));
+        assert_eq!(approved.vote, VoteChoice::For);
+        assert_eq!(
+            approved.allocation,
+            Some(VoteAllocation {
+                against_bps: 0,
+                for_bps: 8_500,
+                abstain_bps: 1_500,
+            })
+        );
+        assert!(approved.reasons.contains(
+            &"fractional split: against 0.00% / for 85.00% / abstain 15.00%".to_string()
+        ));
+
+        let rejected = decide(&fractional, &review(0.1, vec![]));
+        assert_eq!(rejected.vote, VoteChoice::Against);
+        assert_eq!(
+            rejected.allocation.map(|allocation| allocation.against_bps),
+            Some(9_000)
+        );
+
+        let critical = review(
+            0.95,
+            vec![Finding {
+                code: FindingCode::Unclassified,
+                severity: Severity::Critical,
+                message: "bad".to_string(),
+                operator: None,
+            }],
+        );
+        assert_eq!(decide(&fractional, &critical).allocation, None);
+        assert_eq!(decide(&fractional, &review(0.5, vec![])).allocation, None);
+        assert_eq!(
+            decide(&conservative_cfg(), &review(0.85, vec![])).allocation,
+            None
+        );
+    }
+
     #[test]
     fn critical_finding_forces_against() {
         let decision = decide(
@@ -133,12 +276,20 @@ mod tests {
                 reject_threshold: None,
                 deterministic_weight: Some(0.70),
                 llm_weight: Some(0.30),
+                reevaluate_unvoted_on_start: false,
+                blocking_severity: BlockingSeverity::Critical,
+                canary_mode: false,
+                canary_required_agreements: 5,
+                canary_auto_promote: false,
+                fractional: false,
             },
             &review(
                 0.95,
                 vec![Finding {
+                    code: FindingCode::Unclassified,
                     severity: Severity::Critical,
                     message: "bad".to_string(),
+                    operator: None,
                 }],
             ),
         );
@@ -146,6 +297,35 @@ mod tests {
         assert_eq!(decision.blocking_findings, vec!["bad".to_string()]);
     }
 
+    #[test]
+    fn warnings_block_only_when_blocking_severity_is_warning() {
+        let warned = review(
+            0.95,
+            vec![Finding {
+                code: FindingCode::Unclassified,
+                severity: Severity::Warning,
+                message: "suspicious".to_string(),
+                operator: None,
+            }],
+        );
+        let decision = decide(&conservative_cfg(), &warned);
+        assert_eq!(decision.vote, VoteChoice::For);
+        assert!(decision.blocking_findings.is_empty());
+
+        let strict = DecisionConfig {
+            blocking_severity: BlockingSeverity::Warning,
+            canary_mode: false,
+            canary_required_agreements: 5,
+            canary_auto_promote: false,
+            fractional: false,
+            ..conservative_cfg()
+        };
+        let decision = decide(&strict, &warned);
+        assert_eq!(decision.vote, VoteChoice::Against);
+        assert_eq!(decision.blocking_findings, vec!["suspicious".to_string()]);
+        assert!(decision.reasons[0].contains("blocking_severity is warning"));
+    }
+
     #[test]
     fn low_blended_score_drives_against_under_default_thresholds() {
         let decision = decide(
@@ -159,7 +339,13 @@ mod tests {
                 llm_weight: Some(0.30),
                 llm_score: Some(0.05),
                 llm_audit: None,
+                llm_audit_ref: None,
                 score: 0.155,
+                suppressions: vec![],
+                transient_failure: None,
+                reputation: None,
+                score_cap: None,
+                actions: vec![],
                 reviewed_at: Utc::now(),
             },
         );
@@ -177,7 +363,13 @@ mod tests {
             llm_weight: Some(0.30),
             llm_score: Some(0.20),
             llm_audit: None,
+            llm_audit_ref: None,
             score: 0.69,
+            suppressions: vec![],
+            transient_failure: None,
+            reputation: None,
+            score_cap: None,
+            actions: vec![],
             reviewed_at: Utc::now(),
         };
         let llm_heavy = ReviewResult {
@@ -193,4 +385,39 @@ mod tests {
         assert_eq!(decision_default.vote, VoteChoice::Abstain);
         assert_eq!(decision_llm_heavy.vote, VoteChoice::Against);
     }
+
+    #[test]
+    fn multi_action_reason_names_the_worst_action() {
+        let action = |index: usize, score: f32, critical: bool| ActionReview {
+            index,
+            label: format!("publishDapp App{index} 1.0.0"),
+            root_cid: Some(format!("bafy{index}")),
+            score,
+            critical,
+            fingerprint: None,
+            source_scan: None,
+        };
+        let decision = decide(
+            &conservative_cfg(),
+            &ReviewResult {
+                actions: vec![
+                    action(0, 0.2, false),
+                    action(1, 0.9, true),
+                    action(2, 0.1, false),
+                ],
+                ..review(
+                    0.2,
+                    vec![Finding {
+                        code: FindingCode::Unclassified,
+                        severity: Severity::Critical,
+                        message: "action 2/3 (publishDapp App1 1.0.0): bad".to_string(),
+                        operator: None,
+                    }],
+                )
+            },
+        );
+        assert_eq!(decision.vote, VoteChoice::Against);
+        assert!(decision.reasons.iter().any(|reason| reason
+            == "worst of 3 actions: action 2 (publishDapp App1 1.0.0) scored 0.90 with a critical finding"));
+    }
 }
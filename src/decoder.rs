@@ -1,13 +1,16 @@
 use std::str::FromStr;
 
 use alloy::{
-    primitives::{Address, U256},
+    dyn_abi::{DynSolType, DynSolValue, EventExt},
+    json_abi::Event,
+    primitives::{Address, B256, U256},
     rpc::types::Log as RpcLog,
     sol,
-    sol_types::{SolCall, SolEvent},
+    sol_types::SolCall,
 };
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use chrono::Utc;
+use serde::Serialize;
 
 use crate::types::{DecodedAction, Proposal};
 
@@ -28,115 +31,370 @@ sol! {
     function upgradeDapp(uint256 dappId, bytes rootCid, string name, string version, string description);
 }
 
-pub fn proposal_created_topic0() -> String {
-    format!("{:#x}", ProposalCreated::SIGNATURE_HASH)
-}
-
-pub fn decode_proposal_log(log: &RpcLog, dapp_registry: &str) -> Result<Proposal> {
-    let decoded = log
-        .log_decode_validate::<ProposalCreated>()
-        .map_err(|err| anyhow!("failed to decode ProposalCreated log: {err}"))?;
-    let event = decoded.inner.data;
-
-    let proposal_id = event.proposalId.to_string();
-    let vote_start = u256_to_u64(event.voteStart, "voteStart")?;
-    let vote_end = u256_to_u64(event.voteEnd, "voteEnd")?;
-
-    let targets = event
-        .targets
-        .into_iter()
-        .map(|addr| format!("{:#x}", addr))
-        .collect::<Vec<_>>();
-    let values = event
-        .values
-        .into_iter()
-        .map(|value| value.to_string())
-        .collect::<Vec<_>>();
-    let calldatas = event
-        .calldatas
-        .into_iter()
-        .map(|data| format!("0x{}", hex::encode(data)))
-        .collect::<Vec<_>>();
-
-    let action = decode_action(&targets, &calldatas, dapp_registry);
-
-    Ok(Proposal {
-        proposal_id,
-        proposer: format!("{:#x}", event.proposer),
-        description: event.description,
-        vote_start,
-        vote_end,
-        block_number: log.block_number.unwrap_or_default(),
-        tx_hash: log.transaction_hash.map(|hash| format!("{:#x}", hash)),
-        targets,
-        values,
-        calldatas,
-        action,
-        discovered_at: Utc::now(),
-    })
-}
-
-pub fn decode_action(
+// OpenZeppelin Governor (v4 and v5) and Compound GovernorBravo all emit this exact event, so
+// they share one topic0. `network.proposal_created_signature` replaces it for other governors.
+pub const DEFAULT_PROPOSAL_CREATED_SIGNATURE: &str = "ProposalCreated(uint256 proposalId, address proposer, address[] targets, uint256[] values, string[] signatures, bytes[] calldatas, uint256 voteStart, uint256 voteEnd, string description)";
+
+// Layouts probed when the configured signature finds no log where a proposal is known to be.
+pub const KNOWN_PROPOSAL_CREATED_SIGNATURES: &[(&str, &str)] = &[
+    ("openzeppelin", DEFAULT_PROPOSAL_CREATED_SIGNATURE),
+    (
+        "aave-v2",
+        "ProposalCreated(uint256 id, address indexed creator, address indexed executor, address[] targets, uint256[] values, string[] signatures, bytes[] calldatas, bool[] withDelegatecalls, uint256 startBlock, uint256 endBlock, address strategy, bytes32 ipfsHash)",
+    ),
+];
+
+// The governor's other events, so logs with these topics are not taken for an unknown
+// ProposalCreated layout.
+pub const OTHER_GOVERNOR_EVENT_SIGNATURES: &[&str] = &[
+    "VoteCast(address,uint256,uint8,uint256,string)",
+    "VoteCastWithParams(address,uint256,uint8,uint256,string,bytes)",
+    "ProposalQueued(uint256,uint256)",
+    "ProposalExecuted(uint256)",
+    "ProposalCanceled(uint256)",
+    "ProposalExtended(uint256,uint64)",
+    "VotingDelaySet(uint256,uint256)",
+    "VotingPeriodSet(uint256,uint256)",
+    "ProposalThresholdSet(uint256,uint256)",
+    "QuorumNumeratorUpdated(uint256,uint256)",
+    "LateQuorumVoteExtensionSet(uint64,uint64)",
+    "TimelockChange(address,address)",
+    "EIP712DomainChanged()",
+    "Initialized(uint8)",
+    "Initialized(uint64)",
+    "Upgraded(address)",
+];
+
+// A ProposalCreated layout and where the fields a Proposal needs sit in it. Fields are found by
+// parameter name, with the names other governors use as aliases; `description` may be absent.
+#[derive(Debug, Clone)]
+pub struct ProposalCreatedAbi {
+    event: Event,
+    topic0: B256,
+    id: usize,
+    proposer: usize,
+    targets: usize,
+    values: usize,
+    calldatas: usize,
+    vote_start: usize,
+    vote_end: usize,
+    description: Option<usize>,
+}
+
+impl Default for ProposalCreatedAbi {
+    fn default() -> Self {
+        Self::parse(DEFAULT_PROPOSAL_CREATED_SIGNATURE).expect("default ProposalCreated signature")
+    }
+}
+
+impl ProposalCreatedAbi {
+    pub fn from_signature(signature: Option<&str>) -> Result<Self> {
+        match signature {
+            Some(signature) => Self::parse(signature)
+                .with_context(|| format!("invalid ProposalCreated signature {signature:?}")),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(signature: &str) -> Result<Self> {
+        let event = Event::parse(signature.trim()).map_err(|err| anyhow!("{err}"))?;
+        if event.anonymous {
+            bail!("anonymous events have no topic0 to filter on");
+        }
+        let field = |names: &[&str], expected: &str, indexable: bool| -> Result<Option<usize>> {
+            let Some(index) = event
+                .inputs
+                .iter()
+                .position(|input| names.contains(&input.name.as_str()))
+            else {
+                return Ok(None);
+            };
+            let input = &event.inputs[index];
+            let ty = DynSolType::parse(&input.ty).map_err(|err| anyhow!("{err}"))?;
+            let matches = match expected {
+                "uint" => matches!(ty, DynSolType::Uint(_)),
+                _ => ty.sol_type_name() == expected,
+            };
+            if !matches {
+                bail!("{} must be {expected}, not {}", input.name, input.ty);
+            }
+            if input.indexed && !indexable {
+                bail!(
+                    "{} cannot be indexed: only its hash would be logged",
+                    input.name
+                );
+            }
+            Ok(Some(index))
+        };
+        let required = |names: &[&str], expected: &str, indexable: bool| -> Result<usize> {
+            field(names, expected, indexable)?
+                .ok_or_else(|| anyhow!("missing {expected} parameter {}", names.join(" or ")))
+        };
+        Ok(Self {
+            topic0: event.selector(),
+            id: required(&["proposalId", "id"], "uint256", true)?,
+            proposer: required(&["proposer", "creator"], "address", true)?,
+            targets: required(&["targets"], "address[]", false)?,
+            values: required(&["values"], "uint256[]", false)?,
+            calldatas: required(&["calldatas"], "bytes[]", false)?,
+            vote_start: required(&["voteStart", "startBlock"], "uint", true)?,
+            vote_end: required(&["voteEnd", "endBlock"], "uint", true)?,
+            description: field(&["description"], "string", false)?,
+            event,
+        })
+    }
+
+    pub fn topic0(&self) -> B256 {
+        self.topic0
+    }
+
+    pub fn signature(&self) -> String {
+        self.event.signature()
+    }
+
+    pub fn decode(&self, log: &RpcLog, dapp_registry: &str) -> Result<Proposal> {
+        let decoded = self
+            .event
+            .decode_log_parts(log.topics().iter().copied(), &log.data().data)
+            .map_err(|err| anyhow!("failed to decode ProposalCreated log: {err}"))?;
+        let (mut indexed, mut body) = (decoded.indexed.into_iter(), decoded.body.into_iter());
+        let fields = self
+            .event
+            .inputs
+            .iter()
+            .map(|input| {
+                if input.indexed {
+                    indexed.next()
+                } else {
+                    body.next()
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow!("ProposalCreated log has fewer fields than its signature"))?;
+        let uint = |index: usize| fields[index].as_uint().map(|(value, _)| value);
+        let array = |index: usize| fields[index].as_array().unwrap_or_default();
+
+        let proposal_id = uint(self.id).unwrap_or_default().to_string();
+        let vote_start = u256_to_u64(uint(self.vote_start).unwrap_or_default(), "voteStart")?;
+        let vote_end = u256_to_u64(uint(self.vote_end).unwrap_or_default(), "voteEnd")?;
+        let targets = array(self.targets)
+            .iter()
+            .filter_map(DynSolValue::as_address)
+            .map(|addr| format!("{:#x}", addr))
+            .collect::<Vec<_>>();
+        let values = array(self.values)
+            .iter()
+            .filter_map(DynSolValue::as_uint)
+            .map(|(value, _)| value.to_string())
+            .collect::<Vec<_>>();
+        let calldatas = array(self.calldatas)
+            .iter()
+            .filter_map(DynSolValue::as_bytes)
+            .map(|data| format!("0x{}", hex::encode(data)))
+            .collect::<Vec<_>>();
+        let proposer = fields[self.proposer].as_address().unwrap_or_default();
+        let description = self
+            .description
+            .and_then(|index| fields[index].as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let actions = decode_actions(&targets, &calldatas, dapp_registry);
+
+        Ok(Proposal {
+            proposal_id,
+            proposer: format!("{:#x}", proposer),
+            description,
+            vote_start,
+            vote_end,
+            block_number: log.block_number.unwrap_or_default(),
+            tx_hash: log.transaction_hash.map(|hash| format!("{:#x}", hash)),
+            targets,
+            values,
+            calldatas,
+            actions,
+            discovered_at: Utc::now(),
+            creation_context: None,
+        })
+    }
+}
+
+pub fn targets_address(targets: &[String], address: &str) -> bool {
+    let Ok(address) = Address::from_str(address) else {
+        return false;
+    };
+    targets
+        .iter()
+        .any(|target| Address::from_str(target).is_ok_and(|target| target == address))
+}
+
+// Every call is decoded so a batched proposal cannot slip a second action past review; a call to
+// any other contract becomes an unsupported action rather than being dropped.
+pub fn decode_actions(
     targets: &[String],
     calldatas: &[String],
     dapp_registry: &str,
-) -> DecodedAction {
+) -> Vec<DecodedAction> {
     let Ok(dapp_registry_addr) = Address::from_str(dapp_registry) else {
-        return DecodedAction::Unsupported {
+        return vec![DecodedAction::Unsupported {
             reason: format!("invalid dapp registry address configured: {dapp_registry}"),
-        };
+        }];
     };
 
+    let mut actions = Vec::new();
     for (idx, target) in targets.iter().enumerate() {
         let Ok(target_addr) = Address::from_str(target) else {
+            actions.push(DecodedAction::Unsupported {
+                reason: format!("call {idx} has an invalid target address {target:?}"),
+            });
             continue;
         };
 
         if target_addr != dapp_registry_addr {
+            actions.push(DecodedAction::Unsupported {
+                reason: format!("call {idx} targets {target_addr}, not the dapp registry"),
+            });
             continue;
         }
 
-        let Some(calldata_hex) = calldatas.get(idx) else {
-            continue;
-        };
-        let Ok(calldata) = parse_calldata(calldata_hex) else {
+        let Some(calldata) = calldatas
+            .get(idx)
+            .and_then(|calldata_hex| parse_calldata(calldata_hex).ok())
+        else {
+            actions.push(DecodedAction::Unsupported {
+                reason: format!("dapp registry call {idx} has missing or malformed calldata"),
+            });
             continue;
         };
 
         if let Ok(call) = publishDappCall::abi_decode(&calldata) {
-            return DecodedAction::PublishDapp {
+            actions.push(DecodedAction::PublishDapp {
                 root_cid: decode_root_cid(call.rootCid.as_ref()),
                 name: call.name,
                 version: call.version,
                 description: call.description,
-            };
-        }
-
-        if let Ok(call) = upgradeDappCall::abi_decode(&calldata) {
-            return DecodedAction::UpgradeDapp {
+            });
+        } else if let Ok(call) = upgradeDappCall::abi_decode(&calldata) {
+            actions.push(DecodedAction::UpgradeDapp {
                 dapp_id: call.dappId.to_string(),
                 root_cid: decode_root_cid(call.rootCid.as_ref()),
                 name: call.name,
                 version: call.version,
                 description: call.description,
-            };
+            });
+        } else {
+            actions.push(DecodedAction::Unsupported {
+                reason: "target matches dapp registry but calldata did not decode as publishDapp or upgradeDapp"
+                    .to_string(),
+            });
         }
-
-        return DecodedAction::Unsupported {
-            reason: "target matches dapp registry but calldata did not decode as publishDapp or upgradeDapp"
-                .to_string(),
-        };
     }
 
-    DecodedAction::Unsupported {
-        reason: "proposal has no recognized dapp publish/upgrade action".to_string(),
+    if actions.is_empty() {
+        actions.push(DecodedAction::Unsupported {
+            reason: "proposal has no recognized dapp publish/upgrade action".to_string(),
+        });
     }
+    actions
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProposalInspection {
+    pub proposal: Proposal,
+    pub topics: Vec<String>,
+    pub calls: Vec<CallInspection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallInspection {
+    pub index: usize,
+    pub target: String,
+    pub value: Option<String>,
+    pub calldata: Option<String>,
+    pub target_is_dapp_registry: bool,
+    pub selector: Option<String>,
+    pub matched_function: Option<&'static str>,
+    pub decode_error: Option<String>,
+}
+
+pub fn inspect_calls(
+    targets: &[String],
+    values: &[String],
+    calldatas: &[String],
+    dapp_registry: &str,
+) -> Vec<CallInspection> {
+    let dapp_registry_addr = Address::from_str(dapp_registry).ok();
+    let known_selectors = [
+        (publishDappCall::SELECTOR, "publishDapp"),
+        (upgradeDappCall::SELECTOR, "upgradeDapp"),
+    ];
+
+    targets
+        .iter()
+        .enumerate()
+        .map(|(idx, target)| {
+            let target_is_dapp_registry = dapp_registry_addr.is_some()
+                && Address::from_str(target).ok() == dapp_registry_addr;
+            let calldata_hex = calldatas.get(idx).cloned();
+            let mut inspection = CallInspection {
+                index: idx,
+                target: target.clone(),
+                value: values.get(idx).cloned(),
+                calldata: calldata_hex.clone(),
+                target_is_dapp_registry,
+                selector: None,
+                matched_function: None,
+                decode_error: None,
+            };
+
+            let Some(calldata_hex) = calldata_hex else {
+                inspection.decode_error = Some("no calldata at this index".to_string());
+                return inspection;
+            };
+            let calldata = match parse_calldata(&calldata_hex) {
+                Ok(calldata) => calldata,
+                Err(err) => {
+                    inspection.decode_error = Some(err.to_string());
+                    return inspection;
+                }
+            };
+            if calldata.len() < 4 {
+                inspection.decode_error =
+                    Some("calldata shorter than a 4-byte selector".to_string());
+                return inspection;
+            }
+
+            inspection.selector = Some(format!("0x{}", hex::encode(&calldata[..4])));
+            let Some((_, name)) = known_selectors
+                .iter()
+                .find(|(selector, _)| selector[..] == calldata[..4])
+            else {
+                inspection.decode_error =
+                    Some("selector does not match a known registry function".to_string());
+                return inspection;
+            };
+            inspection.matched_function = Some(name);
+
+            let decoded = match *name {
+                "publishDapp" => publishDappCall::abi_decode(&calldata).map(|_| ()),
+                _ => upgradeDappCall::abi_decode(&calldata).map(|_| ()),
+            };
+            if let Err(err) = decoded {
+                inspection.decode_error = Some(format!("{name} arguments failed to decode: {err}"));
+            }
+
+            inspection
+        })
+        .collect()
 }
 
 pub fn decode_root_cid(bytes: &[u8]) -> String {
     if bytes.is_empty() {
         return String::new();
     }
+    if let Some(cid) = binary_cid_to_base32(bytes) {
+        return cid;
+    }
 
     match String::from_utf8(bytes.to_vec()) {
         Ok(text) if !text.trim().is_empty() => text,
@@ -144,6 +402,62 @@ pub fn decode_root_cid(bytes: &[u8]) -> String {
     }
 }
 
+// Content codecs a bundle root can use: raw, dag-pb, dag-cbor, dag-json.
+const CID_CODECS: [u64; 4] = [0x55, 0x70, 0x71, 0x0129];
+const DAG_PB_CODEC: u8 = 0x70;
+const SHA2_256_MULTIHASH: [u8; 2] = [0x12, 0x20];
+
+// A binary CID starts with a control byte (0x01 for v1, 0x12 for a v0 multihash), which a CID
+// string never contains, so this is checked before the UTF-8 path. v0 is upgraded to the
+// equivalent dag-pb v1 so every root renders as a `bafy...` string.
+fn binary_cid_to_base32(bytes: &[u8]) -> Option<String> {
+    if bytes.len() == 34 && bytes.starts_with(&SHA2_256_MULTIHASH) {
+        let mut v1 = vec![0x01, DAG_PB_CODEC];
+        v1.extend_from_slice(bytes);
+        return Some(format!("b{}", base32_lower(&v1)));
+    }
+
+    let (version, rest) = read_varint(bytes)?;
+    let (codec, rest) = read_varint(rest)?;
+    let (_hash_code, rest) = read_varint(rest)?;
+    let (digest_len, digest) = read_varint(rest)?;
+    if version != 1 || !CID_CODECS.contains(&codec) || digest.len() as u64 != digest_len {
+        return None;
+    }
+    Some(format!("b{}", base32_lower(bytes)))
+}
+
+// Unsigned LEB128, as used by multiformats; at most 9 bytes.
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[index + 1..]));
+        }
+    }
+    None
+}
+
+// RFC 4648 base32, lowercase and unpadded: the multibase `b` encoding.
+fn base32_lower(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
 fn u256_to_u64(value: U256, field_name: &str) -> Result<u64> {
     u64::try_from(value).map_err(|_| anyhow!("{field_name} overflows u64: {value}"))
 }
@@ -158,12 +472,101 @@ fn parse_calldata(value: &str) -> Result<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use alloy::primitives::{Bytes, U256};
+    use alloy::{
+        primitives::{Address, Bytes, U256},
+        rpc::types::Log as RpcLog,
+        sol_types::SolEvent,
+    };
 
     use super::{
-        DecodedAction, SolCall, decode_action, decode_root_cid, publishDappCall, upgradeDappCall,
+        DecodedAction, KNOWN_PROPOSAL_CREATED_SIGNATURES, ProposalCreated, ProposalCreatedAbi,
+        SolCall, decode_actions, decode_root_cid, inspect_calls, publishDappCall, upgradeDappCall,
     };
 
+    mod timestamped {
+        alloy::sol! {
+            event ProposalCreated(
+                uint256 proposalId,
+                address indexed proposer,
+                address[] targets,
+                uint256[] values,
+                string[] signatures,
+                bytes[] calldatas,
+                uint256 voteStart,
+                uint256 voteEnd,
+                uint256 voteStartTimestamp,
+                string description
+            );
+        }
+    }
+
+    #[test]
+    fn proposal_created_signature_decides_topic0_and_field_positions() {
+        let default = ProposalCreatedAbi::default();
+        assert_eq!(default.topic0(), ProposalCreated::SIGNATURE_HASH);
+        for (name, signature) in KNOWN_PROPOSAL_CREATED_SIGNATURES {
+            ProposalCreatedAbi::parse(signature).unwrap_or_else(|err| panic!("{name}: {err:#}"));
+        }
+
+        let signature = "event ProposalCreated(uint256 proposalId, address indexed proposer, address[] targets, uint256[] values, string[] signatures, bytes[] calldatas, uint256 voteStart, uint256 voteEnd, uint256 voteStartTimestamp, string description)";
+        let custom = ProposalCreatedAbi::parse(signature).expect("custom signature");
+        assert_eq!(
+            custom.topic0(),
+            timestamped::ProposalCreated::SIGNATURE_HASH
+        );
+        assert_ne!(custom.topic0(), default.topic0());
+
+        let registry = Address::repeat_byte(0x22);
+        let event = timestamped::ProposalCreated {
+            proposalId: U256::from(7),
+            proposer: Address::repeat_byte(0x01),
+            targets: vec![registry],
+            values: vec![U256::ZERO],
+            signatures: vec![String::new()],
+            calldatas: vec![Bytes::from(vec![0xde, 0xad])],
+            voteStart: U256::from(10),
+            voteEnd: U256::from(20),
+            voteStartTimestamp: U256::from(1_700_000_000u64),
+            description: "upgrade".to_string(),
+        };
+        let mut log = RpcLog {
+            block_number: Some(5),
+            ..Default::default()
+        };
+        log.inner.data = event.encode_log_data();
+
+        let proposal = custom
+            .decode(&log, &format!("{registry:#x}"))
+            .expect("decode");
+        assert_eq!(proposal.proposal_id, "7");
+        assert_eq!(
+            proposal.proposer,
+            format!("{:#x}", Address::repeat_byte(0x01))
+        );
+        assert_eq!((proposal.vote_start, proposal.vote_end), (10, 20));
+        assert_eq!(proposal.description, "upgrade");
+        assert_eq!(proposal.calldatas, vec!["0xdead".to_string()]);
+        assert!(default.decode(&log, &format!("{registry:#x}")).is_err());
+
+        for (signature, error) in [
+            (
+                "ProposalCreated(uint256 proposalId)",
+                "missing address parameter proposer or creator",
+            ),
+            (
+                "ProposalCreated(uint256 proposalId, address proposer, address[] targets, uint256[] values, bytes[] calldatas, uint256 voteStart, address voteEnd)",
+                "voteEnd must be uint, not address",
+            ),
+            (
+                "ProposalCreated(uint256 proposalId, address proposer, address[] indexed targets, uint256[] values, bytes[] calldatas, uint256 voteStart, uint256 voteEnd)",
+                "targets cannot be indexed",
+            ),
+        ] {
+            let err = ProposalCreatedAbi::parse(signature).expect_err(signature);
+            assert!(err.to_string().contains(error), "{err:#}");
+        }
+    }
+
     #[test]
     fn decode_root_cid_prefers_utf8() {
         let input = b"bafybeigdyrztv4";
@@ -176,6 +579,24 @@ mod tests {
         assert_eq!(decode_root_cid(&input), "0xff0102");
     }
 
+    #[test]
+    fn decode_root_cid_encodes_binary_cids_as_base32() {
+        // bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi, the IPFS docs example.
+        let digest =
+            hex::decode("c3c4733ec8affd06cf9e9ff50ffc6bcd2ec85a6170004bb709669c31de94391a")
+                .expect("digest hex");
+        let mut v1 = vec![0x01, 0x70, 0x12, 0x20];
+        v1.extend_from_slice(&digest);
+        let expected = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        assert_eq!(decode_root_cid(&v1), expected);
+        assert_eq!(decode_root_cid(&v1[2..]), expected);
+        assert_eq!(decode_root_cid(expected.as_bytes()), expected);
+
+        let mut truncated = v1.clone();
+        truncated.pop();
+        assert!(decode_root_cid(&truncated).starts_with("0x0170"));
+    }
+
     #[test]
     fn decode_publish_action_from_calldata() {
         let call = publishDappCall {
@@ -185,13 +606,13 @@ mod tests {
             description: "desc".to_string(),
         };
 
-        let decoded = decode_action(
+        let decoded = decode_actions(
             &["0xfb84b57e757649dff3870f1381c67c9097d0c67f".to_string()],
             &[format!("0x{}", hex::encode(call.abi_encode()))],
             "0xFb84B57E757649Dff3870F1381C67c9097D0c67f",
         );
 
-        match decoded {
+        match decoded.into_iter().next().expect("decoded action") {
             DecodedAction::PublishDapp {
                 root_cid,
                 name,
@@ -217,13 +638,13 @@ mod tests {
             description: "desc2".to_string(),
         };
 
-        let decoded = decode_action(
+        let decoded = decode_actions(
             &["0xfb84b57e757649dff3870f1381c67c9097d0c67f".to_string()],
             &[format!("0x{}", hex::encode(call.abi_encode()))],
             "0xFb84B57E757649Dff3870F1381C67c9097D0c67f",
         );
 
-        match decoded {
+        match decoded.into_iter().next().expect("decoded action") {
             DecodedAction::UpgradeDapp {
                 dapp_id,
                 root_cid,
@@ -240,4 +661,81 @@ mod tests {
             _ => panic!("expected upgrade action"),
         }
     }
+
+    #[test]
+    fn decodes_every_registry_call_in_a_batched_proposal() {
+        let registry = "0xfb84b57e757649dff3870f1381c67c9097d0c67f".to_string();
+        let publish = publishDappCall {
+            rootCid: Bytes::from(b"bafy-publish".to_vec()),
+            name: "App".to_string(),
+            version: "1.0.0".to_string(),
+            description: "desc".to_string(),
+        };
+        let upgrade = upgradeDappCall {
+            dappId: U256::from(7u64),
+            rootCid: Bytes::from(b"bafy-upgrade".to_vec()),
+            name: "Other".to_string(),
+            version: "2.0.0".to_string(),
+            description: "desc2".to_string(),
+        };
+
+        let decoded = decode_actions(
+            &[
+                registry.clone(),
+                "0x0000000000000000000000000000000000000001".to_string(),
+                registry.clone(),
+                registry,
+            ],
+            &[
+                format!("0x{}", hex::encode(publish.abi_encode())),
+                "0x".to_string(),
+                format!("0x{}", hex::encode(upgrade.abi_encode())),
+                "0xdeadbeef".to_string(),
+            ],
+            "0xFb84B57E757649Dff3870F1381C67c9097D0c67f",
+        );
+
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded[0].root_cid(), Some("bafy-publish"));
+        assert!(matches!(
+            &decoded[1],
+            DecodedAction::Unsupported { reason }
+                if reason == "call 1 targets 0x0000000000000000000000000000000000000001, not the dapp registry"
+        ));
+        assert_eq!(decoded[2].root_cid(), Some("bafy-upgrade"));
+        assert!(matches!(decoded[3], DecodedAction::Unsupported { .. }));
+    }
+
+    #[test]
+    fn inspect_calls_reports_selector_matches_and_unknown_calls() {
+        let call = publishDappCall {
+            rootCid: Bytes::from(b"bafy123".to_vec()),
+            name: "App".to_string(),
+            version: "1.0.0".to_string(),
+            description: "desc".to_string(),
+        };
+
+        let calls = inspect_calls(
+            &[
+                "0xfb84b57e757649dff3870f1381c67c9097d0c67f".to_string(),
+                "0x1111111111111111111111111111111111111111".to_string(),
+            ],
+            &["0".to_string(), "0".to_string()],
+            &[
+                format!("0x{}", hex::encode(call.abi_encode())),
+                "0xdeadbeef".to_string(),
+            ],
+            "0xFb84B57E757649Dff3870F1381C67c9097D0c67f",
+        );
+
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].target_is_dapp_registry);
+        assert_eq!(calls[0].matched_function, Some("publishDapp"));
+        assert!(calls[0].decode_error.is_none());
+
+        assert!(!calls[1].target_is_dapp_registry);
+        assert_eq!(calls[1].selector.as_deref(), Some("0xdeadbeef"));
+        assert!(calls[1].matched_function.is_none());
+        assert!(calls[1].decode_error.is_some());
+    }
 }
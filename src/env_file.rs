@@ -0,0 +1,156 @@
+use std::{env, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+
+#[derive(Debug, Default)]
+pub struct EnvFileLoad {
+    pub applied: Vec<String>,
+    pub kept: Vec<String>,
+}
+
+// Must run before any other thread exists: it writes to the process environment.
+pub fn load(path: &Path) -> Result<EnvFileLoad> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read env file {}", path.display()))?;
+    let entries =
+        parse(&raw).with_context(|| format!("failed to parse env file {}", path.display()))?;
+
+    let (apply, load) = partition(entries, |key| env::var_os(key).is_some());
+    for (key, value) in apply {
+        // SAFETY: called from `app::prepare` before the tokio runtime or any other thread starts.
+        unsafe { env::set_var(key, value) };
+    }
+    Ok(load)
+}
+
+// Variables already present in the process environment always win over the file.
+fn partition(
+    entries: Vec<(String, String)>,
+    is_set: impl Fn(&str) -> bool,
+) -> (Vec<(String, String)>, EnvFileLoad) {
+    let mut load = EnvFileLoad::default();
+    let mut apply = Vec::new();
+    for (key, value) in entries {
+        if is_set(&key) || load.applied.contains(&key) {
+            load.kept.push(key);
+        } else {
+            load.applied.push(key.clone());
+            apply.push((key, value));
+        }
+    }
+    (apply, load)
+}
+
+fn parse(raw: &str) -> Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+    for (idx, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected KEY=VALUE", idx + 1);
+        };
+        let key = key.trim();
+        let valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            bail!("line {}: invalid variable name {key:?}", idx + 1);
+        }
+        entries.push((key.to_string(), parse_value(value.trim())));
+    }
+    Ok(entries)
+}
+
+fn parse_value(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].to_string();
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut out = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n')) => {
+                    out.push('\n');
+                    chars.next();
+                }
+                ('\\', Some(escaped @ ('"' | '\\'))) => {
+                    out.push(escaped);
+                    chars.next();
+                }
+                _ => out.push(c),
+            }
+        }
+        return out;
+    }
+    match value.find(" #") {
+        Some(comment) => value[..comment].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, partition};
+
+    #[test]
+    fn parses_comments_exports_and_quoting() {
+        let entries = parse(
+            r#"
+# agent settings
+GOV_AGENT_PROFILE=sepolia
+export OPENAI_API_KEY = sk-test # trailing comment
+GOV_AGENT_RPC_URL="https://rpc.example/v2/key#frag"
+PASSWORD='p@ss #word'
+MULTILINE="a\nb \"quoted\""
+EMPTY=
+"#,
+        )
+        .expect("parse env file");
+
+        assert_eq!(
+            entries,
+            vec![
+                ("GOV_AGENT_PROFILE".to_string(), "sepolia".to_string()),
+                ("OPENAI_API_KEY".to_string(), "sk-test".to_string()),
+                (
+                    "GOV_AGENT_RPC_URL".to_string(),
+                    "https://rpc.example/v2/key#frag".to_string()
+                ),
+                ("PASSWORD".to_string(), "p@ss #word".to_string()),
+                ("MULTILINE".to_string(), "a\nb \"quoted\"".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+        assert!(parse("NOT AN ASSIGNMENT").is_err());
+        assert!(parse("1BAD=x").is_err());
+    }
+
+    #[test]
+    fn existing_process_env_is_never_overridden() {
+        let entries = vec![
+            ("GOV_AGENT_PROFILE".to_string(), "sepolia".to_string()),
+            ("OPENAI_API_KEY".to_string(), "from-file".to_string()),
+            ("GOV_AGENT_PROFILE".to_string(), "devnet".to_string()),
+        ];
+        let (apply, load) = partition(entries, |key| key == "OPENAI_API_KEY");
+
+        assert_eq!(
+            apply,
+            vec![("GOV_AGENT_PROFILE".to_string(), "sepolia".to_string())]
+        );
+        assert_eq!(load.applied, vec!["GOV_AGENT_PROFILE".to_string()]);
+        assert_eq!(
+            load.kept,
+            vec![
+                "OPENAI_API_KEY".to_string(),
+                "GOV_AGENT_PROFILE".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::{collections::BTreeMap, env, time::Duration};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ExportWebhookConfig, HttpConfig},
+    http::{self, HttpService},
+};
+
+const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+// A failed entry waits this long before its next attempt, doubling per failure up to the max, so a
+// dead endpoint is not hit with the whole queue every cycle.
+const RETRY_BASE_DELAY: chrono::Duration = chrono::Duration::minutes(1);
+const MAX_RETRY_DELAY: chrono::Duration = chrono::Duration::hours(1);
+// Bounds how long one flush can hold up a scan cycle; the rest wait for the next one.
+const MAX_ATTEMPTS_PER_FLUSH: usize = 20;
+// Long past the point where a proposal could be processed, and so queued, again.
+const DELIVERED_RETENTION: chrono::Duration = chrono::Duration::days(90);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub idempotency_key: String,
+    pub queued_at: DateTime<Utc>,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl OutboxEntry {
+    fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        let last = self.last_attempt_at?;
+        let doublings = self.attempts.saturating_sub(1).min(16);
+        Some(last + (RETRY_BASE_DELAY * 2_i32.pow(doublings)).min(MAX_RETRY_DELAY))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDelivery {
+    pub idempotency_key: String,
+    pub delivered_at: DateTime<Utc>,
+}
+
+// Persisted in state so queued exports survive restarts. Keyed by proposal id; an id moves from
+// `pending` to `delivered` once the endpoint accepts it and is not queued again while it is
+// recorded there. Delivered ids are dropped after `DELIVERED_RETENTION`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportOutbox {
+    #[serde(default)]
+    pub pending: BTreeMap<String, OutboxEntry>,
+    #[serde(default)]
+    pub delivered: BTreeMap<String, ExportDelivery>,
+}
+
+impl ExportOutbox {
+    // Returns false when the proposal was already queued or delivered.
+    pub fn enqueue(&mut self, proposal_id: &str, config_hash: &str) -> bool {
+        if self.pending.contains_key(proposal_id) || self.delivered.contains_key(proposal_id) {
+            return false;
+        }
+        self.pending.insert(
+            proposal_id.to_string(),
+            OutboxEntry {
+                idempotency_key: format!("{proposal_id}:{config_hash}"),
+                queued_at: Utc::now(),
+                attempts: 0,
+                last_error: None,
+                last_attempt_at: None,
+            },
+        );
+        true
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FlushReport {
+    pub delivered: usize,
+    pub failed: usize,
+    // Left for a later flush: still backing off, or over the per-flush cap.
+    pub deferred: usize,
+    pub pending: usize,
+}
+
+pub struct ExportWebhook {
+    url: String,
+    bearer_token_env: Option<String>,
+    client: Client,
+}
+
+impl ExportWebhook {
+    pub fn from_config(config: &ExportWebhookConfig, http_cfg: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            url: config.url.clone(),
+            bearer_token_env: config.bearer_token_env.clone(),
+            client: http::build_client(
+                http_cfg,
+                HttpService {
+                    name: "export",
+                    ..HttpService::default()
+                },
+                Some(DELIVERY_TIMEOUT),
+            )?,
+        })
+    }
+
+    async fn deliver(&self, idempotency_key: &str, payload: &impl Serialize) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(IDEMPOTENCY_HEADER, idempotency_key)
+            .json(payload);
+        if let Some(name) = &self.bearer_token_env {
+            let token = env::var(name)
+                .map_err(|_| anyhow!("export webhook token env var {name} is not set"))?;
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .context("export webhook request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("export webhook returned HTTP {status}");
+        }
+        Ok(())
+    }
+
+    // `load` builds the payload for a queued proposal id. A failed entry stays queued with its
+    // error and is retried once its backoff has passed; `ignore_backoff` retries it right away.
+    // At most `MAX_ATTEMPTS_PER_FLUSH` entries are tried, oldest first.
+    pub async fn flush<T: Serialize>(
+        &self,
+        outbox: &mut ExportOutbox,
+        ignore_backoff: bool,
+        mut load: impl FnMut(&str) -> Result<T>,
+    ) -> FlushReport {
+        let now = Utc::now();
+        outbox
+            .delivered
+            .retain(|_, delivery| now - delivery.delivered_at < DELIVERED_RETENTION);
+
+        let mut due = outbox
+            .pending
+            .iter()
+            .filter(|(_, entry)| {
+                ignore_backoff || entry.next_attempt_at().is_none_or(|at| at <= now)
+            })
+            .map(|(proposal_id, entry)| (entry.queued_at, proposal_id.clone()))
+            .collect::<Vec<_>>();
+        due.sort();
+        due.truncate(MAX_ATTEMPTS_PER_FLUSH);
+        let mut report = FlushReport {
+            deferred: outbox.pending.len() - due.len(),
+            ..FlushReport::default()
+        };
+        for (_, proposal_id) in due {
+            let Some(entry) = outbox.pending.get_mut(&proposal_id) else {
+                continue;
+            };
+            entry.attempts += 1;
+            entry.last_attempt_at = Some(Utc::now());
+            let result = match load(&proposal_id) {
+                Ok(payload) => self.deliver(&entry.idempotency_key, &payload).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(()) => {
+                    let idempotency_key = entry.idempotency_key.clone();
+                    outbox.pending.remove(&proposal_id);
+                    outbox.delivered.insert(
+                        proposal_id,
+                        ExportDelivery {
+                            idempotency_key,
+                            delivered_at: Utc::now(),
+                        },
+                    );
+                    report.delivered += 1;
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        proposal_id = %proposal_id,
+                        attempts = entry.attempts,
+                        error = %format!("{err:#}"),
+                        "review export delivery failed; keeping it queued"
+                    );
+                    entry.last_error = Some(format!("{err:#}"));
+                    report.failed += 1;
+                }
+            }
+        }
+        report.pending = outbox.pending.len();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use serde_json::json;
+
+    use chrono::Utc;
+
+    use super::{
+        DELIVERED_RETENTION, ExportDelivery, ExportOutbox, ExportWebhook, MAX_ATTEMPTS_PER_FLUSH,
+    };
+    use crate::config::{ExportWebhookConfig, HttpConfig};
+
+    // Answers one request per status and returns each request's idempotency key.
+    fn serve(statuses: &'static [u16]) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let url = format!(
+            "http://{}/reviews",
+            listener.local_addr().expect("local addr")
+        );
+        let handle = thread::spawn(move || {
+            let mut keys = Vec::new();
+            for status in statuses {
+                let (stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read header");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    let Some((name, value)) = line.split_once(':') else {
+                        continue;
+                    };
+                    if name.eq_ignore_ascii_case("idempotency-key") {
+                        keys.push(value.trim().to_string());
+                    } else if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().expect("content length");
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).expect("read body");
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .expect("write reply");
+            }
+            keys
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn failed_export_stays_queued_and_is_sent_once_after_success() {
+        let (url, server) = serve(&[500, 200]);
+        let webhook = ExportWebhook::from_config(
+            &ExportWebhookConfig {
+                url,
+                bearer_token_env: None,
+            },
+            &HttpConfig::default(),
+        )
+        .expect("build webhook");
+        let mut outbox = ExportOutbox::default();
+        assert!(outbox.enqueue("7", "abc"));
+        assert!(!outbox.enqueue("7", "abc"));
+
+        let payload = |id: &str| anyhow::Ok(json!({ "proposal": { "proposal_id": id } }));
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!((report.delivered, report.failed, report.pending), (0, 1, 1));
+        let entry = &outbox.pending["7"];
+        assert_eq!(entry.attempts, 1);
+        assert!(entry.last_error.as_deref().unwrap().contains("HTTP 500"));
+
+        // The failed entry backs off; the next cycle leaves it alone.
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!(
+            (report.delivered, report.deferred, report.pending),
+            (0, 1, 1)
+        );
+        assert_eq!(outbox.pending["7"].attempts, 1);
+
+        outbox.pending.get_mut("7").unwrap().last_attempt_at =
+            Some(Utc::now() - chrono::Duration::minutes(2));
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!((report.delivered, report.failed, report.pending), (1, 0, 0));
+        assert_eq!(outbox.delivered["7"].idempotency_key, "7:abc");
+        assert!(!outbox.enqueue("7", "abc"));
+
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!(report.delivered, 0);
+        assert_eq!(server.join().expect("mock server"), vec!["7:abc", "7:abc"]);
+    }
+
+    #[tokio::test]
+    async fn flush_is_capped_per_cycle_and_forgets_old_deliveries() {
+        let webhook = ExportWebhook::from_config(
+            &ExportWebhookConfig {
+                url: "http://127.0.0.1:1/reviews".to_string(),
+                bearer_token_env: None,
+            },
+            &HttpConfig::default(),
+        )
+        .expect("build webhook");
+        let mut outbox = ExportOutbox::default();
+        for id in 0..MAX_ATTEMPTS_PER_FLUSH + 5 {
+            outbox.enqueue(&id.to_string(), "abc");
+        }
+        for (id, age) in [
+            ("old", DELIVERED_RETENTION),
+            ("recent", chrono::Duration::days(1)),
+        ] {
+            outbox.delivered.insert(
+                id.to_string(),
+                ExportDelivery {
+                    idempotency_key: format!("{id}:abc"),
+                    delivered_at: Utc::now() - age,
+                },
+            );
+        }
+
+        let payload = |_: &str| anyhow::Ok(json!({}));
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!(
+            (report.failed, report.deferred, report.pending),
+            (MAX_ATTEMPTS_PER_FLUSH, 5, MAX_ATTEMPTS_PER_FLUSH + 5)
+        );
+        assert_eq!(outbox.delivered.keys().collect::<Vec<_>>(), vec!["recent"]);
+
+        // The untried entries go next, and on demand the backoff is skipped as well.
+        let report = webhook.flush(&mut outbox, false, payload).await;
+        assert_eq!(
+            (report.failed, report.deferred),
+            (5, MAX_ATTEMPTS_PER_FLUSH)
+        );
+        let report = webhook.flush(&mut outbox, true, payload).await;
+        assert_eq!(
+            (report.failed, report.deferred),
+            (MAX_ATTEMPTS_PER_FLUSH, 5)
+        );
+    }
+}
@@ -0,0 +1,862 @@
+use std::{borrow::Cow, env, time::Duration};
+
+use alloy::{
+    primitives::{Address, Bytes, U256, aliases::U48},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    signers::{SignerSync, local::PrivateKeySigner},
+    sol,
+    sol_types::{Eip712Domain, SolCall, SolStruct},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        ForwarderConfig, HttpConfig, NetworkConfig, SignerAccount, SignerConfig, SupportMapping,
+    },
+    http::{self, HttpService},
+    observability, rpc_stats,
+    signer::{
+        ClockMode, FRACTIONAL_SUPPORT, IVfiGovernor, Preflight, VoteExecutor, VoteGuard,
+        build_vote_reason, decrypt_account,
+    },
+    storage::{PendingRelay, VoteIntent, VoteIntentLog},
+    types::{Decision, Proposal, VoteExecution},
+};
+
+const RELAY_TIMEOUT: Duration = Duration::from_secs(30);
+// A relayed tx seen on chain may still confirm after the request deadline has passed.
+const SUBMITTED_GRACE_SECS: u64 = 300;
+// How long one vote attempt polls the relay before handing the task to the next cycle, so a slow
+// relay cannot stall the scan loop.
+const RELAY_WAIT_PER_ATTEMPT: Duration = Duration::from_secs(10);
+
+sol! {
+    // OpenZeppelin ERC2771Forwarder's EIP-712 type; the nonce is read from the forwarder.
+    #[derive(Debug, PartialEq, Eq)]
+    struct ForwardRequest {
+        address from;
+        address to;
+        uint256 value;
+        uint256 gas;
+        uint256 nonce;
+        uint48 deadline;
+        bytes data;
+    }
+
+    #[sol(rpc)]
+    interface IErc2771Forwarder {
+        #[derive(Debug, PartialEq, Eq)]
+        struct ForwardRequestData {
+            address from;
+            address to;
+            uint256 value;
+            uint256 gas;
+            uint48 deadline;
+            bytes data;
+            bytes signature;
+        }
+
+        function nonces(address owner) external view returns (uint256);
+        function verify(ForwardRequestData request) external view returns (bool);
+        function eip712Domain() external view returns (bytes1 fields, string name, string version, uint256 chainId, address verifyingContract, bytes32 salt, uint256[] extensions);
+    }
+}
+
+use IErc2771Forwarder::ForwardRequestData;
+
+#[async_trait]
+pub trait ForwarderReads: Send + Sync {
+    async fn nonce(&self, from: Address) -> Result<U256>;
+    async fn verify(&self, request: &ForwardRequestData) -> Result<bool>;
+}
+
+struct ProviderForwarderReads<'a> {
+    forwarder: &'a IErc2771Forwarder::IErc2771ForwarderInstance<DynProvider>,
+}
+
+#[async_trait]
+impl ForwarderReads for ProviderForwarderReads<'_> {
+    async fn nonce(&self, from: Address) -> Result<U256> {
+        self.forwarder
+            .nonces(from)
+            .call()
+            .await
+            .context("failed to read forwarder nonce")
+    }
+
+    async fn verify(&self, request: &ForwardRequestData) -> Result<bool> {
+        self.forwarder
+            .verify(request.clone())
+            .call()
+            .await
+            .context("failed to call forwarder verify")
+    }
+}
+
+// Wire format of `signer.forwarder.relay_url`: POST the submission, get back `{"task_id"}`, then
+// GET `<relay_url>/<task_id>` until the status is final.
+#[derive(Debug, Serialize)]
+pub struct RelaySubmission {
+    pub chain_id: u64,
+    pub forwarder: Address,
+    pub request: RelayRequest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelayRequest {
+    pub from: Address,
+    pub to: Address,
+    pub value: String,
+    pub gas: String,
+    pub nonce: String,
+    pub deadline: u64,
+    pub data: Bytes,
+    pub signature: Bytes,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelayTask {
+    task_id: String,
+}
+
+// `mined` means the forwarder tx succeeded; a reverted tx is reported as `failed`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum RelayStatus {
+    Pending,
+    Submitted {
+        tx_hash: String,
+    },
+    Mined {
+        tx_hash: String,
+    },
+    Failed {
+        #[serde(default)]
+        tx_hash: Option<String>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+}
+
+#[async_trait]
+pub trait RelayClient: Send + Sync {
+    async fn submit(&self, submission: &RelaySubmission) -> Result<String>;
+    async fn status(&self, task_id: &str) -> Result<RelayStatus>;
+}
+
+pub struct HttpRelay {
+    url: String,
+    api_key_env: Option<String>,
+    client: Client,
+}
+
+impl HttpRelay {
+    pub fn from_config(config: &ForwarderConfig, http_cfg: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            url: config.relay_url.trim_end_matches('/').to_string(),
+            api_key_env: config.api_key_env.clone(),
+            client: http::build_client(
+                http_cfg,
+                HttpService {
+                    name: "relay",
+                    ..HttpService::default()
+                },
+                Some(RELAY_TIMEOUT),
+            )?,
+        })
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+        let Some(name) = &self.api_key_env else {
+            return Ok(request);
+        };
+        let key = env::var(name).map_err(|_| anyhow!("relay api key env var {name} is not set"))?;
+        Ok(request.bearer_auth(key))
+    }
+}
+
+#[async_trait]
+impl RelayClient for HttpRelay {
+    async fn submit(&self, submission: &RelaySubmission) -> Result<String> {
+        let response = self
+            .authorize(self.client.post(&self.url).json(submission))?
+            .send()
+            .await
+            .context("relay submit request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("relay rejected the forward request with HTTP {status}");
+        }
+        let task = response
+            .json::<RelayTask>()
+            .await
+            .context("relay submit response is not {\"task_id\": ...}")?;
+        Ok(task.task_id)
+    }
+
+    async fn status(&self, task_id: &str) -> Result<RelayStatus> {
+        let response = self
+            .authorize(self.client.get(format!("{}/{task_id}", self.url)))?
+            .send()
+            .await
+            .context("relay status request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("relay status for task {task_id} returned HTTP {status}");
+        }
+        response
+            .json::<RelayStatus>()
+            .await
+            .with_context(|| format!("invalid relay status for task {task_id}"))
+    }
+}
+
+// Signs castVoteWithReason as an EIP-2771 meta-transaction; the relayer pays the gas and the
+// governor sees the signer as the voter.
+pub struct ForwarderVoteExecutor {
+    guard: VoteGuard,
+    forwarder: IErc2771Forwarder::IErc2771ForwarderInstance<DynProvider>,
+    domain: Eip712Domain,
+    chain_id: u64,
+    key: PrivateKeySigner,
+    relay: HttpRelay,
+    gas_limit: u64,
+    request_ttl_secs: u64,
+    poll_interval: Duration,
+    max_vote_reason_len: usize,
+    support_mapping: SupportMapping,
+}
+
+impl ForwarderVoteExecutor {
+    pub async fn from_config(
+        network: &NetworkConfig,
+        signer: &SignerConfig,
+        forwarder_cfg: &ForwarderConfig,
+        account: &SignerAccount,
+        http_cfg: &HttpConfig,
+        intents: VoteIntentLog,
+        clock_mode: ClockMode,
+    ) -> Result<Self> {
+        let keystore_path = account
+            .keystore_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("auto-vote requires signer.keystore_path"))?;
+        let key = decrypt_account(account, keystore_path)?;
+        let signer_address = key.address();
+
+        let client = rpc_stats::instrumented_client(&network.rpc_url, http_cfg).await?;
+        let provider = ProviderBuilder::new().connect_client(client).erased();
+        let forwarder_address = forwarder_cfg.address.parse::<Address>().with_context(|| {
+            format!("invalid signer.forwarder.address {}", forwarder_cfg.address)
+        })?;
+        let forwarder = IErc2771Forwarder::new(forwarder_address, provider.clone());
+        let domain = forwarder
+            .eip712Domain()
+            .call()
+            .await
+            .context("failed to read the forwarder EIP-712 domain")?;
+        if domain.chainId != U256::from(network.chain_id) {
+            bail!(
+                "forwarder {forwarder_address} signs for chain {}, but network.chain_id is {}",
+                domain.chainId,
+                network.chain_id
+            );
+        }
+        let domain = Eip712Domain::new(
+            Some(Cow::Owned(domain.name)),
+            Some(Cow::Owned(domain.version)),
+            Some(domain.chainId),
+            Some(domain.verifyingContract),
+            None,
+        );
+
+        let guard = VoteGuard::new(
+            provider,
+            network,
+            signer,
+            account,
+            signer_address,
+            intents,
+            clock_mode,
+        )
+        .await?;
+        tracing::info!(
+            account = %account.label,
+            signer = %observability::log_address(&signer_address.to_string()),
+            forwarder = %forwarder_address,
+            "forwarder executor ready; votes are relayed as meta-transactions"
+        );
+
+        Ok(Self {
+            guard,
+            forwarder,
+            domain,
+            chain_id: network.chain_id,
+            key,
+            relay: HttpRelay::from_config(forwarder_cfg, http_cfg)?,
+            gas_limit: forwarder_cfg.gas_limit,
+            request_ttl_secs: forwarder_cfg.request_ttl_secs,
+            poll_interval: Duration::from_secs(forwarder_cfg.poll_interval_secs),
+            max_vote_reason_len: signer.max_vote_reason_len,
+            support_mapping: signer.support_mapping,
+        })
+    }
+}
+
+#[async_trait]
+impl VoteExecutor for ForwarderVoteExecutor {
+    async fn submit_vote(&self, proposal: &Proposal, decision: &Decision) -> Result<VoteExecution> {
+        let (proposal_id, intent) = match self.guard.preflight(proposal, decision).await? {
+            Preflight::Done(execution) => return Ok(execution),
+            Preflight::Send {
+                proposal_id,
+                intent,
+            } => (proposal_id, intent),
+        };
+        let reason = build_vote_reason(decision, &self.support_mapping, self.max_vote_reason_len);
+        let signer = self.guard.signer_address.to_string();
+        let intents = &self.guard.intents;
+
+        // A request relayed by an earlier attempt may still land, so it is polled again rather
+        // than racing a second signed request against it.
+        if let Some(mut intent) = intent
+            && let Some(task) = intent.relay_task.clone()
+        {
+            match self.await_relay(&mut intent, &task).await {
+                Ok(tx_hash) => {
+                    intents.clear(&decision.proposal_id, &signer)?;
+                    return Ok(VoteExecution {
+                        proposal_id: decision.proposal_id.clone(),
+                        submitted: true,
+                        tx_hash: Some(tx_hash),
+                        reason,
+                        at: Utc::now(),
+                        account: None,
+                    });
+                }
+                Err(RelayWaitError::Failed(err)) => {
+                    tracing::warn!(
+                        proposal_id = %decision.proposal_id,
+                        task_id = %task.task_id,
+                        error = %format!("{err:#}"),
+                        "earlier relay task failed; signing a new request"
+                    );
+                    intents.clear(&decision.proposal_id, &signer)?;
+                }
+                Err(RelayWaitError::Unsettled(err)) => return Err(err),
+            }
+        }
+        let data = match decision.allocation {
+            None => IVfiGovernor::castVoteWithReasonCall {
+                proposalId: proposal_id,
+                support: self.support_mapping.support(decision.vote),
+                reason: reason.clone(),
+            }
+            .abi_encode(),
+            Some(allocation) => IVfiGovernor::castVoteWithReasonAndParamsCall {
+                proposalId: proposal_id,
+                support: FRACTIONAL_SUPPORT,
+                reason: reason.clone(),
+                params: self
+                    .guard
+                    .fractional_params(proposal_id, allocation)
+                    .await?,
+            }
+            .abi_encode(),
+        };
+
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+        let deadline = forward_deadline(
+            now,
+            self.request_ttl_secs,
+            self.guard.clock_mode(),
+            proposal.vote_end,
+        );
+        let reads = ProviderForwarderReads {
+            forwarder: &self.forwarder,
+        };
+        let request = build_forward_request(
+            &reads,
+            self.guard.signer_address,
+            self.guard.governor_address,
+            data.into(),
+            self.gas_limit,
+            deadline,
+        )
+        .await?;
+        let signed = sign_forward_request(&self.key, &request, &self.domain)?;
+        if !reads.verify(&signed).await? {
+            bail!(
+                "forwarder rejected the signed vote request; check that the governor trusts {} and the deadline has not passed",
+                self.forwarder.address()
+            );
+        }
+
+        let mut intent = VoteIntent {
+            proposal_id: decision.proposal_id.clone(),
+            signer: signer.clone(),
+            nonce: u64::try_from(request.nonce).context("forwarder nonce does not fit in u64")?,
+            tx_hash: None,
+            relay_task: None,
+            created_at: Utc::now(),
+        };
+        intents
+            .record(&intent)
+            .context("failed to persist vote intent; not relaying the vote")?;
+
+        let task_id = self
+            .relay
+            .submit(&relay_submission(
+                self.chain_id,
+                *self.forwarder.address(),
+                &signed,
+                request.nonce,
+            ))
+            .await?;
+        tracing::info!(
+            proposal_id = %decision.proposal_id,
+            task_id = %task_id,
+            nonce = intent.nonce,
+            deadline,
+            "vote meta-transaction accepted by relay"
+        );
+        let task = PendingRelay { task_id, deadline };
+        intent.relay_task = Some(task.clone());
+        if let Err(err) = intents.record(&intent) {
+            tracing::warn!(error = %format!("{err:#}"), "failed to record relay task in intent");
+        }
+
+        // Until the relay reports a final status the request may still land, so the intent stays
+        // and the next attempt polls the same task, or its hasVoted check reconciles it.
+        let tx_hash = match self.await_relay(&mut intent, &task).await {
+            Ok(tx_hash) => tx_hash,
+            Err(RelayWaitError::Failed(err)) => {
+                intents.clear(&decision.proposal_id, &signer)?;
+                return Err(err);
+            }
+            Err(RelayWaitError::Unsettled(err)) => return Err(err),
+        };
+        intents.clear(&decision.proposal_id, &signer)?;
+
+        Ok(VoteExecution {
+            proposal_id: decision.proposal_id.clone(),
+            submitted: true,
+            tx_hash: Some(tx_hash),
+            reason,
+            at: Utc::now(),
+            account: None,
+        })
+    }
+}
+
+impl ForwarderVoteExecutor {
+    async fn await_relay(
+        &self,
+        intent: &mut VoteIntent,
+        task: &PendingRelay,
+    ) -> Result<String, RelayWaitError> {
+        let intents = &self.guard.intents;
+        wait_for_relay(
+            &self.relay,
+            &task.task_id,
+            task.deadline,
+            self.poll_interval,
+            RELAY_WAIT_PER_ATTEMPT,
+            |tx| {
+                intent.tx_hash = Some(tx.to_string());
+                if let Err(err) = intents.record(intent) {
+                    tracing::warn!(error = %format!("{err:#}"), "failed to record vote tx hash in intent");
+                }
+            },
+        )
+        .await
+    }
+}
+
+// On timestamp clocks a request outliving the voting window could only revert, so the deadline
+// stops at the vote end there. Block-number governors keep the plain TTL.
+pub fn forward_deadline(now: u64, ttl_secs: u64, clock_mode: ClockMode, vote_end: u64) -> u64 {
+    let deadline = now.saturating_add(ttl_secs);
+    match clock_mode {
+        ClockMode::Timestamp => deadline.min(vote_end),
+        ClockMode::BlockNumber => deadline,
+    }
+}
+
+// The forwarder consumes its nonce on execution, so signing with the current one means a
+// re-signed retry and an earlier request still queued at the relay cannot both land.
+pub async fn build_forward_request(
+    reads: &dyn ForwarderReads,
+    from: Address,
+    to: Address,
+    data: Bytes,
+    gas: u64,
+    deadline: u64,
+) -> Result<ForwardRequest> {
+    Ok(ForwardRequest {
+        from,
+        to,
+        value: U256::ZERO,
+        gas: U256::from(gas),
+        nonce: reads.nonce(from).await?,
+        deadline: U48::saturating_from(deadline),
+        data,
+    })
+}
+
+pub fn sign_forward_request(
+    key: &PrivateKeySigner,
+    request: &ForwardRequest,
+    domain: &Eip712Domain,
+) -> Result<ForwardRequestData> {
+    let hash = request.eip712_signing_hash(domain);
+    let signature = key
+        .sign_hash_sync(&hash)
+        .context("failed to sign forward request")?;
+    Ok(ForwardRequestData {
+        from: request.from,
+        to: request.to,
+        value: request.value,
+        gas: request.gas,
+        deadline: request.deadline,
+        data: request.data.clone(),
+        signature: Bytes::from(signature.as_bytes()),
+    })
+}
+
+fn relay_submission(
+    chain_id: u64,
+    forwarder: Address,
+    signed: &ForwardRequestData,
+    nonce: U256,
+) -> RelaySubmission {
+    RelaySubmission {
+        chain_id,
+        forwarder,
+        request: RelayRequest {
+            from: signed.from,
+            to: signed.to,
+            value: signed.value.to_string(),
+            gas: signed.gas.to_string(),
+            nonce: nonce.to_string(),
+            deadline: signed.deadline.to(),
+            data: signed.data.clone(),
+            signature: signed.signature.clone(),
+        },
+    }
+}
+
+#[derive(Debug)]
+pub enum RelayWaitError {
+    // The relay gave up; the request will not land.
+    Failed(anyhow::Error),
+    // Stopped waiting while the request could still be executed.
+    Unsettled(anyhow::Error),
+}
+
+// `on_tx` sees each tx hash the relay reports before the final status. Polling stops after
+// `max_wait` with the task unsettled; the caller keeps it for a later attempt.
+pub async fn wait_for_relay(
+    relay: &dyn RelayClient,
+    task_id: &str,
+    deadline: u64,
+    poll_interval: Duration,
+    max_wait: Duration,
+    mut on_tx: impl FnMut(&str) + Send,
+) -> Result<String, RelayWaitError> {
+    let started = std::time::Instant::now();
+    let mut last_tx = None::<String>;
+    loop {
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+        match relay.status(task_id).await {
+            Ok(RelayStatus::Mined { tx_hash }) => return Ok(tx_hash),
+            Ok(RelayStatus::Failed { tx_hash, error }) => {
+                return Err(RelayWaitError::Failed(anyhow!(
+                    "relay task {task_id} failed (tx {}): {}",
+                    tx_hash.as_deref().unwrap_or("none"),
+                    error.as_deref().unwrap_or("no error given")
+                )));
+            }
+            Ok(RelayStatus::Pending) if now > deadline => {
+                return Err(RelayWaitError::Failed(anyhow!(
+                    "relay task {task_id} was not sent before the forward request deadline {deadline}"
+                )));
+            }
+            Ok(RelayStatus::Pending) => {}
+            Ok(RelayStatus::Submitted { tx_hash }) => {
+                if last_tx.as_deref() != Some(tx_hash.as_str()) {
+                    on_tx(&tx_hash);
+                    last_tx = Some(tx_hash);
+                }
+            }
+            Err(err) => {
+                tracing::warn!(task_id, error = %format!("{err:#}"), "failed to poll relay status");
+            }
+        }
+        if now > deadline.saturating_add(SUBMITTED_GRACE_SECS) {
+            return Err(RelayWaitError::Unsettled(anyhow!(
+                "relay task {task_id} (tx {}) is still unconfirmed {SUBMITTED_GRACE_SECS}s after its deadline",
+                last_tx.as_deref().unwrap_or("unknown")
+            )));
+        }
+        if started.elapsed() >= max_wait {
+            return Err(RelayWaitError::Unsettled(anyhow!(
+                "relay task {task_id} is not final after {}s; it is polled again on the next attempt",
+                max_wait.as_secs()
+            )));
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use alloy::{
+        primitives::{Address, Bytes, Signature, U256, address},
+        signers::local::PrivateKeySigner,
+        sol_types::{Eip712Domain, SolStruct, eip712_domain},
+    };
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::{
+        ForwardRequest, ForwardRequestData, ForwarderReads, RelayClient, RelayStatus,
+        RelaySubmission, RelayWaitError, build_forward_request, forward_deadline,
+        sign_forward_request, wait_for_relay,
+    };
+    use crate::signer::ClockMode;
+
+    const GOVERNOR: Address = address!("0x00000000000000000000000000000000000000aa");
+    const FORWARDER: Address = address!("0x00000000000000000000000000000000000000fb");
+
+    fn domain(chain_id: u64) -> Eip712Domain {
+        eip712_domain! {
+            name: "VfiForwarder",
+            version: "1",
+            chain_id: chain_id,
+            verifying_contract: FORWARDER,
+        }
+    }
+
+    fn key() -> PrivateKeySigner {
+        PrivateKeySigner::from_str(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .expect("test key")
+    }
+
+    fn now() -> u64 {
+        u64::try_from(Utc::now().timestamp()).expect("clock after 1970")
+    }
+
+    // Mirrors ERC2771Forwarder.verify: the signature must recover `from` over the request with
+    // the forwarder's current nonce, and the deadline must not have passed.
+    struct MockForwarder {
+        nonce: U256,
+        domain: Eip712Domain,
+        trusted_target: Address,
+    }
+
+    #[async_trait]
+    impl ForwarderReads for MockForwarder {
+        async fn nonce(&self, _from: Address) -> Result<U256> {
+            Ok(self.nonce)
+        }
+
+        async fn verify(&self, request: &ForwardRequestData) -> Result<bool> {
+            let hash = ForwardRequest {
+                from: request.from,
+                to: request.to,
+                value: request.value,
+                gas: request.gas,
+                nonce: self.nonce,
+                deadline: request.deadline,
+                data: request.data.clone(),
+            }
+            .eip712_signing_hash(&self.domain);
+            let signer = Signature::try_from(request.signature.as_ref())
+                .ok()
+                .and_then(|signature| signature.recover_address_from_prehash(&hash).ok());
+            Ok(request.to == self.trusted_target
+                && request.deadline.to::<u64>() >= now()
+                && signer == Some(request.from))
+        }
+    }
+
+    #[derive(Default)]
+    struct MockRelay {
+        statuses: Mutex<VecDeque<Result<RelayStatus>>>,
+        polls: Arc<Mutex<usize>>,
+    }
+
+    #[async_trait]
+    impl RelayClient for MockRelay {
+        async fn submit(&self, _submission: &RelaySubmission) -> Result<String> {
+            Ok("task-1".to_string())
+        }
+
+        async fn status(&self, _task_id: &str) -> Result<RelayStatus> {
+            *self.polls.lock().expect("polls lock") += 1;
+            self.statuses
+                .lock()
+                .expect("statuses lock")
+                .pop_front()
+                .unwrap_or(Ok(RelayStatus::Pending))
+        }
+    }
+
+    #[tokio::test]
+    async fn signed_requests_use_the_forwarder_nonce_and_verify_against_its_domain() {
+        let key = key();
+        let forwarder = MockForwarder {
+            nonce: U256::from(7),
+            domain: domain(31337),
+            trusted_target: GOVERNOR,
+        };
+        let request = build_forward_request(
+            &forwarder,
+            key.address(),
+            GOVERNOR,
+            Bytes::from_static(&[0x56, 0x78, 0x1d, 0x88]),
+            300_000,
+            now() + 600,
+        )
+        .await
+        .expect("request");
+        assert_eq!(request.nonce, U256::from(7));
+        assert_eq!(request.value, U256::ZERO);
+
+        let signed = sign_forward_request(&key, &request, &domain(31337)).expect("sign");
+        assert_eq!(signed.signature.len(), 65);
+        assert!(forwarder.verify(&signed).await.expect("verify"));
+
+        let mut tampered = signed.clone();
+        tampered.data = Bytes::from_static(&[0x56, 0x78, 0x1d, 0x89]);
+        assert!(!forwarder.verify(&tampered).await.expect("verify"));
+
+        let other_chain = sign_forward_request(&key, &request, &domain(1)).expect("sign");
+        assert!(!forwarder.verify(&other_chain).await.expect("verify"));
+
+        let consumed = MockForwarder {
+            nonce: U256::from(8),
+            ..forwarder
+        };
+        assert!(!consumed.verify(&signed).await.expect("verify"));
+
+        let expired = ForwardRequest {
+            deadline: alloy::primitives::aliases::U48::from(now() - 1),
+            ..request
+        };
+        let expired = sign_forward_request(&key, &expired, &domain(31337)).expect("sign");
+        assert!(!consumed.verify(&expired).await.expect("verify"));
+    }
+
+    #[test]
+    fn deadlines_stop_at_the_vote_end_on_timestamp_clocks() {
+        assert_eq!(
+            forward_deadline(1_000, 900, ClockMode::Timestamp, 1_500),
+            1_500
+        );
+        assert_eq!(
+            forward_deadline(1_000, 900, ClockMode::Timestamp, 5_000),
+            1_900
+        );
+        assert_eq!(
+            forward_deadline(1_000, 900, ClockMode::BlockNumber, 1_500),
+            1_900
+        );
+    }
+
+    const WAIT: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn relay_polling_reports_the_mined_hash_and_stops_at_the_deadline() {
+        let status = |raw: &str| Ok(serde_json::from_str::<RelayStatus>(raw).expect("status"));
+        let relay = MockRelay::default();
+        relay.statuses.lock().expect("statuses lock").extend([
+            status(r#"{"status":"pending"}"#),
+            Err(anyhow!("relay briefly down")),
+            status(r#"{"status":"submitted","tx_hash":"0xabc"}"#),
+            status(r#"{"status":"submitted","tx_hash":"0xabc"}"#),
+            status(r#"{"status":"mined","tx_hash":"0xabc"}"#),
+        ]);
+        let mut seen = Vec::new();
+        let tx_hash = wait_for_relay(&relay, "task-1", now() + 600, Duration::ZERO, WAIT, |tx| {
+            seen.push(tx.to_string())
+        })
+        .await
+        .expect("mined");
+        assert_eq!(tx_hash, "0xabc");
+        assert_eq!(seen, vec!["0xabc".to_string()]);
+        assert_eq!(*relay.polls.lock().expect("polls lock"), 5);
+
+        let relay = MockRelay::default();
+        let err = wait_for_relay(&relay, "task-2", now() - 1, Duration::ZERO, WAIT, |_| {})
+            .await
+            .expect_err("expired");
+        assert!(matches!(err, RelayWaitError::Failed(_)));
+
+        let relay = MockRelay::default();
+        relay
+            .statuses
+            .lock()
+            .expect("statuses lock")
+            .push_back(status(r#"{"status":"failed","error":"reverted"}"#));
+        let Err(RelayWaitError::Failed(err)) =
+            wait_for_relay(&relay, "task-3", now() + 600, Duration::ZERO, WAIT, |_| {}).await
+        else {
+            panic!("failed status should end the wait");
+        };
+        assert!(err.to_string().contains("reverted"));
+
+        let relay = MockRelay::default();
+        relay
+            .statuses
+            .lock()
+            .expect("statuses lock")
+            .push_back(status(r#"{"status":"submitted","tx_hash":"0xdef"}"#));
+        let err = wait_for_relay(
+            &relay,
+            "task-4",
+            now() - super::SUBMITTED_GRACE_SECS - 1,
+            Duration::ZERO,
+            WAIT,
+            |_| {},
+        )
+        .await
+        .expect_err("unsettled");
+        let RelayWaitError::Unsettled(err) = err else {
+            panic!("a submitted tx past the grace period is unsettled");
+        };
+        assert!(err.to_string().contains("0xdef"));
+
+        // A relay that stays pending well before the deadline is handed back after `max_wait`.
+        let relay = MockRelay::default();
+        let err = wait_for_relay(
+            &relay,
+            "task-5",
+            now() + 600,
+            Duration::from_millis(5),
+            Duration::from_millis(20),
+            |_| {},
+        )
+        .await
+        .expect_err("still pending");
+        let RelayWaitError::Unsettled(err) = err else {
+            panic!("a pending task past max_wait is unsettled, not failed");
+        };
+        assert!(err.to_string().contains("polled again on the next attempt"));
+    }
+}
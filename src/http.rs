@@ -0,0 +1,212 @@
+use std::{fs, time::Duration};
+
+use alloy::transports::http::reqwest::{Certificate, Client, ClientBuilder, Proxy, Url};
+use anyhow::{Context, Result, bail};
+
+use crate::config::HttpConfig;
+
+pub const DEFAULT_USER_AGENT: &str = concat!("gov-agent/", env!("CARGO_PKG_VERSION"));
+
+pub fn resolve_proxy<'a>(http: &'a HttpConfig, service_proxy: Option<&'a str>) -> Option<&'a str> {
+    let proxy = match service_proxy {
+        Some(value) => value,
+        None => http.proxy_url.as_deref()?,
+    };
+    let trimmed = proxy.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(trimmed)
+}
+
+// Per-service knobs layered over the shared `[http]` settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpService<'a> {
+    pub name: &'a str,
+    pub proxy_url: Option<&'a str>,
+    pub danger_accept_invalid_certs: bool,
+}
+
+pub fn load_extra_ca_certs(http: &HttpConfig) -> Result<Vec<Certificate>> {
+    let Some(path) = &http.extra_ca_cert_path else {
+        return Ok(Vec::new());
+    };
+    let pem = fs::read(path)
+        .with_context(|| format!("failed to read http.extra_ca_cert_path {}", path.display()))?;
+    let certs = Certificate::from_pem_bundle(&pem).with_context(|| {
+        format!(
+            "http.extra_ca_cert_path {} is not a valid PEM bundle",
+            path.display()
+        )
+    })?;
+    if certs.is_empty() {
+        bail!(
+            "http.extra_ca_cert_path {} contains no PEM certificates",
+            path.display()
+        );
+    }
+    Ok(certs)
+}
+
+pub fn client_builder(http: &HttpConfig, service: HttpService<'_>) -> Result<ClientBuilder> {
+    let user_agent = http
+        .user_agent
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(Duration::from_secs(http.connect_timeout_secs));
+
+    if let Some(proxy_url) = resolve_proxy(http, service.proxy_url) {
+        let proxy =
+            Proxy::all(proxy_url).with_context(|| format!("invalid proxy url {proxy_url}"))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for cert in load_extra_ca_certs(http)? {
+        builder = builder.add_root_certificate(cert);
+    }
+
+    if service.danger_accept_invalid_certs {
+        tracing::warn!(
+            service = service.name,
+            "TLS CERTIFICATE VERIFICATION IS DISABLED for this service; traffic can be intercepted. Prefer http.extra_ca_cert_path"
+        );
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    Ok(builder)
+}
+
+pub fn build_client(
+    http: &HttpConfig,
+    service: HttpService<'_>,
+    timeout: Option<Duration>,
+) -> Result<Client> {
+    let mut builder = client_builder(http, service)?;
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder.build().context("failed to build http client")
+}
+
+pub fn rpc_http_client(rpc_url: &str, http: &HttpConfig) -> Result<Option<(Client, Url)>> {
+    let url = match Url::parse(rpc_url.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => return Ok(None),
+    };
+
+    let client = build_client(
+        http,
+        HttpService {
+            name: "rpc",
+            ..HttpService::default()
+        },
+        None,
+    )?;
+    Ok(Some((client, url)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::config::HttpConfig;
+
+    use super::{HttpService, build_client, load_extra_ca_certs, resolve_proxy, rpc_http_client};
+
+    fn http(proxy_url: Option<&str>) -> HttpConfig {
+        HttpConfig {
+            proxy_url: proxy_url.map(ToString::to_string),
+            ..HttpConfig::default()
+        }
+    }
+
+    #[test]
+    fn service_proxy_overrides_global_and_empty_disables() {
+        let cfg = http(Some("http://global:3128"));
+        assert_eq!(resolve_proxy(&cfg, None), Some("http://global:3128"));
+        assert_eq!(
+            resolve_proxy(&cfg, Some("socks5://svc:1080")),
+            Some("socks5://svc:1080")
+        );
+        assert_eq!(resolve_proxy(&cfg, Some("")), None);
+        assert_eq!(resolve_proxy(&http(None), None), None);
+    }
+
+    #[test]
+    fn clients_build_with_http_and_socks5_proxies() {
+        assert!(
+            build_client(
+                &http(Some("http://127.0.0.1:3128")),
+                HttpService::default(),
+                None
+            )
+            .is_ok()
+        );
+        assert!(
+            build_client(
+                &http(Some("socks5://127.0.0.1:1080")),
+                HttpService::default(),
+                None
+            )
+            .is_ok()
+        );
+        assert!(build_client(&http(Some("not a url")), HttpService::default(), None).is_err());
+    }
+
+    #[test]
+    fn extra_ca_bundle_is_validated_and_applied() {
+        let ca = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/tls/test-ca.pem");
+        let cfg = HttpConfig {
+            extra_ca_cert_path: Some(ca),
+            ..HttpConfig::default()
+        };
+        assert_eq!(load_extra_ca_certs(&cfg).expect("parse test CA").len(), 1);
+        let service = HttpService {
+            name: "ipfs",
+            proxy_url: None,
+            danger_accept_invalid_certs: true,
+        };
+        assert!(build_client(&cfg, service, None).is_ok());
+        assert!(
+            load_extra_ca_certs(&HttpConfig::default())
+                .expect("no CA")
+                .is_empty()
+        );
+
+        let bogus =
+            std::env::temp_dir().join(format!("gov-agent-bogus-ca-{}.pem", std::process::id()));
+        fs::write(&bogus, "not a certificate").expect("write bogus pem");
+        let bogus_cfg = HttpConfig {
+            extra_ca_cert_path: Some(bogus.clone()),
+            ..HttpConfig::default()
+        };
+        let err = load_extra_ca_certs(&bogus_cfg).expect_err("bogus pem rejected");
+        assert!(format!("{err:#}").contains("extra_ca_cert_path"));
+        let _ = fs::remove_file(&bogus);
+
+        let missing_cfg = HttpConfig {
+            extra_ca_cert_path: Some(PathBuf::from("/nonexistent/gov-agent-ca.pem")),
+            ..HttpConfig::default()
+        };
+        assert!(load_extra_ca_certs(&missing_cfg).is_err());
+    }
+
+    #[test]
+    fn rpc_client_is_only_built_for_http_urls() {
+        let cfg = http(None);
+        assert!(
+            rpc_http_client("http://127.0.0.1:8545", &cfg)
+                .expect("http url")
+                .is_some()
+        );
+        assert!(
+            rpc_http_client("ws://127.0.0.1:8546", &cfg)
+                .expect("ws url")
+                .is_none()
+        );
+    }
+}
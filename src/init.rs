@@ -0,0 +1,349 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{
+    cli::{Cli, InitArgs},
+    config::{AppConfig, ProfileCatalog, expand_tilde_path},
+    ipfs::default_shared_cache_dir,
+};
+
+const DEFAULT_OUT: &str = "~/.gov-agent/config.toml";
+const LLM_PROVIDERS: [&str; 4] = ["openai", "anthropic", "ollama", "venice"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct InitAnswers {
+    rpc_url: String,
+    governor_address: String,
+    dapp_registry_address: String,
+    auto_vote: bool,
+    keystore_path: Option<PathBuf>,
+    llm_providers: Vec<String>,
+}
+
+// Runs before config loading: a fresh install has no config that would pass validation yet.
+pub fn run(cli: &Cli, args: &InitArgs) -> Result<()> {
+    let out = expand_tilde_path(args.out.as_deref().unwrap_or(Path::new(DEFAULT_OUT)))?;
+    if out.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            out.display()
+        );
+    }
+
+    let defaults = ProfileCatalog::new(Some(&out)).resolve(&cli.profile)?;
+    let stdin = io::stdin();
+    let mut prompt = (!args.yes && stdin.is_terminal()).then(|| stdin.lock());
+    let answers = collect_answers(
+        cli,
+        args,
+        &defaults,
+        prompt.as_mut().map(|input| input as &mut dyn BufRead),
+    )?;
+
+    let rendered = render_config(&cli.profile, &defaults, &answers)?;
+    let config = AppConfig::from_toml(&cli.profile, &rendered)
+        .context("generated config does not validate")?;
+
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(&out, &rendered).with_context(|| format!("failed to write {}", out.display()))?;
+    let cache_dir = config
+        .ipfs
+        .cache_dir
+        .clone()
+        .unwrap_or_else(default_shared_cache_dir);
+    for dir in [&config.storage.data_dir, &cache_dir] {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    println!("wrote {}", out.display());
+    println!("data dir: {}", config.storage.data_dir.display());
+    println!("cache dir: {}", cache_dir.display());
+    let missing = missing_env_vars(&config, |name| env::var_os(name).is_some());
+    if !missing.is_empty() {
+        println!("export before running:");
+        for name in missing {
+            println!("  export {name}=...");
+        }
+    }
+    println!(
+        "run with: gov-agent --profile {} --config {} status",
+        cli.profile,
+        out.display()
+    );
+    Ok(())
+}
+
+fn collect_answers(
+    cli: &Cli,
+    args: &InitArgs,
+    defaults: &AppConfig,
+    mut input: Option<&mut dyn BufRead>,
+) -> Result<InitAnswers> {
+    let mut ask = |label: &str, given: Option<String>, default: &str| -> Result<String> {
+        if let Some(value) = given {
+            return Ok(value);
+        }
+        match input.as_deref_mut() {
+            Some(input) => prompt(input, label, default),
+            None => Ok(default.to_string()),
+        }
+    };
+
+    let rpc_url = ask("RPC URL", cli.rpc_url.clone(), &defaults.network.rpc_url)?;
+    let governor_address = ask(
+        "Governor address",
+        args.governor_address.clone(),
+        &defaults.network.governor_address,
+    )?;
+    let dapp_registry_address = ask(
+        "DappRegistry address",
+        args.dapp_registry_address.clone(),
+        &defaults.network.dapp_registry_address,
+    )?;
+    let auto_vote = ask(
+        "Enable auto-vote (yes/no)",
+        cli.auto_vote.then(|| "yes".to_string()),
+        "no",
+    )?;
+    let auto_vote = matches!(
+        auto_vote.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes" | "true" | "1"
+    );
+    let keystore_path = ask(
+        "Keystore path (empty for none)",
+        args.keystore_path
+            .as_ref()
+            .map(|path| path.display().to_string()),
+        "",
+    )?;
+    let enabled = LLM_PROVIDERS
+        .iter()
+        .filter(|name| provider_enabled(defaults, name))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(",");
+    let llm_providers = ask(
+        "LLM providers (comma-separated: openai, anthropic, ollama, venice; empty for none)",
+        (!args.llm.is_empty()).then(|| args.llm.join(",")),
+        &enabled,
+    )?;
+
+    let llm_providers = llm_providers
+        .split(',')
+        .map(|name| name.trim().to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    if let Some(unknown) = llm_providers
+        .iter()
+        .find(|name| !LLM_PROVIDERS.contains(&name.as_str()))
+    {
+        bail!("unknown LLM provider {unknown:?}; expected one of {LLM_PROVIDERS:?}");
+    }
+
+    Ok(InitAnswers {
+        rpc_url: rpc_url.trim().to_string(),
+        governor_address: governor_address.trim().to_string(),
+        dapp_registry_address: dapp_registry_address.trim().to_string(),
+        auto_vote,
+        keystore_path: Some(keystore_path.trim())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from),
+        llm_providers,
+    })
+}
+
+fn prompt(input: &mut dyn BufRead, label: &str, default: &str) -> Result<String> {
+    let mut stderr = io::stderr();
+    if default.is_empty() {
+        write!(stderr, "{label}: ")?;
+    } else {
+        write!(stderr, "{label} [{default}]: ")?;
+    }
+    stderr.flush()?;
+    let mut line = String::new();
+    input
+        .read_line(&mut line)
+        .context("failed to read answer")?;
+    let line = line.trim();
+    Ok(if line.is_empty() { default } else { line }.to_string())
+}
+
+fn provider_enabled(config: &AppConfig, name: &str) -> bool {
+    match name {
+        "openai" => config.llm.openai.enabled,
+        "anthropic" => config.llm.anthropic.enabled,
+        "ollama" => config.llm.ollama.enabled,
+        "venice" => config.llm.venice.enabled,
+        _ => false,
+    }
+}
+
+// Each section is serialized from the profile defaults so the file always round-trips through
+// the config loader; only the answers are changed on top.
+fn render_config(profile: &str, defaults: &AppConfig, answers: &InitAnswers) -> Result<String> {
+    let mut config = defaults.clone();
+    config.auto_vote = answers.auto_vote;
+    config.network.rpc_url = answers.rpc_url.clone();
+    config.network.governor_address = answers.governor_address.clone();
+    config.network.dapp_registry_address = answers.dapp_registry_address.clone();
+    config.signer.keystore_path = answers.keystore_path.clone();
+    for (name, provider) in [
+        ("openai", &mut config.llm.openai),
+        ("anthropic", &mut config.llm.anthropic),
+        ("ollama", &mut config.llm.ollama),
+        ("venice", &mut config.llm.venice),
+    ] {
+        provider.enabled = answers.llm_providers.iter().any(|enabled| enabled == name);
+    }
+
+    let mut out = format!(
+        "# Generated by `gov-agent init` for the {profile} profile.\n\
+         # Use it with: gov-agent --profile {profile} --config <this file> <command>\n\
+         # See config/example.toml in the repository for every available setting.\n\n\
+         profile = {}\n\
+         # \"voter\" may submit votes when auto_vote is on; \"observer\" never touches the signer.\n\
+         mode = \"{}\"\n\
+         auto_vote = {}\n",
+        toml::Value::from(profile),
+        config.mode.as_str(),
+        config.auto_vote
+    );
+    let sections: [(&str, &str, toml::Value); 5] = [
+        (
+            "network",
+            "Chain to watch. governor_address and dapp_registry_address are required.",
+            toml::Value::try_from(&config.network)?,
+        ),
+        (
+            "signer",
+            "Keystore used for auto-vote; its password is read from keystore_password_env.",
+            toml::Value::try_from(&config.signer)?,
+        ),
+        (
+            "ipfs",
+            "Gateway for bundle manifests and files; cache_dir defaults to the shared VibeFi cache.",
+            toml::Value::try_from(&config.ipfs)?,
+        ),
+        (
+            "storage",
+            "Local state, backups, and audits live under data_dir.",
+            toml::Value::try_from(&config.storage)?,
+        ),
+        (
+            "llm",
+            "Providers are tried in order; API keys are read from each api_key_env.",
+            toml::Value::try_from(&config.llm)?,
+        ),
+    ];
+    for (name, comment, value) in sections {
+        let mut table = toml::Table::new();
+        table.insert(name.to_string(), value);
+        out.push_str(&format!("\n# {comment}\n{}", toml::to_string(&table)?));
+    }
+    Ok(out)
+}
+
+fn missing_env_vars(config: &AppConfig, is_set: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut names = Vec::new();
+    if config.signer.keystore_path.is_some()
+        && config.signer.keystore_password.is_none()
+        && let Some(name) = &config.signer.keystore_password_env
+    {
+        names.push(name.clone());
+    }
+    for provider in [
+        &config.llm.openai,
+        &config.llm.anthropic,
+        &config.llm.ollama,
+        &config.llm.venice,
+    ] {
+        if provider.enabled
+            && let Some(name) = &provider.api_key_env
+        {
+            names.push(name.clone());
+        }
+    }
+    names.retain(|name| !is_set(name));
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use clap::Parser;
+
+    use super::{collect_answers, missing_env_vars, render_config};
+    use crate::{
+        cli::{Cli, Command},
+        config::AppConfig,
+    };
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("gov-agent").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn flags_and_prompts_render_a_config_that_loads() {
+        let cli = parse(&[
+            "--profile",
+            "devnet",
+            "--auto-vote",
+            "init",
+            "--governor-address",
+            "0x1111111111111111111111111111111111111111",
+            "--llm",
+            "openai,ollama",
+        ]);
+        let Command::Init(args) = &cli.command else {
+            panic!("expected init command");
+        };
+        let defaults = AppConfig::for_profile("devnet");
+        let mut input =
+            Cursor::new("\n0x2222222222222222222222222222222222222222\n/keys/voter.json\n");
+        let answers =
+            collect_answers(&cli, args, &defaults, Some(&mut input)).expect("collect answers");
+        assert_eq!(answers.rpc_url, "http://127.0.0.1:8545");
+        assert!(answers.auto_vote);
+        assert_eq!(answers.llm_providers, vec!["openai", "ollama"]);
+
+        let rendered = render_config("devnet", &defaults, &answers).expect("render");
+        let config = AppConfig::from_toml("devnet", &rendered).expect("generated config loads");
+        assert_eq!(
+            config.network.dapp_registry_address,
+            "0x2222222222222222222222222222222222222222"
+        );
+        assert!(config.auto_vote);
+        assert!(config.llm.openai.enabled && !config.llm.venice.enabled);
+        assert_eq!(
+            missing_env_vars(&config, |name| name == "OPENAI_API_KEY"),
+            vec!["GOV_AGENT_KEYSTORE_PASSWORD".to_string()]
+        );
+    }
+
+    #[test]
+    fn non_interactive_devnet_without_addresses_fails_validation() {
+        let cli = parse(&["init", "--yes"]);
+        let Command::Init(args) = &cli.command else {
+            panic!("expected init command");
+        };
+        let defaults = AppConfig::for_profile("devnet");
+        let answers = collect_answers(&cli, args, &defaults, None).expect("collect answers");
+        let rendered = render_config("devnet", &defaults, &answers).expect("render");
+        assert!(AppConfig::from_toml("devnet", &rendered).is_err());
+
+        let sepolia = AppConfig::for_profile("sepolia");
+        let answers = collect_answers(&cli, args, &sepolia, None).expect("collect answers");
+        let rendered = render_config("sepolia", &sepolia, &answers).expect("render");
+        assert!(AppConfig::from_toml("sepolia", &rendered).is_ok());
+    }
+}
@@ -1,14 +1,27 @@
 use std::{
-    fs,
-    path::{Component, Path, PathBuf},
-    time::Duration,
+    collections::{HashMap, HashSet},
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
 };
 
-use anyhow::{Context, Result, anyhow};
-use reqwest::Client;
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use reqwest::{
+    Client, RequestBuilder, StatusCode,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
 use serde::{Deserialize, Serialize};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::Instrument;
 
-use crate::{config::IpfsConfig, observability};
+use crate::{
+    config::{HttpConfig, IpfsConfig},
+    http::{self, HttpService},
+    observability,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Manifest {
@@ -25,20 +38,155 @@ pub struct ManifestFile {
     pub bytes: u64,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct CacheMeta {
+    keccak256: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchFailure {
+    Transient,
+    Permanent,
+}
+
+impl FetchFailure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transient => "transient",
+            Self::Permanent => "permanent",
+        }
+    }
+
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.downcast_ref::<FetchError>()
+            .map(|err| err.kind)
+            .unwrap_or(Self::Permanent)
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchError {
+    pub kind: FetchFailure,
+    pub message: String,
+}
+
+impl FetchError {
+    fn error(kind: FetchFailure, message: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(Self {
+            kind,
+            message: message.into(),
+        })
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+pub fn classify_status(status: StatusCode) -> FetchFailure {
+    if status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+    {
+        FetchFailure::Transient
+    } else {
+        FetchFailure::Permanent
+    }
+}
+
+fn is_plausible_cid(root_cid: &str) -> bool {
+    !root_cid.is_empty() && root_cid.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// What review reads from a bundle; the gateway fetcher and `lint`'s local directory both
+// provide it.
+#[async_trait]
+pub trait BundleSource: Send + Sync {
+    async fn fetch_manifest(&self, root_cid: &str) -> Result<Manifest>;
+
+    // Any file type, so binary assets can be hashed; None when missing or over `max_bytes`.
+    async fn fetch_file(
+        &self,
+        root_cid: &str,
+        path: &str,
+        max_bytes: usize,
+    ) -> Result<Option<Vec<u8>>>;
+
+    async fn fetch_text_file(
+        &self,
+        root_cid: &str,
+        path: &str,
+        max_bytes: usize,
+    ) -> Result<Option<String>> {
+        Ok(self
+            .fetch_file(root_cid, path, max_bytes)
+            .await?
+            .and_then(|bytes| String::from_utf8(bytes).ok()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BundleFetcher {
     gateway: String,
     client: Client,
     cache_root: PathBuf,
+    verify_cached: bool,
+    verify_cached_probability: f64,
+    max_cache_bytes: Option<u64>,
+    usage: Arc<Mutex<Option<CacheUsage>>>,
+    gateway_slots: Arc<Semaphore>,
+    max_retries: u32,
+    // Wait before the first retry; each later one doubles it.
+    retry_base_delay: Duration,
+    // Set only with `ipfs.pin_on_discovery`.
+    pin_api: Option<String>,
+    local_gateway: Option<String>,
+    max_pin_bytes: u64,
+    // Root CIDs pinned by this fetcher; their reads go through `local_gateway`.
+    pinned: Arc<Mutex<HashSet<String>>>,
+}
+
+// Per-CID sizes, built with one walk of the cache root and then kept current from the
+// fetcher's own writes and reads.
+#[derive(Debug, Default)]
+struct CacheUsage {
+    total_bytes: u64,
+    cids: HashMap<String, CidUsage>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CidUsage {
+    bytes: u64,
+    last_used: SystemTime,
 }
 
 impl BundleFetcher {
-    pub fn new(cfg: &IpfsConfig) -> Result<Self> {
+    pub fn new(cfg: &IpfsConfig, http_cfg: &HttpConfig) -> Result<Self> {
         let gateway = cfg.gateway_url.trim_end_matches('/').to_string();
-        let client = Client::builder()
-            .timeout(Duration::from_secs(cfg.request_timeout_secs))
-            .build()
-            .context("failed to build http client for ipfs")?;
+        let client = http::build_client(
+            http_cfg,
+            HttpService {
+                name: "ipfs",
+                proxy_url: cfg.proxy_url.as_deref(),
+                danger_accept_invalid_certs: cfg.danger_accept_invalid_certs,
+            },
+            Some(Duration::from_secs(cfg.request_timeout_secs)),
+        )
+        .context("failed to build http client for ipfs")?;
 
         let cache_root = cfg
             .cache_dir
@@ -51,94 +199,325 @@ impl BundleFetcher {
             gateway,
             client,
             cache_root,
+            verify_cached: cfg.verify_cached,
+            verify_cached_probability: cfg.verify_cached_probability.clamp(0.0, 1.0),
+            max_cache_bytes: cfg.max_cache_bytes,
+            usage: Arc::new(Mutex::new(None)),
+            gateway_slots: Arc::new(Semaphore::new(cfg.max_concurrent_fetches.max(1))),
+            max_retries: cfg.max_retries,
+            retry_base_delay: Duration::from_millis(500),
+            pin_api: cfg
+                .api_url
+                .as_deref()
+                .filter(|_| cfg.pin_on_discovery)
+                .map(|url| url.trim_end_matches('/').to_string()),
+            local_gateway: cfg
+                .local_gateway_url
+                .as_deref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+            max_pin_bytes: cfg.max_pin_bytes,
+            pinned: Arc::new(Mutex::new(HashSet::new())),
         })
     }
 
-    pub async fn fetch_manifest(&self, root_cid: &str) -> Result<Manifest> {
+    #[cfg(test)]
+    pub(crate) fn with_retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    pub fn pins_on_discovery(&self) -> bool {
+        self.pin_api.is_some()
+    }
+
+    // `pin add` on the node's RPC API, which fetches the whole DAG into the local node. Does
+    // nothing without `ipfs.pin_on_discovery`. The DAG is chosen by the proposer, so the
+    // manifest is read first and a bundle listing more than `ipfs.max_pin_bytes` is refused.
+    // Once pinned, the bundle's reads go through the node's own gateway.
+    pub async fn pin(&self, root_cid: &str) -> Result<()> {
+        let Some(api) = &self.pin_api else {
+            return Ok(());
+        };
+        if !is_plausible_cid(root_cid) {
+            bail!("root CID {root_cid} is not a valid CID");
+        }
+        let manifest = self
+            .fetch_manifest(root_cid)
+            .await
+            .context("failed to read the manifest before pinning")?;
+        let Some(files) = &manifest.files else {
+            bail!("manifest lists no files, so the bundle size is unknown; not pinning");
+        };
+        let listed = files
+            .iter()
+            .fold(0_u64, |total, file| total.saturating_add(file.bytes));
+        if listed > self.max_pin_bytes {
+            bail!(
+                "manifest lists {listed} bytes, over ipfs.max_pin_bytes ({}); not pinning",
+                self.max_pin_bytes
+            );
+        }
+        let started = observability::now();
+        let response = self
+            .client
+            .post(format!("{api}/api/v0/pin/add?arg={root_cid}"))
+            .send()
+            .await
+            .context("ipfs api request failed")
+            .inspect_err(|_| observability::record_provider_error("ipfs", "pin_add"))?;
+        let status = response.status();
+        if !status.is_success() {
+            observability::record_provider_error("ipfs", "pin_add_http_status");
+            let body = response.text().await.unwrap_or_default();
+            bail!("ipfs api pin/add returned HTTP {status}: {}", body.trim());
+        }
+        observability::observe_stage_latency("ipfs_pin", started);
+        self.pinned
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(root_cid.to_string());
+        Ok(())
+    }
+
+    // `pin rm` for a CID this fetcher pinned; other CIDs are left alone. Reads go back to the
+    // public gateway either way.
+    pub async fn unpin(&self, root_cid: &str) -> Result<()> {
+        let Some(api) = &self.pin_api else {
+            return Ok(());
+        };
+        if !self
+            .pinned
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(root_cid)
+        {
+            return Ok(());
+        }
+        let response = self
+            .client
+            .post(format!("{api}/api/v0/pin/rm?arg={root_cid}"))
+            .send()
+            .await
+            .context("ipfs api request failed")
+            .inspect_err(|_| observability::record_provider_error("ipfs", "pin_rm"))?;
+        let status = response.status();
+        if !status.is_success() {
+            observability::record_provider_error("ipfs", "pin_rm_http_status");
+            let body = response.text().await.unwrap_or_default();
+            bail!("ipfs api pin/rm returned HTTP {status}: {}", body.trim());
+        }
+        Ok(())
+    }
+
+    fn gateway_for(&self, root_cid: &str) -> &str {
+        match &self.local_gateway {
+            Some(local)
+                if self
+                    .pinned
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .contains(root_cid) =>
+            {
+                local
+            }
+            _ => &self.gateway,
+        }
+    }
+
+    pub fn invalidate(&self, root_cid: &str) -> Result<bool> {
+        if !is_plausible_cid(root_cid) {
+            bail!("root CID {root_cid} is not a valid CID");
+        }
+        let dir = self.cache_root.join(root_cid);
+        if !dir.exists() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&dir)
+            .with_context(|| format!("failed to remove cache directory {}", dir.display()))?;
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(usage) = usage.as_mut()
+            && let Some(removed) = usage.cids.remove(root_cid)
+        {
+            usage.total_bytes = usage.total_bytes.saturating_sub(removed.bytes);
+        }
+        Ok(true)
+    }
+
+    // Marks the CID as used and, once the cache is over `max_cache_bytes`, removes whole
+    // CID directories starting with the least recently used one.
+    fn record_cache_use(&self, root_cid: &str, added_bytes: u64) {
+        let Some(max_bytes) = self.max_cache_bytes else {
+            return;
+        };
+        let mut guard = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        // A fresh scan already counts the file that was just written.
+        let added_bytes = if guard.is_some() { added_bytes } else { 0 };
+        let usage = guard.get_or_insert_with(|| scan_cache_usage(&self.cache_root));
+        let entry = usage.cids.entry(root_cid.to_string()).or_insert(CidUsage {
+            bytes: 0,
+            last_used: SystemTime::now(),
+        });
+        entry.bytes += added_bytes;
+        entry.last_used = SystemTime::now();
+        usage.total_bytes += added_bytes;
+        if usage.total_bytes <= max_bytes {
+            return;
+        }
+
+        let mut candidates = usage
+            .cids
+            .iter()
+            .filter(|(cid, _)| cid.as_str() != root_cid)
+            .map(|(cid, entry)| (entry.last_used, cid.clone()))
+            .collect::<Vec<_>>();
+        candidates.sort();
+        for (_, cid) in candidates {
+            if usage.total_bytes <= max_bytes {
+                break;
+            }
+            let dir = self.cache_root.join(&cid);
+            if let Err(err) = fs::remove_dir_all(&dir)
+                && dir.exists()
+            {
+                tracing::warn!(path = %dir.display(), error = %err, "failed to evict ipfs cache entry");
+                continue;
+            }
+            if let Some(removed) = usage.cids.remove(&cid) {
+                usage.total_bytes = usage.total_bytes.saturating_sub(removed.bytes);
+                tracing::info!(
+                    cid = %cid,
+                    bytes = removed.bytes,
+                    cache_bytes = usage.total_bytes,
+                    max_cache_bytes = max_bytes,
+                    "evicted least recently used ipfs cache entry"
+                );
+            }
+        }
+    }
+
+    async fn load_manifest(&self, root_cid: &str) -> Result<Manifest> {
         let fetch_started = observability::now();
         if root_cid.is_empty() {
-            return Err(anyhow!("root CID is empty"));
+            return Err(FetchError::error(
+                FetchFailure::Permanent,
+                "root CID is empty",
+            ));
         }
 
         if let Some(path) = self.cache_path(root_cid, "manifest.json")
-            && path.exists()
+            && let Some(mut bytes) = read_cached(&path)?
         {
-            let bytes = fs::read(&path)
-                .with_context(|| format!("failed reading cached manifest {}", path.display()))?;
+            if self.should_verify_cache() {
+                bytes = self.revalidate_manifest(root_cid, &path, bytes).await;
+            }
             let manifest = serde_json::from_slice::<Manifest>(&bytes)
                 .with_context(|| format!("failed decoding cached manifest {}", path.display()))?;
+            self.record_cache_use(root_cid, 0);
             return Ok(manifest);
         }
 
-        let url = format!("{}/ipfs/{}/manifest.json", self.gateway, root_cid);
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .context("ipfs gateway request failed")
-            .inspect_err(|_| observability::record_provider_error("ipfs", "fetch_manifest"))?;
+        if !is_plausible_cid(root_cid) {
+            return Err(FetchError::error(
+                FetchFailure::Permanent,
+                format!("root CID {root_cid} is not a valid CID"),
+            ));
+        }
+
+        let url = self.manifest_url(root_cid);
+        let _slot = self.gateway_slot().await;
+        let response = self.client.get(url).send().await.map_err(|err| {
+            observability::record_provider_error("ipfs", "fetch_manifest");
+            FetchError::error(
+                FetchFailure::Transient,
+                format!("ipfs gateway request failed: {err}"),
+            )
+        })?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        let validators = response_validators(&response);
+        if !status.is_success() {
             observability::record_provider_error("ipfs", "fetch_manifest_http_status");
-            return Err(anyhow!("ipfs gateway returned HTTP {}", response.status()));
+            return Err(FetchError::error(
+                classify_status(status),
+                format!("ipfs gateway returned HTTP {status}"),
+            ));
         }
 
         let bytes = response
             .bytes()
             .await
-            .context("failed reading manifest response bytes")
-            .inspect_err(|_| observability::record_provider_error("ipfs", "fetch_manifest_bytes"))?
+            .map_err(|err| {
+                observability::record_provider_error("ipfs", "fetch_manifest_bytes");
+                FetchError::error(
+                    FetchFailure::Transient,
+                    format!("failed reading manifest response bytes: {err}"),
+                )
+            })?
             .to_vec();
-        let manifest = serde_json::from_slice::<Manifest>(&bytes)
-            .context("failed to decode manifest.json")
-            .inspect_err(|_| observability::record_provider_error("ipfs", "decode_manifest"))?;
+        let manifest = serde_json::from_slice::<Manifest>(&bytes).map_err(|err| {
+            observability::record_provider_error("ipfs", "decode_manifest");
+            FetchError::error(
+                FetchFailure::Permanent,
+                format!("failed to decode manifest.json: {err}"),
+            )
+        })?;
 
-        if let Some(path) = self.cache_path(root_cid, "manifest.json") {
-            let _ = write_atomic(&path, &bytes);
+        if let Some(path) = self.cache_path(root_cid, "manifest.json")
+            && write_atomic(&path, &bytes).is_ok()
+        {
+            write_cache_meta(&path, &bytes, validators);
+            self.record_cache_use(root_cid, bytes.len() as u64);
         }
         observability::observe_stage_latency("ipfs_fetch_manifest", fetch_started);
 
         Ok(manifest)
     }
 
-    pub async fn fetch_text_file(
+    async fn load_file(
         &self,
         root_cid: &str,
         path: &str,
         max_bytes: usize,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<Vec<u8>>> {
         let fetch_started = observability::now();
         if root_cid.is_empty() || path.is_empty() {
             return Ok(None);
         }
 
-        if let Some(cache_path) = self.cache_path(root_cid, path)
-            && cache_path.exists()
-        {
-            let bytes = fs::read(&cache_path)
-                .with_context(|| format!("failed reading cached file {}", cache_path.display()))?;
-            if bytes.len() > max_bytes {
+        if let Some(cache_path) = self.cache_path(root_cid, path) {
+            if fs::metadata(&cache_path).is_ok_and(|meta| meta.len() > max_bytes as u64) {
+                self.record_cache_use(root_cid, 0);
                 return Ok(None);
             }
-            return Ok(std::str::from_utf8(&bytes)
-                .ok()
-                .map(|text| text.to_string()));
+            if let Some(bytes) = read_cached(&cache_path)? {
+                self.record_cache_use(root_cid, 0);
+                return Ok(Some(bytes));
+            }
         }
 
-        let url = format!("{}/ipfs/{}/{}", self.gateway, root_cid, path);
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .context("ipfs gateway request failed")
-            .inspect_err(|_| observability::record_provider_error("ipfs", "fetch_text_file"))?;
+        let url = format!("{}/ipfs/{}/{}", self.gateway_for(root_cid), root_cid, path);
+        let slot = self.gateway_slot().await;
+        let mut response = self.client.get(url).send().await.map_err(|err| {
+            observability::record_provider_error("ipfs", "fetch_text_file");
+            FetchError::error(
+                FetchFailure::Transient,
+                format!("ipfs gateway request failed: {err}"),
+            )
+        })?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             observability::record_provider_error("ipfs", "fetch_text_file_http_status");
-            return Ok(None);
+            // Retried by `fetch_file`; a missing file is not an error.
+            return match classify_status(status) {
+                FetchFailure::Transient => Err(FetchError::error(
+                    FetchFailure::Transient,
+                    format!("ipfs gateway returned HTTP {status}"),
+                )),
+                FetchFailure::Permanent => Ok(None),
+            };
         }
+        let validators = response_validators(&response);
 
         if let Some(content_length) = response.content_length()
             && content_length > max_bytes as u64
@@ -146,24 +525,141 @@ impl BundleFetcher {
             return Ok(None);
         }
 
-        let bytes = response.bytes().await.inspect_err(|_| {
-            observability::record_provider_error("ipfs", "fetch_text_file_bytes")
-        })?;
-        if bytes.len() > max_bytes {
-            return Ok(None);
+        // Read chunk by chunk so a gateway that omits or understates Content-Length cannot make
+        // us buffer more than `max_bytes`.
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|err| {
+            observability::record_provider_error("ipfs", "fetch_text_file_bytes");
+            FetchError::error(
+                FetchFailure::Transient,
+                format!("failed reading file response bytes: {err}"),
+            )
+        })? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Ok(None);
+            }
+            bytes.extend_from_slice(&chunk);
         }
+        drop(slot);
+
+        if let Some(cache_path) = self.cache_path(root_cid, path)
+            && write_atomic(&cache_path, bytes.as_ref()).is_ok()
+        {
+            write_cache_meta(&cache_path, bytes.as_ref(), validators);
+            self.record_cache_use(root_cid, bytes.len() as u64);
+        }
+        observability::observe_stage_latency("ipfs_fetch_text", fetch_started);
 
-        let text = match std::str::from_utf8(&bytes) {
-            Ok(value) => value.to_string(),
-            Err(_) => return Ok(None),
+        Ok(Some(bytes))
+    }
+
+    // Transient failures are retried with doubling backoff up to `ipfs.max_retries` times; the
+    // error after the last attempt says how many were made.
+    async fn retrying<T, F>(&self, root_cid: &str, path: &str, fetch: impl Fn() -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match fetch().await {
+                Err(err) if FetchFailure::of(&err) == FetchFailure::Transient => {
+                    if attempt > self.max_retries {
+                        if attempt == 1 {
+                            return Err(err);
+                        }
+                        return Err(FetchError::error(
+                            FetchFailure::Transient,
+                            format!("{err} (after {attempt} attempts)"),
+                        ));
+                    }
+                    let delay = self.retry_base_delay * 2u32.pow(attempt - 1);
+                    tracing::warn!(
+                        root_cid,
+                        path,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %err,
+                        "transient ipfs fetch failure; retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Held until the response body is read. Clones share the slots, so the cap covers every
+    // review; the semaphore is never closed, so this only ever waits.
+    async fn gateway_slot(&self) -> Option<SemaphorePermit<'_>> {
+        self.gateway_slots.acquire().await.ok()
+    }
+
+    fn manifest_url(&self, root_cid: &str) -> String {
+        format!(
+            "{}/ipfs/{}/manifest.json",
+            self.gateway_for(root_cid),
+            root_cid
+        )
+    }
+
+    fn should_verify_cache(&self) -> bool {
+        if !self.verify_cached || self.verify_cached_probability <= 0.0 {
+            return false;
+        }
+        // Sub-second clock noise is plenty for spreading re-validation across reads.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or_default();
+        f64::from(nanos % 1_000_000) / 1_000_000.0 < self.verify_cached_probability
+    }
+
+    // Returns the bytes to use: the cached copy unless the gateway serves a different valid
+    // manifest, in which case the cache is replaced. Gateway failures keep the cached copy.
+    // The request carries the stored validators so an unchanged manifest costs a 304.
+    async fn revalidate_manifest(&self, root_cid: &str, path: &Path, cached: Vec<u8>) -> Vec<u8> {
+        let url = self.manifest_url(root_cid);
+        let request = conditional(self.client.get(&url), read_cache_meta(path).as_ref());
+        let _slot = self.gateway_slot().await;
+        let response = match request.send().await {
+            Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+                tracing::debug!(root_cid, "cached manifest not modified");
+                return cached;
+            }
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                tracing::debug!(root_cid, status = %response.status(), "cache revalidation skipped");
+                return cached;
+            }
+            Err(err) => {
+                tracing::debug!(root_cid, error = %err, "cache revalidation skipped");
+                return cached;
+            }
+        };
+        let validators = response_validators(&response);
+        let Ok(fresh) = response.bytes().await.map(|bytes| bytes.to_vec()) else {
+            return cached;
         };
+        if serde_json::from_slice::<Manifest>(&fresh).is_err() {
+            return cached;
+        }
 
-        if let Some(cache_path) = self.cache_path(root_cid, path) {
-            let _ = write_atomic(&cache_path, bytes.as_ref());
+        if keccak256(&fresh) == keccak256(&cached) {
+            write_cache_meta(path, &cached, validators);
+            return cached;
         }
-        observability::observe_stage_latency("ipfs_fetch_text", fetch_started);
 
-        Ok(Some(text))
+        tracing::warn!(
+            root_cid,
+            path = %path.display(),
+            "cached manifest differs from gateway copy; replacing cache entry"
+        );
+        observability::record_provider_error("ipfs", "stale_manifest_cache");
+        if write_replacing(path, &fresh).is_ok() {
+            write_cache_meta(path, &fresh, validators);
+        }
+        fresh
     }
 
     fn cache_path(&self, root_cid: &str, relative: &str) -> Option<PathBuf> {
@@ -178,38 +674,248 @@ impl BundleFetcher {
     }
 }
 
-fn default_shared_cache_dir() -> PathBuf {
-    dirs::cache_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("VibeFi")
+#[async_trait]
+impl BundleSource for BundleFetcher {
+    async fn fetch_manifest(&self, root_cid: &str) -> Result<Manifest> {
+        let span = tracing::info_span!(
+            "ipfs_fetch",
+            root_cid,
+            path = "manifest.json",
+            status = tracing::field::Empty,
+            files = tracing::field::Empty,
+            declared_bytes = tracing::field::Empty,
+        );
+        let result = self
+            .retrying(root_cid, "manifest.json", || self.load_manifest(root_cid))
+            .instrument(span.clone())
+            .await;
+        match &result {
+            Ok(manifest) => {
+                span.record("status", "ok");
+                let files = manifest.files.as_deref().unwrap_or_default();
+                span.record("files", files.len());
+                span.record(
+                    "declared_bytes",
+                    files.iter().map(|file| file.bytes).sum::<u64>(),
+                );
+            }
+            Err(_) => {
+                span.record("status", "error");
+            }
+        }
+        result
+    }
+
+    async fn fetch_file(
+        &self,
+        root_cid: &str,
+        path: &str,
+        max_bytes: usize,
+    ) -> Result<Option<Vec<u8>>> {
+        let span = tracing::info_span!(
+            "ipfs_fetch",
+            root_cid,
+            path,
+            max_bytes,
+            status = tracing::field::Empty,
+            bytes = tracing::field::Empty,
+        );
+        let result = self
+            .retrying(root_cid, path, || self.load_file(root_cid, path, max_bytes))
+            .instrument(span.clone())
+            .await;
+        match &result {
+            Ok(Some(bytes)) => {
+                span.record("status", "ok");
+                span.record("bytes", bytes.len());
+            }
+            Ok(None) => {
+                span.record("status", "skipped");
+            }
+            Err(_) => {
+                span.record("status", "error");
+            }
+        }
+        result
+    }
 }
 
-fn safe_relative_path(input: &str) -> Option<PathBuf> {
-    let path = Path::new(input);
-    if path.is_absolute() {
-        return None;
+// Only CID-named directories are counted or evicted, so unrelated data in a shared cache
+// root is left alone.
+fn scan_cache_usage(cache_root: &Path) -> CacheUsage {
+    let mut usage = CacheUsage::default();
+    let Ok(entries) = fs::read_dir(cache_root) else {
+        return usage;
+    };
+    for entry in entries.flatten() {
+        let Some(cid) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !is_plausible_cid(&cid) || !entry.path().is_dir() {
+            continue;
+        }
+        let (bytes, last_used) = dir_usage(&entry.path());
+        usage.total_bytes += bytes;
+        usage.cids.insert(cid, CidUsage { bytes, last_used });
     }
+    usage
+}
 
-    let mut out = PathBuf::new();
-    for component in path.components() {
-        match component {
-            Component::Normal(seg) => out.push(seg),
-            _ => return None,
+fn dir_usage(dir: &Path) -> (u64, SystemTime) {
+    let mut bytes = 0;
+    let mut last_used = SystemTime::UNIX_EPOCH;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            bytes += metadata.len();
+            if let Ok(modified) = metadata.modified() {
+                last_used = last_used.max(modified);
+            }
         }
     }
+    (bytes, last_used)
+}
 
-    if out.as_os_str().is_empty() {
+// %LOCALAPPDATA%\VibeFi on Windows, the platform cache dir elsewhere. Without either, the temp
+// dir keeps the cache absolute instead of relative to wherever the agent was started.
+pub(crate) fn default_shared_cache_dir() -> PathBuf {
+    let base = if cfg!(windows) {
+        dirs::data_local_dir()
+    } else {
+        dirs::cache_dir()
+    };
+    base.unwrap_or_else(env::temp_dir).join("VibeFi")
+}
+
+// Manifest paths are '/'-separated on every platform. Backslashes, drive letters, UNC
+// prefixes, and names Windows treats specially would resolve differently per OS, so they are
+// rejected everywhere rather than interpreted.
+pub(crate) fn safe_relative_path(input: &str) -> Option<PathBuf> {
+    if input.is_empty() || input.contains(['\\', ':']) || input.chars().any(char::is_control) {
         return None;
     }
 
+    let mut out = PathBuf::new();
+    for segment in input.split('/') {
+        if matches!(segment, "" | "." | "..")
+            || segment.ends_with(['.', ' '])
+            || is_windows_device_name(segment)
+        {
+            return None;
+        }
+        out.push(segment);
+    }
     Some(out)
 }
 
+fn is_windows_device_name(segment: &str) -> bool {
+    let stem = segment
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .to_ascii_uppercase();
+    matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && stem.as_bytes()[3].is_ascii_digit()
+            && stem.as_bytes()[3] != b'0')
+}
+
+fn response_validators(response: &reqwest::Response) -> CacheValidators {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    CacheValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+fn conditional(request: RequestBuilder, meta: Option<&CacheMeta>) -> RequestBuilder {
+    let Some(meta) = meta else {
+        return request;
+    };
+    let request = match &meta.etag {
+        Some(etag) => request.header(IF_NONE_MATCH, etag),
+        None => request,
+    };
+    match &meta.last_modified {
+        Some(last_modified) => request.header(IF_MODIFIED_SINCE, last_modified),
+        None => request,
+    }
+}
+
+// Zero-byte entries come from interrupted writes; they are dropped and treated as misses.
+// Entries whose sidecar hash no longer matches are dropped the same way.
+fn read_cached(path: &Path) -> Result<Option<Vec<u8>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes =
+        fs::read(path).with_context(|| format!("failed reading cached file {}", path.display()))?;
+    let corrupt = if bytes.is_empty() {
+        Some("empty")
+    } else if read_cache_meta(path)
+        .is_some_and(|meta| meta.keccak256 != keccak256(&bytes).to_string())
+    {
+        Some("content hash mismatch")
+    } else {
+        None
+    };
+    if let Some(reason) = corrupt {
+        tracing::warn!(path = %path.display(), reason, "discarding corrupt ipfs cache entry");
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(cache_meta_path(path));
+        return Ok(None);
+    }
+    Ok(Some(bytes))
+}
+
+fn cache_meta_path(path: &Path) -> PathBuf {
+    let mut raw = path.as_os_str().to_owned();
+    raw.push(".meta");
+    PathBuf::from(raw)
+}
+
+fn read_cache_meta(path: &Path) -> Option<CacheMeta> {
+    let raw = fs::read(cache_meta_path(path)).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+fn write_cache_meta(path: &Path, bytes: &[u8], validators: CacheValidators) {
+    let meta = CacheMeta {
+        keccak256: keccak256(bytes).to_string(),
+        etag: validators.etag,
+        last_modified: validators.last_modified,
+    };
+    if let Ok(raw) = serde_json::to_vec(&meta) {
+        let _ = write_replacing(&cache_meta_path(path), &raw);
+    }
+}
+
 fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
     if path.exists() {
         return Ok(());
     }
+    write_replacing(path, bytes)
+}
 
+fn write_replacing(path: &Path, bytes: &[u8]) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed creating cache dir {}", parent.display()))?;
@@ -247,9 +953,429 @@ fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
     }
 }
 
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::{path::Path, time::Duration};
+
+    use super::BundleFetcher;
+    use crate::config::{HttpConfig, IpfsConfig};
+
+    // Cache-only: the gateway is unreachable, so a missing file fails fast.
+    pub(crate) fn test_ipfs_config(cache_dir: &Path) -> IpfsConfig {
+        IpfsConfig {
+            gateway_url: "http://127.0.0.1:1".to_string(),
+            request_timeout_secs: 1,
+            cache_dir: Some(cache_dir.to_path_buf()),
+            proxy_url: None,
+            danger_accept_invalid_certs: false,
+            verify_cached: false,
+            verify_cached_probability: 0.0,
+            max_cache_bytes: None,
+            max_concurrent_fetches: 8,
+            max_retries: 2,
+            api_url: None,
+            pin_on_discovery: false,
+            local_gateway_url: None,
+            max_pin_bytes: 100 * 1024 * 1024,
+        }
+    }
+
+    pub(crate) fn test_fetcher(cfg: &IpfsConfig) -> BundleFetcher {
+        BundleFetcher::new(cfg, &HttpConfig::default())
+            .expect("build fetcher")
+            .with_retry_base_delay(Duration::from_millis(1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::safe_relative_path;
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+        time::Duration,
+    };
+
+    use anyhow::anyhow;
+    use futures::future::join_all;
+    use reqwest::StatusCode;
+
+    use super::{
+        BundleSource, CacheValidators, FetchError, FetchFailure, cache_meta_path, classify_status,
+        conditional, is_plausible_cid, read_cache_meta, read_cached, safe_relative_path,
+        testing::{test_fetcher, test_ipfs_config},
+        write_atomic, write_cache_meta,
+    };
+    use crate::config::IpfsConfig;
+
+    #[test]
+    fn gateway_statuses_are_classified() {
+        assert_eq!(
+            classify_status(StatusCode::GATEWAY_TIMEOUT),
+            FetchFailure::Transient
+        );
+        assert_eq!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS),
+            FetchFailure::Transient
+        );
+        assert_eq!(
+            classify_status(StatusCode::NOT_FOUND),
+            FetchFailure::Permanent
+        );
+        assert_eq!(classify_status(StatusCode::GONE), FetchFailure::Permanent);
+
+        let transient = FetchError::error(FetchFailure::Transient, "HTTP 504");
+        assert_eq!(FetchFailure::of(&transient), FetchFailure::Transient);
+        assert_eq!(FetchFailure::of(&anyhow!("other")), FetchFailure::Permanent);
+        assert!(is_plausible_cid(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        ));
+        assert!(!is_plausible_cid("bafy/../etc"));
+    }
+
+    #[test]
+    fn cache_reads_drop_empty_and_hash_mismatched_entries() {
+        let dir = std::env::temp_dir().join(format!("gov-agent-ipfs-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("bafytest").join("manifest.json");
+
+        write_atomic(&path, b"").expect("write empty entry");
+        assert!(read_cached(&path).expect("read empty").is_none());
+        assert!(!path.exists());
+
+        write_atomic(&path, br#"{"name":"ok"}"#).expect("write entry");
+        write_cache_meta(&path, br#"{"name":"ok"}"#, CacheValidators::default());
+        assert!(read_cached(&path).expect("read valid").is_some());
+
+        fs::write(&path, br#"<html>gateway error</html>"#).expect("overwrite entry");
+        assert!(read_cached(&path).expect("read tampered").is_none());
+        assert!(!path.exists());
+        assert!(!cache_meta_path(&path).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn gateway_requests_queue_behind_the_concurrency_cap() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock gateway");
+        let gateway_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                    thread::spawn(move || {
+                        let mut reader = BufReader::new(stream);
+                        let mut line = String::new();
+                        while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                            line.clear();
+                        }
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(50));
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        let _ = reader.get_mut().write_all(
+                            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                        );
+                    });
+                }
+            });
+        }
+
+        let dir = std::env::temp_dir().join(format!("gov-agent-ipfs-slots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let fetcher = test_fetcher(&IpfsConfig {
+            gateway_url,
+            request_timeout_secs: 5,
+            max_concurrent_fetches: 2,
+            ..test_ipfs_config(&dir)
+        });
+
+        let fetches = (0..6).map(|i| {
+            let fetcher = fetcher.clone();
+            async move {
+                fetcher
+                    .fetch_file("bafyslots", &format!("f{i}.js"), 16)
+                    .await
+            }
+        });
+        for result in join_all(fetches).await {
+            assert_eq!(
+                result.expect("fetch queues instead of failing"),
+                Some(b"ok".to_vec())
+            );
+        }
+        // How many requests overlap depends on scheduling; only the cap is guaranteed.
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak <= 2, "{peak} gateway requests ran at once");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn transient_gateway_failures_are_retried_up_to_max_retries() {
+        // Answers 503 to the first `failures` requests and 200 with a manifest afterwards.
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock gateway");
+        let gateway_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let failures = Arc::new(AtomicUsize::new(2));
+        {
+            let (requests, failures) = (requests.clone(), failures.clone());
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                        line.clear();
+                    }
+                    let seen = requests.fetch_add(1, Ordering::SeqCst);
+                    let response: &[u8] = if seen < failures.load(Ordering::SeqCst) {
+                        b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    } else {
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 14\r\nConnection: close\r\n\r\n{\"name\":\"app\"}"
+                    };
+                    let _ = reader.get_mut().write_all(response);
+                }
+            });
+        }
+
+        let dir = std::env::temp_dir().join(format!("gov-agent-ipfs-retry-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let fetcher_with = |max_retries: u32| {
+            test_fetcher(&IpfsConfig {
+                gateway_url: gateway_url.clone(),
+                request_timeout_secs: 5,
+                max_retries,
+                ..test_ipfs_config(&dir)
+            })
+        };
+
+        let manifest = fetcher_with(2)
+            .fetch_manifest("bafyretry")
+            .await
+            .expect("third attempt succeeds");
+        assert_eq!(manifest.name.as_deref(), Some("app"));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        requests.store(0, Ordering::SeqCst);
+        let err = fetcher_with(1)
+            .fetch_file("bafyretry", "src/app.js", 64)
+            .await
+            .expect_err("retries exhausted");
+        assert_eq!(FetchFailure::of(&err), FetchFailure::Transient);
+        assert_eq!(
+            err.to_string(),
+            "ipfs gateway returned HTTP 503 Service Unavailable (after 2 attempts)"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn pin_posts_to_the_node_api_only_when_enabled() {
+        // Accepts the first pin and every unpin and rejects the rest, recording each request
+        // line.
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock api");
+        let api_url = format!("http://{}/", listener.local_addr().expect("local addr"));
+        let request_lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        {
+            let request_lines = request_lines.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    let _ = reader.read_line(&mut line);
+                    let mut lines = request_lines.lock().unwrap();
+                    lines.push(line.trim().to_string());
+                    let mut header = String::new();
+                    while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                        header.clear();
+                    }
+                    let response: &[u8] = if lines.len() == 1 || line.contains("/pin/rm") {
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+                    } else {
+                        b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 11\r\nConnection: close\r\n\r\nnot pinned\n"
+                    };
+                    let _ = reader.get_mut().write_all(response);
+                }
+            });
+        }
+
+        // The node's own gateway serves every file.
+        let local = TcpListener::bind("127.0.0.1:0").expect("bind mock gateway");
+        let local_gateway_url = format!("http://{}", local.local_addr().expect("local addr"));
+        thread::spawn(move || {
+            for stream in local.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    header.clear();
+                }
+                let _ = reader.get_mut().write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nlocal",
+                );
+            }
+        });
+
+        let dir = std::env::temp_dir().join(format!("gov-agent-ipfs-pin-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        // Cached manifests, so the size check never reaches the (dead) public gateway.
+        write_atomic(
+            &dir.join("bafypin").join("manifest.json"),
+            br#"{"files":[{"path":"app.js","bytes":5}]}"#,
+        )
+        .expect("write manifest");
+        write_atomic(
+            &dir.join("bafybig").join("manifest.json"),
+            br#"{"files":[{"path":"a.bin","bytes":600},{"path":"b.bin","bytes":600}]}"#,
+        )
+        .expect("write manifest");
+        write_atomic(&dir.join("bafybare").join("manifest.json"), br#"{}"#)
+            .expect("write manifest");
+        let fetcher_with = |pin_on_discovery: bool| {
+            test_fetcher(&IpfsConfig {
+                request_timeout_secs: 5,
+                max_retries: 0,
+                api_url: Some(api_url.clone()),
+                pin_on_discovery,
+                local_gateway_url: Some(local_gateway_url.clone()),
+                max_pin_bytes: 1_000,
+                ..test_ipfs_config(&dir)
+            })
+        };
+
+        let disabled = fetcher_with(false);
+        assert!(!disabled.pins_on_discovery());
+        disabled.pin("bafypin").await.expect("no-op");
+        assert!(request_lines.lock().unwrap().is_empty());
+
+        let fetcher = fetcher_with(true);
+        assert!(fetcher.fetch_file("bafypin", "app.js", 64).await.is_err());
+        fetcher.pin("bafypin").await.expect("pinned");
+        let file = fetcher.fetch_file("bafypin", "app.js", 64).await;
+        assert_eq!(file.expect("local read").as_deref(), Some(&b"local"[..]));
+        let err = fetcher.pin("bafypin").await.expect_err("node rejects");
+        assert_eq!(
+            err.to_string(),
+            "ipfs api pin/add returned HTTP 500 Internal Server Error: not pinned"
+        );
+        assert!(fetcher.pin("../etc").await.is_err());
+
+        // Oversized or unsized bundles never reach pin/add.
+        let err = fetcher.pin("bafybig").await.expect_err("too large");
+        assert_eq!(
+            err.to_string(),
+            "manifest lists 1200 bytes, over ipfs.max_pin_bytes (1000); not pinning"
+        );
+        assert!(fetcher.pin("bafybare").await.is_err());
+
+        // Unpinning sends pin/rm once and sends reads back to the public gateway.
+        fetcher.unpin("bafypin").await.expect("unpinned");
+        fetcher.unpin("bafypin").await.expect("no-op");
+        assert!(fetcher.fetch_file("bafypin", "other.js", 64).await.is_err());
+        assert_eq!(
+            request_lines.lock().unwrap().as_slice(),
+            [
+                "POST /api/v0/pin/add?arg=bafypin HTTP/1.1",
+                "POST /api/v0/pin/add?arg=bafypin HTTP/1.1",
+                "POST /api/v0/pin/rm?arg=bafypin HTTP/1.1",
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn invalidate_removes_cid_directory() {
+        let dir =
+            std::env::temp_dir().join(format!("gov-agent-ipfs-invalidate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let fetcher = test_fetcher(&test_ipfs_config(&dir));
+        let path = dir.join("bafytest").join("manifest.json");
+        write_atomic(&path, br#"{"name":"cached"}"#).expect("write entry");
+
+        let manifest = fetcher
+            .fetch_manifest("bafytest")
+            .await
+            .expect("cached manifest");
+        assert_eq!(manifest.name.as_deref(), Some("cached"));
+
+        assert!(fetcher.invalidate("bafytest").expect("invalidate"));
+        assert!(!dir.join("bafytest").exists());
+        assert!(!fetcher.invalidate("bafytest").expect("invalidate again"));
+        assert!(fetcher.invalidate("../etc").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stored_validators_become_conditional_request_headers() {
+        let dir =
+            std::env::temp_dir().join(format!("gov-agent-ipfs-validators-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("bafytest").join("manifest.json");
+        write_atomic(&path, br#"{"name":"ok"}"#).expect("write entry");
+        write_cache_meta(
+            &path,
+            br#"{"name":"ok"}"#,
+            CacheValidators {
+                etag: Some("\"v1\"".to_string()),
+                last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            },
+        );
+
+        let client = reqwest::Client::new();
+        let meta = read_cache_meta(&path).expect("cache meta");
+        let request = conditional(client.get("http://gateway.test/"), Some(&meta))
+            .build()
+            .expect("build request");
+        assert_eq!(request.headers()["if-none-match"], "\"v1\"");
+        assert_eq!(
+            request.headers()["if-modified-since"],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+
+        let bare = conditional(client.get("http://gateway.test/"), None)
+            .build()
+            .expect("build request");
+        assert!(bare.headers().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cache_over_limit_evicts_least_recently_used_cids() {
+        let dir = std::env::temp_dir().join(format!("gov-agent-ipfs-lru-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for cid in ["bafyold", "bafyused"] {
+            write_atomic(&dir.join(cid).join("manifest.json"), &[b'x'; 40]).expect("write");
+        }
+        fs::create_dir_all(dir.join("not-a-cid!")).expect("foreign dir");
+        let mut fetcher = test_fetcher(&test_ipfs_config(&dir));
+        fetcher.max_cache_bytes = Some(100);
+
+        fetcher.record_cache_use("bafyused", 0);
+        write_atomic(&dir.join("bafynew").join("manifest.json"), &[b'x'; 40]).expect("write");
+        fetcher.record_cache_use("bafynew", 40);
+
+        assert!(!dir.join("bafyold").exists());
+        assert!(dir.join("bafyused").exists());
+        assert!(dir.join("bafynew").exists());
+        assert!(dir.join("not-a-cid!").exists());
+        let usage = fetcher.usage.lock().expect("usage lock");
+        assert_eq!(usage.as_ref().expect("usage").total_bytes, 80);
+        drop(usage);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 
     #[test]
     fn relative_path_rejects_traversal() {
@@ -257,4 +1383,54 @@ mod tests {
         assert!(safe_relative_path("/absolute").is_none());
         assert!(safe_relative_path("ok/file.txt").is_some());
     }
+
+    #[test]
+    fn relative_path_rejects_platform_specific_forms() {
+        for input in [
+            "",
+            "C:\\evil",
+            "C:evil",
+            "c:/windows/system32",
+            "\\\\server\\share\\x",
+            "//server/share/x",
+            "\\\\?\\C:\\x",
+            "assets\\..\\..\\x",
+            "a/./b",
+            "a//b",
+            "a/../b",
+            "file.txt:stream",
+            "src/CON",
+            "nul.txt",
+            "assets/com1.js",
+            "LPT9",
+            "trailing.",
+            "trailing ",
+            "tab\tname",
+            "nul\0byte",
+        ] {
+            assert!(
+                safe_relative_path(input).is_none(),
+                "{input:?} should be rejected"
+            );
+        }
+
+        for input in [
+            "src/app.ts",
+            "assets/icon.png",
+            "console.js",
+            "com10.js",
+            "lpt0/x",
+            ".well-known/x",
+        ] {
+            let path = safe_relative_path(input).expect("accepted");
+            assert_eq!(path.components().count(), input.split('/').count());
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn shared_cache_dir_is_under_local_app_data_on_windows() {
+        let local = dirs::data_local_dir().expect("LOCALAPPDATA");
+        assert_eq!(super::default_shared_cache_dir(), local.join("VibeFi"));
+    }
 }
@@ -0,0 +1,564 @@
+use std::{
+    collections::BTreeSet,
+    fmt,
+    str::FromStr,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    primitives::{Address, B256, Bytes, U64},
+    rpc::types::Log as RpcLog,
+    sol_types::SolCall,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{
+    chain::{
+        ChainSource, ProposalScan, TransportKind, cache_clock_mode, decode_proposal_logs,
+        is_revert_response, nonexistent_proposal, parse_proposal_id,
+    },
+    config::{HttpConfig, NetworkConfig},
+    decoder::ProposalCreatedAbi,
+    http, observability,
+    rpc_stats::{self, RpcStats},
+    signer::{ClockMode, IVfiGovernor},
+    types::{Proposal, ProposalLifecycle, ProposalState, ProposalTally},
+};
+
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+// The node's error object, kept typed so callers can tell a contract revert from a failed node.
+#[derive(Debug)]
+pub struct RpcErrorResponse {
+    pub method: String,
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcErrorResponse {
+    pub fn is_revert(&self) -> bool {
+        is_revert_response(self.code, &self.message)
+    }
+}
+
+impl fmt::Display for RpcErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed: {} (code {})",
+            self.method, self.message, self.code
+        )
+    }
+}
+
+impl std::error::Error for RpcErrorResponse {}
+
+enum RpcFailure {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+// Plain JSON-RPC over HTTP POST, one request per call. Calls are counted in the same rpc stats
+// as the alloy provider.
+pub struct JsonRpcClient {
+    url: Url,
+    endpoint: String,
+    allowed_methods: BTreeSet<String>,
+    client: Client,
+    next_id: AtomicU64,
+    retry_base_delay: Duration,
+}
+
+impl JsonRpcClient {
+    pub fn new(rpc_url: &str, allowed_methods: &[String], http_cfg: &HttpConfig) -> Result<Self> {
+        let (client, url) = http::rpc_http_client(rpc_url, http_cfg)?
+            .ok_or_else(|| anyhow!("the jsonrpc read backend needs an http(s) rpc url"))?;
+        Ok(Self {
+            url,
+            endpoint: rpc_stats::endpoint_label(rpc_url),
+            allowed_methods: allowed_methods.iter().cloned().collect(),
+            client,
+            next_id: AtomicU64::new(1),
+            retry_base_delay: RETRY_BASE_DELAY,
+        })
+    }
+
+    // Transport errors, HTTP 429 and 5xx are retried with doubling backoff. A JSON-RPC error
+    // object (revert, bad params, range too large) is the node's answer and is not retried.
+    pub async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        if !self.allowed_methods.is_empty() && !self.allowed_methods.contains(method) {
+            bail!("rpc method {method} is not in network.jsonrpc_allowed_methods");
+        }
+        let mut attempt = 1;
+        loop {
+            let started = Instant::now();
+            let result = self.send(method, &params).await;
+            RpcStats::global().record(&self.endpoint, method, started.elapsed(), result.is_err());
+            match result {
+                Ok(value) => {
+                    return serde_json::from_value(value)
+                        .with_context(|| format!("unexpected {method} result"));
+                }
+                Err(RpcFailure::Retryable(err)) if attempt < MAX_ATTEMPTS => {
+                    let delay = self.retry_base_delay * 2u32.pow(attempt - 1);
+                    tracing::warn!(
+                        method,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %format!("{err:#}"),
+                        "json-rpc request failed; retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(RpcFailure::Retryable(err) | RpcFailure::Fatal(err)) => return Err(err),
+            }
+        }
+    }
+
+    async fn send(&self, method: &str, params: &Value) -> Result<Value, RpcFailure> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let response = self
+            .client
+            .post(self.url.clone())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| {
+                RpcFailure::Retryable(anyhow!(err).context(format!("{method} request failed")))
+            })?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(RpcFailure::Retryable(anyhow!(
+                "{method} returned HTTP {status}"
+            )));
+        }
+        if !status.is_success() {
+            return Err(RpcFailure::Fatal(anyhow!(
+                "{method} returned HTTP {status}"
+            )));
+        }
+        let response = response.json::<RpcResponse>().await.map_err(|err| {
+            RpcFailure::Fatal(anyhow!(err).context(format!("invalid {method} response")))
+        })?;
+        if let Some(error) = response.error {
+            return Err(RpcFailure::Fatal(anyhow!(RpcErrorResponse {
+                method: method.to_string(),
+                code: error.code,
+                message: error.message,
+            })));
+        }
+        Ok(response.result.unwrap_or(Value::Null))
+    }
+}
+
+// The `network.read_backend = "jsonrpc"` chain source. It covers scanning and governor state
+// reads; creation-context lookups stay unavailable, as with file fixtures.
+pub struct JsonRpcChain {
+    client: JsonRpcClient,
+    governor_address: Option<Address>,
+    dapp_registry_address: String,
+    include_non_registry: bool,
+    proposal_event: ProposalCreatedAbi,
+    clock_mode: OnceLock<ClockMode>,
+}
+
+impl JsonRpcChain {
+    pub fn new(
+        network: &NetworkConfig,
+        http_cfg: &HttpConfig,
+        include_non_registry: bool,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: JsonRpcClient::new(
+                &network.rpc_url,
+                &network.jsonrpc_allowed_methods,
+                http_cfg,
+            )?,
+            governor_address: Address::from_str(&network.governor_address).ok(),
+            dapp_registry_address: network.dapp_registry_address.clone(),
+            include_non_registry,
+            proposal_event: ProposalCreatedAbi::from_signature(
+                network.proposal_created_signature.as_deref(),
+            )?,
+            clock_mode: OnceLock::new(),
+        })
+    }
+
+    // Without a topic0, every log the governor emitted in the range.
+    async fn fetch_logs(
+        &self,
+        topic0: Option<B256>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<RpcLog>> {
+        let Some(governor) = self.governor_address else {
+            return Ok(Vec::new());
+        };
+        let mut filter = json!({
+            "address": governor,
+            "fromBlock": format!("{from_block:#x}"),
+            "toBlock": format!("{to_block:#x}"),
+        });
+        if let Some(topic0) = topic0 {
+            filter["topics"] = json!([topic0]);
+        }
+        let rpc_fetch_started = observability::now();
+        let logs = self
+            .client
+            .request::<Vec<RpcLog>>("eth_getLogs", json!([filter]))
+            .await
+            .with_context(|| {
+                format!("failed to fetch governor logs in range [{from_block}, {to_block}]")
+            })
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_logs"))?;
+        observability::observe_stage_latency("rpc_fetch_logs", rpc_fetch_started);
+        Ok(logs)
+    }
+
+    async fn call_governor<C: SolCall>(&self, call: C) -> Result<C::Return> {
+        let data = self.call_governor_raw(&call).await?;
+        C::abi_decode_returns(&data).map_err(|err| anyhow!("failed to decode governor call: {err}"))
+    }
+
+    async fn call_governor_raw<C: SolCall>(&self, call: &C) -> Result<Bytes> {
+        let governor = self
+            .governor_address
+            .ok_or_else(|| anyhow!("governor address is not configured"))?;
+        self.client
+            .request::<Bytes>(
+                "eth_call",
+                json!([
+                    { "to": governor, "data": Bytes::from(call.abi_encode()) },
+                    "latest"
+                ]),
+            )
+            .await
+            .inspect_err(|_| observability::record_provider_error("rpc", "eth_call"))
+    }
+
+    // Mirrors `governor_state_error`: a revert or empty return data means the id is not a
+    // proposal of this governor, anything else is the node failing.
+    async fn proposal_state(&self, proposal_id: &str) -> Result<ProposalState> {
+        let call = IVfiGovernor::stateCall {
+            proposalId: parse_proposal_id(proposal_id)?,
+        };
+        let data = match self.call_governor_raw(&call).await {
+            Ok(data) if data.is_empty() => {
+                return Err(nonexistent_proposal(
+                    proposal_id,
+                    anyhow!("governor returned no data"),
+                ));
+            }
+            Ok(data) => data,
+            Err(err)
+                if err
+                    .downcast_ref::<RpcErrorResponse>()
+                    .is_some_and(RpcErrorResponse::is_revert) =>
+            {
+                return Err(nonexistent_proposal(proposal_id, err));
+            }
+            Err(err) => return Err(err.context("rpc error while reading proposal state")),
+        };
+        let raw = IVfiGovernor::stateCall::abi_decode_returns(&data)
+            .map_err(|err| anyhow!("failed to decode governor call: {err}"))?;
+        ProposalState::from_governor(raw)
+            .ok_or_else(|| anyhow!("governor returned unknown proposal state {raw}"))
+    }
+}
+
+#[async_trait]
+impl ChainSource for JsonRpcChain {
+    fn transport(&self) -> TransportKind {
+        TransportKind::Http
+    }
+
+    async fn health_check(&self) -> Result<u64> {
+        let chain_id = self
+            .client
+            .request::<U64>("eth_chainId", json!([]))
+            .await
+            .context("failed to read chain id")
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_chain_id"))?;
+        Ok(chain_id.to())
+    }
+
+    async fn latest_block(&self) -> Result<u64> {
+        let block = self
+            .client
+            .request::<U64>("eth_blockNumber", json!([]))
+            .await
+            .context("failed to read latest block")
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_block_number"))?;
+        Ok(block.to())
+    }
+
+    async fn clock_mode(&self) -> ClockMode {
+        if let Some(mode) = self.clock_mode.get() {
+            return *mode;
+        }
+        if self.governor_address.is_none() {
+            return ClockMode::BlockNumber;
+        }
+        let read = match self
+            .call_governor_raw(&IVfiGovernor::CLOCK_MODECall {})
+            .await
+        {
+            Ok(data) if data.is_empty() => Ok(None),
+            Ok(data) => IVfiGovernor::CLOCK_MODECall::abi_decode_returns(&data)
+                .map(Some)
+                .map_err(|err| anyhow!("failed to decode CLOCK_MODE: {err}")),
+            Err(err)
+                if err
+                    .downcast_ref::<RpcErrorResponse>()
+                    .is_some_and(RpcErrorResponse::is_revert) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        };
+        cache_clock_mode(&self.clock_mode, read)
+    }
+
+    // Needs eth_getBlockByNumber, which `network.jsonrpc_allowed_methods` may leave out.
+    async fn latest_block_timestamp(&self) -> Result<u64> {
+        let block = self
+            .client
+            .request::<Value>("eth_getBlockByNumber", json!(["latest", false]))
+            .await
+            .context("failed to read latest block")
+            .inspect_err(|_| observability::record_provider_error("rpc", "get_block"))?;
+        let timestamp = serde_json::from_value::<U64>(block["timestamp"].clone())
+            .context("latest block has no timestamp")?;
+        Ok(timestamp.to())
+    }
+
+    async fn fetch_proposals(&self, from_block: u64, to_block: u64) -> Result<Vec<Proposal>> {
+        Ok(self.scan_proposals(from_block, to_block).await?.proposals)
+    }
+
+    async fn scan_proposals(&self, from_block: u64, to_block: u64) -> Result<ProposalScan> {
+        let logs = self
+            .fetch_logs(Some(self.proposal_event.topic0()), from_block, to_block)
+            .await?;
+        Ok(decode_proposal_logs(
+            logs,
+            &self.proposal_event,
+            &self.dapp_registry_address,
+            self.include_non_registry,
+        ))
+    }
+
+    async fn governor_log_topics(&self, from_block: u64, to_block: u64) -> Result<Vec<B256>> {
+        Ok(self
+            .fetch_logs(None, from_block, to_block)
+            .await?
+            .iter()
+            .filter_map(|log| log.topic0().copied())
+            .collect())
+    }
+
+    async fn is_proposal_active(&self, proposal: &Proposal) -> Result<bool> {
+        Ok(self.proposal_state(&proposal.proposal_id).await? == ProposalState::Active)
+    }
+
+    async fn proposal_lifecycle(&self, proposal_id: &str) -> Result<Option<ProposalLifecycle>> {
+        if self.governor_address.is_none() {
+            return Ok(None);
+        }
+        let state = self.proposal_state(proposal_id).await?;
+        let eta = match state {
+            ProposalState::Queued | ProposalState::Executed => self
+                .call_governor(IVfiGovernor::proposalEtaCall {
+                    proposalId: parse_proposal_id(proposal_id)?,
+                })
+                .await
+                .ok()
+                .and_then(|eta| u64::try_from(eta).ok())
+                .filter(|eta| *eta > 0),
+            _ => None,
+        };
+        Ok(Some(ProposalLifecycle {
+            state,
+            eta,
+            checked_at: Utc::now(),
+        }))
+    }
+
+    async fn proposal_tally(&self, proposal_id: &str) -> Result<Option<ProposalTally>> {
+        if self.governor_address.is_none() {
+            return Ok(None);
+        }
+        let state = self.proposal_state(proposal_id).await?;
+        let votes = self
+            .call_governor(IVfiGovernor::proposalVotesCall {
+                proposalId: parse_proposal_id(proposal_id)?,
+            })
+            .await
+            .context("failed to read proposal votes")?;
+        Ok(Some(ProposalTally {
+            state,
+            for_votes: votes.forVotes.to_string(),
+            against_votes: votes.againstVotes.to_string(),
+            abstain_votes: votes.abstainVotes.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    use alloy::primitives::U64;
+    use serde_json::json;
+
+    use super::{JsonRpcChain, JsonRpcClient};
+    use crate::config::{AppConfig, HttpConfig};
+
+    // Answers one request per reply and returns each request's JSON-RPC method.
+    fn serve(replies: &'static [(u16, &'static str)]) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let url = format!("http://{}", listener.local_addr().expect("local addr"));
+        let handle = thread::spawn(move || {
+            let mut methods = Vec::new();
+            for (status, body) in replies {
+                let (stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read header");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().expect("content length");
+                    }
+                }
+                let mut request = vec![0; content_length];
+                reader.read_exact(&mut request).expect("read body");
+                let request: serde_json::Value =
+                    serde_json::from_slice(&request).expect("json request");
+                methods.push(request["method"].as_str().unwrap_or_default().to_string());
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .expect("write reply");
+            }
+            methods
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_but_not_rpc_errors_or_disallowed_methods() {
+        let (url, server) = serve(&[
+            (503, ""),
+            (200, r#"{"jsonrpc":"2.0","id":2,"result":"0x2a"}"#),
+            (
+                200,
+                r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32000,"message":"execution reverted"}}"#,
+            ),
+        ]);
+        let allowed = ["eth_blockNumber".to_string(), "eth_call".to_string()];
+        let mut client =
+            JsonRpcClient::new(&url, &allowed, &HttpConfig::default()).expect("build client");
+        client.retry_base_delay = Duration::from_millis(1);
+
+        let block = client
+            .request::<U64>("eth_blockNumber", json!([]))
+            .await
+            .expect("retried request succeeds");
+        assert_eq!(block.to::<u64>(), 42);
+
+        let err = client
+            .request::<U64>("eth_call", json!([]))
+            .await
+            .expect_err("rpc error is returned");
+        assert!(err.to_string().contains("execution reverted"));
+
+        let err = client
+            .request::<U64>("eth_getLogs", json!([]))
+            .await
+            .expect_err("method outside the allowlist");
+        assert!(err.to_string().contains("network.jsonrpc_allowed_methods"));
+
+        assert_eq!(
+            server.join().expect("mock server"),
+            vec!["eth_blockNumber", "eth_blockNumber", "eth_call"]
+        );
+    }
+
+    #[tokio::test]
+    async fn state_reverts_read_as_nonexistent_proposals_and_node_errors_do_not() {
+        let (url, server) = serve(&[
+            (
+                200,
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted","data":"0x6ad06075"}}"#,
+            ),
+            (200, r#"{"jsonrpc":"2.0","id":2,"result":"0x"}"#),
+            (
+                200,
+                r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32002,"message":"request timed out"}}"#,
+            ),
+        ]);
+        let mut network = AppConfig::for_profile("devnet").network;
+        network.rpc_url = url;
+        network.governor_address = "0x0000000000000000000000000000000000000001".to_string();
+        let chain = JsonRpcChain::new(&network, &HttpConfig::default(), false).expect("chain");
+
+        for _ in 0..2 {
+            let err = chain
+                .proposal_state("7")
+                .await
+                .expect_err("no such proposal");
+            assert_eq!(
+                err.to_string(),
+                "proposal 7 does not exist or is not a governor proposal"
+            );
+        }
+        let err = chain.proposal_state("7").await.expect_err("node failure");
+        assert_eq!(err.to_string(), "rpc error while reading proposal state");
+        assert!(format!("{err:#}").contains("request timed out"));
+
+        server.join().expect("mock server");
+    }
+}
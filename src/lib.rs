@@ -1,15 +1,33 @@
 pub mod agent;
+pub mod api;
 pub mod app;
+pub mod bundle_index;
+pub mod canary;
 pub mod chain;
 pub mod cli;
 pub mod config;
+pub mod config_schema;
 pub mod decision;
 pub mod decoder;
+pub mod env_file;
+pub mod export;
+pub mod forwarder;
+pub mod http;
+pub mod init;
 pub mod ipfs;
+pub mod jsonrpc;
 pub mod llm;
+pub mod llm_cache;
+pub mod local_bundle;
 pub mod notifier;
 pub mod observability;
+pub mod outcome;
+pub mod peers;
+pub mod report;
+pub mod reputation;
 pub mod review;
+pub mod rpc_stats;
 pub mod signer;
 pub mod storage;
+pub mod template;
 pub mod types;
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fmt, path::Path};
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
@@ -7,9 +7,12 @@ use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tracing::Instrument;
 
 use crate::{
-    config::{LlmConfig, ProviderConfig},
+    config::{ApiStyle, HttpConfig, LlmConfig, ProviderConfig},
+    http::{self, HttpService},
+    llm_cache::LlmCache,
     observability,
 };
 
@@ -26,6 +29,12 @@ static REDACTION_PATTERNS: Lazy<[Regex; 5]> = Lazy::new(|| {
     ]
 });
 
+const STRUCTURED_OUTPUT_INSTRUCTIONS: &str =
+    include_str!("../prompts/structured_output_instructions.md");
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a governance review assistant.";
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+const DEFAULT_TEMPERATURE: f32 = 0.1;
+
 #[derive(Debug, Clone)]
 pub struct LlmContext {
     pub prompt: String,
@@ -36,40 +45,182 @@ pub struct LlmResponse {
     pub provider: String,
     pub model: String,
     pub text: String,
+    #[serde(default)]
+    pub cached: bool,
 }
 
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    // Everything besides the prompt that shapes the completion, for the response cache; None
+    // when the provider is disabled.
+    fn cache_key(&self) -> Option<String> {
+        Some(self.name().to_string())
+    }
+
     async fn analyze(&self, ctx: &LlmContext) -> Result<LlmResponse>;
 }
 
+#[derive(Debug)]
+pub struct EmptyLlmResponse {
+    pub provider: String,
+    pub detail: String,
+}
+
+impl EmptyLlmResponse {
+    fn error(provider: &str, detail: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(Self {
+            provider: provider.to_string(),
+            detail: detail.into(),
+        })
+    }
+}
+
+impl fmt::Display for EmptyLlmResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} provider {}", self.provider, self.detail)
+    }
+}
+
+impl std::error::Error for EmptyLlmResponse {}
+
 pub struct CompositeLlm {
     providers: Vec<Box<dyn LlmProvider>>,
+    structured_output: bool,
+    max_retries: u32,
+    cache: Option<LlmCache>,
+    max_description_chars: usize,
+    max_prompt_chars: usize,
 }
 
 impl CompositeLlm {
-    pub fn from_config(config: &LlmConfig) -> Self {
+    pub fn from_config(config: &LlmConfig, http_cfg: &HttpConfig, data_dir: &Path) -> Result<Self> {
+        let client = http::build_client(
+            http_cfg,
+            HttpService {
+                name: "llm",
+                proxy_url: config.proxy_url.as_deref(),
+                danger_accept_invalid_certs: config.danger_accept_invalid_certs,
+            },
+            None,
+        )?;
         let providers: Vec<Box<dyn LlmProvider>> = vec![
-            Box::new(OllamaProvider::new(&config.ollama)),
-            Box::new(OpenAiLikeProvider::new("openai", &config.openai)),
-            Box::new(VeniceProvider::new(&config.venice)),
-            Box::new(AnthropicProvider::new(&config.anthropic)),
+            Box::new(OllamaProvider::new(&config.ollama, client.clone())),
+            Box::new(OpenAiLikeProvider::new(
+                "openai",
+                &config.openai,
+                client.clone(),
+                config.structured_output,
+            )),
+            Box::new(VeniceProvider::new(&config.venice, client.clone())),
+            Box::new(AnthropicProvider::new(&config.anthropic, client)),
         ];
-        Self { providers }
+        Ok(Self {
+            providers,
+            structured_output: config.structured_output,
+            max_retries: config.max_retries,
+            cache: config
+                .cache
+                .then(|| LlmCache::new(data_dir, config.cache_ttl_secs)),
+            max_description_chars: config.max_description_chars,
+            max_prompt_chars: config.max_prompt_chars,
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_providers(providers: Vec<Box<dyn LlmProvider>>) -> Self {
+        Self {
+            providers,
+            structured_output: false,
+            max_retries: 0,
+            cache: None,
+            max_description_chars: usize::MAX,
+            max_prompt_chars: usize::MAX,
+        }
+    }
+
+    pub fn structured_output(&self) -> bool {
+        self.structured_output
+    }
+
+    pub fn max_description_chars(&self) -> usize {
+        self.max_description_chars
+    }
+
+    pub fn max_prompt_chars(&self) -> usize {
+        self.max_prompt_chars
     }
 
     pub async fn analyze_best_effort(&self, ctx: &LlmContext) -> Option<LlmResponse> {
+        if let Some(response) = self.cache.as_ref().and_then(|cache| {
+            self.providers
+                .iter()
+                .filter_map(|provider| provider.cache_key())
+                .find_map(|key| cache.get(&key, &ctx.prompt))
+        }) {
+            tracing::info!(
+                provider = %response.provider,
+                model = %response.model,
+                "reusing cached llm response for identical prompt"
+            );
+            return Some(response);
+        }
+
         let llm_started = observability::now();
         for provider in &self.providers {
-            match provider.analyze(ctx).await {
-                Ok(response) => {
-                    observability::observe_stage_latency("llm_review", llm_started);
-                    return Some(response);
+            let mut attempt = 0;
+            loop {
+                let span = tracing::info_span!(
+                    "llm_attempt",
+                    provider = provider.name(),
+                    attempt,
+                    prompt_chars = ctx.prompt.len(),
+                    status = tracing::field::Empty,
+                    response_chars = tracing::field::Empty,
+                );
+                let result = provider.analyze(ctx).instrument(span.clone()).await;
+                match &result {
+                    Ok(response) => {
+                        span.record("status", "ok");
+                        span.record("response_chars", response.text.len());
+                    }
+                    Err(err) if err.downcast_ref::<EmptyLlmResponse>().is_some() => {
+                        span.record("status", "empty");
+                    }
+                    Err(_) => {
+                        span.record("status", "error");
+                    }
                 }
-                Err(err) => {
-                    observability::record_provider_error("llm", "provider_attempt");
-                    tracing::warn!(error = %err, "llm provider attempt failed; trying next provider");
-                    continue;
+                match result {
+                    Ok(response) => {
+                        observability::observe_stage_latency("llm_review", llm_started);
+                        if let Some(cache) = &self.cache
+                            && let Some(key) = provider.cache_key()
+                            && let Err(err) = cache.put(&key, &ctx.prompt, &response)
+                        {
+                            tracing::warn!(error = %err, "failed to cache llm response");
+                        }
+                        return Some(response);
+                    }
+                    Err(err)
+                        if attempt < self.max_retries
+                            && err.downcast_ref::<EmptyLlmResponse>().is_some() =>
+                    {
+                        attempt += 1;
+                        observability::record_provider_error("llm", "empty_response");
+                        tracing::warn!(
+                            error = %err,
+                            attempt,
+                            max_retries = self.max_retries,
+                            "llm provider returned empty content; retrying same provider"
+                        );
+                    }
+                    Err(err) => {
+                        observability::record_provider_error("llm", "provider_attempt");
+                        tracing::warn!(error = %err, "llm provider attempt failed; trying next provider");
+                        break;
+                    }
                 }
             }
         }
@@ -78,24 +229,50 @@ impl CompositeLlm {
     }
 }
 
+fn settings_key(name: &str, cfg: &ProviderConfig, structured_output: bool) -> Option<String> {
+    cfg.enabled.then(|| {
+        json!({
+            "provider": name,
+            "base_url": cfg.base_url,
+            "model": cfg.model,
+            "system_prompt": cfg.system_prompt,
+            "temperature": cfg.temperature,
+            "max_tokens": cfg.max_tokens,
+            "api_style": cfg.api_style,
+            "structured_output": structured_output,
+        })
+        .to_string()
+    })
+}
+
 struct OpenAiLikeProvider {
     name: String,
     cfg: ProviderConfig,
     http: Client,
+    structured_output: bool,
 }
 
 impl OpenAiLikeProvider {
-    fn new(name: &str, cfg: &ProviderConfig) -> Self {
+    fn new(name: &str, cfg: &ProviderConfig, http: Client, structured_output: bool) -> Self {
         Self {
             name: name.to_string(),
             cfg: cfg.clone(),
-            http: Client::new(),
+            http,
+            structured_output,
         }
     }
 }
 
 #[async_trait]
 impl LlmProvider for OpenAiLikeProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        settings_key(&self.name, &self.cfg, self.structured_output)
+    }
+
     async fn analyze(&self, ctx: &LlmContext) -> Result<LlmResponse> {
         if !self.cfg.enabled {
             return Err(anyhow!("provider disabled"));
@@ -121,37 +298,49 @@ impl LlmProvider for OpenAiLikeProvider {
             .unwrap_or_else(|| "default".to_string());
 
         let text = self
-            .call_responses_api(&base_url, &api_key, &model, &ctx.prompt)
+            .call_api(&base_url, &api_key, &model, &ctx.prompt)
             .await?;
 
-        if text.is_empty() {
-            return Err(anyhow!("{} provider response missing content", self.name));
-        }
-
         Ok(LlmResponse {
             provider: self.name.clone(),
             model,
             text,
+            cached: false,
         })
     }
 }
 
 impl OpenAiLikeProvider {
-    async fn call_responses_api(
+    async fn call_api(
         &self,
         base_url: &str,
         api_key: &str,
         model: &str,
         prompt: &str,
     ) -> Result<String> {
+        let (path, request, extract): (_, _, fn(&serde_json::Value) -> Option<String>) =
+            match self.cfg.api_style {
+                ApiStyle::Chat => (
+                    "chat/completions",
+                    chat_request_body(model, prompt, &self.cfg, self.structured_output),
+                    extract_chat_completion_text,
+                ),
+                ApiStyle::Responses => (
+                    "responses",
+                    responses_request_body(model, prompt, &self.cfg, self.structured_output),
+                    extract_responses_text,
+                ),
+                ApiStyle::Completions => (
+                    "completions",
+                    completions_request_body(model, prompt, &self.cfg),
+                    extract_completion_text,
+                ),
+            };
         let response = self
             .http
-            .post(format!("{}/responses", base_url.trim_end_matches('/')))
+            .post(format!("{}/{path}", base_url.trim_end_matches('/')))
             .bearer_auth(api_key)
-            .json(&json!({
-                "model": model,
-                "input": prompt,
-            }))
+            .json(&request)
             .send()
             .await?;
 
@@ -166,8 +355,8 @@ impl OpenAiLikeProvider {
             ));
         }
 
-        extract_responses_text(&body)
-            .ok_or_else(|| anyhow!("{} provider response missing output text", self.name))
+        extract(&body)
+            .ok_or_else(|| EmptyLlmResponse::error(&self.name, "response missing content"))
     }
 }
 
@@ -177,16 +366,24 @@ struct AnthropicProvider {
 }
 
 impl AnthropicProvider {
-    fn new(cfg: &ProviderConfig) -> Self {
+    fn new(cfg: &ProviderConfig, http: Client) -> Self {
         Self {
             cfg: cfg.clone(),
-            http: Client::new(),
+            http,
         }
     }
 }
 
 #[async_trait]
 impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        settings_key(self.name(), &self.cfg, false)
+    }
+
     async fn analyze(&self, ctx: &LlmContext) -> Result<LlmResponse> {
         if !self.cfg.enabled {
             return Err(anyhow!("provider disabled"));
@@ -216,14 +413,7 @@ impl LlmProvider for AnthropicProvider {
             .post(format!("{}/messages", base_url.trim_end_matches('/')))
             .header("x-api-key", api_key)
             .header("anthropic-version", "2023-06-01")
-            .json(&json!({
-                "model": model,
-                "max_tokens": 1024,
-                "temperature": 0.1,
-                "messages": [
-                    {"role": "user", "content": ctx.prompt}
-                ]
-            }))
+            .json(&anthropic_request_body(&model, &ctx.prompt, &self.cfg))
             .send()
             .await?;
 
@@ -235,22 +425,32 @@ impl LlmProvider for AnthropicProvider {
         }
 
         let body: serde_json::Value = response.json().await?;
-        let text = body
-            .get("content")
-            .and_then(|v| v.get(0))
-            .and_then(|v| v.get("text"))
-            .and_then(|v| v.as_str())
-            .unwrap_or_default()
-            .to_string();
-
-        if text.is_empty() {
-            return Err(anyhow!("anthropic provider response missing content"));
+        let stop_reason = body
+            .get("stop_reason")
+            .and_then(|value| value.as_str())
+            .unwrap_or("unknown");
+        if stop_reason == "max_tokens" {
+            tracing::warn!(
+                model = %model,
+                stop_reason,
+                "anthropic response truncated at max_tokens; output may be partial"
+            );
+        } else {
+            tracing::debug!(model = %model, stop_reason, "anthropic response stop reason");
         }
 
+        let text = extract_anthropic_text(&body).ok_or_else(|| {
+            EmptyLlmResponse::error(
+                "anthropic",
+                format!("response missing text content (stop_reason={stop_reason})"),
+            )
+        })?;
+
         Ok(LlmResponse {
             provider: "anthropic".to_string(),
             model,
             text,
+            cached: false,
         })
     }
 }
@@ -261,16 +461,24 @@ struct OllamaProvider {
 }
 
 impl OllamaProvider {
-    fn new(cfg: &ProviderConfig) -> Self {
+    fn new(cfg: &ProviderConfig, http: Client) -> Self {
         Self {
             cfg: cfg.clone(),
-            http: Client::new(),
+            http,
         }
     }
 }
 
 #[async_trait]
 impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        settings_key(self.name(), &self.cfg, false)
+    }
+
     async fn analyze(&self, ctx: &LlmContext) -> Result<LlmResponse> {
         if !self.cfg.enabled {
             return Err(anyhow!("provider disabled"));
@@ -297,14 +505,7 @@ impl LlmProvider for OllamaProvider {
         let mut request = self
             .http
             .post(format!("{}/api/generate", base_url.trim_end_matches('/')))
-            .json(&json!({
-                "model": model,
-                "prompt": ctx.prompt,
-                "stream": false,
-                "options": {
-                    "temperature": 0.1
-                }
-            }));
+            .json(&ollama_request_body(&model, &ctx.prompt, &self.cfg));
         if let Some(key) = api_key {
             request = request.bearer_auth(key);
         }
@@ -321,12 +522,13 @@ impl LlmProvider for OllamaProvider {
         }
 
         let text = extract_ollama_text(&body)
-            .ok_or_else(|| anyhow!("ollama provider response missing content"))?;
+            .ok_or_else(|| EmptyLlmResponse::error("ollama", "response missing content"))?;
 
         Ok(LlmResponse {
             provider: "ollama".to_string(),
             model,
             text,
+            cached: false,
         })
     }
 }
@@ -337,16 +539,24 @@ struct VeniceProvider {
 }
 
 impl VeniceProvider {
-    fn new(cfg: &ProviderConfig) -> Self {
+    fn new(cfg: &ProviderConfig, http: Client) -> Self {
         Self {
             cfg: cfg.clone(),
-            http: Client::new(),
+            http,
         }
     }
 }
 
 #[async_trait]
 impl LlmProvider for VeniceProvider {
+    fn name(&self) -> &str {
+        "venice"
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        settings_key(self.name(), &self.cfg, false)
+    }
+
     async fn analyze(&self, ctx: &LlmContext) -> Result<LlmResponse> {
         if !self.cfg.enabled {
             return Err(anyhow!("provider disabled"));
@@ -380,8 +590,9 @@ impl LlmProvider for VeniceProvider {
             .bearer_auth(api_key)
             .json(&json!({
                 "model": model,
-                "temperature": 0.1,
+                "temperature": self.cfg.temperature.unwrap_or(DEFAULT_TEMPERATURE),
                 "messages": [
+                    {"role": "system", "content": system_prompt(&self.cfg)},
                     {"role": "user", "content": ctx.prompt}
                 ]
             }))
@@ -399,16 +610,126 @@ impl LlmProvider for VeniceProvider {
         }
 
         let text = extract_chat_completion_text(&body)
-            .ok_or_else(|| anyhow!("venice provider response missing content"))?;
+            .ok_or_else(|| EmptyLlmResponse::error("venice", "response missing content"))?;
 
         Ok(LlmResponse {
             provider: "venice".to_string(),
             model,
             text,
+            cached: false,
         })
     }
 }
 
+fn system_prompt(cfg: &ProviderConfig) -> &str {
+    cfg.system_prompt
+        .as_deref()
+        .filter(|prompt| !prompt.trim().is_empty())
+        .unwrap_or(DEFAULT_SYSTEM_PROMPT)
+}
+
+fn responses_request_body(
+    model: &str,
+    prompt: &str,
+    cfg: &ProviderConfig,
+    structured_output: bool,
+) -> serde_json::Value {
+    let mut body = json!({
+        "model": model,
+        "input": prompt,
+        "instructions": system_prompt(cfg),
+    });
+    // Reasoning models reject sampling parameters, so these are only sent when configured.
+    if let Some(max_tokens) = cfg.max_tokens {
+        body["max_output_tokens"] = json!(max_tokens);
+    }
+    if let Some(temperature) = cfg.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if structured_output {
+        // The Responses API carries chat-completions' `response_format` under `text.format`.
+        body["instructions"] = json!(format!(
+            "{}\n\n{STRUCTURED_OUTPUT_INSTRUCTIONS}",
+            system_prompt(cfg)
+        ));
+        body["text"] = json!({ "format": { "type": "json_object" } });
+    }
+    body
+}
+
+fn chat_request_body(
+    model: &str,
+    prompt: &str,
+    cfg: &ProviderConfig,
+    structured_output: bool,
+) -> serde_json::Value {
+    let system = if structured_output {
+        format!("{}\n\n{STRUCTURED_OUTPUT_INSTRUCTIONS}", system_prompt(cfg))
+    } else {
+        system_prompt(cfg).to_string()
+    };
+    let mut body = json!({
+        "model": model,
+        "messages": [
+            {"role": "system", "content": system},
+            {"role": "user", "content": prompt}
+        ]
+    });
+    if let Some(max_tokens) = cfg.max_tokens {
+        body["max_tokens"] = json!(max_tokens);
+    }
+    if let Some(temperature) = cfg.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if structured_output {
+        body["response_format"] = json!({ "type": "json_object" });
+    }
+    body
+}
+
+// Legacy completions have no system role, so the system prompt is prepended to the prompt.
+fn completions_request_body(model: &str, prompt: &str, cfg: &ProviderConfig) -> serde_json::Value {
+    let mut body = json!({
+        "model": model,
+        "prompt": format!("{}\n\n{prompt}", system_prompt(cfg)),
+    });
+    if let Some(max_tokens) = cfg.max_tokens {
+        body["max_tokens"] = json!(max_tokens);
+    }
+    if let Some(temperature) = cfg.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    body
+}
+
+fn anthropic_request_body(model: &str, prompt: &str, cfg: &ProviderConfig) -> serde_json::Value {
+    json!({
+        "model": model,
+        "max_tokens": cfg.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+        "temperature": cfg.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+        "system": system_prompt(cfg),
+        "messages": [
+            {"role": "user", "content": prompt}
+        ]
+    })
+}
+
+fn ollama_request_body(model: &str, prompt: &str, cfg: &ProviderConfig) -> serde_json::Value {
+    let mut body = json!({
+        "model": model,
+        "prompt": prompt,
+        "system": system_prompt(cfg),
+        "stream": false,
+        "options": {
+            "temperature": cfg.temperature.unwrap_or(DEFAULT_TEMPERATURE)
+        }
+    });
+    if let Some(max_tokens) = cfg.max_tokens {
+        body["options"]["num_predict"] = json!(max_tokens);
+    }
+    body
+}
+
 pub fn redact_secrets(input: &str) -> String {
     let mut redacted = input.to_string();
     for regex in REDACTION_PATTERNS.iter() {
@@ -454,6 +775,22 @@ fn extract_responses_text(body: &serde_json::Value) -> Option<String> {
     text.filter(|value| !value.trim().is_empty())
 }
 
+fn extract_anthropic_text(body: &serde_json::Value) -> Option<String> {
+    let text = body
+        .get("content")
+        .and_then(|value| value.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|block| block.get("type").and_then(|value| value.as_str()) == Some("text"))
+                .filter_map(|block| block.get("text").and_then(|value| value.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+
+    text.filter(|value| !value.trim().is_empty())
+}
+
 fn extract_ollama_text(body: &serde_json::Value) -> Option<String> {
     if let Some(text) = body.get("response").and_then(|value| value.as_str())
         && !text.trim().is_empty()
@@ -496,11 +833,355 @@ fn extract_chat_completion_text(body: &serde_json::Value) -> Option<String> {
     text.filter(|value| !value.trim().is_empty())
 }
 
+fn extract_completion_text(body: &serde_json::Value) -> Option<String> {
+    let text = body
+        .get("choices")
+        .and_then(|value| value.as_array())
+        .and_then(|choices| {
+            choices.iter().find_map(|choice| {
+                choice
+                    .get("text")
+                    .and_then(|value| value.as_str())
+                    .map(ToString::to_string)
+            })
+        });
+
+    text.filter(|value| !value.trim().is_empty())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+    };
+
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
     use serde_json::json;
 
-    use super::{extract_chat_completion_text, extract_ollama_text, redact_secrets};
+    use super::{
+        CompositeLlm, DEFAULT_SYSTEM_PROMPT, EmptyLlmResponse, LlmContext, LlmProvider,
+        LlmResponse, OpenAiLikeProvider, anthropic_request_body, extract_anthropic_text,
+        extract_chat_completion_text, extract_ollama_text, redact_secrets, responses_request_body,
+        settings_key,
+    };
+    use crate::{
+        config::{ApiStyle, ProviderConfig},
+        llm_cache::LlmCache,
+    };
+
+    struct ScriptedProvider {
+        name: &'static str,
+        calls: Arc<AtomicUsize>,
+        empty_until: usize,
+        hard_error: bool,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn analyze(&self, _ctx: &LlmContext) -> Result<LlmResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.hard_error {
+                return Err(anyhow!("{} provider returned HTTP 500", self.name));
+            }
+            if call < self.empty_until {
+                return Err(EmptyLlmResponse::error(
+                    self.name,
+                    "response missing content",
+                ));
+            }
+            Ok(LlmResponse {
+                provider: self.name.to_string(),
+                model: "test".to_string(),
+                text: "{\"score\": 0.5}".to_string(),
+                cached: false,
+            })
+        }
+    }
+
+    fn scripted(
+        name: &'static str,
+        empty_until: usize,
+        hard_error: bool,
+    ) -> (Box<dyn LlmProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ScriptedProvider {
+            name,
+            calls: calls.clone(),
+            empty_until,
+            hard_error,
+        };
+        (Box::new(provider), calls)
+    }
+
+    fn provider(
+        max_tokens: Option<u32>,
+        system_prompt: Option<&str>,
+        temperature: Option<f32>,
+    ) -> ProviderConfig {
+        ProviderConfig {
+            enabled: true,
+            base_url: None,
+            api_key_env: None,
+            model: None,
+            max_tokens,
+            system_prompt: system_prompt.map(ToString::to_string),
+            temperature,
+            api_style: ApiStyle::default(),
+        }
+    }
+
+    // Answers a single request with `reply` and hands back the request path and JSON body.
+    fn serve_once(
+        reply: serde_json::Value,
+    ) -> (String, thread::JoinHandle<(String, serde_json::Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let base_url = format!("http://{}/v1", listener.local_addr().expect("local addr"));
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept");
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader
+                .read_line(&mut request_line)
+                .expect("read request line");
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("read header");
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().expect("content length");
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("read body");
+            let reply = reply.to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            )
+            .expect("write reply");
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            (path, serde_json::from_slice(&body).expect("json request"))
+        });
+        (base_url, handle)
+    }
+
+    fn openai_like(api_style: ApiStyle, base_url: String) -> OpenAiLikeProvider {
+        let cfg = ProviderConfig {
+            base_url: Some(base_url),
+            // Set by cargo for every test run, so no test has to write the process environment.
+            api_key_env: Some("CARGO_PKG_NAME".to_string()),
+            model: Some("test-model".to_string()),
+            api_style,
+            ..provider(Some(256), None, None)
+        };
+        OpenAiLikeProvider::new("openai", &cfg, reqwest::Client::new(), false)
+    }
+
+    #[tokio::test]
+    async fn each_api_style_uses_its_endpoint_and_response_shape() {
+        let cases = [
+            (
+                ApiStyle::Chat,
+                "/v1/chat/completions",
+                json!({"choices": [{"message": {"content": "{\"score\": 0.7}"}}]}),
+            ),
+            (
+                ApiStyle::Responses,
+                "/v1/responses",
+                json!({"output_text": "{\"score\": 0.7}"}),
+            ),
+            (
+                ApiStyle::Completions,
+                "/v1/completions",
+                json!({"choices": [{"text": "{\"score\": 0.7}"}]}),
+            ),
+        ];
+        for (style, expected_path, reply) in cases {
+            let (base_url, server) = serve_once(reply);
+            let response = openai_like(style, base_url)
+                .analyze(&ctx())
+                .await
+                .expect("analyze");
+            assert_eq!(response.text, "{\"score\": 0.7}");
+            assert_eq!(response.model, "test-model");
+
+            let (path, request) = server.join().expect("mock server");
+            assert_eq!(path, expected_path, "{style:?}");
+            match style {
+                ApiStyle::Chat => {
+                    assert_eq!(request["messages"][1]["content"], "prompt");
+                    assert_eq!(request["max_tokens"], 256);
+                }
+                ApiStyle::Responses => {
+                    assert_eq!(request["input"], "prompt");
+                    assert_eq!(request["max_output_tokens"], 256);
+                }
+                ApiStyle::Completions => {
+                    assert_eq!(
+                        request["prompt"],
+                        format!("{DEFAULT_SYSTEM_PROMPT}\n\nprompt")
+                    );
+                    assert_eq!(request["max_tokens"], 256);
+                }
+            }
+        }
+
+        for style in [ApiStyle::Chat, ApiStyle::Responses, ApiStyle::Completions] {
+            let (base_url, server) = serve_once(json!({"choices": [], "output": []}));
+            let err = openai_like(style, base_url)
+                .analyze(&ctx())
+                .await
+                .expect_err("malformed response");
+            server.join().expect("mock server");
+            assert!(err.downcast_ref::<EmptyLlmResponse>().is_some());
+            assert_eq!(err.to_string(), "openai provider response missing content");
+        }
+    }
+
+    fn ctx() -> LlmContext {
+        LlmContext {
+            prompt: "prompt".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_response_is_retried_on_same_provider() {
+        let (flaky, flaky_calls) = scripted("flaky", 1, false);
+        let (backup, backup_calls) = scripted("backup", 0, false);
+        let llm = CompositeLlm {
+            providers: vec![flaky, backup],
+            structured_output: false,
+            max_retries: 1,
+            cache: None,
+            max_description_chars: usize::MAX,
+            max_prompt_chars: usize::MAX,
+        };
+
+        let response = llm.analyze_best_effort(&ctx()).await.expect("response");
+        assert_eq!(response.provider, "flaky");
+        assert_eq!(flaky_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(backup_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn cached_response_skips_providers_for_identical_prompt() {
+        let dir = std::env::temp_dir().join(format!(
+            "gov-agent-llm-composite-cache-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (provider, calls) = scripted("primary", 0, false);
+        let llm = CompositeLlm {
+            providers: vec![provider],
+            structured_output: false,
+            max_retries: 0,
+            cache: Some(LlmCache::new(&dir, 3600)),
+            max_description_chars: usize::MAX,
+            max_prompt_chars: usize::MAX,
+        };
+
+        let first = llm.analyze_best_effort(&ctx()).await.expect("response");
+        assert!(!first.cached);
+        let second = llm
+            .analyze_best_effort(&ctx())
+            .await
+            .expect("cached response");
+        assert!(second.cached);
+        assert_eq!(second.text, first.text);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_and_hard_errors_fail_over() {
+        let (empty, empty_calls) = scripted("empty", usize::MAX, false);
+        let (broken, broken_calls) = scripted("broken", 0, true);
+        let (backup, _) = scripted("backup", 0, false);
+        let llm = CompositeLlm {
+            providers: vec![empty, broken, backup],
+            structured_output: false,
+            max_retries: 2,
+            cache: None,
+            max_description_chars: usize::MAX,
+            max_prompt_chars: usize::MAX,
+        };
+
+        let response = llm.analyze_best_effort(&ctx()).await.expect("response");
+        assert_eq!(response.provider, "backup");
+        assert_eq!(empty_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(broken_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cache_key_covers_every_setting_that_shapes_the_completion() {
+        let base = provider(Some(512), Some("review"), Some(0.2));
+        let key = settings_key("openai", &base, false).expect("enabled");
+        let variants = [
+            settings_key("anthropic", &base, false),
+            settings_key("openai", &base, true),
+            settings_key(
+                "openai",
+                &provider(Some(1024), Some("review"), Some(0.2)),
+                false,
+            ),
+            settings_key(
+                "openai",
+                &provider(Some(512), Some("other"), Some(0.2)),
+                false,
+            ),
+            settings_key(
+                "openai",
+                &provider(Some(512), Some("review"), Some(0.7)),
+                false,
+            ),
+            settings_key(
+                "openai",
+                &ProviderConfig {
+                    model: Some("gpt-5".to_string()),
+                    ..base.clone()
+                },
+                false,
+            ),
+            settings_key(
+                "openai",
+                &ProviderConfig {
+                    api_style: ApiStyle::Chat,
+                    ..base.clone()
+                },
+                false,
+            ),
+        ];
+        for variant in variants {
+            assert_ne!(variant.expect("enabled"), key);
+        }
+        let disabled = ProviderConfig {
+            enabled: false,
+            ..base
+        };
+        assert!(settings_key("openai", &disabled, false).is_none());
+    }
 
     #[test]
     fn redacts_common_secret_patterns() {
@@ -520,6 +1201,77 @@ mod tests {
         assert!(redacted.contains("ethereum_private_key=[REDACTED]"));
     }
 
+    #[test]
+    fn responses_request_requests_json_only_when_structured() {
+        let cfg = provider(None, None, None);
+        let plain = responses_request_body("gpt-5-nano", "prompt", &cfg, false);
+        assert!(plain.get("text").is_none());
+        assert_eq!(plain["instructions"], DEFAULT_SYSTEM_PROMPT);
+        assert!(plain.get("max_output_tokens").is_none());
+        assert!(plain.get("temperature").is_none());
+
+        let structured = responses_request_body("gpt-5-nano", "prompt", &cfg, true);
+        assert_eq!(structured["text"]["format"]["type"], "json_object");
+        assert!(structured["instructions"].as_str().is_some_and(|text| {
+            text.starts_with(DEFAULT_SYSTEM_PROMPT) && text.contains("risk_score")
+        }));
+    }
+
+    #[test]
+    fn responses_request_sends_configured_provider_settings() {
+        let cfg = provider(Some(2048), Some("Review dapp upgrades."), Some(0.3));
+        let body = responses_request_body("gpt-5-nano", "prompt", &cfg, false);
+        assert_eq!(body["instructions"], "Review dapp upgrades.");
+        assert_eq!(body["max_output_tokens"], 2048);
+        assert_eq!(body["temperature"].as_f64(), Some(f64::from(0.3f32)));
+        assert_eq!(body["input"], "prompt");
+    }
+
+    #[test]
+    fn anthropic_request_uses_system_prompt_and_configured_limits() {
+        let defaults =
+            anthropic_request_body("claude-haiku-4-5", "prompt", &provider(None, None, None));
+        assert_eq!(defaults["max_tokens"], 1024);
+        assert_eq!(defaults["temperature"].as_f64(), Some(f64::from(0.1f32)));
+        assert_eq!(defaults["system"], DEFAULT_SYSTEM_PROMPT);
+        assert_eq!(defaults["messages"][0]["role"], "user");
+        assert_eq!(defaults["messages"][0]["content"], "prompt");
+
+        let cfg = provider(Some(4096), Some("Review dapp upgrades."), Some(0.0));
+        let body = anthropic_request_body("claude-haiku-4-5", "prompt", &cfg);
+        assert_eq!(body["max_tokens"], 4096);
+        assert_eq!(body["temperature"].as_f64(), Some(0.0));
+        assert_eq!(body["system"], "Review dapp upgrades.");
+        assert_eq!(body["messages"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn extracts_anthropic_text_blocks_after_tool_use() {
+        let body = json!({
+            "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {}},
+                {"type": "text", "text": "first part"},
+                {"type": "text", "text": "second part"}
+            ],
+            "stop_reason": "end_turn"
+        });
+        assert_eq!(
+            extract_anthropic_text(&body).as_deref(),
+            Some("first part\nsecond part")
+        );
+    }
+
+    #[test]
+    fn anthropic_without_text_blocks_is_missing_content() {
+        let body = json!({
+            "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {}}
+            ],
+            "stop_reason": "tool_use"
+        });
+        assert!(extract_anthropic_text(&body).is_none());
+    }
+
     #[test]
     fn extracts_ollama_generate_text() {
         let body = json!({ "response": "hello from ollama" });
@@ -0,0 +1,124 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::llm::LlmResponse;
+
+const CACHE_DIR: &str = "llm_cache";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: DateTime<Utc>,
+    response: LlmResponse,
+}
+
+// Responses keyed by the hash of the exact prompt and the provider settings that shape the
+// completion, so a retry or re-review of an unchanged proposal does not pay for the same
+// completion twice, and a changed model, system prompt, or sampling setting asks again.
+pub struct LlmCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl LlmCache {
+    pub fn new(data_dir: &Path, ttl_secs: u64) -> Self {
+        Self {
+            dir: data_dir.join(CACHE_DIR),
+            ttl: i64::try_from(ttl_secs)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .unwrap_or(Duration::MAX),
+        }
+    }
+
+    pub fn get(&self, provider_key: &str, prompt: &str) -> Option<LlmResponse> {
+        self.get_at(provider_key, prompt, Utc::now())
+    }
+
+    fn get_at(&self, provider_key: &str, prompt: &str, now: DateTime<Utc>) -> Option<LlmResponse> {
+        let path = self.path(provider_key, prompt);
+        let raw = fs::read(&path).ok()?;
+        let entry = serde_json::from_slice::<CacheEntry>(&raw).ok()?;
+        if now.signed_duration_since(entry.stored_at) > self.ttl {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some(LlmResponse {
+            cached: true,
+            ..entry.response
+        })
+    }
+
+    pub fn put(&self, provider_key: &str, prompt: &str, response: &LlmResponse) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let entry = CacheEntry {
+            stored_at: Utc::now(),
+            response: LlmResponse {
+                cached: false,
+                ..response.clone()
+            },
+        };
+        let path = self.path(provider_key, prompt);
+        let tmp = path.with_extension(format!("json.tmp.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(&entry)?)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    fn path(&self, provider_key: &str, prompt: &str) -> PathBuf {
+        let key = [provider_key.as_bytes(), b"\0", prompt.as_bytes()].concat();
+        self.dir
+            .join(format!("{}.json", hex::encode(keccak256(&key))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{Duration, Utc};
+
+    use super::LlmCache;
+    use crate::llm::LlmResponse;
+
+    #[test]
+    fn cached_response_is_reused_until_it_expires() {
+        let dir = std::env::temp_dir().join(format!("gov-agent-llm-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = LlmCache::new(&dir, 60);
+        assert!(cache.get("openai", "prompt").is_none());
+
+        cache
+            .put(
+                "openai",
+                "prompt",
+                &LlmResponse {
+                    provider: "openai".to_string(),
+                    model: "gpt-5-nano".to_string(),
+                    text: "{\"score\": 0.8}".to_string(),
+                    cached: false,
+                },
+            )
+            .expect("store response");
+
+        let hit = cache.get("openai", "prompt").expect("cache hit");
+        assert!(hit.cached);
+        assert_eq!(hit.provider, "openai");
+        assert_eq!(hit.text, "{\"score\": 0.8}");
+        assert!(cache.get("openai", "other prompt").is_none());
+        assert!(cache.get("openai-at-temperature-1", "prompt").is_none());
+
+        let later = Utc::now() + Duration::seconds(61);
+        assert!(cache.get_at("openai", "prompt", later).is_none());
+        assert!(cache.get("openai", "prompt").is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
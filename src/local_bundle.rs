@@ -0,0 +1,227 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+
+use crate::ipfs::{BundleSource, Manifest, ManifestFile, safe_relative_path};
+
+// A working tree served as if it were a pinned bundle, for `lint`. The manifest is synthesized
+// from the files git would not ignore, so build output and dependencies listed in .gitignore do
+// not count against the bundle.
+#[derive(Debug)]
+pub struct LocalBundleSource {
+    root: PathBuf,
+    manifest: Manifest,
+}
+
+impl LocalBundleSource {
+    pub fn new(root: &Path) -> Result<Self> {
+        let metadata = fs::metadata(root)
+            .with_context(|| format!("failed to read bundle directory {}", root.display()))?;
+        if !metadata.is_dir() {
+            bail!("{} is not a directory", root.display());
+        }
+
+        let files = list_files(root)?;
+        let manifest = Manifest {
+            name: root.canonicalize().ok().and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            }),
+            version: None,
+            description: None,
+            entry: files
+                .iter()
+                .any(|file| file.path == "index.html")
+                .then(|| "index.html".to_string()),
+            files: Some(files),
+        };
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+}
+
+// The root CID is ignored: a local source serves exactly one tree.
+#[async_trait]
+impl BundleSource for LocalBundleSource {
+    async fn fetch_manifest(&self, _root_cid: &str) -> Result<Manifest> {
+        Ok(self.manifest.clone())
+    }
+
+    async fn fetch_file(
+        &self,
+        _root_cid: &str,
+        path: &str,
+        max_bytes: usize,
+    ) -> Result<Option<Vec<u8>>> {
+        // Only manifest entries are served, so ignored files stay invisible to the checks.
+        let listed = self
+            .manifest
+            .files
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .any(|file| file.path == path);
+        let Some(relative) = safe_relative_path(path).filter(|_| listed) else {
+            return Ok(None);
+        };
+        let full = self.root.join(relative);
+        let len = fs::metadata(&full)
+            .with_context(|| format!("failed to stat {}", full.display()))?
+            .len();
+        if len > max_bytes as u64 {
+            return Ok(None);
+        }
+        fs::read(&full)
+            .map(Some)
+            .with_context(|| format!("failed to read {}", full.display()))
+    }
+}
+
+// Only the tree's own .gitignore files count, whether or not it is a git checkout, so the listing
+// does not depend on the machine running `lint`. Entries are visited in name order, and an ignored
+// directory is skipped entirely, as git does.
+fn list_files(root: &Path) -> Result<Vec<ManifestFile>> {
+    let walker = WalkBuilder::new(root)
+        .standard_filters(false)
+        .git_ignore(true)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = entry.with_context(|| format!("failed to list {}", root.display()))?;
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        let path = relative
+            .iter()
+            .map(|part| part.to_str())
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| format!("{} is not a valid UTF-8 path", entry.path().display()))?
+            .join("/");
+        let bytes = entry
+            .metadata()
+            .with_context(|| format!("failed to stat {}", entry.path().display()))?
+            .len();
+        files.push(ManifestFile { path, bytes });
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::LocalBundleSource;
+    use crate::ipfs::BundleSource;
+
+    #[tokio::test]
+    async fn local_source_lists_and_serves_only_unignored_files() {
+        let dir = std::env::temp_dir().join(format!("gov-agent-lint-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("node_modules/pkg")).expect("create node_modules");
+        fs::create_dir_all(dir.join("src")).expect("create src");
+        fs::create_dir_all(dir.join(".git")).expect("create .git");
+        fs::write(dir.join(".gitignore"), "node_modules/\n").expect("write gitignore");
+        fs::write(dir.join("src/.gitignore"), "*.tmp\n").expect("write nested gitignore");
+        fs::write(dir.join("index.html"), "<html></html>").expect("write index");
+        fs::write(dir.join("src/app.js"), "console.log(1)").expect("write app");
+        fs::write(dir.join("src/scratch.tmp"), "x").expect("write tmp");
+        fs::write(dir.join("node_modules/pkg/index.js"), "x").expect("write dep");
+        fs::write(dir.join(".git/HEAD"), "ref").expect("write HEAD");
+
+        let source = LocalBundleSource::new(&dir).expect("local source");
+        let manifest = source.fetch_manifest("").await.expect("manifest");
+        let paths = manifest
+            .files
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|file| (file.path.as_str(), file.bytes))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                (".gitignore", 14),
+                ("index.html", 13),
+                ("src/.gitignore", 6),
+                ("src/app.js", 14),
+            ]
+        );
+        assert_eq!(manifest.entry.as_deref(), Some("index.html"));
+
+        let served = source
+            .fetch_file("", "src/app.js", 1024)
+            .await
+            .expect("app");
+        assert_eq!(served.as_deref(), Some(&b"console.log(1)"[..]));
+        assert_eq!(
+            source.fetch_file("", "src/app.js", 4).await.expect("cap"),
+            None
+        );
+        assert_eq!(
+            source
+                .fetch_file("", "src/scratch.tmp", 1024)
+                .await
+                .expect("ignored"),
+            None
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn gitignore_rules_follow_git_precedence() {
+        let dir = std::env::temp_dir().join(format!("gov-agent-lint-rules-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for sub in ["node_modules", "dist", "app/dist", "src/a/b", "logs"] {
+            fs::create_dir_all(dir.join(sub)).expect("create dir");
+        }
+        fs::write(
+            dir.join(".gitignore"),
+            "# build output\nnode_modules/\n*.log\n!keep.log\n/dist\nsrc/**/*.map\nlogs/\n!logs/keep.log\n",
+        )
+        .expect("write gitignore");
+        for file in [
+            "node_modules/dep.js",
+            "debug.log",
+            "keep.log",
+            "app/debug.log",
+            "dist/out.js",
+            "app/dist/out.js",
+            "src/app.js.map",
+            "src/a/b/app.js.map",
+            "app.js.map",
+            "logs/keep.log",
+        ] {
+            fs::write(dir.join(file), "x").expect("write file");
+        }
+
+        let source = LocalBundleSource::new(&dir).expect("local source");
+        let manifest = source.fetch_manifest("").await.expect("manifest");
+        let paths = manifest
+            .files
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+        // A negation cannot re-include a file whose parent directory is excluded.
+        assert_eq!(
+            paths,
+            vec![".gitignore", "app/dist/out.js", "app.js.map", "keep.log"]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,11 @@
-#[tokio::main]
-async fn main() {
-    if let Err(err) = gov_agent::app::run().await {
+fn main() {
+    let result = gov_agent::app::prepare().and_then(|startup| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?
+            .block_on(gov_agent::app::run(startup))
+    });
+    if let Err(err) = result {
         eprintln!("error: {err:#}");
         std::process::exit(1);
     }
@@ -1,48 +1,772 @@
-use std::env;
+use std::{collections::BTreeMap, env, fmt::Write as _, fs, path::Path, sync::OnceLock};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use reqwest::Client;
+use serde::Serialize;
 
-use crate::config::NotificationConfig;
+use crate::{
+    chain::parse_proposal_id,
+    config::{AppConfig, GithubConfig, HttpConfig, NotificationConfig},
+    http::{self, HttpService},
+    observability,
+    peers::disagreement_summary,
+    signer::{ChainClock, ClockMode},
+    template::{self, Vars, flag},
+    types::{
+        CursorRewind, Decision, DecodedAction, Finding, FindingCode, ProcessedProposal, Proposal,
+        ReviewResult, Severity, VoteChoice,
+    },
+};
+
+const TOP_FINDINGS: usize = 3;
+const MAX_FINDING_CHARS: usize = 160;
+const DEFAULT_TEMPLATES: &str = include_str!("../config/notifications.en.toml");
 
 #[async_trait]
 pub trait Notifier: Send + Sync {
     fn name(&self) -> &'static str;
     async fn notify(&self, message: &str) -> Result<()>;
+
+    // Notifiers that pick their destination per proposal override this.
+    async fn notify_review(&self, _processed: &ProcessedProposal, message: &str) -> Result<()> {
+        self.notify(message).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    ReviewCompleted,
+    DeadlineApproaching,
+    ProposalExecuted,
+    ProposalAnnotated,
+    ProposalReevaluated,
+    CatchUpProgress,
+    CatchUpDigest,
+    VotingPaused,
+    VotingResumed,
+    CanaryEligible,
+    CanaryPromoted,
+    CursorRewound,
+}
+
+impl NotificationEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationEvent::ReviewCompleted => "review_completed",
+            NotificationEvent::DeadlineApproaching => "deadline_approaching",
+            NotificationEvent::ProposalExecuted => "proposal_executed",
+            NotificationEvent::ProposalAnnotated => "proposal_annotated",
+            NotificationEvent::ProposalReevaluated => "proposal_reevaluated",
+            NotificationEvent::CatchUpProgress => "catch_up_progress",
+            NotificationEvent::CatchUpDigest => "catch_up_digest",
+            NotificationEvent::VotingPaused => "voting_paused",
+            NotificationEvent::VotingResumed => "voting_resumed",
+            NotificationEvent::CanaryEligible => "canary_eligible",
+            NotificationEvent::CanaryPromoted => "canary_promoted",
+            NotificationEvent::CursorRewound => "cursor_rewound",
+        }
+    }
+
+    // Sent through `notify_review`, so per-proposal notifiers such as GitHub pick them up.
+    fn per_proposal(self) -> bool {
+        matches!(
+            self,
+            NotificationEvent::ReviewCompleted
+                | NotificationEvent::ProposalExecuted
+                | NotificationEvent::ProposalAnnotated
+        )
+    }
+}
+
+// A notifier's `template_file` overrides the bundled English templates event by event. A custom
+// template that fails to render is logged and replaced by the default, so the event still goes out.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationTemplates {
+    custom: BTreeMap<String, String>,
+}
+
+impl NotificationTemplates {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read notification templates {}", path.display()))?;
+        let custom = parse_templates(&raw)
+            .with_context(|| format!("invalid notification templates {}", path.display()))?;
+        Ok(Self { custom })
+    }
+
+    pub fn from_config(path: Option<&Path>) -> Result<Self> {
+        Ok(path.map(Self::load).transpose()?.unwrap_or_default())
+    }
+
+    pub fn render(&self, event: NotificationEvent, vars: &Vars) -> String {
+        if let Some(custom) = self.custom.get(event.as_str()) {
+            match template::render(custom, vars) {
+                Ok(message) => return message,
+                Err(err) => tracing::warn!(
+                    target = "notifier",
+                    event = event.as_str(),
+                    error = %err,
+                    "notification template failed to render; using the default"
+                ),
+            }
+        }
+        let default = default_templates()
+            .get(event.as_str())
+            .map(String::as_str)
+            .unwrap_or_default();
+        template::render(default, vars).unwrap_or_else(|err| {
+            tracing::warn!(
+                target = "notifier",
+                event = event.as_str(),
+                error = %err,
+                "default notification template failed to render"
+            );
+            format!("gov-agent {}", event.as_str())
+        })
+    }
+}
+
+fn default_templates() -> &'static BTreeMap<String, String> {
+    static DEFAULTS: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+    DEFAULTS.get_or_init(|| parse_templates(DEFAULT_TEMPLATES).unwrap_or_default())
+}
+
+fn parse_templates(raw: &str) -> Result<BTreeMap<String, String>> {
+    let table: toml::Table = toml::from_str(raw)?;
+    table
+        .into_iter()
+        .map(|(name, value)| {
+            if NotificationEvent::value_variants()
+                .iter()
+                .all(|event| event.as_str() != name)
+            {
+                let events = NotificationEvent::value_variants()
+                    .iter()
+                    .map(|event| event.as_str())
+                    .collect::<Vec<_>>();
+                bail!(
+                    "unknown notification event {name:?}; expected one of {}",
+                    events.join(", ")
+                );
+            }
+            let toml::Value::String(template) = value else {
+                bail!("template {name} must be a string");
+            };
+            template::check(&template).with_context(|| format!("invalid template {name}"))?;
+            Ok((name, template))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NotificationLinks {
+    pub gateway_url: String,
+    pub explorer_url_base: Option<String>,
+}
+
+impl NotificationLinks {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            gateway_url: config.ipfs.gateway_url.trim_end_matches('/').to_string(),
+            explorer_url_base: config
+                .network
+                .explorer_url_base
+                .as_deref()
+                .map(|base| base.trim_end_matches('/').to_string())
+                .filter(|base| !base.is_empty()),
+        }
+    }
+
+    fn tx_url(&self, tx_hash: Option<&str>) -> String {
+        match (&self.explorer_url_base, tx_hash) {
+            (Some(base), Some(tx_hash)) => format!("{base}/tx/{tx_hash}"),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestDelivery {
+    pub notifier: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 pub struct MultiNotifier {
-    notifiers: Vec<Box<dyn Notifier>>,
+    notifiers: Vec<(Box<dyn Notifier>, NotificationTemplates)>,
+    links: NotificationLinks,
 }
 
 impl MultiNotifier {
-    pub fn from_config(config: &NotificationConfig) -> Self {
-        let mut notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(LogNotifier {})];
+    pub fn from_config(
+        config: &NotificationConfig,
+        http_cfg: &HttpConfig,
+        links: NotificationLinks,
+    ) -> Result<Self> {
+        let mut notifiers: Vec<(Box<dyn Notifier>, NotificationTemplates)> =
+            vec![(Box::new(LogNotifier {}), NotificationTemplates::default())];
+        let client = || {
+            http::build_client(
+                http_cfg,
+                HttpService {
+                    name: "notifications",
+                    proxy_url: config.proxy_url.as_deref(),
+                    danger_accept_invalid_certs: config.danger_accept_invalid_certs,
+                },
+                None,
+            )
+        };
 
         if config.telegram.enabled {
-            notifiers.push(Box::new(TelegramNotifier {
-                bot_token_env: config.telegram.bot_token_env.clone(),
-                chat_id: config.telegram.chat_id.clone(),
-                client: Client::new(),
-            }));
+            notifiers.push((
+                Box::new(TelegramNotifier {
+                    bot_token_env: config.telegram.bot_token_env.clone(),
+                    chat_id: config.telegram.chat_id.clone(),
+                    client: client()?,
+                }),
+                NotificationTemplates::from_config(config.telegram.template_file.as_deref())?,
+            ));
+        }
+        if config.github.enabled {
+            notifiers.push((
+                Box::new(GithubNotifier::new(&config.github, client()?)?),
+                NotificationTemplates::from_config(config.github.template_file.as_deref())?,
+            ));
         }
 
-        Self { notifiers }
+        Ok(Self { notifiers, links })
     }
 
-    pub async fn notify_all(&self, message: &str) {
-        for notifier in &self.notifiers {
-            if let Err(err) = notifier.notify(message).await {
-                tracing::warn!(
-                    target = "notifier",
-                    notifier = notifier.name(),
-                    error = %err,
-                    "notification attempt failed"
-                );
+    pub async fn review_completed(&self, processed: &ProcessedProposal, clock: ChainClock) {
+        let vars = review_completed_vars(processed, clock, &self.links);
+        self.send(NotificationEvent::ReviewCompleted, &vars, Some(processed))
+            .await;
+    }
+
+    pub async fn deadline_approaching(&self, proposal: &Proposal, reason: &str, clock: ChainClock) {
+        let vars = deadline_alert_vars(proposal, reason, clock, &self.links);
+        self.send(NotificationEvent::DeadlineApproaching, &vars, None)
+            .await;
+    }
+
+    pub async fn proposal_executed(&self, processed: &ProcessedProposal) {
+        let vars = proposal_executed_vars(processed, &self.links);
+        self.send(NotificationEvent::ProposalExecuted, &vars, Some(processed))
+            .await;
+    }
+
+    pub async fn proposal_annotated(
+        &self,
+        processed: &ProcessedProposal,
+        finding: &Finding,
+        previous_vote: VoteChoice,
+    ) {
+        let vars = proposal_annotated_vars(processed, finding, previous_vote);
+        self.send(NotificationEvent::ProposalAnnotated, &vars, Some(processed))
+            .await;
+    }
+
+    pub async fn proposal_reevaluated(&self, processed: &ProcessedProposal) {
+        let vars = proposal_reevaluated_vars(processed);
+        self.send(NotificationEvent::ProposalReevaluated, &vars, None)
+            .await;
+    }
+
+    pub async fn catch_up_progress(&self, percent: u64, discovered: usize) {
+        let vars = catch_up_progress_vars(percent, discovered);
+        self.send(NotificationEvent::CatchUpProgress, &vars, None)
+            .await;
+    }
+
+    pub async fn catch_up_digest(&self, processed: &[ProcessedProposal], from: u64, to: u64) {
+        let vars = catch_up_digest_vars(processed, from, to, &self.links);
+        self.send(NotificationEvent::CatchUpDigest, &vars, None)
+            .await;
+    }
+
+    pub async fn voting_paused(&self, paused: bool) {
+        let event = if paused {
+            NotificationEvent::VotingPaused
+        } else {
+            NotificationEvent::VotingResumed
+        };
+        self.send(event, &Vars::new(), None).await;
+    }
+
+    pub async fn canary_eligible(&self, streak: u32) {
+        self.send(
+            NotificationEvent::CanaryEligible,
+            &streak_vars(streak),
+            None,
+        )
+        .await;
+    }
+
+    pub async fn canary_promoted(&self, streak: u32) {
+        self.send(
+            NotificationEvent::CanaryPromoted,
+            &streak_vars(streak),
+            None,
+        )
+        .await;
+    }
+
+    pub async fn cursor_rewound(&self, rewind: &CursorRewind) {
+        self.send(
+            NotificationEvent::CursorRewound,
+            &cursor_rewound_vars(rewind),
+            None,
+        )
+        .await;
+    }
+
+    async fn send(
+        &self,
+        event: NotificationEvent,
+        vars: &Vars,
+        processed: Option<&ProcessedProposal>,
+    ) {
+        for (notifier, templates) in &self.notifiers {
+            let message = templates.render(event, vars);
+            let sent = match processed {
+                Some(processed) => notifier.notify_review(processed, &message).await,
+                None => notifier.notify(&message).await,
+            };
+            if let Err(err) = sent {
+                warn_failed(notifier.as_ref(), &err);
             }
         }
     }
+
+    // Renders a sample event with each notifier's templates and sends it, reporting every
+    // notifier's result instead of only logging failures.
+    pub async fn send_test(&self, event: NotificationEvent) -> Vec<TestDelivery> {
+        let processed = sample_processed();
+        let current_block = processed.proposal.vote_end - 300;
+        let clock = ChainClock::blocks(current_block);
+        let annotation = Finding {
+            code: FindingCode::OperatorFinding,
+            severity: Severity::Warning,
+            message: "sample operator finding".to_string(),
+            operator: None,
+        };
+        let vars = match event {
+            NotificationEvent::ReviewCompleted => {
+                review_completed_vars(&processed, clock, &self.links)
+            }
+            NotificationEvent::DeadlineApproaching => deadline_alert_vars(
+                &processed.proposal,
+                "decision requires human override",
+                clock,
+                &self.links,
+            ),
+            NotificationEvent::ProposalExecuted => proposal_executed_vars(&processed, &self.links),
+            NotificationEvent::ProposalAnnotated => {
+                proposal_annotated_vars(&processed, &annotation, VoteChoice::For)
+            }
+            NotificationEvent::ProposalReevaluated => proposal_reevaluated_vars(&processed),
+            NotificationEvent::CatchUpProgress => catch_up_progress_vars(50, 1),
+            NotificationEvent::CatchUpDigest => catch_up_digest_vars(
+                std::slice::from_ref(&processed),
+                processed.proposal.block_number,
+                current_block,
+                &self.links,
+            ),
+            NotificationEvent::VotingPaused | NotificationEvent::VotingResumed => Vars::new(),
+            NotificationEvent::CanaryEligible | NotificationEvent::CanaryPromoted => streak_vars(5),
+            NotificationEvent::CursorRewound => cursor_rewound_vars(&CursorRewind {
+                previous_block: current_block + 1_000_000,
+                rewound_to: processed.proposal.block_number,
+                latest_block: current_block,
+                check_block: None,
+                anomaly_cycles: 3,
+                stored_proposals: 1,
+                rewound_at: Utc::now(),
+            }),
+        };
+
+        let mut deliveries = Vec::new();
+        for (notifier, templates) in &self.notifiers {
+            let message = templates.render(event, &vars);
+            let sent = if event.per_proposal() {
+                notifier.notify_review(&processed, &message).await
+            } else {
+                notifier.notify(&message).await
+            };
+            deliveries.push(TestDelivery {
+                notifier: notifier.name(),
+                message,
+                error: sent.err().map(|err| format!("{err:#}")),
+            });
+        }
+        deliveries
+    }
+}
+
+fn warn_failed(notifier: &dyn Notifier, err: &anyhow::Error) {
+    tracing::warn!(
+        target = "notifier",
+        notifier = notifier.name(),
+        error = %err,
+        "notification attempt failed"
+    );
+}
+
+// The variables each event's template can use; config/notifications.en.toml lists them too.
+pub fn review_completed_vars(
+    processed: &ProcessedProposal,
+    clock: ChainClock,
+    links: &NotificationLinks,
+) -> Vars {
+    let proposal = &processed.proposal;
+    let mut findings = processed.review.findings.iter().collect::<Vec<_>>();
+    findings.sort_by_key(|finding| std::cmp::Reverse(severity_rank(finding.severity)));
+    let more_findings = findings.len().saturating_sub(TOP_FINDINGS);
+
+    let mut cids = proposal
+        .actions
+        .iter()
+        .filter_map(|action| action.root_cid())
+        .collect::<Vec<_>>();
+    cids.dedup();
+
+    let mut vars = Vars::from([
+        ("proposal_id", proposal.proposal_id.clone().into()),
+        ("vote", format!("{:?}", processed.decision.vote).into()),
+        ("paused", flag(processed.voting_paused)),
+        (
+            "peer_disagreement",
+            processed
+                .peer_check
+                .as_ref()
+                .filter(|check| check.held)
+                .map(|check| disagreement_summary(check, processed.decision.vote))
+                .unwrap_or_default()
+                .into(),
+        ),
+        (
+            "proposer",
+            observability::log_address(&proposal.proposer).into(),
+        ),
+        (
+            "created",
+            proposal
+                .creation_context
+                .as_ref()
+                .and_then(|context| context.block_timestamp)
+                .map(|created| created.to_rfc3339())
+                .unwrap_or_default()
+                .into(),
+        ),
+        ("score", format!("{:.2}", processed.review.score).into()),
+        ("has_findings", flag(!findings.is_empty())),
+        (
+            "findings",
+            findings
+                .iter()
+                .take(TOP_FINDINGS)
+                .map(|finding| finding_vars(finding))
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        (
+            "more_findings",
+            if more_findings > 0 {
+                more_findings.to_string().into()
+            } else {
+                "".into()
+            },
+        ),
+        (
+            "bundle_urls",
+            cids.into_iter()
+                .map(|cid| {
+                    Vars::from([("url", format!("{}/ipfs/{cid}", links.gateway_url).into())])
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        ("tx_url", links.tx_url(proposal.tx_hash.as_deref()).into()),
+    ]);
+    insert_time_remaining(&mut vars, proposal.vote_end, clock);
+    vars
+}
+
+pub fn deadline_alert_vars(
+    proposal: &Proposal,
+    reason: &str,
+    clock: ChainClock,
+    links: &NotificationLinks,
+) -> Vars {
+    let mut vars = Vars::from([
+        ("proposal_id", proposal.proposal_id.clone().into()),
+        ("reason", reason.into()),
+        ("tx_url", links.tx_url(proposal.tx_hash.as_deref()).into()),
+    ]);
+    insert_time_remaining(&mut vars, proposal.vote_end, clock);
+    vars
+}
+
+// One line per proposal in place of the per-proposal messages suppressed during catch-up.
+pub fn catch_up_digest_vars(
+    processed: &[ProcessedProposal],
+    from: u64,
+    to: u64,
+    links: &NotificationLinks,
+) -> Vars {
+    let proposals = processed
+        .iter()
+        .map(|item| {
+            Vars::from([
+                ("proposal_id", item.proposal.proposal_id.clone().into()),
+                ("vote", format!("{:?}", item.decision.vote).into()),
+                ("score", format!("{:.2}", item.review.score).into()),
+                ("missed", flag(item.vote_missed)),
+                ("paused", flag(!item.vote_missed && item.voting_paused)),
+                (
+                    "needs_human",
+                    flag(
+                        !item.vote_missed
+                            && !item.voting_paused
+                            && item.decision.requires_human_override,
+                    ),
+                ),
+                (
+                    "tx_url",
+                    links.tx_url(item.proposal.tx_hash.as_deref()).into(),
+                ),
+            ])
+        })
+        .collect::<Vec<_>>();
+    Vars::from([
+        ("from", from.to_string().into()),
+        ("to", to.to_string().into()),
+        ("count", processed.len().to_string().into()),
+        ("proposals", proposals.into()),
+    ])
+}
+
+pub fn proposal_executed_vars(processed: &ProcessedProposal, links: &NotificationLinks) -> Vars {
+    let eta = processed
+        .lifecycle
+        .as_ref()
+        .and_then(|lifecycle| lifecycle.eta)
+        .and_then(|eta| DateTime::<Utc>::from_timestamp(i64::try_from(eta).ok()?, 0))
+        .map(|eta| eta.to_rfc3339())
+        .unwrap_or_default();
+    let vote_txs = processed
+        .vote_executions
+        .iter()
+        .filter(|execution| execution.submitted)
+        .filter_map(|execution| execution.tx_hash.as_deref())
+        .map(|tx_hash| {
+            let tx = match &links.explorer_url_base {
+                Some(base) => format!("{base}/tx/{tx_hash}"),
+                None => tx_hash.to_string(),
+            };
+            Vars::from([("tx", tx.into())])
+        })
+        .collect::<Vec<_>>();
+    Vars::from([
+        ("proposal_id", processed.proposal.proposal_id.clone().into()),
+        ("eta", eta.into()),
+        ("vote_txs", vote_txs.into()),
+    ])
+}
+
+pub fn proposal_annotated_vars(
+    processed: &ProcessedProposal,
+    finding: &Finding,
+    previous_vote: VoteChoice,
+) -> Vars {
+    let mut vars = finding_vars(finding);
+    vars.extend([
+        ("proposal_id", processed.proposal.proposal_id.clone().into()),
+        ("changed", flag(processed.decision.vote != previous_vote)),
+        ("previous_vote", format!("{previous_vote:?}").into()),
+        ("vote", format!("{:?}", processed.decision.vote).into()),
+    ]);
+    vars
+}
+
+fn proposal_reevaluated_vars(processed: &ProcessedProposal) -> Vars {
+    Vars::from([
+        ("proposal_id", processed.proposal.proposal_id.clone().into()),
+        ("vote", format!("{:?}", processed.decision.vote).into()),
+    ])
+}
+
+fn catch_up_progress_vars(percent: u64, discovered: usize) -> Vars {
+    Vars::from([
+        ("percent", percent.to_string().into()),
+        ("discovered", discovered.to_string().into()),
+    ])
+}
+
+fn cursor_rewound_vars(rewind: &CursorRewind) -> Vars {
+    Vars::from([
+        ("previous_block", rewind.previous_block.to_string().into()),
+        (
+            "rewound_to",
+            match rewind.rewound_to {
+                0 => "network.from_block".to_string(),
+                block => block.to_string(),
+            }
+            .into(),
+        ),
+        ("latest_block", rewind.latest_block.to_string().into()),
+        (
+            "check_block",
+            rewind
+                .check_block
+                .map(|block| block.to_string())
+                .unwrap_or_default()
+                .into(),
+        ),
+        ("cycles", rewind.anomaly_cycles.to_string().into()),
+        (
+            "stored_proposals",
+            rewind.stored_proposals.to_string().into(),
+        ),
+    ])
+}
+
+fn streak_vars(streak: u32) -> Vars {
+    Vars::from([("streak", streak.to_string().into())])
+}
+
+// On a timestamp clock vote_end is a unix time, so it is shown as a date and there is no block
+// count.
+fn insert_time_remaining(vars: &mut Vars, vote_end: u64, clock: ChainClock) {
+    let left = clock.left(vote_end);
+    let minutes = clock.secs_left(vote_end) / 60;
+    let (vote_end_label, blocks_left) = match clock.mode {
+        ClockMode::BlockNumber => (format!("block {vote_end}"), left.to_string()),
+        ClockMode::Timestamp => (
+            i64::try_from(vote_end)
+                .ok()
+                .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+                .map_or_else(|| vote_end.to_string(), |at| at.to_rfc3339()),
+            String::new(),
+        ),
+    };
+    vars.extend([
+        ("vote_end", vote_end.to_string().into()),
+        ("vote_end_label", vote_end_label.into()),
+        ("voting_ended", flag(left == 0)),
+        ("blocks_left", blocks_left.into()),
+        ("hours_left", (minutes / 60).to_string().into()),
+        ("minutes_left", format!("{:02}", minutes % 60).into()),
+    ]);
+}
+
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Critical => 2,
+        Severity::Warning => 1,
+        Severity::Info => 0,
+    }
+}
+
+fn finding_vars(finding: &Finding) -> Vars {
+    let mut message = finding
+        .message
+        .chars()
+        .take(MAX_FINDING_CHARS)
+        .collect::<String>();
+    if finding.message.chars().count() > MAX_FINDING_CHARS {
+        message.push('…');
+    }
+    Vars::from([
+        ("severity", finding.severity.as_str().into()),
+        (
+            "operator",
+            finding
+                .operator
+                .as_ref()
+                .map(|operator| operator.author.clone())
+                .unwrap_or_default()
+                .into(),
+        ),
+        ("message", message.into()),
+    ])
+}
+
+// What `notify test` sends: a review with one finding of each severity, a bundle, and a vote tx.
+fn sample_processed() -> ProcessedProposal {
+    let now = Utc::now();
+    let finding = |severity: Severity, message: &str| Finding {
+        code: FindingCode::Unclassified,
+        severity,
+        message: message.to_string(),
+        operator: None,
+    };
+    ProcessedProposal {
+        proposal: Proposal {
+            proposal_id: "0".to_string(),
+            proposer: "0x0000000000000000000000000000000000000001".to_string(),
+            description: "gov-agent notification test".to_string(),
+            vote_start: 1_000,
+            vote_end: 2_000,
+            block_number: 990,
+            tx_hash: Some(format!("0x{}", "ab".repeat(32))),
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::UpgradeDapp {
+                dapp_id: "1".to_string(),
+                root_cid: "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+                name: "Sample".to_string(),
+                version: "1.0.0".to_string(),
+                description: "sample dapp".to_string(),
+            }],
+            discovered_at: now,
+            creation_context: None,
+        },
+        review: ReviewResult {
+            proposal_id: "0".to_string(),
+            root_cid: None,
+            findings: vec![
+                finding(Severity::Critical, "sample critical finding"),
+                finding(Severity::Warning, "sample warning finding"),
+                finding(Severity::Info, "sample info finding"),
+            ],
+            deterministic_score: None,
+            deterministic_weight: None,
+            llm_weight: None,
+            llm_score: None,
+            llm_audit: None,
+            llm_audit_ref: None,
+            score: 0.35,
+            suppressions: vec![],
+            transient_failure: None,
+            reputation: None,
+            score_cap: None,
+            actions: vec![],
+            reviewed_at: now,
+        },
+        decision: Decision {
+            proposal_id: "0".to_string(),
+            vote: VoteChoice::Against,
+            reasons: vec!["sample decision reason".to_string()],
+            blocking_findings: vec![],
+            requires_human_override: false,
+            decided_at: now,
+            config_hash: None,
+            allocation: None,
+        },
+        vote_executions: Vec::new(),
+        decision_history: Vec::new(),
+        voting_paused: false,
+        lifecycle: None,
+        vote_missed: false,
+        peer_check: None,
+    }
 }
 
 pub struct LogNotifier {}
@@ -102,3 +826,374 @@ impl Notifier for TelegramNotifier {
         Ok(())
     }
 }
+
+pub struct GithubNotifier {
+    repo: String,
+    token_env: Option<String>,
+    api_url: String,
+    issues: BTreeMap<String, u64>,
+    client: Client,
+}
+
+impl GithubNotifier {
+    fn new(config: &GithubConfig, client: Client) -> Result<Self> {
+        let repo = config
+            .repo
+            .as_deref()
+            .map(|repo| repo.trim().trim_matches('/'))
+            .filter(|repo| repo.split('/').count() == 2)
+            .ok_or_else(|| anyhow!("notifications.github.repo must be set as \"owner/name\""))?;
+        Ok(Self {
+            repo: repo.to_string(),
+            token_env: config.token_env.clone(),
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            issues: config.issues.clone(),
+            client,
+        })
+    }
+
+    fn issue_for(&self, proposal: &Proposal) -> Option<u64> {
+        let requested = parse_proposal_id(&proposal.proposal_id).ok();
+        self.issues
+            .iter()
+            .find(|(id, _)| {
+                *id == &proposal.proposal_id
+                    || (requested.is_some() && parse_proposal_id(id).ok() == requested)
+            })
+            .map(|(_, issue)| *issue)
+            .or_else(|| linked_issue(&proposal.description, &self.repo))
+    }
+}
+
+// First `github.com/<repo>/issues/<n>` or `/pull/<n>` link in the text.
+fn linked_issue(text: &str, repo: &str) -> Option<u64> {
+    let lower = text.to_ascii_lowercase();
+    let prefix = format!("github.com/{}/", repo.to_ascii_lowercase());
+    lower.match_indices(&prefix).find_map(|(start, _)| {
+        let rest = &lower[start + prefix.len()..];
+        let number = rest
+            .strip_prefix("issues/")
+            .or_else(|| rest.strip_prefix("pull/"))?;
+        let digits = number
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>();
+        digits.parse().ok()
+    })
+}
+
+#[async_trait]
+impl Notifier for GithubNotifier {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    // Comments only make sense on a specific proposal's discussion.
+    async fn notify(&self, _message: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn notify_review(&self, processed: &ProcessedProposal, message: &str) -> Result<()> {
+        let proposal_id = &processed.proposal.proposal_id;
+        let Some(issue) = self.issue_for(&processed.proposal) else {
+            tracing::debug!(
+                target = "notifier",
+                proposal_id = %proposal_id,
+                repo = %self.repo,
+                "no GitHub issue mapped or linked for proposal; skipping comment"
+            );
+            return Ok(());
+        };
+        let env_name = self
+            .token_env
+            .clone()
+            .ok_or_else(|| anyhow!("github token env var is not configured"))?;
+        let token = env::var(&env_name)
+            .map_err(|_| anyhow!("github token env var {env_name} is not set"))?;
+
+        let url = format!(
+            "{}/repos/{}/issues/{issue}/comments",
+            self.api_url, self.repo
+        );
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .json(&serde_json::json!({ "body": render_github_comment(processed, message) }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "github API returned HTTP {} for {}#{issue}",
+                response.status(),
+                self.repo
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn render_github_comment(processed: &ProcessedProposal, message: &str) -> String {
+    let mut body = format!(
+        "**gov-agent decision for proposal {}: {:?}**\n\n```text\n{message}\n```",
+        processed.proposal.proposal_id, processed.decision.vote
+    );
+    if !processed.decision.reasons.is_empty() {
+        body.push_str("\n\nReasons:");
+        for reason in &processed.decision.reasons {
+            let _ = write!(body, "\n- {reason}");
+        }
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Utc;
+    use reqwest::Client;
+
+    use clap::ValueEnum;
+
+    use super::{
+        GithubNotifier, NotificationEvent, NotificationLinks, NotificationTemplates,
+        catch_up_digest_vars, default_templates, linked_issue, parse_templates,
+        review_completed_vars, sample_processed,
+    };
+    use crate::{
+        config::GithubConfig,
+        signer::{ChainClock, ClockMode},
+        types::{
+            Decision, DecodedAction, Finding, FindingCode, ProcessedProposal, Proposal,
+            ReviewResult, Severity, VoteChoice,
+        },
+    };
+
+    fn finding(severity: Severity, message: &str) -> Finding {
+        Finding {
+            code: FindingCode::Unclassified,
+            severity,
+            message: message.to_string(),
+            operator: None,
+        }
+    }
+
+    #[test]
+    fn review_notification_lists_top_findings_and_links() {
+        let processed = ProcessedProposal {
+            proposal: Proposal {
+                proposal_id: "42".to_string(),
+                proposer: "0x0000000000000000000000000000000000000001".to_string(),
+                description: "upgrade".to_string(),
+                vote_start: 90,
+                vote_end: 400,
+                block_number: 80,
+                tx_hash: Some("0xabc".to_string()),
+                targets: vec![],
+                values: vec![],
+                calldatas: vec![],
+                actions: vec![DecodedAction::UpgradeDapp {
+                    dapp_id: "7".to_string(),
+                    root_cid: "bafyroot".to_string(),
+                    name: "Swap".to_string(),
+                    version: "1.0.1".to_string(),
+                    description: "swap".to_string(),
+                }],
+                discovered_at: Utc::now(),
+                creation_context: None,
+            },
+            review: ReviewResult {
+                proposal_id: "42".to_string(),
+                root_cid: Some("bafyroot".to_string()),
+                findings: vec![
+                    finding(Severity::Info, "manifest has 3 files"),
+                    finding(Severity::Warning, "bundle loads remote script"),
+                    finding(Severity::Critical, &"x".repeat(400)),
+                    finding(Severity::Warning, "uses eval"),
+                ],
+                deterministic_score: None,
+                deterministic_weight: None,
+                llm_weight: None,
+                llm_score: None,
+                llm_audit: None,
+                llm_audit_ref: None,
+                score: 0.2,
+                suppressions: vec![],
+                transient_failure: None,
+                reputation: None,
+                score_cap: None,
+                actions: vec![],
+                reviewed_at: Utc::now(),
+            },
+            decision: Decision {
+                proposal_id: "42".to_string(),
+                vote: VoteChoice::Against,
+                reasons: vec![],
+                blocking_findings: vec![],
+                requires_human_override: false,
+                decided_at: Utc::now(),
+                config_hash: None,
+                allocation: None,
+            },
+            vote_executions: Vec::new(),
+            decision_history: Vec::new(),
+            voting_paused: false,
+            lifecycle: None,
+            vote_missed: false,
+            peer_check: None,
+        };
+        let links = NotificationLinks {
+            gateway_url: "https://ipfs.io".to_string(),
+            explorer_url_base: Some("https://sepolia.etherscan.io".to_string()),
+        };
+
+        let render = |clock: ChainClock, links: &NotificationLinks| {
+            NotificationTemplates::default().render(
+                NotificationEvent::ReviewCompleted,
+                &review_completed_vars(&processed, clock, links),
+            )
+        };
+        let message = render(ChainClock::blocks(100), &links);
+        let lines = message.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "gov-agent processed proposal 42 with vote Against"
+        );
+        assert_eq!(
+            lines[1],
+            "proposer 0x0000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            lines[2],
+            "score 0.20; voting ends at block 400 (300 blocks, ~1h00m left)"
+        );
+        assert!(lines[4].starts_with("- [critical] xxx"));
+        assert!(lines[4].ends_with('…'));
+        assert_eq!(lines[5], "- [warning] bundle loads remote script");
+        assert_eq!(lines[6], "- [warning] uses eval");
+        assert_eq!(lines[7], "(+1 more)");
+        assert_eq!(lines[8], "bundle: https://ipfs.io/ipfs/bafyroot");
+        assert_eq!(lines[9], "tx: https://sepolia.etherscan.io/tx/0xabc");
+
+        let message = render(ChainClock::blocks(500), &NotificationLinks::default());
+        assert!(message.contains("voting ended at block 400"));
+        assert!(!message.contains("tx:"));
+
+        // On a timestamp clock vote_end is a unix time and there is no block count.
+        let timestamp = |now| ChainClock {
+            mode: ClockMode::Timestamp,
+            now,
+        };
+        let message = render(timestamp(100), &links);
+        assert_eq!(
+            message.lines().nth(2),
+            Some("score 0.20; voting ends at 1970-01-01T00:06:40+00:00 (~0h05m left)")
+        );
+        let message = render(timestamp(400), &links);
+        assert!(message.contains("voting ended at 1970-01-01T00:06:40+00:00"));
+    }
+
+    #[test]
+    fn custom_templates_override_defaults_and_fall_back_on_errors() {
+        for event in NotificationEvent::value_variants() {
+            assert!(
+                default_templates().contains_key(event.as_str()),
+                "no default template for {}",
+                event.as_str()
+            );
+        }
+
+        let templates = NotificationTemplates {
+            custom: parse_templates(
+                r#"
+review_completed = "propuesta {{proposal_id}}: voto {{vote}}{{#findings}} [{{severity}}]{{/findings}}"
+catch_up_digest = "{{count}} propuestas {{missing}}"
+"#,
+            )
+            .expect("templates"),
+        };
+        let processed = sample_processed();
+        let links = NotificationLinks::default();
+        assert_eq!(
+            templates.render(
+                NotificationEvent::ReviewCompleted,
+                &review_completed_vars(&processed, ChainClock::blocks(1_700), &links)
+            ),
+            "propuesta 0: voto Against [critical] [warning] [info]"
+        );
+        // The unknown placeholder falls back to the default English text.
+        let digest = templates.render(
+            NotificationEvent::CatchUpDigest,
+            &catch_up_digest_vars(std::slice::from_ref(&processed), 1, 2, &links),
+        );
+        assert_eq!(
+            digest,
+            "gov-agent catch-up finished blocks 1..=2: 1 proposals processed\n- 0 Against score 0.35"
+        );
+        // Events without a custom template use the default.
+        assert_eq!(
+            templates.render(NotificationEvent::VotingResumed, &Default::default()),
+            "gov-agent voting RESUMED"
+        );
+
+        let err = parse_templates("review_complete = \"x\"").expect_err("unknown event");
+        assert!(
+            err.to_string()
+                .contains("unknown notification event \"review_complete\"")
+        );
+        assert!(parse_templates("review_completed = \"{{#vote}}\"").is_err());
+    }
+
+    #[test]
+    fn github_comment_target_comes_from_mapping_or_description_link() {
+        let config = |repo: &str| GithubConfig {
+            enabled: true,
+            repo: Some(repo.to_string()),
+            issues: BTreeMap::from([("0x2a".to_string(), 7)]),
+            ..GithubConfig::default()
+        };
+        let notifier =
+            GithubNotifier::new(&config(" VibeFi/Governance/ "), Client::new()).expect("notifier");
+        assert!(GithubNotifier::new(&config("governance"), Client::new()).is_err());
+
+        let proposal = |proposal_id: &str, description: &str| Proposal {
+            proposal_id: proposal_id.to_string(),
+            proposer: "0x0000000000000000000000000000000000000001".to_string(),
+            description: description.to_string(),
+            vote_start: 1,
+            vote_end: 100,
+            block_number: 1,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        };
+        let linked = "Publish Swap\n\nDiscussion: https://github.com/vibefi/governance/pull/31#top";
+        assert_eq!(notifier.issue_for(&proposal("42", linked)), Some(7));
+        assert_eq!(notifier.issue_for(&proposal("43", linked)), Some(31));
+        assert_eq!(notifier.issue_for(&proposal("44", "no discussion")), None);
+
+        assert_eq!(
+            linked_issue(
+                "see github.com/other/repo/issues/1 and https://github.com/vibefi/governance/issues/12",
+                "vibefi/governance"
+            ),
+            Some(12)
+        );
+        assert_eq!(
+            linked_issue(
+                "github.com/vibefi/governance/discussions/3",
+                "vibefi/governance"
+            ),
+            None
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
     net::SocketAddr,
     sync::OnceLock,
-    sync::atomic::{AtomicI64, Ordering},
+    sync::atomic::{AtomicBool, AtomicI64, Ordering},
     time::Instant,
 };
 
@@ -13,6 +13,28 @@ use crate::config::ObservabilityConfig;
 
 static LAST_SUCCESSFUL_POLL_TS: AtomicI64 = AtomicI64::new(0);
 static METRICS_INIT_RESULT: OnceLock<Result<(), String>> = OnceLock::new();
+static MASK_ADDRESSES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_mask_addresses(mask: bool) {
+    MASK_ADDRESSES.store(mask, Ordering::Relaxed);
+}
+
+// For info-and-above logs and notifications; debug logs keep the full address.
+pub fn log_address(address: &str) -> String {
+    if MASK_ADDRESSES.load(Ordering::Relaxed) {
+        mask_address(address)
+    } else {
+        address.to_string()
+    }
+}
+
+pub fn mask_address(address: &str) -> String {
+    let address = address.trim();
+    if address.len() <= 10 || !address.is_ascii() {
+        return address.to_string();
+    }
+    format!("{}…{}", &address[..6], &address[address.len() - 4..])
+}
 
 pub fn init_metrics(cfg: &ObservabilityConfig) -> Result<()> {
     if !cfg.metrics_enabled {
@@ -60,6 +82,10 @@ pub fn incr_proposals_discovered(count: usize) {
     }
 }
 
+pub fn incr_proposal_logs_skipped() {
+    counter!("gov_agent_proposal_logs_skipped_total").increment(1);
+}
+
 pub fn incr_proposals_processed() {
     counter!("gov_agent_proposals_processed_total").increment(1);
 }
@@ -112,3 +138,23 @@ pub fn record_last_processed_proposal_timestamp() {
     let now = chrono::Utc::now().timestamp();
     gauge!("gov_agent_last_processed_proposal_timestamp_seconds").set(now as f64);
 }
+
+// None leaves catch-up mode; Some carries the fraction of the backlog scanned so far.
+pub fn record_catch_up_progress(progress: Option<f64>) {
+    gauge!("gov_agent_catch_up_active").set(if progress.is_some() { 1.0 } else { 0.0 });
+    gauge!("gov_agent_catch_up_progress_ratio").set(progress.unwrap_or(1.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mask_address;
+
+    #[test]
+    fn masked_address_keeps_prefix_and_suffix() {
+        assert_eq!(
+            mask_address("0x753d33e2E61F249c87e6D33c4e04b39731776297"),
+            "0x753d…6297"
+        );
+        assert_eq!(mask_address("0x1234"), "0x1234");
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::types::{ProposalState, ProposalTally, VoteChoice};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DaoOutcome {
+    Passed,
+    Defeated,
+}
+
+impl DaoOutcome {
+    // Expired proposals passed the vote and only missed execution; canceled ones never got a
+    // verdict, and pending or active ones do not have one yet.
+    pub fn from_state(state: ProposalState) -> Option<Self> {
+        match state {
+            ProposalState::Succeeded
+            | ProposalState::Queued
+            | ProposalState::Expired
+            | ProposalState::Executed => Some(Self::Passed),
+            ProposalState::Defeated => Some(Self::Defeated),
+            ProposalState::Pending | ProposalState::Active | ProposalState::Canceled => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Defeated => "defeated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Agreement {
+    Agree,
+    Disagree,
+    Abstained,
+    Unresolved,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutcomeComparison {
+    pub proposal_id: String,
+    pub agent_vote: VoteChoice,
+    pub outcome: Option<DaoOutcome>,
+    pub agreement: Agreement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tally: Option<ProposalTally>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Agreement counts only proposals the DAO decided and the agent took a side on; abstains are
+// listed in the matrix but excluded from the rate.
+#[derive(Debug, Default, Serialize)]
+pub struct OutcomeReport {
+    pub agreed: usize,
+    pub disagreed: usize,
+    pub abstained: usize,
+    pub unresolved: usize,
+    pub agreement_rate: Option<f64>,
+    // Agent vote -> DAO outcome -> proposals.
+    pub matrix: BTreeMap<&'static str, BTreeMap<&'static str, usize>>,
+    pub proposals: Vec<OutcomeComparison>,
+}
+
+impl OutcomeReport {
+    pub fn push(
+        &mut self,
+        proposal_id: String,
+        agent_vote: VoteChoice,
+        tally: Result<Option<ProposalTally>>,
+    ) {
+        let (tally, error) = match tally {
+            Ok(tally) => (tally, None),
+            Err(err) => (None, Some(format!("{err:#}"))),
+        };
+        let outcome = tally
+            .as_ref()
+            .and_then(|tally| DaoOutcome::from_state(tally.state));
+        let agreement = match (outcome, agent_vote) {
+            (None, _) => Agreement::Unresolved,
+            (Some(_), VoteChoice::Abstain) => Agreement::Abstained,
+            (Some(DaoOutcome::Passed), VoteChoice::For)
+            | (Some(DaoOutcome::Defeated), VoteChoice::Against) => Agreement::Agree,
+            (Some(_), _) => Agreement::Disagree,
+        };
+        match agreement {
+            Agreement::Agree => self.agreed += 1,
+            Agreement::Disagree => self.disagreed += 1,
+            Agreement::Abstained => self.abstained += 1,
+            Agreement::Unresolved => self.unresolved += 1,
+        }
+        if let Some(outcome) = outcome {
+            *self
+                .matrix
+                .entry(vote_label(agent_vote))
+                .or_default()
+                .entry(outcome.as_str())
+                .or_default() += 1;
+        }
+        let decided = self.agreed + self.disagreed;
+        self.agreement_rate = (decided > 0).then(|| self.agreed as f64 / decided as f64);
+        self.proposals.push(OutcomeComparison {
+            proposal_id,
+            agent_vote,
+            outcome,
+            agreement,
+            tally,
+            error,
+        });
+    }
+}
+
+fn vote_label(vote: VoteChoice) -> &'static str {
+    match vote {
+        VoteChoice::For => "for",
+        VoteChoice::Against => "against",
+        VoteChoice::Abstain => "abstain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::{Agreement, OutcomeReport};
+    use crate::types::{ProposalState, ProposalTally, VoteChoice};
+
+    fn tally(state: ProposalState) -> anyhow::Result<Option<ProposalTally>> {
+        Ok(Some(ProposalTally {
+            state,
+            for_votes: "10".to_string(),
+            against_votes: "3".to_string(),
+            abstain_votes: "0".to_string(),
+        }))
+    }
+
+    #[test]
+    fn report_counts_agreement_against_final_outcomes_only() {
+        let mut report = OutcomeReport::default();
+        report.push("1".into(), VoteChoice::For, tally(ProposalState::Executed));
+        report.push(
+            "2".into(),
+            VoteChoice::Against,
+            tally(ProposalState::Defeated),
+        );
+        report.push(
+            "3".into(),
+            VoteChoice::Against,
+            tally(ProposalState::Expired),
+        );
+        report.push(
+            "4".into(),
+            VoteChoice::Abstain,
+            tally(ProposalState::Succeeded),
+        );
+        report.push("5".into(), VoteChoice::For, tally(ProposalState::Active));
+        report.push("6".into(), VoteChoice::For, tally(ProposalState::Canceled));
+        report.push("7".into(), VoteChoice::For, Err(anyhow!("rpc down")));
+        report.push("8".into(), VoteChoice::For, Ok(None));
+
+        assert_eq!(
+            (
+                report.agreed,
+                report.disagreed,
+                report.abstained,
+                report.unresolved
+            ),
+            (2, 1, 1, 4)
+        );
+        assert_eq!(report.agreement_rate, Some(2.0 / 3.0));
+        assert_eq!(report.matrix["for"]["passed"], 1);
+        assert_eq!(report.matrix["against"]["defeated"], 1);
+        assert_eq!(report.matrix["against"]["passed"], 1);
+        assert_eq!(report.matrix["abstain"]["passed"], 1);
+        assert!(!report.matrix["for"].contains_key("defeated"));
+        assert_eq!(report.proposals[2].agreement, Agreement::Disagree);
+        assert_eq!(report.proposals[6].error.as_deref(), Some("rpc down"));
+    }
+}
@@ -0,0 +1,294 @@
+use std::{env, time::Duration};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Utc;
+use futures::future::join_all;
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+use crate::{
+    config::{HttpConfig, PeersConfig, redact_url_secrets},
+    http::{self, HttpService},
+    types::{Decision, PeerComparison, PeerReview, VoteChoice},
+};
+
+// Asks other agents' API servers (`GET /proposals/{id}`) how they decided a proposal. Peers are
+// queried concurrently and each request is bounded by `peers.timeout_secs`, so a slow or dead
+// peer delays a vote by at most that long and never fails it.
+pub struct PeerClient {
+    peers: Vec<Peer>,
+    quorum: usize,
+    client: Client,
+}
+
+struct Peer {
+    url: String,
+    bearer_token: Option<String>,
+}
+
+impl PeerClient {
+    // None when no peers are configured. A peer token env var that is unset fails here, since a
+    // peer answering 401 would never count toward the quorum.
+    pub fn from_config(cfg: &PeersConfig, http_cfg: &HttpConfig) -> Result<Option<Self>> {
+        if cfg.urls.is_empty() {
+            return Ok(None);
+        }
+        let peers = cfg
+            .urls
+            .iter()
+            .map(|endpoint| {
+                let bearer_token = endpoint
+                    .bearer_token_env()
+                    .map(|name| {
+                        env::var(name)
+                            .map_err(|_| anyhow!("peer bearer token env var {name} is not set"))
+                    })
+                    .transpose()?;
+                Ok(Peer {
+                    url: endpoint.url().to_string(),
+                    bearer_token,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(Self {
+            peers,
+            quorum: cfg.quorum,
+            client: http::build_client(
+                http_cfg,
+                HttpService {
+                    name: "peers",
+                    ..HttpService::default()
+                },
+                Some(Duration::from_secs(cfg.timeout_secs)),
+            )?,
+        }))
+    }
+
+    pub async fn compare(&self, decision: &Decision) -> PeerComparison {
+        let reviews = join_all(
+            self.peers
+                .iter()
+                .map(|peer| self.review(peer, &decision.proposal_id)),
+        )
+        .await;
+        let disagreeing = reviews
+            .iter()
+            .filter(|review| review.vote.is_some_and(|vote| vote != decision.vote))
+            .count();
+        PeerComparison {
+            reviews,
+            disagreeing,
+            quorum: self.quorum,
+            held: disagreeing >= self.quorum,
+        }
+    }
+
+    async fn review(&self, peer: &Peer, proposal_id: &str) -> PeerReview {
+        let (vote, decision, error) = match self.fetch_decision(peer, proposal_id).await {
+            Ok((vote, decision)) => (Some(vote), Some(decision), None),
+            Err(err) => (None, None, Some(format!("{err:#}"))),
+        };
+        let mut url = peer.url.clone();
+        redact_url_secrets(&mut url);
+        PeerReview {
+            peer: url,
+            vote,
+            decision,
+            error,
+            fetched_at: Utc::now(),
+        }
+    }
+
+    async fn fetch_decision(&self, peer: &Peer, proposal_id: &str) -> Result<(VoteChoice, Value)> {
+        let mut request = self.client.get(format!(
+            "{}/proposals/{proposal_id}",
+            peer.url.trim_end_matches('/')
+        ));
+        if let Some(token) = &peer.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.context("peer request failed")?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            bail!("peer has no decision for this proposal");
+        }
+        if !status.is_success() {
+            bail!("peer returned HTTP {status}");
+        }
+        let body = response
+            .json::<Value>()
+            .await
+            .context("peer returned invalid json")?;
+        let decision = body
+            .get("decision")
+            .cloned()
+            .context("peer response has no decision")?;
+        let vote =
+            serde_json::from_value::<VoteChoice>(decision.get("vote").cloned().unwrap_or_default())
+                .context("peer decision has no valid vote")?;
+        Ok((vote, decision))
+    }
+}
+
+// Added to the decision reasons and the review notification when the vote is held.
+pub fn disagreement_summary(comparison: &PeerComparison, vote: VoteChoice) -> String {
+    let votes = comparison
+        .reviews
+        .iter()
+        .filter_map(|review| {
+            review
+                .vote
+                .filter(|peer_vote| *peer_vote != vote)
+                .map(|peer_vote| format!("{} voted {peer_vote:?}", review.peer))
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "{} of {} peer agents disagree with {vote:?} (quorum {}): {}; vote held for human override",
+        comparison.disagreeing,
+        comparison.reviews.len(),
+        comparison.quorum,
+        votes.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use chrono::Utc;
+
+    use super::{PeerClient, disagreement_summary};
+    use crate::{
+        config::{HttpConfig, PeerEndpoint, PeersConfig},
+        types::{Decision, VoteChoice},
+    };
+
+    // Serves one canned response per connection, picked by the request path and headers.
+    fn mock_peer(respond: fn(&str, &str) -> String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock peer");
+        let url = format!("http://{}", listener.local_addr().expect("local addr"));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                let _ = reader.read_line(&mut line);
+                let mut headers = String::new();
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    headers.push_str(&header.to_ascii_lowercase());
+                    header.clear();
+                }
+                let path = line.split_whitespace().nth(1).unwrap_or_default();
+                let _ = reader
+                    .get_mut()
+                    .write_all(respond(path, &headers).as_bytes());
+            }
+        });
+        url
+    }
+
+    fn reply(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn quorum_of_disagreeing_peers_holds_the_vote() {
+        let against = mock_peer(|path, _| {
+            assert_eq!(path, "/proposals/7");
+            reply(
+                "200 OK",
+                r#"{"decision":{"vote":"against","reasons":["critical finding"]}}"#,
+            )
+        });
+        let agrees = mock_peer(|_, _| reply("200 OK", r#"{"decision":{"vote":"for"}}"#));
+        let undecided = mock_peer(|_, _| reply("404 Not Found", r#"{"error":"not found"}"#));
+        let protected = mock_peer(|_, headers| {
+            if headers.contains("authorization: bearer peer-secret") {
+                reply("200 OK", r#"{"decision":{"vote":"against"}}"#)
+            } else {
+                reply("401 Unauthorized", r#"{"error":"unauthorized"}"#)
+            }
+        });
+        // SAFETY: the variable name is unique to this test.
+        unsafe { std::env::set_var("GOV_AGENT_TEST_PEER_TOKEN", "peer-secret") };
+        let urls = vec![
+            PeerEndpoint::Url(format!("{against}/")),
+            PeerEndpoint::Url(agrees),
+            PeerEndpoint::Url(undecided),
+            PeerEndpoint::Url("http://127.0.0.1:1".to_string()),
+            PeerEndpoint::WithToken {
+                url: protected.clone(),
+                bearer_token_env: "GOV_AGENT_TEST_PEER_TOKEN".to_string(),
+            },
+        ];
+        let decision = Decision {
+            proposal_id: "7".to_string(),
+            vote: VoteChoice::For,
+            reasons: Vec::new(),
+            blocking_findings: Vec::new(),
+            requires_human_override: false,
+            decided_at: Utc::now(),
+            config_hash: None,
+            allocation: None,
+        };
+
+        let client_with = |quorum: usize| {
+            PeerClient::from_config(
+                &PeersConfig {
+                    urls: urls.clone(),
+                    quorum,
+                    timeout_secs: 2,
+                },
+                &HttpConfig::default(),
+            )
+            .expect("build client")
+            .expect("peers configured")
+        };
+
+        let comparison = client_with(1).compare(&decision).await;
+        assert_eq!(comparison.reviews.len(), 5);
+        assert_eq!(comparison.disagreeing, 2);
+        assert!(comparison.held);
+        assert_eq!(comparison.reviews[0].vote, Some(VoteChoice::Against));
+        assert_eq!(
+            comparison.reviews[0].decision.as_ref().expect("stored")["reasons"][0],
+            "critical finding"
+        );
+        assert_eq!(comparison.reviews[1].vote, Some(VoteChoice::For));
+        assert_eq!(
+            comparison.reviews[2].error.as_deref(),
+            Some("peer has no decision for this proposal")
+        );
+        assert!(comparison.reviews[3].error.is_some());
+        assert_eq!(comparison.reviews[4].vote, Some(VoteChoice::Against));
+        assert!(
+            disagreement_summary(&comparison, VoteChoice::For).starts_with(&format!(
+                "2 of 5 peer agents disagree with For (quorum 1): {against}/ voted Against, {protected} voted Against"
+            ))
+        );
+
+        // Failed and agreeing peers never count toward the quorum.
+        assert!(client_with(2).compare(&decision).await.held);
+        assert!(!client_with(3).compare(&decision).await.held);
+
+        let missing_token = PeersConfig {
+            urls: vec![PeerEndpoint::WithToken {
+                url: protected,
+                bearer_token_env: "GOV_AGENT_TEST_PEER_TOKEN_UNSET".to_string(),
+            }],
+            quorum: 1,
+            timeout_secs: 2,
+        };
+        let err = PeerClient::from_config(&missing_token, &HttpConfig::default())
+            .err()
+            .expect("unset token env");
+        assert!(err.to_string().contains("GOV_AGENT_TEST_PEER_TOKEN_UNSET"));
+    }
+}
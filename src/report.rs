@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    ActionReview, Decision, DecodedAction, Finding, FindingCode, LlmAudit, Proposal, ReviewResult,
+    Severity, VoteAllocation, VoteChoice,
+};
+
+// Public output of `review-once`, `review-cid` and `POST /review/{id}`. It is built from the
+// internal review types, which are free to change; this shape only changes together with
+// REVIEW_REPORT_SCHEMA_VERSION. Adding an optional field or a finding code keeps the version.
+pub const REVIEW_REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewReport {
+    pub schema_version: u32,
+    pub proposal: ProposalSummary,
+    pub findings: Vec<ReportFinding>,
+    pub score: ReportScore,
+    pub decision: ReportDecision,
+    pub llm_audit: Option<ReportLlmAudit>,
+    pub reviewed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalSummary {
+    pub proposal_id: String,
+    // `review-cid` reviews: no on-chain proposal, so proposer and blocks are zero.
+    pub synthetic: bool,
+    pub proposer: String,
+    pub block_number: u64,
+    pub vote_start: u64,
+    pub vote_end: u64,
+    pub tx_hash: Option<String>,
+    pub actions: Vec<ReportAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportAction {
+    pub kind: String,
+    pub dapp_id: Option<String>,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub root_cid: Option<String>,
+    // Unset when the review did not cover this action.
+    pub score: Option<f32>,
+    pub critical: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportFinding {
+    pub code: FindingCode,
+    pub severity: Severity,
+    pub message: String,
+    // Set for findings a human added with `proposals annotate`.
+    pub operator: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScore {
+    pub total: f32,
+    pub deterministic: Option<f32>,
+    pub llm: Option<f32>,
+    pub deterministic_weight: Option<f32>,
+    pub llm_weight: Option<f32>,
+    pub cap: Option<ReportScoreCap>,
+    pub reputation_adjustment: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScoreCap {
+    pub rule: String,
+    pub cap: f32,
+    pub uncapped: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportDecision {
+    pub vote: VoteChoice,
+    pub reasons: Vec<String>,
+    pub blocking_findings: Vec<String>,
+    pub requires_human_override: bool,
+    pub config_hash: Option<String>,
+    pub decided_at: DateTime<Utc>,
+    // Set with `decision.fractional`.
+    #[serde(default)]
+    pub allocation: Option<VoteAllocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportLlmAudit {
+    pub provider: String,
+    pub model: String,
+    pub cached: bool,
+    pub prompt: String,
+    pub response: String,
+}
+
+// One entry of a `review-once --proposal-ids` run or an API reply: the report, or why there is
+// none for this id.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReview {
+    pub proposal_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ReviewReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReviewReport {
+    pub fn new(
+        proposal: &Proposal,
+        review: &ReviewResult,
+        decision: &Decision,
+        synthetic: bool,
+    ) -> Self {
+        Self {
+            schema_version: REVIEW_REPORT_SCHEMA_VERSION,
+            proposal: ProposalSummary {
+                proposal_id: proposal.proposal_id.clone(),
+                synthetic,
+                proposer: proposal.proposer.clone(),
+                block_number: proposal.block_number,
+                vote_start: proposal.vote_start,
+                vote_end: proposal.vote_end,
+                tx_hash: proposal.tx_hash.clone(),
+                actions: proposal
+                    .actions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, action)| {
+                        ReportAction::new(
+                            action,
+                            review.actions.iter().find(|review| review.index == index),
+                        )
+                    })
+                    .collect(),
+            },
+            findings: review.findings.iter().map(ReportFinding::from).collect(),
+            score: ReportScore {
+                total: review.score,
+                deterministic: review.deterministic_score,
+                llm: review.llm_score,
+                deterministic_weight: review.deterministic_weight,
+                llm_weight: review.llm_weight,
+                cap: review.score_cap.as_ref().map(|cap| ReportScoreCap {
+                    rule: cap.rule.clone(),
+                    cap: cap.cap,
+                    uncapped: cap.uncapped_score,
+                }),
+                reputation_adjustment: review
+                    .reputation
+                    .as_ref()
+                    .map(|reputation| reputation.adjustment),
+            },
+            decision: ReportDecision {
+                vote: decision.vote,
+                reasons: decision.reasons.clone(),
+                blocking_findings: decision.blocking_findings.clone(),
+                requires_human_override: decision.requires_human_override,
+                config_hash: decision.config_hash.clone(),
+                decided_at: decision.decided_at,
+                allocation: decision.allocation,
+            },
+            llm_audit: review.llm_audit.as_ref().map(ReportLlmAudit::from),
+            reviewed_at: review.reviewed_at,
+        }
+    }
+}
+
+impl ReportAction {
+    fn new(action: &DecodedAction, review: Option<&ActionReview>) -> Self {
+        let (dapp_id, name, version) = match action {
+            DecodedAction::PublishDapp { name, version, .. } => {
+                (None, Some(name.clone()), Some(version.clone()))
+            }
+            DecodedAction::UpgradeDapp {
+                dapp_id,
+                name,
+                version,
+                ..
+            } => (
+                Some(dapp_id.clone()),
+                Some(name.clone()),
+                Some(version.clone()),
+            ),
+            DecodedAction::Unsupported { .. } => (None, None, None),
+        };
+        Self {
+            kind: action.kind().to_string(),
+            dapp_id,
+            name,
+            version,
+            root_cid: action.root_cid().map(ToString::to_string),
+            score: review.map(|review| review.score),
+            critical: review.is_some_and(|review| review.critical),
+        }
+    }
+}
+
+impl From<&Finding> for ReportFinding {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            code: finding.code,
+            severity: finding.severity,
+            message: finding.message.clone(),
+            operator: finding
+                .operator
+                .as_ref()
+                .map(|operator| operator.author.clone()),
+        }
+    }
+}
+
+impl From<&LlmAudit> for ReportLlmAudit {
+    fn from(audit: &LlmAudit) -> Self {
+        Self {
+            provider: audit.provider.clone(),
+            model: audit.model.clone(),
+            cached: audit.cached,
+            prompt: audit.prompt_redacted.clone(),
+            response: audit.response_redacted.clone(),
+        }
+    }
+}
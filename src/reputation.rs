@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Decision, DecodedAction, Proposal, ReputationAdjustment, VoteChoice};
+
+pub const MAX_REPUTATION_WEIGHT: f32 = 0.05;
+const CLEAN_STREAK_FOR_FULL_CREDIT: u32 = 3;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReputationRecord {
+    pub approved: u32,
+    pub rejected: u32,
+    pub abstained: u32,
+    pub last_decision: Option<VoteChoice>,
+    pub last_decided_at: Option<DateTime<Utc>>,
+    pub critical_findings: u32,
+    pub last_critical_finding: Option<String>,
+    pub clean_streak: u32,
+}
+
+impl ReputationRecord {
+    fn record(&mut self, decision: &Decision) {
+        match decision.vote {
+            VoteChoice::For => self.approved += 1,
+            VoteChoice::Against => self.rejected += 1,
+            VoteChoice::Abstain => self.abstained += 1,
+        }
+        self.critical_findings += decision.blocking_findings.len() as u32;
+        if let Some(finding) = decision.blocking_findings.first() {
+            self.last_critical_finding = Some(finding.clone());
+        }
+        self.clean_streak =
+            if decision.vote == VoteChoice::For && decision.blocking_findings.is_empty() {
+                self.clean_streak + 1
+            } else {
+                0
+            };
+        self.last_decision = Some(decision.vote);
+        self.last_decided_at = Some(decision.decided_at);
+    }
+
+    // +1 after a run of clean approvals, -1 right after an Against, proportional in between.
+    fn signal(&self) -> f32 {
+        match self.last_decision {
+            Some(VoteChoice::Against) => -1.0,
+            _ => {
+                self.clean_streak.min(CLEAN_STREAK_FOR_FULL_CREDIT) as f32
+                    / CLEAN_STREAK_FOR_FULL_CREDIT as f32
+            }
+        }
+    }
+
+    fn describe(&self, key: &str) -> String {
+        let last = self
+            .last_decision
+            .map(|vote| format!("{vote:?}"))
+            .unwrap_or_else(|| "none".to_string());
+        format!(
+            "{key}: approved={} rejected={} abstained={} critical_findings={} clean_streak={} last={last}",
+            self.approved, self.rejected, self.abstained, self.critical_findings, self.clean_streak
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReputationBook {
+    #[serde(default)]
+    pub dapps: BTreeMap<String, ReputationRecord>,
+    #[serde(default)]
+    pub proposers: BTreeMap<String, ReputationRecord>,
+}
+
+impl ReputationBook {
+    pub fn record(&mut self, proposal: &Proposal, decision: &Decision) {
+        for key in dapp_keys(proposal) {
+            self.dapps.entry(key).or_default().record(decision);
+        }
+        self.proposers
+            .entry(proposer_key(&proposal.proposer))
+            .or_default()
+            .record(decision);
+    }
+
+    pub fn knows_proposer(&self, proposer: &str) -> bool {
+        self.proposers.contains_key(&proposer_key(proposer))
+    }
+
+    pub fn assess(
+        &self,
+        proposal: &Proposal,
+        weight: f32,
+        has_critical: bool,
+        base_score: f32,
+    ) -> Option<ReputationAdjustment> {
+        let weight = weight.clamp(0.0, MAX_REPUTATION_WEIGHT);
+        if weight <= 0.0 {
+            return None;
+        }
+
+        let proposer = proposer_key(&proposal.proposer);
+        let records = dapp_keys(proposal)
+            .into_iter()
+            .filter_map(|key| self.dapps.get(&key).map(|record| (key, record)))
+            .chain(
+                self.proposers
+                    .get(&proposer)
+                    .map(|record| (format!("proposer:{proposer}"), record)),
+            )
+            .collect::<Vec<_>>();
+        if records.is_empty() {
+            return None;
+        }
+
+        let signal = records
+            .iter()
+            .map(|(_, record)| record.signal())
+            .sum::<f32>()
+            / records.len() as f32;
+        let mut adjustment = weight * signal;
+        // A good track record never softens a review that already has a blocking finding.
+        if has_critical {
+            adjustment = adjustment.min(0.0);
+        }
+
+        Some(ReputationAdjustment {
+            base_score,
+            signal,
+            weight,
+            adjustment,
+            inputs: records
+                .iter()
+                .map(|(key, record)| record.describe(key))
+                .collect(),
+        })
+    }
+}
+
+// A publish has no dapp id yet and its name is free text anyone can reuse, so it is keyed by
+// proposer and name: another proposer publishing under the same name starts a fresh record.
+fn dapp_key(proposer: &str, action: &DecodedAction) -> Option<String> {
+    match action {
+        DecodedAction::UpgradeDapp { dapp_id, .. } => Some(format!("dapp:{dapp_id}")),
+        DecodedAction::PublishDapp { name, .. } => Some(format!(
+            "publish:{proposer}:{}",
+            name.trim().to_ascii_lowercase()
+        )),
+        DecodedAction::Unsupported { .. } => None,
+    }
+}
+
+fn dapp_keys(proposal: &Proposal) -> BTreeSet<String> {
+    let proposer = proposer_key(&proposal.proposer);
+    proposal
+        .actions
+        .iter()
+        .filter_map(|action| dapp_key(&proposer, action))
+        .collect()
+}
+
+fn proposer_key(proposer: &str) -> String {
+    proposer.trim().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{MAX_REPUTATION_WEIGHT, ReputationBook};
+    use crate::types::{Decision, DecodedAction, Proposal, VoteChoice};
+
+    fn upgrade(proposal_id: &str) -> Proposal {
+        Proposal {
+            proposal_id: proposal_id.to_string(),
+            proposer: "0x00000000000000000000000000000000000000AA".to_string(),
+            description: "upgrade".to_string(),
+            vote_start: 1,
+            vote_end: 100,
+            block_number: 1,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::UpgradeDapp {
+                dapp_id: "7".to_string(),
+                root_cid: "bafy".to_string(),
+                name: "Swap".to_string(),
+                version: "1.0.0".to_string(),
+                description: "swap".to_string(),
+            }],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        }
+    }
+
+    fn decision(vote: VoteChoice, blocking: &[&str]) -> Decision {
+        Decision {
+            proposal_id: "1".to_string(),
+            vote,
+            reasons: vec![],
+            blocking_findings: blocking.iter().map(|f| f.to_string()).collect(),
+            requires_human_override: false,
+            decided_at: Utc::now(),
+            config_hash: None,
+            allocation: None,
+        }
+    }
+
+    #[test]
+    fn clean_history_earns_capped_bonus_but_not_with_critical_findings() {
+        let mut book = ReputationBook::default();
+        let proposal = upgrade("1");
+        assert!(book.assess(&proposal, 0.05, false, 0.7).is_none());
+
+        for _ in 0..4 {
+            book.record(&proposal, &decision(VoteChoice::For, &[]));
+        }
+        let adjustment = book.assess(&proposal, 0.5, false, 0.7).expect("adjustment");
+        assert_eq!(adjustment.weight, MAX_REPUTATION_WEIGHT);
+        assert_eq!(adjustment.adjustment, MAX_REPUTATION_WEIGHT);
+        assert_eq!(adjustment.inputs.len(), 2);
+        assert!(adjustment.inputs[0].starts_with("dapp:7: approved=4"));
+
+        let blocked = book.assess(&proposal, 0.05, true, 0.7).expect("adjustment");
+        assert_eq!(blocked.adjustment, 0.0);
+        assert!(book.assess(&proposal, 0.0, false, 0.7).is_none());
+    }
+
+    #[test]
+    fn against_vote_resets_streak_and_penalizes() {
+        let mut book = ReputationBook::default();
+        let proposal = upgrade("1");
+        book.record(&proposal, &decision(VoteChoice::For, &[]));
+        book.record(
+            &proposal,
+            &decision(VoteChoice::Against, &["failed to fetch manifest"]),
+        );
+
+        let record = &book.dapps["dapp:7"];
+        assert_eq!(record.clean_streak, 0);
+        assert_eq!(record.critical_findings, 1);
+        assert_eq!(record.rejected, 1);
+
+        let adjustment = book
+            .assess(&proposal, 0.05, false, 0.7)
+            .expect("adjustment");
+        assert_eq!(adjustment.signal, -1.0);
+        assert!(adjustment.adjustment < 0.0);
+    }
+
+    #[test]
+    fn publishes_share_history_only_with_the_same_proposer() {
+        let publish = |proposer: &str| {
+            let mut proposal = upgrade("1");
+            proposal.proposer = proposer.to_string();
+            proposal.actions = vec![DecodedAction::PublishDapp {
+                root_cid: "bafy".to_string(),
+                name: "Swap".to_string(),
+                version: "1.0.0".to_string(),
+                description: "swap".to_string(),
+            }];
+            proposal
+        };
+        let mut book = ReputationBook::default();
+        for _ in 0..3 {
+            book.record(&publish("0xAA"), &decision(VoteChoice::For, &[]));
+        }
+        assert!(book.dapps.contains_key("publish:0xaa:swap"));
+
+        // A newcomer reusing the name gets neither the bonus nor a dapp record.
+        assert!(book.assess(&publish("0xBB"), 0.05, false, 0.7).is_none());
+        let adjustment = book
+            .assess(&publish("0xaa"), 0.05, false, 0.7)
+            .expect("adjustment");
+        assert_eq!(adjustment.inputs.len(), 2);
+        assert!(adjustment.inputs[0].starts_with("publish:0xaa:swap: approved=3"));
+    }
+}
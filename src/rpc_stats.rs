@@ -0,0 +1,343 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    rpc::{
+        client::{ClientBuilder, RpcClient},
+        json_rpc::{RequestPacket, ResponsePacket},
+    },
+    transports::{TransportError, TransportFut},
+};
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use metrics::{counter, gauge, histogram};
+use serde::{Deserialize, Serialize};
+use tower::{Layer, Service};
+
+use crate::{config::HttpConfig, http};
+
+pub const DEFAULT_WINDOW: usize = 200;
+
+static GLOBAL: RpcStats = RpcStats::new(DEFAULT_WINDOW);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodCounts {
+    pub calls: u64,
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointStats {
+    pub endpoint: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub methods: BTreeMap<String, MethodCounts>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RpcStatsSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub endpoints: Vec<EndpointStats>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointWindow {
+    methods: BTreeMap<String, MethodCounts>,
+    latencies: VecDeque<Duration>,
+}
+
+#[derive(Debug)]
+pub struct RpcStats {
+    window: AtomicUsize,
+    endpoints: Mutex<BTreeMap<String, EndpointWindow>>,
+}
+
+impl RpcStats {
+    pub const fn new(window: usize) -> Self {
+        Self {
+            window: AtomicUsize::new(window),
+            endpoints: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    pub fn set_window(&self, window: usize) {
+        self.window.store(window.max(1), Ordering::Relaxed);
+    }
+
+    pub fn record(&self, endpoint: &str, method: &str, latency: Duration, failed: bool) {
+        let window = self.window.load(Ordering::Relaxed).max(1);
+        let (p50, p95) = {
+            let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = endpoints.entry(endpoint.to_string()).or_default();
+            let counts = entry.methods.entry(method.to_string()).or_default();
+            counts.calls += 1;
+            if failed {
+                counts.errors += 1;
+            }
+            entry.latencies.push_back(latency);
+            while entry.latencies.len() > window {
+                entry.latencies.pop_front();
+            }
+            latency_percentiles(&entry.latencies)
+        };
+
+        let labels = [
+            ("endpoint", endpoint.to_string()),
+            ("method", method.to_string()),
+        ];
+        counter!("gov_agent_rpc_calls_total", &labels).increment(1);
+        if failed {
+            counter!("gov_agent_rpc_errors_total", &labels).increment(1);
+        }
+        histogram!("gov_agent_rpc_latency_seconds", &labels).record(latency.as_secs_f64());
+        gauge!("gov_agent_rpc_latency_p50_seconds", "endpoint" => endpoint.to_string())
+            .set(p50.as_secs_f64());
+        gauge!("gov_agent_rpc_latency_p95_seconds", "endpoint" => endpoint.to_string())
+            .set(p95.as_secs_f64());
+    }
+
+    pub fn endpoints(&self) -> Vec<EndpointStats> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints
+            .iter()
+            .map(|(endpoint, window)| {
+                let (p50, p95) = latency_percentiles(&window.latencies);
+                EndpointStats {
+                    endpoint: endpoint.clone(),
+                    calls: window.methods.values().map(|c| c.calls).sum(),
+                    errors: window.methods.values().map(|c| c.errors).sum(),
+                    samples: window.latencies.len(),
+                    p50_ms: p50.as_secs_f64() * 1000.0,
+                    p95_ms: p95.as_secs_f64() * 1000.0,
+                    methods: window.methods.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> RpcStatsSnapshot {
+        RpcStatsSnapshot {
+            captured_at: Utc::now(),
+            endpoints: self.endpoints(),
+        }
+    }
+}
+
+pub fn render_table(endpoints: &[EndpointStats]) -> String {
+    let mut out = String::new();
+    for stats in endpoints {
+        let _ = writeln!(
+            out,
+            "{}\tcalls={}\terrors={}\tp50={:.1}ms\tp95={:.1}ms\tsamples={}",
+            stats.endpoint, stats.calls, stats.errors, stats.p50_ms, stats.p95_ms, stats.samples
+        );
+        for (method, counts) in &stats.methods {
+            let _ = writeln!(
+                out,
+                "  {:<32}\tcalls={}\terrors={}",
+                method, counts.calls, counts.errors
+            );
+        }
+    }
+    out
+}
+
+// Only scheme, host and port are kept: hosted RPC URLs routinely embed API keys in the path.
+pub fn endpoint_label(rpc_url: &str) -> String {
+    let Ok(url) = url::Url::parse(rpc_url) else {
+        return "unknown".to_string();
+    };
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}://{host}:{port}", url.scheme()),
+        (Some(host), None) => format!("{}://{host}", url.scheme()),
+        (None, _) => url.scheme().to_string(),
+    }
+}
+
+fn method_label(request: &RequestPacket) -> String {
+    match request {
+        RequestPacket::Single(req) => req.method().to_string(),
+        RequestPacket::Batch(_) => "batch".to_string(),
+    }
+}
+
+fn latency_percentiles(latencies: &VecDeque<Duration>) -> (Duration, Duration) {
+    if latencies.is_empty() {
+        return (Duration::ZERO, Duration::ZERO);
+    }
+    let mut sorted: Vec<Duration> = latencies.iter().copied().collect();
+    sorted.sort_unstable();
+    (percentile(&sorted, 0.50), percentile(&sorted, 0.95))
+}
+
+fn percentile(sorted: &[Duration], quantile: f64) -> Duration {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub async fn instrumented_client(rpc_url: &str, http_cfg: &HttpConfig) -> Result<RpcClient> {
+    let builder = ClientBuilder::default().layer(RpcStatsLayer::new(rpc_url));
+    match http::rpc_http_client(rpc_url, http_cfg)? {
+        Some((client, url)) => Ok(builder.http_with_client(client, url)),
+        None => builder
+            .connect(rpc_url)
+            .await
+            .with_context(|| format!("failed to connect to rpc url {rpc_url}")),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcStatsLayer {
+    endpoint: String,
+}
+
+impl RpcStatsLayer {
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            endpoint: endpoint_label(rpc_url),
+        }
+    }
+}
+
+impl<S> Layer<S> for RpcStatsLayer {
+    type Service = RpcStatsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcStatsService {
+            inner,
+            endpoint: self.endpoint.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcStatsService<S> {
+    inner: S,
+    endpoint: String,
+}
+
+impl<S> Service<RequestPacket> for RpcStatsService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Send
+        + Sync
+        + Clone
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let method = method_label(&request);
+        let endpoint = self.endpoint.clone();
+        let started = Instant::now();
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let result = response.await;
+            let failed = match &result {
+                Ok(packet) => packet.is_error(),
+                Err(_) => true,
+            };
+            RpcStats::global().record(&endpoint, &method, started.elapsed(), failed);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::U64;
+
+    use super::{RpcStats, endpoint_label, instrumented_client, render_table};
+    use crate::config::HttpConfig;
+
+    #[test]
+    fn keeps_rolling_window_and_reports_percentiles() {
+        let stats = RpcStats::new(10);
+        for ms in 1..=20 {
+            stats.record(
+                "http://rpc.test",
+                "eth_blockNumber",
+                Duration::from_millis(ms),
+                false,
+            );
+        }
+        stats.record(
+            "http://rpc.test",
+            "eth_getLogs",
+            Duration::from_millis(100),
+            true,
+        );
+
+        let endpoints = stats.endpoints();
+        assert_eq!(endpoints.len(), 1);
+        let endpoint = &endpoints[0];
+        assert_eq!(endpoint.calls, 21);
+        assert_eq!(endpoint.errors, 1);
+        assert_eq!(endpoint.samples, 10);
+        assert_eq!(endpoint.p50_ms, 16.0);
+        assert_eq!(endpoint.p95_ms, 100.0);
+        assert_eq!(endpoint.methods["eth_blockNumber"].calls, 20);
+        assert_eq!(endpoint.methods["eth_getLogs"].errors, 1);
+
+        let table = render_table(&endpoints);
+        assert!(table.contains("http://rpc.test\tcalls=21\terrors=1"));
+        assert!(table.contains("eth_getLogs"));
+    }
+
+    #[test]
+    fn endpoint_label_drops_path_and_credentials() {
+        assert_eq!(
+            endpoint_label("https://user:pw@eth.example.com/v2/secret-key"),
+            "https://eth.example.com"
+        );
+        assert_eq!(endpoint_label("ws://127.0.0.1:8546"), "ws://127.0.0.1:8546");
+        assert_eq!(endpoint_label("not a url"), "unknown");
+    }
+
+    #[tokio::test]
+    async fn http_rpc_client_goes_through_the_shared_builder_and_is_counted() {
+        let client = instrumented_client("http://127.0.0.1:9", &HttpConfig::default())
+            .await
+            .expect("http transport builds without connecting");
+        assert!(
+            client
+                .request_noparams::<U64>("eth_blockNumber")
+                .await
+                .is_err()
+        );
+
+        let endpoints = RpcStats::global().endpoints();
+        let endpoint = endpoints
+            .iter()
+            .find(|endpoint| endpoint.endpoint == "http://127.0.0.1:9")
+            .expect("endpoint recorded");
+        assert_eq!(endpoint.methods["eth_blockNumber"].errors, 1);
+    }
+}
@@ -1,19 +1,24 @@
-use std::{env, str::FromStr};
+use std::{env, path::Path, str::FromStr};
 
 use alloy::{
+    eips::BlockNumberOrTag,
     network::EthereumWallet,
-    primitives::{Address, U256},
+    primitives::{Address, Bytes, U256, utils::format_ether},
     providers::{DynProvider, Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
     sol,
 };
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use async_trait::async_trait;
 use chrono::Utc;
+use serde::Serialize;
 
 use crate::{
-    config::{NetworkConfig, SignerConfig},
-    types::{Decision, Proposal, VoteExecution},
+    chain::governor_state_error,
+    config::{HttpConfig, NetworkConfig, SignerAccount, SignerConfig, SupportMapping},
+    observability, rpc_stats,
+    storage::{VoteIntent, VoteIntentLog},
+    types::{Decision, KeyRotation, Proposal, VoteAllocation, VoteExecution},
 };
 
 sol! {
@@ -22,18 +27,227 @@ sol! {
         function state(uint256 proposalId) external view returns (uint8);
         function hasVoted(uint256 proposalId, address account) external view returns (bool);
         function castVoteWithReason(uint256 proposalId, uint8 support, string reason) external returns (uint256);
+        function castVoteWithReasonAndParams(uint256 proposalId, uint8 support, string reason, bytes params) external returns (uint256);
+        function CLOCK_MODE() external view returns (string);
+        function COUNTING_MODE() external view returns (string);
+        function proposalSnapshot(uint256 proposalId) external view returns (uint256);
+        function proposalProposer(uint256 proposalId) external view returns (address);
+        function votingDelay() external view returns (uint256);
+        function getVotes(address account, uint256 timepoint) external view returns (uint256);
+        function proposalEta(uint256 proposalId) external view returns (uint256);
+        function proposalVotes(uint256 proposalId) external view returns (uint256 againstVotes, uint256 forVotes, uint256 abstainVotes);
     }
 }
 
-const ACTIVE_PROPOSAL_STATE: u8 = 1;
+pub const ACTIVE_PROPOSAL_STATE: u8 = 1;
+// GovernorCountingFractional's support value for a split vote; the split itself goes in params.
+pub const FRACTIONAL_SUPPORT: u8 = 255;
 const GWEI_IN_WEI: u128 = 1_000_000_000;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockMode {
+    BlockNumber,
+    Timestamp,
+}
+
+impl ClockMode {
+    pub fn from_clock_mode(value: &str) -> Self {
+        let is_timestamp = value
+            .split('&')
+            .filter_map(|part| part.split_once('='))
+            .any(|(key, mode)| key.trim() == "mode" && mode.trim() == "timestamp");
+        if is_timestamp {
+            Self::Timestamp
+        } else {
+            Self::BlockNumber
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockNumber => "blocknumber",
+            Self::Timestamp => "timestamp",
+        }
+    }
+}
+
+// Used to turn a block count into time left; the governor's chain is assumed to be mainnet-like.
+pub const ASSUMED_BLOCK_SECS: u64 = 12;
+
+// "Now" on the governor's clock, in the unit its vote_start and vote_end are counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainClock {
+    pub mode: ClockMode,
+    pub now: u64,
+}
+
+impl ChainClock {
+    pub fn blocks(now: u64) -> Self {
+        Self {
+            mode: ClockMode::BlockNumber,
+            now,
+        }
+    }
+
+    pub fn left(self, vote_end: u64) -> u64 {
+        vote_end.saturating_sub(self.now)
+    }
+
+    pub fn secs_left(self, vote_end: u64) -> u64 {
+        match self.mode {
+            ClockMode::BlockNumber => self.left(vote_end).saturating_mul(ASSUMED_BLOCK_SECS),
+            ClockMode::Timestamp => self.left(vote_end),
+        }
+    }
+}
+
+// `COUNTING_MODE()` is a query string like CLOCK_MODE, e.g.
+// "support=bravo,fractional&quorum=for,abstain&params=fractional".
+pub fn supports_fractional(counting_mode: &str) -> bool {
+    counting_mode
+        .split('&')
+        .filter_map(|part| part.split_once('='))
+        .any(|(key, values)| {
+            matches!(key.trim(), "support" | "params")
+                && values.split(',').any(|value| value.trim() == "fractional")
+        })
+}
+
+// abi.encodePacked(uint128 against, uint128 for, uint128 abstain) for `weight` votes. Rounding
+// leftovers go to the largest share so the whole weight is cast.
+pub fn fractional_params(allocation: VoteAllocation, weight: U256) -> Result<Bytes> {
+    let bps = [
+        allocation.against_bps,
+        allocation.for_bps,
+        allocation.abstain_bps,
+    ];
+    let total = U256::from(VoteAllocation::TOTAL_BPS);
+    let mut shares = bps
+        .map(|bps| {
+            weight
+                .checked_mul(U256::from(bps))
+                .map(|scaled| scaled / total)
+        })
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow!("voting weight {weight} is too large to split"))?;
+    let assigned = shares.iter().fold(U256::ZERO, |sum, share| sum + share);
+    let largest = (0..bps.len()).max_by_key(|&index| bps[index]).unwrap_or(0);
+    shares[largest] += weight.saturating_sub(assigned);
+
+    let mut params = Vec::with_capacity(48);
+    for share in shares {
+        let share = u128::try_from(share)
+            .map_err(|_| anyhow!("vote share {share} does not fit in uint128"))?;
+        params.extend_from_slice(&share.to_be_bytes());
+    }
+    Ok(Bytes::from(params))
+}
+
+#[async_trait]
+pub trait GovernorClockReads: Send + Sync {
+    async fn clock_mode(&self) -> Result<String>;
+    async fn latest_block_number(&self) -> Result<u64>;
+    async fn latest_block_timestamp(&self) -> Result<u64>;
+}
+
+struct ProviderClockReads<'a> {
+    provider: &'a DynProvider,
+    governor_address: Address,
+}
+
+#[async_trait]
+impl GovernorClockReads for ProviderClockReads<'_> {
+    async fn clock_mode(&self) -> Result<String> {
+        IVfiGovernor::new(self.governor_address, self.provider.clone())
+            .CLOCK_MODE()
+            .call()
+            .await
+            .context("failed to read CLOCK_MODE")
+    }
+
+    async fn latest_block_number(&self) -> Result<u64> {
+        self.provider
+            .get_block_number()
+            .await
+            .context("failed to read latest block before vote submit")
+    }
+
+    async fn latest_block_timestamp(&self) -> Result<u64> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await
+            .context("failed to read latest block before vote submit")?
+            .ok_or_else(|| anyhow!("latest block not returned by rpc"))?;
+        Ok(block.header.timestamp)
+    }
+}
+
+pub async fn detect_clock_mode(reads: &dyn GovernorClockReads) -> ClockMode {
+    match reads.clock_mode().await {
+        Ok(value) => {
+            let mode = ClockMode::from_clock_mode(&value);
+            tracing::info!(clock_mode = %value, resolved = mode.as_str(), "detected governor clock mode");
+            mode
+        }
+        Err(err) => {
+            tracing::info!(
+                error = %err,
+                "governor does not expose CLOCK_MODE; assuming block-number clock"
+            );
+            ClockMode::BlockNumber
+        }
+    }
+}
+
+pub async fn ensure_vote_deadline_margin(
+    reads: &dyn GovernorClockReads,
+    clock_mode: ClockMode,
+    proposal: &Proposal,
+    min_vote_blocks_remaining: u64,
+    min_vote_seconds_remaining: u64,
+) -> Result<()> {
+    let (now, min_remaining, unit) = match clock_mode {
+        ClockMode::BlockNumber => (
+            reads.latest_block_number().await?,
+            min_vote_blocks_remaining,
+            "blocks",
+        ),
+        ClockMode::Timestamp => (
+            reads.latest_block_timestamp().await?,
+            min_vote_seconds_remaining,
+            "seconds",
+        ),
+    };
+
+    if proposal.vote_end <= now.saturating_add(min_remaining) {
+        return Err(anyhow!(
+            "proposal {} is too close to deadline; vote_end={} now={} clock={} min_remaining={} {}",
+            proposal.proposal_id,
+            proposal.vote_end,
+            now,
+            clock_mode.as_str(),
+            min_remaining,
+            unit
+        ));
+    }
+    Ok(())
+}
+
 #[async_trait]
 pub trait VoteExecutor: Send + Sync {
     async fn submit_vote(&self, proposal: &Proposal, decision: &Decision) -> Result<VoteExecution>;
+
+    // False for executors that only record what they would send.
+    fn is_live(&self) -> bool {
+        true
+    }
 }
 
-pub struct DryRunVoteExecutor;
+pub struct DryRunVoteExecutor {
+    pub support_mapping: SupportMapping,
+}
 
 #[async_trait]
 impl VoteExecutor for DryRunVoteExecutor {
@@ -46,46 +260,113 @@ impl VoteExecutor for DryRunVoteExecutor {
             proposal_id: decision.proposal_id.clone(),
             submitted: false,
             tx_hash: None,
-            reason: format!(
-                "dry-run: would submit support={}",
-                decision.vote.to_support_u8(),
-            ),
+            reason: match decision.allocation {
+                Some(allocation) => format!("dry-run: would submit fractional split {allocation}"),
+                None => format!(
+                    "dry-run: would submit support={}",
+                    self.support_mapping.support(decision.vote),
+                ),
+            },
             at: Utc::now(),
+            account: None,
         })
     }
+
+    fn is_live(&self) -> bool {
+        false
+    }
 }
 
 pub struct KeystoreVoteExecutor {
-    provider: DynProvider,
-    governor_address: Address,
-    signer_address: Address,
+    guard: VoteGuard,
     max_vote_reason_len: usize,
-    min_vote_blocks_remaining: u64,
     max_gas_price_gwei: Option<u64>,
     max_priority_fee_gwei: Option<u64>,
+    support_mapping: SupportMapping,
 }
 
 impl KeystoreVoteExecutor {
-    pub async fn from_config(network: &NetworkConfig, signer: &SignerConfig) -> Result<Self> {
-        let keystore_path = signer
+    pub async fn from_config(
+        network: &NetworkConfig,
+        signer: &SignerConfig,
+        account: &SignerAccount,
+        http_cfg: &HttpConfig,
+        intents: VoteIntentLog,
+        clock_mode: ClockMode,
+    ) -> Result<Self> {
+        let keystore_path = account
             .keystore_path
             .as_ref()
             .ok_or_else(|| anyhow!("auto-vote requires signer.keystore_path"))?;
 
-        let password = resolve_keystore_password(signer)?;
-
-        let signer_key = PrivateKeySigner::decrypt_keystore(keystore_path, password)
-            .with_context(|| format!("failed to decrypt keystore {}", keystore_path.display()))?;
+        let signer_key = decrypt_account(account, keystore_path)?;
         let signer_address = signer_key.address();
 
         let wallet = EthereumWallet::from(signer_key);
+        let client = rpc_stats::instrumented_client(&network.rpc_url, http_cfg).await?;
         let provider = ProviderBuilder::new()
             .wallet(wallet)
-            .connect(&network.rpc_url)
-            .await
-            .with_context(|| format!("failed to connect to rpc url {}", network.rpc_url))?
+            .connect_client(client)
             .erased();
+        let guard = VoteGuard::new(
+            provider,
+            network,
+            signer,
+            account,
+            signer_address,
+            intents,
+            clock_mode,
+        )
+        .await?;
+        tracing::info!(
+            account = %account.label,
+            signer = %observability::log_address(&signer_address.to_string()),
+            "signer executor ready"
+        );
+        tracing::debug!(account = %account.label, signer = %signer_address, "signer address");
+
+        Ok(Self {
+            guard,
+            max_vote_reason_len: signer.max_vote_reason_len,
+            max_gas_price_gwei: signer.max_gas_price_gwei,
+            max_priority_fee_gwei: signer.max_priority_fee_gwei,
+            support_mapping: signer.support_mapping,
+        })
+    }
+}
+
+// The governor-side checks every executor runs before sending a vote for `signer_address`,
+// whoever ends up paying for the transaction.
+pub(crate) struct VoteGuard {
+    pub(crate) provider: DynProvider,
+    pub(crate) governor_address: Address,
+    pub(crate) signer_address: Address,
+    pub(crate) intents: VoteIntentLog,
+    previous_addresses: Vec<Address>,
+    clock_mode: ClockMode,
+    min_vote_blocks_remaining: u64,
+    min_vote_seconds_remaining: u64,
+}
+
+pub(crate) enum Preflight {
+    // Nothing to send; the vote is already represented on chain.
+    Done(VoteExecution),
+    Send {
+        proposal_id: U256,
+        intent: Option<VoteIntent>,
+    },
+}
 
+impl VoteGuard {
+    pub(crate) async fn new(
+        provider: DynProvider,
+        network: &NetworkConfig,
+        signer: &SignerConfig,
+        account: &SignerAccount,
+        signer_address: Address,
+        intents: VoteIntentLog,
+        clock_mode: ClockMode,
+    ) -> Result<Self> {
         let governor_address = network
             .governor_address
             .parse::<Address>()
@@ -96,19 +377,201 @@ impl KeystoreVoteExecutor {
                 )
             })?;
 
+        // The signing provider is checked on its own: a vote must never be signed for another
+        // chain, even when the read backend points at the right one.
+        let chain_id = provider
+            .get_chain_id()
+            .await
+            .context("failed to read the signer RPC chain id")?;
+        if chain_id != network.chain_id && !network.allow_chain_mismatch {
+            bail!(
+                "the signer RPC reports chain id {chain_id}, but network.chain_id is {}; refusing to sign votes",
+                network.chain_id
+            );
+        }
+
+        let previous_addresses = account
+            .previous_addresses
+            .iter()
+            .map(|address| {
+                address.parse::<Address>().with_context(|| {
+                    format!(
+                        "invalid previous_addresses entry {address} for signer account {}",
+                        account.label
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
             provider,
             governor_address,
             signer_address,
-            max_vote_reason_len: signer.max_vote_reason_len,
+            intents,
+            previous_addresses,
+            clock_mode,
             min_vote_blocks_remaining: signer.min_vote_blocks_remaining,
-            max_gas_price_gwei: signer.max_gas_price_gwei,
-            max_priority_fee_gwei: signer.max_priority_fee_gwei,
+            min_vote_seconds_remaining: signer.min_vote_seconds_remaining,
+        })
+    }
+
+    pub(crate) fn clock_mode(&self) -> ClockMode {
+        self.clock_mode
+    }
+
+    pub(crate) fn governor(&self) -> IVfiGovernor::IVfiGovernorInstance<DynProvider> {
+        IVfiGovernor::new(self.governor_address, self.provider.clone())
+    }
+
+    // Refuses governors whose COUNTING_MODE has no fractional counting, then sizes the split to
+    // the signer's weight at the proposal snapshot.
+    pub(crate) async fn fractional_params(
+        &self,
+        proposal_id: U256,
+        allocation: VoteAllocation,
+    ) -> Result<Bytes> {
+        let governor = self.governor();
+        let counting_mode = governor
+            .COUNTING_MODE()
+            .call()
+            .await
+            .context("failed to read COUNTING_MODE; not sending a fractional vote")?;
+        if !supports_fractional(&counting_mode) {
+            bail!(
+                "governor COUNTING_MODE {counting_mode:?} has no fractional counting; refusing to send a fractional vote"
+            );
+        }
+        let snapshot = governor
+            .proposalSnapshot(proposal_id)
+            .call()
+            .await
+            .context("failed to read proposalSnapshot")?;
+        let weight = governor
+            .getVotes(self.signer_address, snapshot)
+            .call()
+            .await
+            .context("failed to read getVotes")?;
+        if weight.is_zero() {
+            bail!("signer has no voting weight at the proposal snapshot to split");
+        }
+        fractional_params(allocation, weight)
+    }
+
+    pub(crate) async fn preflight(
+        &self,
+        proposal: &Proposal,
+        decision: &Decision,
+    ) -> Result<Preflight> {
+        let governor = self.governor();
+        let proposal_id = parse_proposal_id(&decision.proposal_id)?;
+
+        let state = governor
+            .state(proposal_id)
+            .call()
+            .await
+            .map_err(|err| governor_state_error(&decision.proposal_id, err))?;
+        if state != ACTIVE_PROPOSAL_STATE {
+            return Err(anyhow!(
+                "proposal {} is not Active; current state={}",
+                decision.proposal_id,
+                state
+            ));
+        }
+
+        let signer = self.signer_address.to_string();
+        let intent = self.intents.get(&decision.proposal_id, &signer)?;
+        let has_voted = governor
+            .hasVoted(proposal_id, self.signer_address)
+            .call()
+            .await
+            .context("failed to read hasVoted")?;
+        if has_voted {
+            // An earlier attempt landed but crashed before its record was saved.
+            if let Some(intent) = intent {
+                self.intents.clear(&decision.proposal_id, &signer)?;
+                tracing::warn!(
+                    proposal_id = %decision.proposal_id,
+                    nonce = intent.nonce,
+                    tx_hash = intent.tx_hash.as_deref().unwrap_or("unknown"),
+                    "vote from an interrupted attempt is already on chain; recording it"
+                );
+                return Ok(Preflight::Done(VoteExecution {
+                    proposal_id: decision.proposal_id.clone(),
+                    submitted: true,
+                    tx_hash: intent.tx_hash,
+                    reason: format!(
+                        "recovered vote sent before a restart (nonce {})",
+                        intent.nonce
+                    ),
+                    at: Utc::now(),
+                    account: None,
+                }));
+            }
+            return Err(anyhow!(
+                "signer {} already voted on proposal {}",
+                observability::log_address(&self.signer_address.to_string()),
+                decision.proposal_id
+            ));
+        }
+
+        // After a key rotation, a vote cast by a retired key already represents this delegate.
+        for previous in &self.previous_addresses {
+            let voted = governor
+                .hasVoted(proposal_id, *previous)
+                .call()
+                .await
+                .context("failed to read hasVoted for a previous signer address")?;
+            if voted {
+                tracing::info!(
+                    proposal_id = %decision.proposal_id,
+                    previous_signer = %observability::log_address(&previous.to_string()),
+                    "proposal already voted by a previous signer key; not voting again"
+                );
+                return Ok(Preflight::Done(VoteExecution {
+                    proposal_id: decision.proposal_id.clone(),
+                    submitted: true,
+                    tx_hash: None,
+                    reason: format!("already voted by previous signer key {previous}"),
+                    at: Utc::now(),
+                    account: None,
+                }));
+            }
+        }
+
+        ensure_vote_deadline_margin(
+            &ProviderClockReads {
+                provider: &self.provider,
+                governor_address: self.governor_address,
+            },
+            self.clock_mode,
+            proposal,
+            self.min_vote_blocks_remaining,
+            self.min_vote_seconds_remaining,
+        )
+        .await?;
+
+        Ok(Preflight::Send {
+            proposal_id,
+            intent,
         })
     }
 }
 
+// With `signer.accounts`, the first account that cannot sign is reported with its label.
 pub fn signing_readiness_reason(signer: &SignerConfig) -> Option<String> {
+    if signer.accounts.is_empty() {
+        return signer
+            .resolved_accounts()
+            .iter()
+            .find_map(account_readiness_reason);
+    }
+    signer.accounts.iter().find_map(|account| {
+        account_readiness_reason(account)
+            .map(|reason| format!("account {}: {reason}", account.label))
+    })
+}
+
+pub fn account_readiness_reason(signer: &SignerAccount) -> Option<String> {
     let Some(keystore_path) = signer.keystore_path.as_ref() else {
         return Some("signer.keystore_path is not set".to_string());
     };
@@ -146,54 +609,18 @@ pub fn signing_readiness_reason(signer: &SignerConfig) -> Option<String> {
 #[async_trait]
 impl VoteExecutor for KeystoreVoteExecutor {
     async fn submit_vote(&self, proposal: &Proposal, decision: &Decision) -> Result<VoteExecution> {
-        let governor = IVfiGovernor::new(self.governor_address, self.provider.clone());
-        let proposal_id = parse_proposal_id(&decision.proposal_id)?;
-
-        let state = governor
-            .state(proposal_id)
-            .call()
-            .await
-            .context("failed to read proposal state")?;
-        if state != ACTIVE_PROPOSAL_STATE {
-            return Err(anyhow!(
-                "proposal {} is not Active; current state={}",
-                decision.proposal_id,
-                state
-            ));
-        }
-
-        let has_voted = governor
-            .hasVoted(proposal_id, self.signer_address)
-            .call()
-            .await
-            .context("failed to read hasVoted")?;
-        if has_voted {
-            return Err(anyhow!(
-                "signer {} already voted on proposal {}",
-                self.signer_address,
-                decision.proposal_id
-            ));
-        }
-
-        let latest_block = self
-            .provider
-            .get_block_number()
-            .await
-            .context("failed to read latest block before vote submit")?;
-        let minimum_required = latest_block.saturating_add(self.min_vote_blocks_remaining);
-        if proposal.vote_end <= minimum_required {
-            return Err(anyhow!(
-                "proposal {} is too close to deadline; vote_end={} latest_block={} min_remaining={}",
-                proposal.proposal_id,
-                proposal.vote_end,
-                latest_block,
-                self.min_vote_blocks_remaining
-            ));
-        }
+        let (proposal_id, intent) = match self.guard.preflight(proposal, decision).await? {
+            Preflight::Done(execution) => return Ok(execution),
+            Preflight::Send {
+                proposal_id,
+                intent,
+            } => (proposal_id, intent),
+        };
+        let provider = &self.guard.provider;
+        let signer_address = self.guard.signer_address;
 
         if let Some(max_gas_gwei) = self.max_gas_price_gwei {
-            let gas_price = self
-                .provider
+            let gas_price = provider
                 .get_gas_price()
                 .await
                 .context("failed to read gas price")?;
@@ -208,8 +635,7 @@ impl VoteExecutor for KeystoreVoteExecutor {
         }
 
         if let Some(max_priority_gwei) = self.max_priority_fee_gwei {
-            let priority_fee = self
-                .provider
+            let priority_fee = provider
                 .get_max_priority_fee_per_gas()
                 .await
                 .context("failed to read max priority fee per gas")?;
@@ -223,18 +649,76 @@ impl VoteExecutor for KeystoreVoteExecutor {
             }
         }
 
-        let reason = build_vote_reason(decision, self.max_vote_reason_len);
-        let pending = governor
-            .castVoteWithReason(proposal_id, decision.vote.to_support_u8(), reason.clone())
-            .send()
+        let confirmed_nonce = provider
+            .get_transaction_count(signer_address)
+            .latest()
+            .await
+            .context("failed to read signer nonce")?;
+        let pending_nonce = provider
+            .get_transaction_count(signer_address)
+            .pending()
             .await
-            .context("failed to submit castVoteWithReason tx")?;
+            .context("failed to read signer pending nonce")?;
+        let params = match decision.allocation {
+            Some(allocation) => Some(
+                self.guard
+                    .fractional_params(proposal_id, allocation)
+                    .await?,
+            ),
+            None => None,
+        };
+        let signer = signer_address.to_string();
+        let mut intent = VoteIntent {
+            proposal_id: decision.proposal_id.clone(),
+            signer: signer.clone(),
+            nonce: vote_nonce(intent.as_ref(), confirmed_nonce, pending_nonce),
+            tx_hash: None,
+            relay_task: None,
+            created_at: Utc::now(),
+        };
+        let intents = &self.guard.intents;
+        intents
+            .record(&intent)
+            .context("failed to persist vote intent; not sending the vote")?;
+
+        // From here until the receipt, a failure leaves the intent in place: the tx may already
+        // be in the mempool, so the next attempt must reconcile instead of sending blind.
+        let reason = build_vote_reason(decision, &self.support_mapping, self.max_vote_reason_len);
+        let governor = self.guard.governor();
+        let pending = match params {
+            None => governor
+                .castVoteWithReason(
+                    proposal_id,
+                    self.support_mapping.support(decision.vote),
+                    reason.clone(),
+                )
+                .nonce(intent.nonce)
+                .send()
+                .await
+                .context("failed to submit castVoteWithReason tx")?,
+            Some(params) => governor
+                .castVoteWithReasonAndParams(
+                    proposal_id,
+                    FRACTIONAL_SUPPORT,
+                    reason.clone(),
+                    params,
+                )
+                .nonce(intent.nonce)
+                .send()
+                .await
+                .context("failed to submit castVoteWithReasonAndParams tx")?,
+        };
 
         let tx_hash = format!("{:#x}", pending.tx_hash());
+        intent.tx_hash = Some(tx_hash.clone());
+        if let Err(err) = intents.record(&intent) {
+            tracing::warn!(error = %format!("{err:#}"), "failed to record vote tx hash in intent");
+        }
         let receipt = pending
             .get_receipt()
             .await
             .context("failed waiting for vote tx receipt")?;
+        intents.clear(&decision.proposal_id, &signer)?;
         if !receipt.status() {
             return Err(anyhow!("vote tx {} reverted on-chain", tx_hash));
         }
@@ -245,11 +729,205 @@ impl VoteExecutor for KeystoreVoteExecutor {
             tx_hash: Some(tx_hash),
             reason,
             at: Utc::now(),
+            account: None,
         })
     }
 }
 
-fn resolve_keystore_password(signer: &SignerConfig) -> Result<String> {
+#[derive(Debug, Serialize)]
+pub struct SignerIdentity {
+    pub account: String,
+    pub address: String,
+    pub balance_wei: String,
+    pub balance_eth: String,
+    pub clock_mode: &'static str,
+    pub votes_timepoint: u64,
+    pub votes: Option<String>,
+    pub votes_error: Option<String>,
+    pub has_voting_power: bool,
+}
+
+// Read-only: decrypts the keystore to learn the address, then only issues view calls.
+pub async fn whoami(
+    network: &NetworkConfig,
+    account: &SignerAccount,
+    http_cfg: &HttpConfig,
+) -> Result<SignerIdentity> {
+    let keystore_path = account
+        .keystore_path
+        .as_ref()
+        .ok_or_else(|| anyhow!("account {} has no keystore_path", account.label))?;
+    let address = decrypt_account(account, keystore_path)?.address();
+
+    let client = rpc_stats::instrumented_client(&network.rpc_url, http_cfg).await?;
+    let provider = ProviderBuilder::new().connect_client(client).erased();
+    let balance = provider
+        .get_balance(address)
+        .await
+        .with_context(|| format!("failed to read balance of {address}"))?;
+
+    let governor_address = network
+        .governor_address
+        .parse::<Address>()
+        .with_context(|| {
+            format!(
+                "invalid governor address configured: {}",
+                network.governor_address
+            )
+        })?;
+    let reads = ProviderClockReads {
+        provider: &provider,
+        governor_address,
+    };
+    let clock_mode = detect_clock_mode(&reads).await;
+    let timepoint = latest_past_timepoint(&reads, clock_mode).await?;
+    let votes = IVfiGovernor::new(governor_address, provider.clone())
+        .getVotes(address, U256::from(timepoint))
+        .call()
+        .await
+        .context("failed to read getVotes from governor");
+
+    Ok(SignerIdentity {
+        account: account.label.clone(),
+        address: address.to_string(),
+        balance_wei: balance.to_string(),
+        balance_eth: format_ether(balance),
+        clock_mode: clock_mode.as_str(),
+        votes_timepoint: timepoint,
+        has_voting_power: votes.as_ref().is_ok_and(|votes| !votes.is_zero()),
+        votes: votes.as_ref().ok().map(ToString::to_string),
+        votes_error: votes.err().map(|err| format!("{err:#}")),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystoreCheck {
+    pub account: String,
+    pub keystore_path: Option<String>,
+    pub password_source: String,
+    pub address: Option<String>,
+    pub error: Option<String>,
+    pub hint: Option<&'static str>,
+}
+
+impl KeystoreCheck {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// Offline: only reads the keystore file and the password, so it is safe to run anywhere.
+pub fn test_keystore(account: &SignerAccount) -> KeystoreCheck {
+    let password_source = match (&account.keystore_password, &account.keystore_password_env) {
+        (Some(_), _) => "config keystore_password".to_string(),
+        (None, Some(name)) => format!("env {name}"),
+        (None, None) => "env GOV_AGENT_KEYSTORE_PASSWORD".to_string(),
+    };
+    let mut check = KeystoreCheck {
+        account: account.label.clone(),
+        keystore_path: account
+            .keystore_path
+            .as_ref()
+            .map(|path| path.display().to_string()),
+        password_source,
+        address: None,
+        error: None,
+        hint: None,
+    };
+    let result = account
+        .keystore_path
+        .as_ref()
+        .ok_or_else(|| anyhow!("account {} has no keystore_path", account.label))
+        .and_then(|path| decrypt_account(account, path));
+    match result {
+        Ok(signer) => check.address = Some(signer.address().to_string()),
+        Err(err) => {
+            check.hint = keystore_error_hint(&err);
+            check.error = Some(format!("{err:#}"));
+        }
+    }
+    check
+}
+
+fn keystore_error_hint(err: &anyhow::Error) -> Option<&'static str> {
+    let message = format!("{err:#}");
+    if message.contains("Mac Mismatch") {
+        Some("the password does not decrypt this keystore")
+    } else if message.contains("keystore password is not set") {
+        Some("set the password env var or signer.keystore_password")
+    } else if message.contains("has no keystore_path") {
+        Some("set signer.keystore_path")
+    } else if message.contains("IO:") {
+        Some("the keystore file could not be read; check the path and permissions")
+    } else if message.contains("serde-json") {
+        Some("the file is not a JSON v3 keystore")
+    } else {
+        None
+    }
+}
+
+// Offline like `test_keystore`. The new keystore is decrypted with the password settings it will
+// be used with, and the current one only to learn the address being retired.
+pub fn rotate_keystore(signer: &SignerConfig, new_keystore: &Path) -> Result<KeyRotation> {
+    if !signer.accounts.is_empty() {
+        bail!(
+            "signer rotate replaces the top-level signer key; with signer.accounts, edit the account's keystore_path instead"
+        );
+    }
+    let current = signer
+        .resolved_accounts()
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("no signer account is configured"))?;
+    let old_keystore = current
+        .keystore_path
+        .clone()
+        .ok_or_else(|| anyhow!("signer.keystore_path is not set; there is no key to rotate"))?;
+    let old_address = decrypt_account(&current, &old_keystore)
+        .context("failed to decrypt the current keystore")?
+        .address();
+
+    let next = SignerAccount {
+        keystore_path: Some(new_keystore.to_path_buf()),
+        ..current
+    };
+    let new_address = decrypt_account(&next, new_keystore)
+        .map_err(|err| match keystore_error_hint(&err) {
+            Some(hint) => err.context(format!("new keystore check failed: {hint}")),
+            None => err.context("new keystore check failed"),
+        })?
+        .address();
+    if new_address == old_address {
+        bail!("new keystore holds the current signer address {old_address}");
+    }
+
+    Ok(KeyRotation {
+        old_address: old_address.to_string(),
+        new_address: new_address.to_string(),
+        keystore_path: new_keystore.display().to_string(),
+        rotated_at: Utc::now(),
+    })
+}
+
+// OpenZeppelin governors reject `getVotes` at the current clock, so read one tick back.
+async fn latest_past_timepoint(reads: &dyn GovernorClockReads, mode: ClockMode) -> Result<u64> {
+    let now = match mode {
+        ClockMode::BlockNumber => reads.latest_block_number().await?,
+        ClockMode::Timestamp => reads.latest_block_timestamp().await?,
+    };
+    Ok(now.saturating_sub(1))
+}
+
+pub(crate) fn decrypt_account(
+    account: &SignerAccount,
+    keystore_path: &Path,
+) -> Result<PrivateKeySigner> {
+    let password = resolve_keystore_password(account)?;
+    PrivateKeySigner::decrypt_keystore(keystore_path, password)
+        .with_context(|| format!("failed to decrypt keystore {}", keystore_path.display()))
+}
+
+fn resolve_keystore_password(signer: &SignerAccount) -> Result<String> {
     if let Some(value) = &signer.keystore_password {
         return Ok(value.clone());
     }
@@ -284,12 +962,26 @@ fn parse_proposal_id(value: &str) -> Result<U256> {
     U256::from_str(trimmed).map_err(|err| anyhow!("invalid decimal proposal id {}: {}", value, err))
 }
 
-pub fn build_vote_reason(decision: &Decision, max_len: usize) -> String {
-    let mut text = format!(
-        "gov-agent vote={}; {}",
-        decision.vote.to_support_u8(),
-        decision.reasons.join(" | ")
-    );
+// Reusing an unsettled intent's nonce means a retry can only replace the earlier tx, never land a
+// second vote next to it. Once the confirmed nonce has moved past it, that tx is settled either
+// way (hasVoted already said it did not vote), so a fresh nonce is safe.
+fn vote_nonce(intent: Option<&VoteIntent>, confirmed_nonce: u64, pending_nonce: u64) -> u64 {
+    match intent {
+        Some(intent) if intent.nonce >= confirmed_nonce => intent.nonce,
+        _ => pending_nonce,
+    }
+}
+
+pub fn build_vote_reason(
+    decision: &Decision,
+    support_mapping: &SupportMapping,
+    max_len: usize,
+) -> String {
+    let support = match decision.allocation {
+        Some(_) => FRACTIONAL_SUPPORT,
+        None => support_mapping.support(decision.vote),
+    };
+    let mut text = format!("gov-agent vote={support}; {}", decision.reasons.join(" | "));
 
     if !decision.blocking_findings.is_empty() {
         text.push_str("; blockers=");
@@ -311,18 +1003,241 @@ pub fn build_vote_reason(decision: &Decision, max_len: usize) -> String {
     text
 }
 
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::{Result, bail};
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::VoteExecutor;
+    use crate::types::{Decision, Proposal, VoteChoice, VoteExecution};
+
+    #[derive(Clone, Default)]
+    pub(crate) struct MockVoteExecutor {
+        pub calls: Arc<Mutex<Vec<(String, VoteChoice)>>>,
+        pub fail: bool,
+    }
+
+    impl MockVoteExecutor {
+        pub fn recorded(&self) -> Vec<(String, VoteChoice)> {
+            self.calls.lock().expect("mock executor lock").clone()
+        }
+    }
+
+    #[async_trait]
+    impl VoteExecutor for MockVoteExecutor {
+        async fn submit_vote(
+            &self,
+            proposal: &Proposal,
+            decision: &Decision,
+        ) -> Result<VoteExecution> {
+            self.calls
+                .lock()
+                .expect("mock executor lock")
+                .push((proposal.proposal_id.clone(), decision.vote));
+            if self.fail {
+                bail!("mock executor rejected the vote");
+            }
+            Ok(VoteExecution {
+                proposal_id: decision.proposal_id.clone(),
+                submitted: true,
+                tx_hash: Some(format!("0xmock{}", proposal.proposal_id)),
+                reason: "mock".to_string(),
+                at: Utc::now(),
+                account: None,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs, path::PathBuf};
 
+    use alloy::primitives::U256;
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
     use chrono::Utc;
 
     use crate::{
-        config::SignerConfig,
-        types::{Decision, VoteChoice},
+        config::{SignerAccount, SignerConfig, SupportMapping},
+        storage::VoteIntent,
+        types::{Decision, DecodedAction, Proposal, VoteAllocation, VoteChoice},
+    };
+
+    use super::{
+        ClockMode, GovernorClockReads, build_vote_reason, detect_clock_mode,
+        ensure_vote_deadline_margin, fractional_params, latest_past_timepoint, rotate_keystore,
+        signing_readiness_reason, supports_fractional, test_keystore, vote_nonce,
     };
 
-    use super::{build_vote_reason, signing_readiness_reason};
+    struct MockClockReads {
+        clock_mode: Option<&'static str>,
+        block_number: u64,
+        block_timestamp: u64,
+    }
+
+    #[async_trait]
+    impl GovernorClockReads for MockClockReads {
+        async fn clock_mode(&self) -> Result<String> {
+            self.clock_mode
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("execution reverted"))
+        }
+
+        async fn latest_block_number(&self) -> Result<u64> {
+            Ok(self.block_number)
+        }
+
+        async fn latest_block_timestamp(&self) -> Result<u64> {
+            Ok(self.block_timestamp)
+        }
+    }
+
+    fn proposal_ending_at(vote_end: u64) -> Proposal {
+        Proposal {
+            proposal_id: "1".to_string(),
+            proposer: "0x0000000000000000000000000000000000000001".to_string(),
+            description: "test".to_string(),
+            vote_start: 0,
+            vote_end,
+            block_number: 0,
+            tx_hash: None,
+            targets: vec![],
+            values: vec![],
+            calldatas: vec![],
+            actions: vec![DecodedAction::Unsupported {
+                reason: "test".to_string(),
+            }],
+            discovered_at: Utc::now(),
+            creation_context: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn voting_power_is_read_one_clock_tick_back() {
+        let reads = MockClockReads {
+            clock_mode: None,
+            block_number: 100,
+            block_timestamp: 1_700_000_000,
+        };
+        assert_eq!(
+            latest_past_timepoint(&reads, ClockMode::BlockNumber)
+                .await
+                .expect("block timepoint"),
+            99
+        );
+        assert_eq!(
+            latest_past_timepoint(&reads, ClockMode::Timestamp)
+                .await
+                .expect("timestamp timepoint"),
+            1_699_999_999
+        );
+    }
+
+    #[tokio::test]
+    async fn block_number_clock_uses_blocks_remaining() {
+        let reads = MockClockReads {
+            clock_mode: Some("mode=blocknumber&from=default"),
+            block_number: 100,
+            block_timestamp: 1_700_000_000,
+        };
+        let mode = detect_clock_mode(&reads).await;
+        assert_eq!(mode, ClockMode::BlockNumber);
+
+        assert!(
+            ensure_vote_deadline_margin(&reads, mode, &proposal_ending_at(110), 3, 36)
+                .await
+                .is_ok()
+        );
+        assert!(
+            ensure_vote_deadline_margin(&reads, mode, &proposal_ending_at(103), 3, 36)
+                .await
+                .is_err()
+        );
+
+        let legacy = MockClockReads {
+            clock_mode: None,
+            ..reads
+        };
+        assert_eq!(detect_clock_mode(&legacy).await, ClockMode::BlockNumber);
+    }
+
+    #[tokio::test]
+    async fn timestamp_clock_uses_seconds_remaining() {
+        let reads = MockClockReads {
+            clock_mode: Some("mode=timestamp"),
+            block_number: 100,
+            block_timestamp: 1_700_000_000,
+        };
+        let mode = detect_clock_mode(&reads).await;
+        assert_eq!(mode, ClockMode::Timestamp);
+
+        assert!(
+            ensure_vote_deadline_margin(&reads, mode, &proposal_ending_at(1_700_000_100), 3, 36)
+                .await
+                .is_ok()
+        );
+        let err =
+            ensure_vote_deadline_margin(&reads, mode, &proposal_ending_at(1_700_000_030), 3, 36)
+                .await
+                .expect_err("inside seconds margin");
+        assert!(err.to_string().contains("clock=timestamp"));
+    }
+
+    #[test]
+    fn retry_reuses_the_nonce_of_an_unsettled_intent() {
+        let intent = VoteIntent {
+            proposal_id: "1".to_string(),
+            signer: "0x0000000000000000000000000000000000000001".to_string(),
+            nonce: 5,
+            tx_hash: Some("0xabc".to_string()),
+            relay_task: None,
+            created_at: Utc::now(),
+        };
+        assert_eq!(vote_nonce(None, 5, 6), 6);
+        // The earlier tx may still be pending: only a replacement with the same nonce is allowed.
+        assert_eq!(vote_nonce(Some(&intent), 5, 6), 5);
+        // Nonce 5 was mined without a vote (reverted or replaced), so a new tx is needed.
+        assert_eq!(vote_nonce(Some(&intent), 6, 6), 6);
+    }
+
+    #[test]
+    fn fractional_votes_need_fractional_counting_and_pack_the_whole_weight() {
+        assert!(supports_fractional(
+            "support=bravo,fractional&quorum=for,abstain&params=fractional"
+        ));
+        assert!(!supports_fractional("support=bravo&quorum=for,abstain"));
+        assert!(!supports_fractional(""));
+
+        let allocation = VoteAllocation {
+            against_bps: 0,
+            for_bps: 7_000,
+            abstain_bps: 3_000,
+        };
+        let params = fractional_params(allocation, U256::from(1_001u64)).expect("encode");
+        assert_eq!(params.len(), 48);
+        let share = |index: usize| {
+            u128::from_be_bytes(params[index * 16..(index + 1) * 16].try_into().expect("16"))
+        };
+        // 700.7 and 300.3 round down; the leftover vote goes to the larger For share.
+        assert_eq!((share(0), share(1), share(2)), (0, 701, 300));
+
+        let decision = Decision {
+            proposal_id: "1".to_string(),
+            vote: VoteChoice::For,
+            reasons: vec!["fractional split".to_string()],
+            blocking_findings: Vec::new(),
+            requires_human_override: false,
+            decided_at: Utc::now(),
+            config_hash: None,
+            allocation: Some(allocation),
+        };
+        let reason = build_vote_reason(&decision, &SupportMapping::default(), 120);
+        assert!(reason.starts_with("gov-agent vote=255; "));
+    }
 
     #[test]
     fn vote_reason_is_truncated() {
@@ -333,9 +1248,11 @@ mod tests {
             blocking_findings: Vec::new(),
             requires_human_override: false,
             decided_at: Utc::now(),
+            config_hash: None,
+            allocation: None,
         };
 
-        let reason = build_vote_reason(&decision, 120);
+        let reason = build_vote_reason(&decision, &SupportMapping::default(), 120);
         assert_eq!(reason.len(), 120);
     }
 
@@ -348,9 +1265,11 @@ mod tests {
             blocking_findings: Vec::new(),
             requires_human_override: false,
             decided_at: Utc::now(),
+            config_hash: None,
+            allocation: None,
         };
 
-        let reason = build_vote_reason(&decision, 121);
+        let reason = build_vote_reason(&decision, &SupportMapping::default(), 121);
         assert!(reason.len() <= 121);
         assert!(reason.is_char_boundary(reason.len()));
     }
@@ -363,8 +1282,14 @@ mod tests {
             keystore_password: None,
             max_vote_reason_len: 240,
             min_vote_blocks_remaining: 3,
+            min_vote_seconds_remaining: 36,
             max_gas_price_gwei: Some(200),
             max_priority_fee_gwei: Some(5),
+            require_ready: false,
+            accounts: Vec::new(),
+            support_mapping: SupportMapping::default(),
+            previous_addresses: Vec::new(),
+            forwarder: None,
         };
 
         let reason = signing_readiness_reason(&signer);
@@ -392,12 +1317,122 @@ mod tests {
             keystore_password: Some("password".to_string()),
             max_vote_reason_len: 240,
             min_vote_blocks_remaining: 3,
+            min_vote_seconds_remaining: 36,
             max_gas_price_gwei: Some(200),
             max_priority_fee_gwei: Some(5),
+            require_ready: false,
+            accounts: Vec::new(),
+            support_mapping: SupportMapping::default(),
+            previous_addresses: Vec::new(),
+            forwarder: None,
         };
 
         let reason = signing_readiness_reason(&signer);
         let _ = fs::remove_file(&path);
         assert!(reason.is_none());
     }
+
+    // Anvil's first dev key, encrypted with the password "correct horse" and a cheap scrypt cost.
+    const TEST_KEYSTORE: &str = r#"{"crypto":{"cipher":"aes-128-ctr","cipherparams":{"iv":"000102030405060708090a0b0c0d0e0f"},"ciphertext":"87840be9878a91a6c0485f30d85c12152bf88b1d7553acf455dd8666d79b5172","kdf":"scrypt","kdfparams":{"dklen":32,"n":16,"p":1,"r":8,"salt":"000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"},"mac":"b711884b86c22c473e4e0b78ba201fc205869e9e3e4aaa5250ca9c33334813cc"},"id":"0e0d6c2c-7d1f-4f8e-9a3b-5b7d2f0c9a11","version":3}"#;
+
+    #[test]
+    fn test_keystore_reports_the_address_or_the_decryption_error() {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "gov-agent-test-keystore-decrypt-{}-{}.json",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::write(&path, TEST_KEYSTORE).expect("write temp keystore");
+        let account = |password: &str| SignerAccount {
+            label: "voter".to_string(),
+            keystore_path: Some(path.clone()),
+            keystore_password_env: None,
+            keystore_password: Some(password.to_string()),
+            previous_addresses: Vec::new(),
+        };
+
+        let ok = test_keystore(&account("correct horse"));
+        assert!(ok.ok(), "{:?}", ok.error);
+        assert_eq!(
+            ok.address.as_deref(),
+            Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+        assert_eq!(ok.password_source, "config keystore_password");
+
+        let wrong = test_keystore(&account("battery staple"));
+        assert!(wrong.address.is_none());
+        assert!(wrong.error.as_deref().unwrap().contains("Mac Mismatch"));
+        assert_eq!(
+            wrong.hint,
+            Some("the password does not decrypt this keystore")
+        );
+
+        let _ = fs::remove_file(&path);
+        let missing = test_keystore(&account("correct horse"));
+        assert!(!missing.ok());
+        assert!(missing.hint.unwrap().contains("could not be read"));
+    }
+
+    // Anvil's second dev key, same password and scrypt cost as TEST_KEYSTORE.
+    const NEXT_KEYSTORE: &str = r#"{"crypto":{"cipher":"aes-128-ctr","cipherparams":{"iv":"000102030405060708090a0b0c0d0e0f"},"ciphertext":"724be609dd9f11e00be8b0e2faf0be08bccab9bf5632888ce951ab264811c7ff","kdf":"scrypt","kdfparams":{"dklen":32,"n":16,"p":1,"r":8,"salt":"000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"},"mac":"799a60eb37d762b7a4841f74f0f60c7051d1e5f35b99cc6de014238beb0e8742"},"id":"5a4d1c3b-2e6f-4a8b-9c0d-1e2f3a4b5c6d","version":3}"#;
+
+    #[test]
+    fn rotation_needs_a_decryptable_keystore_for_a_different_address() {
+        let dir = std::env::temp_dir().join(format!(
+            "gov-agent-test-rotate-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let (current, next, junk) = (
+            dir.join("current.json"),
+            dir.join("next.json"),
+            dir.join("junk.json"),
+        );
+        fs::write(&current, TEST_KEYSTORE).expect("write current keystore");
+        fs::write(&next, NEXT_KEYSTORE).expect("write next keystore");
+        fs::write(&junk, b"{}").expect("write junk keystore");
+        let mut signer = SignerConfig {
+            keystore_path: Some(current.clone()),
+            keystore_password_env: None,
+            keystore_password: Some("correct horse".to_string()),
+            max_vote_reason_len: 240,
+            min_vote_blocks_remaining: 3,
+            min_vote_seconds_remaining: 36,
+            max_gas_price_gwei: None,
+            max_priority_fee_gwei: None,
+            require_ready: false,
+            accounts: Vec::new(),
+            support_mapping: SupportMapping::default(),
+            previous_addresses: Vec::new(),
+            forwarder: None,
+        };
+
+        let rotation = rotate_keystore(&signer, &next).expect("rotation validates");
+        assert_eq!(
+            rotation.old_address,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert_eq!(
+            rotation.new_address,
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+        );
+        assert_eq!(rotation.keystore_path, next.display().to_string());
+
+        let same = rotate_keystore(&signer, &current).expect_err("same key");
+        assert!(same.to_string().contains("current signer address"));
+        let junk = rotate_keystore(&signer, &junk).expect_err("not a keystore");
+        assert!(
+            junk.to_string()
+                .contains("new keystore check failed: the file is not a JSON v3 keystore"),
+            "{junk:#}"
+        );
+
+        signer.accounts = signer.resolved_accounts();
+        let err = rotate_keystore(&signer, &next).expect_err("multi-account config");
+        assert!(err.to_string().contains("signer.accounts"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
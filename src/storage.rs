@@ -1,19 +1,224 @@
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Context, Result};
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Duration, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::{config::StorageConfig, types::ProcessedProposal};
+use crate::{
+    bundle_index::BundleIndex,
+    canary::CanaryState,
+    chain::parse_proposal_id,
+    config::StorageConfig,
+    export::ExportOutbox,
+    reputation::ReputationBook,
+    rpc_stats::RpcStatsSnapshot,
+    types::{
+        ArchivedProposal, CatchUpProgress, CursorRewind, KeyRotation, LlmAudit, PendingReview,
+        ProcessedProposal, ReviewResult, SkippedProposal,
+    },
+};
+
+const SHORT_ID_HEX_LEN: usize = 8;
+pub const CURRENT_STATE_VERSION: u32 = 3;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const AUDIT_DIR: &str = "audits";
+const ARCHIVE_DIR: &str = "archive";
+const FAILED_LOGS_DIR: &str = "failed_logs";
+const PAUSE_FILE: &str = "PAUSE_VOTING";
+const VOTE_INTENTS_FILE: &str = "vote_intents.json";
+
+// MIGRATIONS[n] upgrades a version-n document to version n + 1 in place.
+type Migration = fn(&mut Value) -> Result<()>;
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
 
 #[derive(Debug, Clone)]
 pub struct Storage {
+    base_path: PathBuf,
     state_path: PathBuf,
+    compress: bool,
+    lock_path: PathBuf,
+    data_dir: PathBuf,
+    backup_count: usize,
+    max_inline_audit_bytes: usize,
+}
+
+// Written before a vote tx is sent and cleared once its outcome is known. It lives outside the
+// state file, which is only saved at the end of a cycle, so a crash in between still leaves a
+// record for the next attempt to reconcile against the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteIntent {
+    pub proposal_id: String,
+    pub signer: String,
+    pub nonce: u64,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    // Set by the forwarder executor once a relay accepted the request; later cycles poll it
+    // instead of signing a second one.
+    #[serde(default)]
+    pub relay_task: Option<PendingRelay>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingRelay {
+    pub task_id: String,
+    pub deadline: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct VoteIntentLog {
+    path: PathBuf,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug)]
+pub struct StateLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+        tracing::debug!(path = %self.path.display(), "released state lock");
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct State {
+    #[serde(default)]
+    pub version: u32,
     pub last_scanned_block: u64,
     pub proposals: BTreeMap<String, ProcessedProposal>,
+    #[serde(default)]
+    pub pending_reviews: BTreeMap<String, PendingReview>,
+    #[serde(default)]
+    pub rpc_stats: Option<RpcStatsSnapshot>,
+    #[serde(default)]
+    pub reputation: ReputationBook,
+    #[serde(default)]
+    pub bundles: BundleIndex,
+    #[serde(default)]
+    pub deadline_alerts: BTreeSet<String>,
+    #[serde(default)]
+    pub export_outbox: ExportOutbox,
+    // Proposals found after their voting window closed; never reviewed.
+    #[serde(default)]
+    pub skipped_proposals: BTreeMap<String, SkippedProposal>,
+    #[serde(default)]
+    pub key_rotations: Vec<KeyRotation>,
+    #[serde(default)]
+    pub canary: CanaryState,
+    #[serde(default)]
+    pub cursor_rewinds: Vec<CursorRewind>,
+    #[serde(default)]
+    pub archived: BTreeMap<String, ArchivedProposal>,
+    #[serde(default)]
+    pub last_pruned_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub catch_up: Option<CatchUpProgress>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_STATE_VERSION,
+            last_scanned_block: 0,
+            proposals: BTreeMap::new(),
+            pending_reviews: BTreeMap::new(),
+            rpc_stats: None,
+            reputation: ReputationBook::default(),
+            bundles: BundleIndex::default(),
+            deadline_alerts: BTreeSet::new(),
+            export_outbox: ExportOutbox::default(),
+            skipped_proposals: BTreeMap::new(),
+            key_rotations: Vec::new(),
+            canary: CanaryState::default(),
+            cursor_rewinds: Vec::new(),
+            archived: BTreeMap::new(),
+            last_pruned_at: None,
+            catch_up: None,
+        }
+    }
+}
+
+impl State {
+    // `0x` hex, or a number longer than a short handle, is a full id and only ever matches
+    // itself; an id missing from state passes through rather than matching a longer one.
+    pub fn resolve_proposal_id(&self, input: &str) -> Result<String> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            bail!("proposal id is empty");
+        }
+
+        let exact = parse_proposal_id(trimmed).ok();
+        if let Some(requested) = exact
+            && let Some(key) = self
+                .known_ids()
+                .find(|key| parse_proposal_id(key).ok() == Some(requested))
+        {
+            return Ok(key.clone());
+        }
+        let full = trimmed.starts_with("0x")
+            || trimmed.starts_with("0X")
+            || trimmed.len() > SHORT_ID_HEX_LEN;
+        if let Some(requested) = exact
+            && full
+        {
+            return Ok(requested.to_string());
+        }
+
+        let hex_prefix = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .unwrap_or(trimmed)
+            .to_ascii_lowercase();
+        let candidates = self
+            .known_ids()
+            .filter(|key| {
+                key.starts_with(trimmed)
+                    || parse_proposal_id(key)
+                        .is_ok_and(|id| format!("{id:x}").starts_with(&hex_prefix))
+            })
+            .collect::<Vec<_>>();
+
+        match candidates.as_slice() {
+            [single] => Ok((*single).clone()),
+            [] => exact.map(|id| id.to_string()).ok_or_else(|| {
+                anyhow!("proposal id {trimmed} is not a valid id or a prefix of a known proposal")
+            }),
+            many => Err(anyhow!(
+                "proposal id prefix {trimmed} is ambiguous; candidates: {}",
+                many.iter()
+                    .map(|key| format!("{} ({})", short_proposal_id(key), key))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    // Reviewed proposals, whether still in state or archived.
+    pub fn known_ids(&self) -> impl Iterator<Item = &String> {
+        self.proposals.keys().chain(self.archived.keys())
+    }
+
+    pub fn is_known(&self, proposal_id: &str) -> bool {
+        self.proposals.contains_key(proposal_id) || self.archived.contains_key(proposal_id)
+    }
+}
+
+pub fn short_proposal_id(proposal_id: &str) -> String {
+    match parse_proposal_id(proposal_id) {
+        Ok(id) => format!("{id:x}").chars().take(SHORT_ID_HEX_LEN).collect(),
+        Err(_) => proposal_id.chars().take(SHORT_ID_HEX_LEN).collect(),
+    }
 }
 
 impl Storage {
@@ -21,40 +226,1263 @@ impl Storage {
         fs::create_dir_all(&cfg.data_dir).with_context(|| {
             format!("failed to create data directory {}", cfg.data_dir.display())
         })?;
+        let base_path = cfg.data_dir.join(&cfg.state_file);
+        let state_path = if cfg.compress {
+            suffixed_path(&base_path, "gz")
+        } else {
+            base_path.clone()
+        };
         Ok(Self {
-            state_path: cfg.data_dir.join(&cfg.state_file),
+            base_path,
+            state_path,
+            compress: cfg.compress,
+            lock_path: cfg.data_dir.join(&cfg.lock_file),
+            data_dir: cfg.data_dir.clone(),
+            backup_count: cfg.backup_count,
+            max_inline_audit_bytes: cfg.max_inline_audit_bytes,
         })
     }
 
+    pub fn lock(&self, force_unlock: bool) -> Result<StateLock> {
+        if force_unlock && self.lock_path.exists() {
+            tracing::warn!(
+                path = %self.lock_path.display(),
+                "force-unlock requested; replacing existing state lock"
+            );
+            fs::remove_file(&self.lock_path).with_context(|| {
+                format!("failed to remove lock file {}", self.lock_path.display())
+            })?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.lock_path)
+            .with_context(|| format!("failed to open lock file {}", self.lock_path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let holder = fs::read_to_string(&self.lock_path).unwrap_or_default();
+                let holder = match holder.trim() {
+                    "" => "unknown process".to_string(),
+                    owner => owner.to_string(),
+                };
+                bail!(
+                    "state lock {} is held by another gov-agent instance ({holder}); stop it or rerun with --force-unlock",
+                    self.lock_path.display()
+                );
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(err)
+                    .with_context(|| format!("failed to lock {}", self.lock_path.display()));
+            }
+        }
+
+        file.set_len(0)
+            .and_then(|()| writeln!(file, "pid={}", std::process::id()))
+            .with_context(|| format!("failed to write lock file {}", self.lock_path.display()))?;
+        tracing::debug!(path = %self.lock_path.display(), "acquired state lock");
+        Ok(StateLock {
+            file,
+            path: self.lock_path.clone(),
+        })
+    }
+
+    // Backups keep the uncompressed base name; their format is detected from the content.
+    pub fn backup_path(&self, generation: usize) -> PathBuf {
+        suffixed_path(&self.base_path, &generation.to_string())
+    }
+
     pub fn state_path(&self) -> &PathBuf {
         &self.state_path
     }
 
     pub fn load(&self) -> Result<State> {
-        if !self.state_path.exists() {
+        let Some(path) = self.current_path() else {
             return Ok(State::default());
-        }
+        };
 
-        let raw = fs::read_to_string(&self.state_path)
-            .with_context(|| format!("failed to read {}", self.state_path.display()))?;
-        let parsed: State = serde_json::from_str(&raw)
-            .with_context(|| format!("failed to parse {}", self.state_path.display()))?;
-        Ok(parsed)
+        let err = match read_state(&path) {
+            Ok(state) => return Ok(state),
+            // Falling back to an older backup would silently downgrade newer state.
+            Err(err) if err.downcast_ref::<UnsupportedStateVersion>().is_some() => return Err(err),
+            Err(err) => err,
+        };
+        for generation in 1..=self.backup_count {
+            let backup = self.backup_path(generation);
+            if !backup.exists() {
+                continue;
+            }
+            match read_state(&backup) {
+                Ok(state) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        backup = %backup.display(),
+                        error = %format!("{err:#}"),
+                        "state file is unreadable; loaded most recent valid backup"
+                    );
+                    return Ok(state);
+                }
+                Err(backup_err) => tracing::warn!(
+                    backup = %backup.display(),
+                    error = %format!("{backup_err:#}"),
+                    "state backup is unreadable; trying older backup"
+                ),
+            }
+        }
+        Err(err)
     }
 
     pub fn save(&self, state: &State) -> Result<()> {
-        let mut tmp = self.state_path.clone();
-        tmp.set_extension("json.tmp");
+        let mut value = serde_json::to_value(state)?;
+        self.externalize_audits(&mut value)?;
+        let json = serde_json::to_vec_pretty(&value)?;
+        let current_path = self.current_path();
+        let current = current_path.as_ref().and_then(|path| fs::read(path).ok());
+        if current_path.as_ref() == Some(&self.state_path)
+            && current
+                .as_deref()
+                .and_then(|raw| decode_state_bytes(raw).ok())
+                .is_some_and(|current| current == json)
+        {
+            return Ok(());
+        }
 
-        let data = serde_json::to_vec_pretty(state)?;
-        fs::write(&tmp, data).with_context(|| format!("failed to write {}", tmp.display()))?;
-        fs::rename(&tmp, &self.state_path).with_context(|| {
+        let tmp = self.tmp_path();
+        write_synced(&tmp, &self.encode(&json)?)?;
+        // Never rotate a corrupt primary over the last good backup.
+        if let Some(path) = &current_path
+            && current.is_some_and(|current| parse_state(&current).is_ok())
+        {
+            self.rotate_backups(path)?;
+        }
+        self.replace_state_file(&tmp)
+    }
+
+    pub fn restore_backup(&self, generation: usize) -> Result<State> {
+        if generation == 0 {
+            bail!("backup generation must be >= 1");
+        }
+        let backup = self.backup_path(generation);
+        if !backup.exists() {
+            bail!("backup {} does not exist", backup.display());
+        }
+        let state = read_state(&backup)?;
+
+        if let Some(current) = self.current_path() {
+            let previous = suffixed_path(&current, "pre-restore");
+            fs::copy(&current, &previous).with_context(|| {
+                format!(
+                    "failed to keep current state as {} before restore",
+                    previous.display()
+                )
+            })?;
+        }
+
+        let raw =
+            fs::read(&backup).with_context(|| format!("failed to read {}", backup.display()))?;
+        let tmp = self.tmp_path();
+        write_synced(&tmp, &self.encode(&decode_state_bytes(&raw)?)?)?;
+        self.replace_state_file(&tmp)?;
+        Ok(state)
+    }
+
+    // Audits over `max_inline_audit_bytes` keep only provider, model, and body hashes in state;
+    // the prompt/response text moves to data_dir/audits so the hot state stays small. A failed
+    // artifact write leaves that audit inline rather than failing the save.
+    fn externalize_audits(&self, state: &mut Value) -> Result<()> {
+        let Some(proposals) = state.get_mut("proposals").and_then(Value::as_object_mut) else {
+            return Ok(());
+        };
+        for (key, processed) in proposals.iter_mut() {
+            let Some(review) = processed.get_mut("review").and_then(Value::as_object_mut) else {
+                continue;
+            };
+            let Some(audit) = review.get("llm_audit").filter(|audit| !audit.is_null()) else {
+                continue;
+            };
+            let audit = serde_json::from_value::<LlmAudit>(audit.clone())?;
+            // Already cut over on an earlier save; its artifact holds the text.
+            if audit.prompt_keccak256.is_some() {
+                continue;
+            }
+            let data = serde_json::to_vec_pretty(&audit)?;
+            if data.len() <= self.max_inline_audit_bytes {
+                review.insert("llm_audit_ref".to_string(), Value::Null);
+                continue;
+            }
+            match self.write_audit(key, &data) {
+                Ok(reference) => {
+                    review.insert(
+                        "llm_audit".to_string(),
+                        serde_json::to_value(slim_audit(&audit))?,
+                    );
+                    review.insert("llm_audit_ref".to_string(), Value::String(reference));
+                }
+                Err(err) => tracing::warn!(
+                    proposal_id = %key,
+                    error = %format!("{err:#}"),
+                    "failed to write llm audit file; keeping the audit in state"
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_audit(&self, proposal_id: &str, data: &[u8]) -> Result<String> {
+        let reference = artifact_reference(AUDIT_DIR, proposal_id)?;
+        let path = self.data_dir.join(&reference);
+        if fs::read(&path).ok().as_deref() != Some(data) {
+            fs::create_dir_all(self.data_dir.join(AUDIT_DIR))
+                .context("failed to create audit directory")?;
+            let tmp = suffixed_path(&path, "tmp");
+            write_synced(&tmp, data)?;
+            fs::rename(&tmp, &path)
+                .with_context(|| format!("failed to write audit {}", path.display()))?;
+        }
+        Ok(reference)
+    }
+
+    // Moves proposals whose final governor state was seen more than `retention` ago to
+    // data_dir/archive/<id>.json and saves the state with index entries in their place. Every
+    // archive file is synced before that save, so a crash leaves each record in state, in its
+    // archive, or both; an archive left behind by a crash is rewritten by the next prune.
+    pub fn prune(
+        &self,
+        state: &mut State,
+        retention: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>> {
+        let cutoff = now - retention;
+        let due = state
+            .proposals
+            .iter()
+            .filter(|(_, processed)| {
+                processed.lifecycle.as_ref().is_some_and(|lifecycle| {
+                    lifecycle.state.is_final() && lifecycle.checked_at <= cutoff
+                })
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        let dir = self.data_dir.join(ARCHIVE_DIR);
+        let mut references = Vec::with_capacity(due.len());
+        if !due.is_empty() {
+            fs::create_dir_all(&dir).context("failed to create archive directory")?;
+        }
+        for key in &due {
+            let reference = artifact_reference(ARCHIVE_DIR, key)?;
+            let path = self.data_dir.join(&reference);
+            let tmp = suffixed_path(&path, "tmp");
+            write_synced(&tmp, &serde_json::to_vec_pretty(&state.proposals[key])?)?;
+            fs::rename(&tmp, &path)
+                .with_context(|| format!("failed to write archive {}", path.display()))?;
+            references.push(reference);
+        }
+        if !due.is_empty() {
+            File::open(&dir)
+                .and_then(|dir| dir.sync_all())
+                .with_context(|| format!("failed to sync {}", dir.display()))?;
+        }
+
+        for (key, path) in due.iter().zip(references) {
+            if let Some(processed) = state.proposals.remove(key) {
+                state.archived.insert(
+                    key.clone(),
+                    ArchivedProposal {
+                        vote: processed.decision.vote,
+                        tx_hash: processed
+                            .vote_executions
+                            .iter()
+                            .find_map(|execution| execution.tx_hash.clone()),
+                        path,
+                        archived_at: now,
+                    },
+                );
+            }
+        }
+        state.last_pruned_at = Some(now);
+        self.save(state)?;
+        Ok(due)
+    }
+
+    pub fn load_archived(&self, archived: &ArchivedProposal) -> Result<ProcessedProposal> {
+        let path = self.data_dir.join(&archived.path);
+        let raw = fs::read(&path)
+            .with_context(|| format!("failed to read archive {}", path.display()))?;
+        serde_json::from_slice(&raw)
+            .with_context(|| format!("failed to parse archive {}", path.display()))
+    }
+
+    // One JSONL file per scanned range; a rescan of the same range overwrites it.
+    pub fn quarantine_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        logs: &[Value],
+    ) -> Result<PathBuf> {
+        let dir = self.data_dir.join(FAILED_LOGS_DIR);
+        fs::create_dir_all(&dir).context("failed to create failed_logs directory")?;
+        let path = dir.join(format!("{from_block}-{to_block}.jsonl"));
+        let mut data = Vec::new();
+        for log in logs {
+            serde_json::to_writer(&mut data, log)?;
+            data.push(b'\n');
+        }
+        write_synced(&path, &data)?;
+        Ok(path)
+    }
+
+    pub fn vote_intents(&self) -> VoteIntentLog {
+        VoteIntentLog {
+            path: self.data_dir.join(VOTE_INTENTS_FILE),
+        }
+    }
+
+    pub fn pause_path(&self) -> PathBuf {
+        self.data_dir.join(PAUSE_FILE)
+    }
+
+    pub fn voting_paused(&self) -> bool {
+        self.pause_path().exists()
+    }
+
+    // Returns false when the kill switch was already in the requested position.
+    pub fn set_voting_paused(&self, paused: bool) -> Result<bool> {
+        let path = self.pause_path();
+        match (paused, path.exists()) {
+            (true, false) => {
+                fs::write(&path, format!("paused at {}\n", Utc::now().to_rfc3339()))
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                Ok(true)
+            }
+            (false, true) => {
+                fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // Fills in the text of an audit whose bodies were moved out of state. A missing, unreadable,
+    // or mismatched file is logged and the slim audit (provider, model, hashes) returned instead.
+    pub fn load_audit(&self, review: &ReviewResult) -> Option<LlmAudit> {
+        let inline = review.llm_audit.as_ref();
+        let Some(reference) = review
+            .llm_audit_ref
+            .as_ref()
+            .filter(|_| inline.is_none_or(|audit| audit.prompt_keccak256.is_some()))
+        else {
+            return inline.cloned();
+        };
+        match self.read_audit(reference, inline) {
+            Ok(audit) => Some(audit),
+            Err(err) => {
+                tracing::warn!(
+                    proposal_id = %review.proposal_id,
+                    error = %format!("{err:#}"),
+                    "llm audit text unavailable"
+                );
+                inline.cloned()
+            }
+        }
+    }
+
+    fn read_audit(&self, reference: &str, slim: Option<&LlmAudit>) -> Result<LlmAudit> {
+        let path = self.data_dir.join(reference);
+        let raw = fs::read(&path)
+            .with_context(|| format!("failed to read llm audit {}", path.display()))?;
+        let audit = serde_json::from_slice::<LlmAudit>(&raw)
+            .with_context(|| format!("failed to parse llm audit {}", path.display()))?;
+        // States saved before the cutover have no slim audit, so there is nothing to check.
+        if let Some(slim) = slim
+            && (slim.prompt_keccak256 != Some(keccak256(&audit.prompt_redacted).to_string())
+                || slim.response_keccak256 != Some(keccak256(&audit.response_redacted).to_string()))
+        {
+            bail!(
+                "llm audit {} does not match the hashes recorded in state",
+                path.display()
+            );
+        }
+        Ok(LlmAudit {
+            prompt_keccak256: slim.and_then(|slim| slim.prompt_keccak256.clone()),
+            response_keccak256: slim.and_then(|slim| slim.response_keccak256.clone()),
+            ..audit
+        })
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        suffixed_path(&self.base_path, "tmp")
+    }
+
+    // The state file in the other format, left behind when `storage.compress` was flipped.
+    fn inactive_path(&self) -> PathBuf {
+        if self.compress {
+            self.base_path.clone()
+        } else {
+            suffixed_path(&self.base_path, "gz")
+        }
+    }
+
+    fn current_path(&self) -> Option<PathBuf> {
+        [self.state_path.clone(), self.inactive_path()]
+            .into_iter()
+            .find(|path| path.exists())
+    }
+
+    fn encode(&self, json: &[u8]) -> Result<Vec<u8>> {
+        if !self.compress {
+            return Ok(json.to_vec());
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(json)
+            .and_then(|()| encoder.finish())
+            .context("failed to compress state")
+    }
+
+    fn rotate_backups(&self, current: &Path) -> Result<()> {
+        if self.backup_count == 0 {
+            return Ok(());
+        }
+
+        for generation in (1..self.backup_count).rev() {
+            let from = self.backup_path(generation);
+            if from.exists() {
+                let to = self.backup_path(generation + 1);
+                fs::rename(&from, &to).with_context(|| {
+                    format!("failed to rotate {} to {}", from.display(), to.display())
+                })?;
+            }
+        }
+
+        let newest = self.backup_path(1);
+        fs::copy(current, &newest).with_context(|| {
+            format!(
+                "failed to back up {} to {}",
+                current.display(),
+                newest.display()
+            )
+        })?;
+        Ok(())
+    }
+
+    fn replace_state_file(&self, tmp: &Path) -> Result<()> {
+        fs::rename(tmp, &self.state_path).with_context(|| {
             format!(
                 "failed to move {} to {}",
                 tmp.display(),
                 self.state_path.display()
             )
         })?;
+        let inactive = self.inactive_path();
+        if inactive.exists() {
+            fs::remove_file(&inactive).with_context(|| {
+                format!("failed to remove superseded state {}", inactive.display())
+            })?;
+        }
+        // Persist the rename itself; not every platform can open a directory for syncing.
+        if let Some(dir) = self.state_path.parent()
+            && let Ok(dir) = File::open(dir)
+        {
+            let _ = dir.sync_all();
+        }
         Ok(())
     }
 }
+
+impl VoteIntentLog {
+    pub fn load(&self) -> Result<BTreeMap<String, VoteIntent>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let raw = fs::read(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        serde_json::from_slice(&raw)
+            .with_context(|| format!("failed to parse {}", self.path.display()))
+    }
+
+    pub fn get(&self, proposal_id: &str, signer: &str) -> Result<Option<VoteIntent>> {
+        Ok(self.load()?.remove(&intent_key(proposal_id, signer)))
+    }
+
+    pub fn record(&self, intent: &VoteIntent) -> Result<()> {
+        let mut intents = self.load()?;
+        intents.insert(
+            intent_key(&intent.proposal_id, &intent.signer),
+            intent.clone(),
+        );
+        self.write(&intents)
+    }
+
+    pub fn clear(&self, proposal_id: &str, signer: &str) -> Result<()> {
+        let mut intents = self.load()?;
+        if intents.remove(&intent_key(proposal_id, signer)).is_some() {
+            self.write(&intents)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, intents: &BTreeMap<String, VoteIntent>) -> Result<()> {
+        let tmp = suffixed_path(&self.path, "tmp");
+        write_synced(&tmp, &serde_json::to_vec_pretty(intents)?)?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+fn intent_key(proposal_id: &str, signer: &str) -> String {
+    format!("{proposal_id}:{}", signer.to_ascii_lowercase())
+}
+
+fn write_synced(path: &Path, data: &[u8]) -> Result<()> {
+    let mut file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    file.write_all(data)
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn read_state(path: &Path) -> Result<State> {
+    let raw = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    parse_state(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn decode_state_bytes(raw: &[u8]) -> Result<Vec<u8>> {
+    if !raw.starts_with(&GZIP_MAGIC) {
+        return Ok(raw.to_vec());
+    }
+    let mut json = Vec::new();
+    GzDecoder::new(raw)
+        .read_to_end(&mut json)
+        .context("failed to decompress gzip state")?;
+    Ok(json)
+}
+
+fn parse_state(raw: &[u8]) -> Result<State> {
+    let mut value: Value = serde_json::from_slice(&decode_state_bytes(raw)?)?;
+    let version = match value.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow!("state version {v} is not a valid number"))?,
+    };
+    if version > CURRENT_STATE_VERSION {
+        return Err(UnsupportedStateVersion(version).into());
+    }
+    for (from, migrate) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migrate(&mut value).with_context(|| {
+            format!(
+                "failed to migrate state from version {from} to {}",
+                from + 1
+            )
+        })?;
+        value["version"] = Value::from(from + 1);
+    }
+    let mut state: State = serde_json::from_value(value)?;
+    // State written before the bundle index existed still has every reviewed bundle on record.
+    if state.bundles.is_empty() {
+        state.bundles = BundleIndex::from_proposals(state.proposals.values());
+    }
+    Ok(state)
+}
+
+#[derive(Debug)]
+pub struct UnsupportedStateVersion(pub u32);
+
+impl std::fmt::Display for UnsupportedStateVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "state version {} is newer than supported version {CURRENT_STATE_VERSION}; upgrade gov-agent",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedStateVersion {}
+
+// Version 0 is every file written before the version field existed; its shape is unchanged.
+fn migrate_v0_to_v1(value: &mut Value) -> Result<()> {
+    if !value.is_object() {
+        bail!("state root is not a JSON object");
+    }
+    Ok(())
+}
+
+// Version 2 stores every decoded registry call as `proposal.actions` instead of one `action`.
+fn migrate_v1_to_v2(value: &mut Value) -> Result<()> {
+    for section in ["proposals", "pending_reviews"] {
+        let Some(entries) = value.get_mut(section).and_then(Value::as_object_mut) else {
+            continue;
+        };
+        for entry in entries.values_mut() {
+            let Some(proposal) = entry.get_mut("proposal").and_then(Value::as_object_mut) else {
+                continue;
+            };
+            if let Some(action) = proposal.remove("action") {
+                proposal
+                    .entry("actions")
+                    .or_insert_with(|| Value::Array(vec![action]));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Version 3 keeps one vote record per signer account in `vote_executions`.
+fn migrate_v2_to_v3(value: &mut Value) -> Result<()> {
+    let Some(entries) = value.get_mut("proposals").and_then(Value::as_object_mut) else {
+        return Ok(());
+    };
+    for entry in entries.values_mut() {
+        let Some(processed) = entry.as_object_mut() else {
+            continue;
+        };
+        if let Some(execution) = processed.remove("vote_execution") {
+            let executions = if execution.is_null() {
+                Vec::new()
+            } else {
+                vec![execution]
+            };
+            processed
+                .entry("vote_executions")
+                .or_insert_with(|| Value::Array(executions));
+        }
+    }
+    Ok(())
+}
+
+fn slim_audit(audit: &LlmAudit) -> LlmAudit {
+    LlmAudit {
+        provider: audit.provider.clone(),
+        model: audit.model.clone(),
+        prompt_redacted: String::new(),
+        response_redacted: String::new(),
+        cached: audit.cached,
+        prompt_keccak256: Some(keccak256(&audit.prompt_redacted).to_string()),
+        response_keccak256: Some(keccak256(&audit.response_redacted).to_string()),
+    }
+}
+
+fn artifact_reference(dir: &str, proposal_id: &str) -> Result<String> {
+    if proposal_id.is_empty()
+        || !proposal_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("proposal id {proposal_id:?} cannot be used as a file name");
+    }
+    Ok(format!("{dir}/{proposal_id}.json"))
+}
+
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut raw = path.as_os_str().to_owned();
+    raw.push(format!(".{suffix}"));
+    PathBuf::from(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use chrono::{Duration, Utc};
+
+    use super::{State, Storage, VoteIntent, short_proposal_id};
+    use crate::{
+        config::StorageConfig,
+        types::{
+            Decision, DecodedAction, LlmAudit, ProcessedProposal, Proposal, ProposalLifecycle,
+            ProposalState, ReviewResult, VoteChoice, VoteExecution,
+        },
+    };
+
+    fn processed(proposal_id: &str) -> ProcessedProposal {
+        ProcessedProposal {
+            proposal: Proposal {
+                proposal_id: proposal_id.to_string(),
+                proposer: "0x0000000000000000000000000000000000000001".to_string(),
+                description: "test".to_string(),
+                vote_start: 1,
+                vote_end: 100,
+                block_number: 1,
+                tx_hash: None,
+                targets: vec![],
+                values: vec![],
+                calldatas: vec![],
+                actions: vec![DecodedAction::Unsupported {
+                    reason: "test".to_string(),
+                }],
+                discovered_at: Utc::now(),
+                creation_context: None,
+            },
+            review: ReviewResult {
+                proposal_id: proposal_id.to_string(),
+                root_cid: None,
+                findings: vec![],
+                deterministic_score: None,
+                deterministic_weight: None,
+                llm_weight: None,
+                llm_score: None,
+                llm_audit: None,
+                llm_audit_ref: None,
+                score: 0.5,
+                suppressions: vec![],
+                transient_failure: None,
+                reputation: None,
+                score_cap: None,
+                actions: vec![],
+                reviewed_at: Utc::now(),
+            },
+            decision: Decision {
+                proposal_id: proposal_id.to_string(),
+                vote: VoteChoice::Abstain,
+                reasons: vec![],
+                blocking_findings: vec![],
+                requires_human_override: true,
+                decided_at: Utc::now(),
+                config_hash: None,
+                allocation: None,
+            },
+            vote_executions: Vec::new(),
+            decision_history: Vec::new(),
+            voting_paused: false,
+            lifecycle: None,
+            vote_missed: false,
+            peer_check: None,
+        }
+    }
+
+    fn state_with(ids: &[&str]) -> State {
+        let mut state = State::default();
+        for id in ids {
+            state.proposals.insert(id.to_string(), processed(id));
+        }
+        state
+    }
+
+    #[test]
+    fn resolves_full_decimal_hex_and_short_handle() {
+        let id = "85353726111642088776893488059974230743342594789084151765762295675253395008791";
+        let state = state_with(&[id, "42"]);
+        let handle = short_proposal_id(id);
+        assert_eq!(handle.len(), 8);
+
+        assert_eq!(state.resolve_proposal_id(id).expect("decimal"), id);
+        assert_eq!(state.resolve_proposal_id("0x2a").expect("hex"), "42");
+        assert_eq!(state.resolve_proposal_id(&handle).expect("handle"), id);
+    }
+
+    #[test]
+    fn ambiguous_prefix_lists_candidates() {
+        let state = state_with(&["1234", "1299"]);
+        let err = state
+            .resolve_proposal_id("12")
+            .expect_err("prefix should be ambiguous");
+        let message = err.to_string();
+        assert!(message.contains("ambiguous"));
+        assert!(message.contains("1234"));
+        assert!(message.contains("1299"));
+    }
+
+    #[test]
+    fn unknown_full_id_passes_through_as_decimal() {
+        let state = State::default();
+        assert_eq!(state.resolve_proposal_id("0x10").expect("hex id"), "16");
+        assert!(state.resolve_proposal_id("zz").is_err());
+
+        // A full id never falls through to prefix matching.
+        let state = state_with(&["16", "1234567890123"]);
+        assert_eq!(state.resolve_proposal_id("0x1").expect("hex id"), "1");
+        assert_eq!(
+            state
+                .resolve_proposal_id("123456789012")
+                .expect("decimal id"),
+            "123456789012"
+        );
+        assert_eq!(
+            state.resolve_proposal_id("1234").expect("short prefix"),
+            "1234567890123"
+        );
+    }
+
+    #[test]
+    fn save_rotates_backups_and_skips_unchanged_state() {
+        let dir = temp_data_dir("gov-agent-storage-rotate");
+        let storage = storage_in(&dir, 2);
+
+        for block in 1..=4 {
+            let state = State {
+                last_scanned_block: block,
+                ..State::default()
+            };
+            storage.save(&state).expect("save state");
+        }
+        assert!(!storage.backup_path(3).exists());
+        let newest = super::read_state(&storage.backup_path(1)).expect("read backup 1");
+        let oldest = super::read_state(&storage.backup_path(2)).expect("read backup 2");
+        assert_eq!(newest.last_scanned_block, 3);
+        assert_eq!(oldest.last_scanned_block, 2);
+
+        let unchanged = State {
+            last_scanned_block: 4,
+            ..State::default()
+        };
+        storage.save(&unchanged).expect("save unchanged state");
+        let newest = super::read_state(&storage.backup_path(1)).expect("read backup 1");
+        assert_eq!(newest.last_scanned_block, 3);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn vote_intents_persist_until_cleared_per_signer() {
+        let dir = temp_data_dir("gov-agent-storage-intents");
+        let storage = storage_in(&dir, 1);
+        let intent = |signer: &str, nonce| VoteIntent {
+            proposal_id: "7".to_string(),
+            signer: signer.to_string(),
+            nonce,
+            tx_hash: None,
+            relay_task: None,
+            created_at: Utc::now(),
+        };
+        storage
+            .vote_intents()
+            .record(&intent("0xAbC", 4))
+            .expect("record intent");
+        storage
+            .vote_intents()
+            .record(&intent("0xdef", 9))
+            .expect("record intent");
+
+        let reopened = storage_in(&dir, 1).vote_intents();
+        let stored = reopened
+            .get("7", "0xabc")
+            .expect("read intent")
+            .expect("intent survives reopen");
+        assert_eq!((stored.signer.as_str(), stored.nonce), ("0xAbC", 4));
+        reopened.clear("7", "0xABC").expect("clear intent");
+        assert!(reopened.get("7", "0xabc").expect("read intent").is_none());
+        assert_eq!(reopened.load().expect("load intents").len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restore_recovers_from_corrupted_primary() {
+        let dir = temp_data_dir("gov-agent-storage-restore");
+        let storage = storage_in(&dir, 3);
+
+        storage.save(&state_with(&["1"])).expect("save first state");
+        storage
+            .save(&state_with(&["1", "2"]))
+            .expect("save second state");
+        fs::write(storage.state_path(), b"{ not json").expect("corrupt primary");
+        assert_eq!(
+            storage.load().expect("fall back to backup").proposals.len(),
+            1
+        );
+
+        fs::write(storage.backup_path(2), b"garbage").expect("write corrupt backup");
+        assert!(storage.restore_backup(2).is_err());
+        assert!(storage.restore_backup(3).is_err());
+
+        let restored = storage.restore_backup(1).expect("restore backup 1");
+        assert_eq!(restored.proposals.len(), 1);
+        let loaded = storage.load().expect("load restored state");
+        assert!(loaded.proposals.contains_key("1"));
+        assert!(dir.join("state.json.pre-restore").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_falls_back_to_backup_and_save_keeps_it() {
+        let dir = temp_data_dir("gov-agent-storage-fallback");
+        let storage = storage_in(&dir, 2);
+
+        storage.save(&state_with(&["1"])).expect("save first state");
+        storage
+            .save(&state_with(&["1", "2"]))
+            .expect("save second state");
+        fs::write(storage.state_path(), b"{\"last_scanned_bl").expect("truncate primary");
+
+        let recovered = storage.load().expect("load from backup");
+        assert_eq!(recovered.proposals.len(), 1);
+
+        storage
+            .save(&state_with(&["1", "3"]))
+            .expect("save after recovery");
+        let backup = super::read_state(&storage.backup_path(1)).expect("read backup 1");
+        assert_eq!(backup.proposals.len(), 1);
+        assert_eq!(storage.load().expect("load").proposals.len(), 2);
+
+        fs::write(storage.state_path(), b"garbage").expect("corrupt primary");
+        fs::write(storage.backup_path(1), b"garbage").expect("corrupt backup");
+        assert!(storage.load().is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unversioned_state_is_migrated_and_newer_versions_are_rejected() {
+        let dir = temp_data_dir("gov-agent-storage-version");
+        let storage = storage_in(&dir, 2);
+
+        fs::write(
+            storage.state_path(),
+            br#"{"last_scanned_block": 42, "proposals": {}}"#,
+        )
+        .expect("write legacy state");
+        let mut state = storage.load().expect("load legacy state");
+        assert_eq!(state.version, super::CURRENT_STATE_VERSION);
+        assert_eq!(state.last_scanned_block, 42);
+
+        state.last_scanned_block = 43;
+        storage.save(&state).expect("save migrated state");
+        let raw = fs::read_to_string(storage.state_path()).expect("read saved state");
+        let saved: serde_json::Value = serde_json::from_str(&raw).expect("saved state is json");
+        assert_eq!(saved["version"], super::CURRENT_STATE_VERSION);
+
+        fs::write(
+            storage.state_path(),
+            br#"{"version": 99, "last_scanned_block": 1, "proposals": {}}"#,
+        )
+        .expect("write future state");
+        let err = storage.load().expect_err("future state should be rejected");
+        assert!(format!("{err:#}").contains("upgrade gov-agent"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn v1_single_action_proposals_migrate_to_action_lists() {
+        let dir = temp_data_dir("gov-agent-storage-actions");
+        let storage = storage_in(&dir, 0);
+
+        let mut legacy = serde_json::to_value(state_with(&["1"])).expect("state to json");
+        legacy["version"] = serde_json::Value::from(1);
+        let proposal = legacy["proposals"]["1"]["proposal"]
+            .as_object_mut()
+            .expect("proposal object");
+        let action = proposal.remove("actions").expect("actions")[0].clone();
+        proposal.insert("action".to_string(), action);
+        fs::write(
+            storage.state_path(),
+            serde_json::to_vec(&legacy).expect("serialize legacy"),
+        )
+        .expect("write v1 state");
+
+        let state = storage.load().expect("load v1 state");
+        assert_eq!(state.version, super::CURRENT_STATE_VERSION);
+        assert!(matches!(
+            state.proposals["1"].proposal.actions.as_slice(),
+            [DecodedAction::Unsupported { .. }]
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn v2_single_vote_execution_migrates_to_per_account_list() {
+        let dir = temp_data_dir("gov-agent-storage-votes");
+        let storage = storage_in(&dir, 0);
+
+        let mut legacy = serde_json::to_value(state_with(&["1", "2"])).expect("state to json");
+        legacy["version"] = serde_json::Value::from(2);
+        for (id, execution) in [
+            ("1", serde_json::Value::Null),
+            (
+                "2",
+                serde_json::json!({
+                    "proposal_id": "2",
+                    "submitted": true,
+                    "tx_hash": "0xabc",
+                    "reason": "gov-agent vote=1",
+                    "at": "2025-01-01T00:00:00Z",
+                }),
+            ),
+        ] {
+            let processed = legacy["proposals"][id]
+                .as_object_mut()
+                .expect("processed object");
+            processed.remove("vote_executions");
+            processed.insert("vote_execution".to_string(), execution);
+        }
+        fs::write(
+            storage.state_path(),
+            serde_json::to_vec(&legacy).expect("serialize legacy"),
+        )
+        .expect("write v2 state");
+
+        let state = storage.load().expect("load v2 state");
+        assert!(state.proposals["1"].vote_executions.is_empty());
+        let executions = &state.proposals["2"].vote_executions;
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].tx_hash.as_deref(), Some("0xabc"));
+        assert!(executions[0].account.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn large_llm_audits_are_saved_outside_the_state_file() {
+        let dir = temp_data_dir("gov-agent-storage-audits");
+        let storage = storage_in(&dir, 0);
+
+        let mut state = state_with(&["1", "2", "3"]);
+        let audit = |prompt: &str| LlmAudit {
+            provider: "openai".to_string(),
+            model: "gpt-5-nano".to_string(),
+            prompt_redacted: prompt.to_string(),
+            response_redacted: "{\"score\": 0.9}".to_string(),
+            cached: false,
+            prompt_keccak256: None,
+            response_keccak256: None,
+        };
+        let long_prompt = "a long prompt ".repeat(40);
+        for (id, prompt) in [("1", long_prompt.as_str()), ("2", "short prompt")] {
+            state
+                .proposals
+                .get_mut(id)
+                .expect("proposal")
+                .review
+                .llm_audit = Some(audit(prompt));
+        }
+        storage.save(&state).expect("save state");
+
+        let raw = fs::read_to_string(storage.state_path()).expect("read state");
+        assert!(!raw.contains("a long prompt"));
+        assert!(raw.contains("short prompt"));
+        assert!(dir.join("audits/1.json").exists());
+        assert!(!dir.join("audits/2.json").exists());
+
+        let loaded = storage.load().expect("load state");
+        let review = &loaded.proposals["1"].review;
+        let slim = review.llm_audit.as_ref().expect("slim audit");
+        assert_eq!(slim.model, "gpt-5-nano");
+        assert!(slim.prompt_redacted.is_empty());
+        assert!(slim.prompt_keccak256.is_some());
+        assert_eq!(review.llm_audit_ref.as_deref(), Some("audits/1.json"));
+        let full = storage.load_audit(review).expect("audit present");
+        assert_eq!(full.prompt_redacted, long_prompt);
+        assert_eq!(
+            storage
+                .load_audit(&loaded.proposals["2"].review)
+                .expect("inline audit")
+                .prompt_redacted,
+            "short prompt"
+        );
+        assert!(storage.load_audit(&loaded.proposals["3"].review).is_none());
+
+        // Saving the loaded state again must not overwrite the artifact with the slim form.
+        storage.save(&loaded).expect("resave state");
+        assert_eq!(
+            storage
+                .load_audit(&storage.load().expect("reload").proposals["1"].review)
+                .expect("audit present")
+                .prompt_redacted,
+            long_prompt
+        );
+
+        // A tampered or missing artifact falls back to the slim audit.
+        fs::write(
+            dir.join("audits/1.json"),
+            serde_json::to_vec(&audit("edited")).expect("encode audit"),
+        )
+        .expect("tamper audit");
+        let fallback = storage.load_audit(review).expect("slim fallback");
+        assert!(fallback.prompt_redacted.is_empty());
+        fs::remove_file(dir.join("audits/1.json")).expect("remove audit");
+        assert!(storage.load_audit(review).is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn states_with_only_an_audit_reference_still_load_the_text() {
+        let dir = temp_data_dir("gov-agent-storage-audit-ref");
+        let storage = storage_in(&dir, 0);
+        fs::create_dir_all(dir.join("audits")).expect("create audits");
+        fs::write(
+            dir.join("audits/1.json"),
+            r#"{"provider":"openai","model":"m","prompt_redacted":"old prompt","response_redacted":"{}"}"#,
+        )
+        .expect("write audit");
+        let mut state = state_with(&["1"]);
+        state
+            .proposals
+            .get_mut("1")
+            .expect("proposal")
+            .review
+            .llm_audit_ref = Some("audits/1.json".to_string());
+        storage.save(&state).expect("save state");
+
+        let loaded = storage.load().expect("load state");
+        let audit = storage
+            .load_audit(&loaded.proposals["1"].review)
+            .expect("audit present");
+        assert_eq!(audit.prompt_redacted, "old prompt");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_archives_old_finalized_proposals_behind_an_index_entry() {
+        let dir = temp_data_dir("gov-agent-storage-prune");
+        let storage = storage_in(&dir, 2);
+        let now = Utc::now();
+        let mut state = state_with(&["1", "2", "3", "4"]);
+        let finalize = |processed: &mut ProcessedProposal, state, days| {
+            processed.lifecycle = Some(ProposalLifecycle {
+                state,
+                eta: None,
+                checked_at: now - Duration::days(days),
+            });
+        };
+        let old_executed = state.proposals.get_mut("1").expect("1");
+        finalize(old_executed, ProposalState::Executed, 200);
+        old_executed.vote_executions.push(VoteExecution {
+            proposal_id: "1".to_string(),
+            submitted: true,
+            tx_hash: Some("0xabc".to_string()),
+            reason: "vote".to_string(),
+            at: now,
+            account: None,
+        });
+        finalize(
+            state.proposals.get_mut("2").expect("2"),
+            ProposalState::Defeated,
+            10,
+        );
+        finalize(
+            state.proposals.get_mut("3").expect("3"),
+            ProposalState::Queued,
+            200,
+        );
+        storage.save(&state).expect("save state");
+
+        let pruned = storage
+            .prune(&mut state, Duration::days(90), now)
+            .expect("prune");
+        assert_eq!(pruned, vec!["1".to_string()]);
+
+        let saved = storage.load().expect("load state");
+        assert_eq!(saved.proposals.len(), 3);
+        assert!(saved.is_known("1"));
+        assert_eq!(saved.last_pruned_at, Some(now));
+        let archived = &saved.archived["1"];
+        assert_eq!(archived.path, "archive/1.json");
+        assert_eq!(archived.tx_hash.as_deref(), Some("0xabc"));
+        assert_eq!(saved.resolve_proposal_id("1").expect("resolve"), "1");
+        let record = storage.load_archived(archived).expect("read archive");
+        assert_eq!(record.proposal.proposal_id, "1");
+        assert_eq!(record.vote_executions.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compress_flag_transitions_state_format_both_ways() {
+        let dir = temp_data_dir("gov-agent-storage-gzip");
+        let plain = storage_in(&dir, 2);
+        let gzip = Storage::new(&StorageConfig {
+            data_dir: dir.clone(),
+            state_file: "state.json".to_string(),
+            lock_file: "state.lock".to_string(),
+            backup_count: 2,
+            compress: true,
+            max_inline_audit_bytes: 256,
+            retention_days: None,
+        })
+        .expect("build compressed storage");
+
+        plain.save(&state_with(&["1"])).expect("save plain state");
+        assert_eq!(
+            gzip.load()
+                .expect("load plain via gzip storage")
+                .proposals
+                .len(),
+            1
+        );
+
+        let two = state_with(&["1", "2"]);
+        gzip.save(&two).expect("save compressed state");
+        assert!(!dir.join("state.json").exists());
+        let raw = fs::read(dir.join("state.json.gz")).expect("read gz state");
+        assert!(raw.starts_with(&super::GZIP_MAGIC));
+        assert_eq!(gzip.load().expect("load gz").proposals.len(), 2);
+        assert_eq!(
+            super::read_state(&gzip.backup_path(1))
+                .expect("read plain backup")
+                .proposals
+                .len(),
+            1
+        );
+
+        gzip.save(&two).expect("save unchanged state");
+        assert!(!gzip.backup_path(2).exists());
+
+        assert_eq!(plain.load().expect("load gz via plain").proposals.len(), 2);
+        plain
+            .save(&state_with(&["1", "2", "3"]))
+            .expect("save back to plain");
+        assert!(!dir.join("state.json.gz").exists());
+        assert_eq!(plain.load().expect("load plain").proposals.len(), 3);
+        assert!(
+            super::read_state(&plain.backup_path(1))
+                .expect("read gz backup")
+                .proposals
+                .contains_key("2")
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lock_rejects_second_holder_until_released_or_forced() {
+        let dir = temp_data_dir("gov-agent-storage-lock");
+        let storage = storage_in(&dir, 0);
+
+        let first = storage.lock(false).expect("acquire lock");
+        let err = storage.lock(false).expect_err("second lock should fail");
+        assert!(err.to_string().contains("--force-unlock"));
+        assert!(
+            err.to_string()
+                .contains(&format!("pid={}", std::process::id()))
+        );
+
+        let forced = storage.lock(true).expect("force unlock");
+        drop(forced);
+        drop(first);
+
+        let again = storage.lock(false).expect("lock after release");
+        drop(again);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn storage_in(dir: &std::path::Path, backup_count: usize) -> Storage {
+        Storage::new(&StorageConfig {
+            data_dir: dir.to_path_buf(),
+            state_file: "state.json".to_string(),
+            lock_file: "state.lock".to_string(),
+            backup_count,
+            compress: false,
+            max_inline_audit_bytes: 256,
+            retention_days: None,
+        })
+        .expect("build storage")
+    }
+
+    fn temp_data_dir(prefix: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", prefix, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow, bail};
+
+// Placeholder templates for operator-facing text. `{{name}}` is replaced by a variable,
+// `{{#name}}...{{/name}}` keeps its body when the variable is non-empty (once per item for a
+// list, with the item's variables in scope) and `{{^name}}...{{/name}}` when it is empty.
+// Unknown names are errors rather than blanks, so a typo never goes out as missing text.
+pub type Vars = BTreeMap<&'static str, Value>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    List(Vec<Vars>),
+}
+
+impl Value {
+    fn is_set(&self) -> bool {
+        match self {
+            Value::Text(text) => !text.is_empty(),
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<Vec<Vars>> for Value {
+    fn from(items: Vec<Vars>) -> Self {
+        Value::List(items)
+    }
+}
+
+// A section flag: set renders `{{#name}}`, unset renders `{{^name}}`.
+pub fn flag(set: bool) -> Value {
+    Value::Text(if set { "true" } else { "" }.to_string())
+}
+
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    Section {
+        name: &'a str,
+        inverted: bool,
+        body: Vec<Node<'a>>,
+    },
+}
+
+// Syntax only: whether the variables exist is checked when rendering.
+pub fn check(template: &str) -> Result<()> {
+    parse(template).map(|_| ())
+}
+
+pub fn render(template: &str, vars: &Vars) -> Result<String> {
+    let nodes = parse(template)?;
+    let mut out = String::new();
+    render_nodes(&nodes, &mut vec![vars], &mut out)?;
+    Ok(out)
+}
+
+fn parse(template: &str) -> Result<Vec<Node<'_>>> {
+    let mut nodes = Vec::new();
+    let mut open: Vec<(&str, bool, Vec<Node<'_>>)> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            nodes.push(Node::Text(&rest[..start]));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow!("unclosed {{{{ in template"))?;
+        let tag = after[..end].trim();
+        rest = &after[end + 2..];
+        if let Some(name) = tag.strip_prefix('#').or_else(|| tag.strip_prefix('^')) {
+            let name = tag_name(name)?;
+            open.push((name, tag.starts_with('^'), std::mem::take(&mut nodes)));
+        } else if let Some(name) = tag.strip_prefix('/') {
+            let name = tag_name(name)?;
+            let Some((opened, inverted, parent)) = open.pop() else {
+                bail!("{{{{/{name}}}}} closes no section");
+            };
+            if opened != name {
+                bail!("section {opened:?} is closed by {{{{/{name}}}}}");
+            }
+            let body = std::mem::replace(&mut nodes, parent);
+            nodes.push(Node::Section {
+                name,
+                inverted,
+                body,
+            });
+        } else {
+            nodes.push(Node::Var(tag_name(tag)?));
+        }
+    }
+    if let Some((name, ..)) = open.last() {
+        bail!("section {name:?} is never closed");
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest));
+    }
+    Ok(nodes)
+}
+
+fn tag_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("invalid placeholder {{{{{name}}}}}");
+    }
+    Ok(name)
+}
+
+fn lookup<'v>(scopes: &[&'v Vars], name: &str) -> Result<&'v Value> {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|vars| vars.get(name))
+        .ok_or_else(|| anyhow!("unknown placeholder {{{{{name}}}}}"))
+}
+
+fn render_nodes(nodes: &[Node<'_>], scopes: &mut Vec<&Vars>, out: &mut String) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(name) => match lookup(scopes, name)? {
+                Value::Text(text) => out.push_str(text),
+                Value::List(_) => {
+                    bail!("{{{{{name}}}}} is a list; use {{{{#{name}}}}}...{{{{/{name}}}}}")
+                }
+            },
+            Node::Section {
+                name,
+                inverted,
+                body,
+            } => {
+                let value = lookup(scopes, name)?;
+                if value.is_set() == *inverted {
+                    continue;
+                }
+                match value {
+                    Value::List(items) if !inverted => {
+                        for item in items {
+                            scopes.push(item);
+                            let rendered = render_nodes(body, scopes, out);
+                            scopes.pop();
+                            rendered?;
+                        }
+                    }
+                    _ => render_nodes(body, scopes, out)?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Vars, check, flag, render};
+
+    #[test]
+    fn renders_variables_flags_and_lists() {
+        let item = |name: &str| Vars::from([("name", name.into())]);
+        let vars = Vars::from([
+            ("id", "42".into()),
+            ("paused", flag(true)),
+            ("created", "".into()),
+            ("items", vec![item("a"), item("b")].into()),
+        ]);
+        let rendered = render(
+            "proposal {{ id }}{{#paused}} (paused){{/paused}}{{^created}}, no date{{/created}}{{#items}}\n- {{name}} of {{id}}{{/items}}",
+            &vars,
+        )
+        .expect("render");
+        assert_eq!(
+            rendered,
+            "proposal 42 (paused), no date\n- a of 42\n- b of 42"
+        );
+
+        let err = render("{{missing}}", &vars).expect_err("unknown placeholder");
+        assert_eq!(err.to_string(), "unknown placeholder {{missing}}");
+        assert!(render("{{items}}", &vars).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(check("{{#a}}open").is_err());
+        assert!(check("{{#a}}x{{/b}}").is_err());
+        assert!(check("{{/a}}").is_err());
+        assert!(check("{{a").is_err());
+        assert!(check("{{a b}}").is_err());
+        assert!(check("plain {{a}} text").is_ok());
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -14,8 +16,20 @@ pub struct Proposal {
     pub targets: Vec<String>,
     pub values: Vec<String>,
     pub calldatas: Vec<String>,
-    pub action: DecodedAction,
+    // Older state files stored a single `action` object.
+    #[serde(alias = "action", deserialize_with = "deserialize_actions")]
+    pub actions: Vec<DecodedAction>,
     pub discovered_at: DateTime<Utc>,
+    #[serde(default)]
+    pub creation_context: Option<ProposalCreationContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalCreationContext {
+    pub sender: String,
+    pub sender_is_contract: bool,
+    pub block_timestamp: Option<DateTime<Utc>>,
+    pub gas_used: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +53,40 @@ pub enum DecodedAction {
     },
 }
 
+impl DecodedAction {
+    pub fn root_cid(&self) -> Option<&str> {
+        match self {
+            DecodedAction::PublishDapp { root_cid, .. }
+            | DecodedAction::UpgradeDapp { root_cid, .. } => Some(root_cid),
+            DecodedAction::Unsupported { .. } => None,
+        }
+    }
+
+    // Matches the serde tag, which is also the key used by `review.score_caps`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DecodedAction::PublishDapp { .. } => "publish_dapp",
+            DecodedAction::UpgradeDapp { .. } => "upgrade_dapp",
+            DecodedAction::Unsupported { .. } => "unsupported",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            DecodedAction::PublishDapp { name, version, .. } => {
+                format!("publishDapp {name} {version}")
+            }
+            DecodedAction::UpgradeDapp {
+                dapp_id,
+                name,
+                version,
+                ..
+            } => format!("upgradeDapp #{dapp_id} {name} {version}"),
+            DecodedAction::Unsupported { .. } => "unsupported call".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewResult {
     #[serde(deserialize_with = "deserialize_proposal_id")]
@@ -52,23 +100,157 @@ pub struct ReviewResult {
     #[serde(default)]
     pub llm_weight: Option<f32>,
     pub llm_score: Option<f32>,
+    // Audits over `storage.max_inline_audit_bytes` are saved with their text in the
+    // `llm_audit_ref` file, relative to `storage.data_dir`, and only hashes inline.
     pub llm_audit: Option<LlmAudit>,
+    #[serde(default)]
+    pub llm_audit_ref: Option<String>,
     pub score: f32,
+    #[serde(default)]
+    pub suppressions: Vec<String>,
+    #[serde(default)]
+    pub transient_failure: Option<String>,
+    #[serde(default)]
+    pub reputation: Option<ReputationAdjustment>,
+    #[serde(default)]
+    pub score_cap: Option<ScoreCap>,
+    #[serde(default)]
+    pub actions: Vec<ActionReview>,
     pub reviewed_at: DateTime<Utc>,
 }
 
+impl ReviewResult {
+    pub fn merge_operator_findings(&mut self, previous: &ReviewResult) {
+        for finding in previous
+            .findings
+            .iter()
+            .filter(|finding| finding.operator.is_some())
+        {
+            if !self
+                .findings
+                .iter()
+                .any(|existing| existing.operator == finding.operator)
+            {
+                self.findings.push(finding.clone());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionReview {
+    pub index: usize,
+    pub label: String,
+    pub root_cid: Option<String>,
+    pub score: f32,
+    pub critical: bool,
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub source_scan: Option<SourceScanSummary>,
+}
+
+// Which files the lightweight source scan sampled, so `proposals show` can confirm it looked at
+// the dapp's code rather than bundled libraries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceScanSummary {
+    pub scanned: Vec<String>,
+    pub skipped: usize,
+    // Source files left out by `review.scan_exclude_globs`.
+    pub excluded: usize,
+    // Files scanned only because `review.scan_include_globs` matched them.
+    pub force_included: usize,
+}
+
+// Recorded only when a `review.score_caps` entry lowered the final score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreCap {
+    pub rule: String,
+    pub cap: f32,
+    pub uncapped_score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationAdjustment {
+    pub base_score: f32,
+    pub signal: f32,
+    pub weight: f32,
+    pub adjustment: f32,
+    pub inputs: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmAudit {
     pub provider: String,
     pub model: String,
+    // Empty in saved state once the text has moved to the `llm_audit_ref` file; the hashes
+    // then identify the bodies that file must hold.
+    #[serde(default)]
     pub prompt_redacted: String,
+    #[serde(default)]
     pub response_redacted: String,
+    #[serde(default)]
+    pub cached: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_keccak256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_keccak256: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
+    // Findings stored before codes existed load as `unclassified`.
+    #[serde(default)]
+    pub code: FindingCode,
     pub severity: Severity,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<OperatorAnnotation>,
+}
+
+// Stable identifier for the check behind a finding; messages are free text and may change.
+// Part of the review report schema, so codes are only ever added, never renamed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCode {
+    #[default]
+    Unclassified,
+    UnsupportedAction,
+    ManifestUnavailable,
+    ManifestFetchFailed,
+    MissingRootCid,
+    DuplicateRootCid,
+    CopiedBundle,
+    ContractSender,
+    SenderMismatch,
+    MissingFileList,
+    BundleTooLarge,
+    FileTooLarge,
+    HighFileCount,
+    SuspiciousPath,
+    BinaryFiles,
+    DuplicatePath,
+    PathCollision,
+    ControlCharacters,
+    EmptyFiles,
+    SourceScanSkipped,
+    UnexpectedPackageJson,
+    MissingVibefiJson,
+    WasmModule,
+    DisguisedWasm,
+    DisguisedBinary,
+    RiskyTokens,
+    PromptInjectionStripped,
+    LlmEchoedInstructions,
+    OperatorFinding,
+}
+
+// Marks a finding a human reviewer added with `proposals annotate`. Such findings are carried
+// over whenever the proposal is reviewed again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperatorAnnotation {
+    pub author: String,
+    pub annotated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
@@ -79,6 +261,16 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Decision {
     #[serde(deserialize_with = "deserialize_proposal_id")]
@@ -88,6 +280,12 @@ pub struct Decision {
     pub blocking_findings: Vec<String>,
     pub requires_human_override: bool,
     pub decided_at: DateTime<Utc>,
+    #[serde(default)]
+    pub config_hash: Option<String>,
+    // Only with `decision.fractional`: the weight split cast instead of `vote`, which stays the
+    // direction the split leans toward.
+    #[serde(default)]
+    pub allocation: Option<VoteAllocation>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
@@ -98,16 +296,54 @@ pub enum VoteChoice {
     Abstain,
 }
 
-impl VoteChoice {
-    pub fn to_support_u8(self) -> u8 {
-        match self {
-            VoteChoice::Against => 0,
-            VoteChoice::For => 1,
-            VoteChoice::Abstain => 2,
+// Shares of the voting weight in basis points, summing to TOTAL_BPS.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VoteAllocation {
+    pub against_bps: u16,
+    pub for_bps: u16,
+    pub abstain_bps: u16,
+}
+
+impl VoteAllocation {
+    pub const TOTAL_BPS: u16 = 10_000;
+
+    // `share` (0..=1) of the weight goes to `vote` and the rest abstains.
+    pub fn leaning(vote: VoteChoice, share: f32) -> Self {
+        let leaning = (share.clamp(0.0, 1.0) * f32::from(Self::TOTAL_BPS)).round() as u16;
+        let rest = Self::TOTAL_BPS - leaning;
+        match vote {
+            VoteChoice::For => Self {
+                against_bps: 0,
+                for_bps: leaning,
+                abstain_bps: rest,
+            },
+            VoteChoice::Against => Self {
+                against_bps: leaning,
+                for_bps: 0,
+                abstain_bps: rest,
+            },
+            VoteChoice::Abstain => Self {
+                against_bps: 0,
+                for_bps: 0,
+                abstain_bps: Self::TOTAL_BPS,
+            },
         }
     }
 }
 
+impl fmt::Display for VoteAllocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |bps: u16| f32::from(bps) / 100.0;
+        write!(
+            f,
+            "against {:.2}% / for {:.2}% / abstain {:.2}%",
+            percent(self.against_bps),
+            percent(self.for_bps),
+            percent(self.abstain_bps)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoteExecution {
     #[serde(deserialize_with = "deserialize_proposal_id")]
@@ -116,6 +352,78 @@ pub struct VoteExecution {
     pub tx_hash: Option<String>,
     pub reason: String,
     pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+// A `review-cid` review of a bundle that has no on-chain proposal behind it.
+#[derive(Debug, Clone)]
+pub struct SyntheticReview {
+    pub proposal: Proposal,
+    pub review: ReviewResult,
+    pub decision: Decision,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReview {
+    pub proposal: Proposal,
+    pub attempts: u32,
+    pub last_error: String,
+    pub last_attempt_at: DateTime<Utc>,
+}
+
+// One `signer rotate` run; the old address also goes to signer.previous_addresses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotation {
+    pub old_address: String,
+    pub new_address: String,
+    pub keystore_path: String,
+    pub rotated_at: DateTime<Utc>,
+}
+
+// A catch-up pass whose digest has not been sent yet. Cleared when the pass ends; a pass cut short
+// by an error is resumed by the next scan, which then reports every proposal it found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchUpProgress {
+    pub start_block: u64,
+    pub proposal_ids: Vec<String>,
+}
+
+// The scan cursor was found ahead of the RPC's tip and moved back; stored proposals are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorRewind {
+    pub previous_block: u64,
+    // 0 means the next scan starts again from network.from_block.
+    pub rewound_to: u64,
+    pub latest_block: u64,
+    pub check_block: Option<u64>,
+    pub anomaly_cycles: u32,
+    pub stored_proposals: usize,
+    pub rewound_at: DateTime<Utc>,
+}
+
+// What stays in state for a finalized proposal moved to data_dir/archive by `storage prune`:
+// enough for scans to skip it and for `proposals show` to find the full record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedProposal {
+    pub vote: VoteChoice,
+    pub tx_hash: Option<String>,
+    pub path: String,
+    pub archived_at: DateTime<Utc>,
+}
+
+// Stored instead of a review so later scans do not pick the proposal up again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedProposal {
+    pub proposal: Proposal,
+    pub skipped: SkipReason,
+    pub skipped_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    VotingClosed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,7 +431,110 @@ pub struct ProcessedProposal {
     pub proposal: Proposal,
     pub review: ReviewResult,
     pub decision: Decision,
-    pub vote_execution: Option<VoteExecution>,
+    #[serde(default)]
+    pub vote_executions: Vec<VoteExecution>,
+    #[serde(default)]
+    pub decision_history: Vec<Decision>,
+    #[serde(default)]
+    pub voting_paused: bool,
+    #[serde(default)]
+    pub lifecycle: Option<ProposalLifecycle>,
+    // Reviewed after its voting window had already closed (catch-up or `backfill --review-closed`),
+    // so no vote was sent.
+    #[serde(default)]
+    pub vote_missed: bool,
+    // Set when `peers.urls` is configured: what each peer agent answered before we voted.
+    #[serde(default)]
+    pub peer_check: Option<PeerComparison>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerComparison {
+    pub reviews: Vec<PeerReview>,
+    pub disagreeing: usize,
+    pub quorum: usize,
+    // The quorum disagreed, so the vote went to dry-run for a human to decide.
+    pub held: bool,
+}
+
+// `decision` is the peer's stored decision exactly as it returned it; `error` replaces it when
+// the peer could not be read or has not decided the proposal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerReview {
+    pub peer: String,
+    pub vote: Option<VoteChoice>,
+    pub decision: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+// OpenZeppelin Governor's `state()` values, in on-chain order.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalState {
+    Pending,
+    Active,
+    Canceled,
+    Defeated,
+    Succeeded,
+    Queued,
+    Expired,
+    Executed,
+}
+
+impl ProposalState {
+    pub fn from_governor(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Pending,
+            1 => Self::Active,
+            2 => Self::Canceled,
+            3 => Self::Defeated,
+            4 => Self::Succeeded,
+            5 => Self::Queued,
+            6 => Self::Expired,
+            7 => Self::Executed,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Canceled => "canceled",
+            Self::Defeated => "defeated",
+            Self::Succeeded => "succeeded",
+            Self::Queued => "queued",
+            Self::Expired => "expired",
+            Self::Executed => "executed",
+        }
+    }
+
+    // No further transitions are possible, so the proposal is no longer polled.
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            Self::Canceled | Self::Defeated | Self::Expired | Self::Executed
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalLifecycle {
+    pub state: ProposalState,
+    // Timelock execution time in seconds, set once the proposal is queued on a governor with a timelock.
+    #[serde(default)]
+    pub eta: Option<u64>,
+    pub checked_at: DateTime<Utc>,
+}
+
+// Final state plus GovernorCountingSimple totals, as decimal strings of token units.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProposalTally {
+    pub state: ProposalState,
+    pub for_votes: String,
+    pub against_votes: String,
+    pub abstain_votes: String,
 }
 
 fn deserialize_proposal_id<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -143,10 +554,29 @@ where
     }
 }
 
+fn deserialize_actions<'de, D>(deserializer: D) -> Result<Vec<DecodedAction>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ActionsRepr {
+        Many(Vec<DecodedAction>),
+        One(DecodedAction),
+    }
+
+    match ActionsRepr::deserialize(deserializer)? {
+        ActionsRepr::Many(actions) => Ok(actions),
+        ActionsRepr::One(action) => Ok(vec![action]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
 
+    use super::{DecodedAction, Proposal};
+
     #[derive(Deserialize)]
     struct Holder {
         #[serde(deserialize_with = "super::deserialize_proposal_id")]
@@ -170,4 +600,32 @@ mod tests {
             .expect("numeric proposal id should parse");
         assert_eq!(value.proposal_id, "123");
     }
+
+    #[test]
+    fn proposal_accepts_legacy_single_action() {
+        let legacy = r#"{
+            "proposal_id": "1",
+            "proposer": "0x01",
+            "description": "d",
+            "vote_start": 1,
+            "vote_end": 2,
+            "block_number": 1,
+            "tx_hash": null,
+            "targets": [],
+            "values": [],
+            "calldatas": [],
+            "action": {"type": "unsupported", "reason": "none"},
+            "discovered_at": "2025-01-01T00:00:00Z"
+        }"#;
+        let proposal: Proposal = serde_json::from_str(legacy).expect("legacy proposal");
+        assert!(matches!(
+            proposal.actions.as_slice(),
+            [DecodedAction::Unsupported { reason }] if reason == "none"
+        ));
+
+        let current = serde_json::to_string(&proposal).expect("serialize");
+        assert!(current.contains("\"actions\":["));
+        let roundtrip: Proposal = serde_json::from_str(&current).expect("current proposal");
+        assert_eq!(roundtrip.actions.len(), 1);
+    }
 }